@@ -19,6 +19,7 @@ pub struct HalaIndexType(i32);
 impl HalaIndexType {
   pub const UINT16: Self = Self(vk::IndexType::UINT16.as_raw());
   pub const UINT32: Self = Self(vk::IndexType::UINT32.as_raw());
+  pub const NONE: Self = Self(vk::IndexType::NONE_KHR.as_raw());
 }
 
 impl std::convert::From<vk::IndexType> for HalaIndexType {
@@ -384,7 +385,149 @@ impl std::convert::From<&HalaAccelerationStructureGeometry> for vk::Acceleration
 
 }
 
+/// The triangle geometry of the bottom level acceleration structure.
+/// The vertex, index and transform data are referenced by the device addresses.
+#[derive(Clone, Copy)]
+pub struct HalaTriangleGeometry {
+  pub flags: HalaGeometryFlags,
+  pub vertex_address: u64,
+  pub vertex_stride: u64,
+  pub vertex_format: HalaFormat,
+  pub vertex_count: u32,
+  /// The index data address, it is ignored if the index type is NONE.
+  pub index_address: u64,
+  pub index_type: HalaIndexType,
+  pub index_count: u32,
+  /// The address of a VkTransformMatrixKHR, 0 means the identity transform.
+  pub transform_address: u64,
+}
+
+/// The Default trait implementation of the triangle geometry.
+/// The geometry is non-indexed by default.
+impl Default for HalaTriangleGeometry {
+  fn default() -> Self {
+    Self {
+      flags: HalaGeometryFlags::default(),
+      vertex_address: 0,
+      vertex_stride: 0,
+      vertex_format: HalaFormat::default(),
+      vertex_count: 0,
+      index_address: 0,
+      index_type: HalaIndexType::NONE,
+      index_count: 0,
+      transform_address: 0,
+    }
+  }
+}
+
+/// The AsRef trait implementation of the triangle geometry.
+impl AsRef<HalaTriangleGeometry> for HalaTriangleGeometry {
+  fn as_ref(&self) -> &HalaTriangleGeometry {
+    self
+  }
+}
+
+/// The implementation of the triangle geometry.
+impl HalaTriangleGeometry {
+  /// Get the number of the triangles.
+  /// return: The number of the triangles.
+  pub fn primitive_count(&self) -> u32 {
+    if self.index_type == HalaIndexType::NONE {
+      self.vertex_count / 3
+    } else {
+      self.index_count / 3
+    }
+  }
+
+  /// Validate the geometry.
+  /// return: The result.
+  pub fn validate(&self) -> Result<(), HalaGfxError> {
+    if self.vertex_address == 0 || self.vertex_count == 0 {
+      return Err(HalaGfxError::new("The triangle geometry has no vertex data.", None));
+    }
+    let (vertex_size, component_size) = match self.vertex_format {
+      HalaFormat::R32G32B32_SFLOAT => (12, 4),
+      HalaFormat::R32G32_SFLOAT => (8, 4),
+      HalaFormat::R16G16B16A16_SFLOAT | HalaFormat::R16G16B16A16_SNORM => (8, 2),
+      HalaFormat::R16G16_SFLOAT | HalaFormat::R16G16_SNORM => (4, 2),
+      HalaFormat::R8G8B8A8_SNORM => (4, 1),
+      HalaFormat::R8G8_SNORM => (2, 1),
+      _ => return Err(HalaGfxError::new(
+        &format!("The vertex format {} is not supported by the triangle geometry.", self.vertex_format),
+        None,
+      )),
+    };
+    if self.vertex_stride < vertex_size {
+      return Err(HalaGfxError::new(
+        &format!("The vertex stride {} is less than the vertex size {} of the format {}.", self.vertex_stride, vertex_size, self.vertex_format),
+        None,
+      ));
+    }
+    if !self.vertex_stride.is_multiple_of(component_size) {
+      return Err(HalaGfxError::new(
+        &format!("The vertex stride {} is not a multiple of the component size {}.", self.vertex_stride, component_size),
+        None,
+      ));
+    }
+    if self.index_type == HalaIndexType::NONE {
+      if !self.vertex_count.is_multiple_of(3) {
+        return Err(HalaGfxError::new("The vertex count of the non-indexed triangle geometry is not a multiple of 3.", None));
+      }
+    } else {
+      let index_size = if self.index_type == HalaIndexType::UINT16 { 2 } else { 4 };
+      if self.index_address == 0 || self.index_count == 0 {
+        return Err(HalaGfxError::new("The indexed triangle geometry has no index data.", None));
+      }
+      if !self.index_address.is_multiple_of(index_size) {
+        return Err(HalaGfxError::new(&format!("The index address is not aligned to the index size {}.", index_size), None));
+      }
+      if !self.index_count.is_multiple_of(3) {
+        return Err(HalaGfxError::new("The index count of the triangle geometry is not a multiple of 3.", None));
+      }
+    }
+    if !self.transform_address.is_multiple_of(16) {
+      return Err(HalaGfxError::new("The transform address is not aligned to 16 bytes.", None));
+    }
+
+    Ok(())
+  }
+
+  /// Get the acceleration structure geometry.
+  /// return: The acceleration structure geometry.
+  pub fn to_geometry(&self) -> HalaAccelerationStructureGeometry {
+    HalaAccelerationStructureGeometry {
+      ty: HalaGeometryType::TRIANGLES,
+      flags: self.flags,
+      triangles_data: Some(HalaAccelerationStructureGeometryTrianglesData {
+        vertex_format: self.vertex_format,
+        vertex_data_address: self.vertex_address,
+        vertex_stride: self.vertex_stride,
+        // The max vertex is the highest vertex index.
+        vertex_count: self.vertex_count.saturating_sub(1),
+        index_type: self.index_type,
+        index_data_address: if self.index_type == HalaIndexType::NONE { 0 } else { self.index_address },
+        transform_data_address: self.transform_address,
+      }),
+      aabbs_data: None,
+      instances_data: None,
+    }
+  }
+
+  /// Get the acceleration structure build range info.
+  /// return: The acceleration structure build range info.
+  pub fn to_range_info(&self) -> HalaAccelerationStructureBuildRangeInfo {
+    HalaAccelerationStructureBuildRangeInfo {
+      primitive_count: self.primitive_count(),
+      primitive_offset: 0,
+      first_vertex: 0,
+      transform_offset: 0,
+    }
+  }
+}
+
 /// The acceleration structure instance.
+/// The transform is a row-major 3x4 matrix, which is the first three rows of a 4x4 matrix.
+/// The custom index and the record offset only have 24 bits.
 #[derive(Clone, Default)]
 pub struct HalaAccelerationStructureInstance {
   pub transform: [f32; 12],
@@ -395,10 +538,130 @@ pub struct HalaAccelerationStructureInstance {
   pub acceleration_structure_device_address: u64,
 }
 
+// The instance is tightly packed in 64 bytes as the Vulkan specification requires.
+const _: () = assert!(std::mem::size_of::<vk::AccelerationStructureInstanceKHR>() == 64);
+const _: () = assert!(std::mem::offset_of!(vk::AccelerationStructureInstanceKHR, instance_custom_index_and_mask) == 48);
+const _: () = assert!(std::mem::offset_of!(vk::AccelerationStructureInstanceKHR, instance_shader_binding_table_record_offset_and_flags) == 52);
+const _: () = assert!(std::mem::offset_of!(vk::AccelerationStructureInstanceKHR, acceleration_structure_reference) == 56);
+
+/// The AsRef trait implementation of the acceleration structure instance.
+impl AsRef<HalaAccelerationStructureInstance> for HalaAccelerationStructureInstance {
+  fn as_ref(&self) -> &HalaAccelerationStructureInstance {
+    self
+  }
+}
+
+/// The implementation of the acceleration structure instance.
 impl HalaAccelerationStructureInstance {
+  /// The size of an instance in the instances buffer.
+  pub const SIZE: u64 = std::mem::size_of::<vk::AccelerationStructureInstanceKHR>() as u64;
+
+  /// Create an instance of the bottom level acceleration structure.
+  /// The mask is 0xFF and the flags are empty.
+  /// param blas: The bottom level acceleration structure.
+  /// param transform: The row-major 3x4 transform.
+  /// param custom_index: The custom index, which is gl_InstanceCustomIndexEXT in the shader.
+  /// param shader_binding_table_record_offset: The hit group record offset in the shader binding table.
+  /// return: The instance.
+  pub fn new(
+    blas: &HalaAccelerationStructure,
+    transform: [f32; 12],
+    custom_index: u32,
+    shader_binding_table_record_offset: u32,
+  ) -> Self {
+    Self {
+      transform,
+      custom_index,
+      mask: 0xFF,
+      shader_binding_table_record_offset,
+      shader_binding_table_flags: HalaGeometryInstanceFlags::empty(),
+      acceleration_structure_device_address: blas.address,
+    }
+  }
+
+  /// Convert a column-major 4x4 matrix(glam, nalgebra and GLSL layout) to the row-major 3x4 transform.
+  /// param matrix: The column-major 4x4 matrix.
+  /// return: The row-major 3x4 transform.
+  pub fn transform_from_column_major(matrix: &[f32; 16]) -> [f32; 12] {
+    let mut transform = [0.0; 12];
+    for row in 0..3 {
+      for column in 0..4 {
+        transform[row * 4 + column] = matrix[column * 4 + row];
+      }
+    }
+    transform
+  }
+
+  /// Convert to the raw instance.
+  /// return: The raw instance.
   pub fn as_data(&self) -> vk::AccelerationStructureInstanceKHR {
     self.into()
   }
+
+  /// Write the instances into the instances buffer.
+  /// The GPU only buffer is uploaded with the transfer command buffers, otherwise the memory is written directly.
+  /// param buffer: The instances buffer.
+  /// param instances: The instances.
+  /// param transfer_command_buffers: The transfer command buffers.
+  /// return: The result.
+  pub fn write_buffer<I>(
+    buffer: &HalaBuffer,
+    instances: &[I],
+    transfer_command_buffers: &HalaCommandBufferSet,
+  ) -> Result<(), HalaGfxError>
+    where I: AsRef<HalaAccelerationStructureInstance>
+  {
+    if instances.is_empty() {
+      return Ok(());
+    }
+    if instances.len() as u64 * Self::SIZE > buffer.size {
+      return Err(HalaGfxError::new(
+        &format!("The instances buffer is too small for {} instances.", instances.len()),
+        None,
+      ));
+    }
+
+    let data = instances.iter()
+      .map(|instance| instance.as_ref().as_data())
+      .collect::<Vec<_>>();
+    if buffer.memory_location == gpu_allocator::MemoryLocation::GpuOnly {
+      buffer.update_gpu_memory(data.as_slice(), transfer_command_buffers)
+    } else {
+      buffer.update_memory(0, data.as_slice())
+    }
+  }
+
+  /// Create a GPU only instances buffer and write the instances into it.
+  /// param logical_device: The logical device.
+  /// param instances: The instances.
+  /// param transfer_command_buffers: The transfer command buffers.
+  /// param debug_name: The debug name.
+  /// return: The instances buffer.
+  pub fn create_buffer<I>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    instances: &[I],
+    transfer_command_buffers: &HalaCommandBufferSet,
+    debug_name: &str,
+  ) -> Result<HalaBuffer, HalaGfxError>
+    where I: AsRef<HalaAccelerationStructureInstance>
+  {
+    if instances.is_empty() {
+      return Err(HalaGfxError::new("The instances buffer needs at least one instance.", None));
+    }
+
+    let buffer = HalaBuffer::new(
+      logical_device,
+      instances.len() as u64 * Self::SIZE,
+      HalaBufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY
+        | HalaBufferUsageFlags::SHADER_DEVICE_ADDRESS
+        | HalaBufferUsageFlags::TRANSFER_DST,
+      HalaMemoryLocation::GpuOnly,
+      debug_name,
+    )?;
+    Self::write_buffer(&buffer, instances, transfer_command_buffers)?;
+
+    Ok(buffer)
+  }
 }
 
 impl std::convert::From<vk::AccelerationStructureInstanceKHR> for HalaAccelerationStructureInstance {
@@ -637,4 +900,233 @@ impl HalaAccelerationStructure {
       debug_name: debug_name.to_string(),
     })
   }
-}
\ No newline at end of file
+
+  /// Create a bottom level acceleration structure with the triangle geometries.
+  /// param logical_device: The logical device.
+  /// param graphics_command_buffers: The graphics command buffers.
+  /// param geometries: The triangle geometries.
+  /// param debug_name: The debug name.
+  /// return: The acceleration structure.
+  pub fn new_blas_with_triangles<TG>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    graphics_command_buffers: &HalaCommandBufferSet,
+    geometries: &[TG],
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError>
+    where TG: AsRef<HalaTriangleGeometry>
+  {
+    if geometries.is_empty() {
+      return Err(HalaGfxError::new("The bottom level acceleration structure needs at least one geometry.", None));
+    }
+    for geometry in geometries.iter() {
+      geometry.as_ref().validate()?;
+    }
+
+    let as_geometries = geometries.iter()
+      .map(|geometry| geometry.as_ref().to_geometry())
+      .collect::<Vec<_>>();
+    let range_infos = geometries.iter()
+      .map(|geometry| geometry.as_ref().to_range_info())
+      .collect::<Vec<_>>();
+    let max_primitive_counts = geometries.iter()
+      .map(|geometry| geometry.as_ref().primitive_count())
+      .collect::<Vec<_>>();
+
+    Self::new(
+      logical_device,
+      graphics_command_buffers,
+      HalaAccelerationStructureLevel::BOTTOM_LEVEL,
+      &as_geometries,
+      &[range_infos.as_slice()],
+      &max_primitive_counts,
+      debug_name,
+    )
+  }
+
+  /// Create a top level acceleration structure with the instances buffer.
+  /// param logical_device: The logical device.
+  /// param graphics_command_buffers: The graphics command buffers.
+  /// param instances_buffer: The instances buffer, which is written by HalaAccelerationStructureInstance::write_buffer.
+  /// param instance_count: The number of the instances.
+  /// param debug_name: The debug name.
+  /// return: The acceleration structure.
+  pub fn new_tlas_with_instances(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    graphics_command_buffers: &HalaCommandBufferSet,
+    instances_buffer: &HalaBuffer,
+    instance_count: u32,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    if instance_count as u64 * HalaAccelerationStructureInstance::SIZE > instances_buffer.size {
+      return Err(HalaGfxError::new(
+        &format!("The instances buffer is too small for {} instances.", instance_count),
+        None,
+      ));
+    }
+
+    let geometry = HalaAccelerationStructureGeometry {
+      ty: HalaGeometryType::INSTANCES,
+      flags: HalaGeometryFlags::empty(),
+      instances_data: Some(HalaAccelerationStructureGeometryInstancesData {
+        array_of_pointers: false,
+        data_address: instances_buffer.get_device_address(),
+      }),
+      ..Default::default()
+    };
+    let range_info = HalaAccelerationStructureBuildRangeInfo {
+      primitive_count: instance_count,
+      ..Default::default()
+    };
+
+    Self::new(
+      logical_device,
+      graphics_command_buffers,
+      HalaAccelerationStructureLevel::TOP_LEVEL,
+      &[geometry],
+      &[&[range_info]],
+      &[instance_count],
+      debug_name,
+    )
+  }
+
+  /// Set the debug name of the acceleration structure and its buffer.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for the acceleration structure.", Some(Box::new(err))))?;
+    self.buffer.set_debug_name(&format!("{}.buffer", debug_name))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn transform_from_column_major_is_row_major_3x4() {
+    // The columns are the basis vectors and the translation(10, 20, 30).
+    let matrix = [
+      1.0, 2.0, 3.0, 0.0,
+      4.0, 5.0, 6.0, 0.0,
+      7.0, 8.0, 9.0, 0.0,
+      10.0, 20.0, 30.0, 1.0,
+    ];
+    let transform = HalaAccelerationStructureInstance::transform_from_column_major(&matrix);
+    assert_eq!(transform, [
+      1.0, 4.0, 7.0, 10.0,
+      2.0, 5.0, 8.0, 20.0,
+      3.0, 6.0, 9.0, 30.0,
+    ]);
+  }
+
+  #[test]
+  fn instance_is_packed_as_vulkan_layout() {
+    let transform = [
+      1.0, 0.0, 0.0, 10.0,
+      0.0, 1.0, 0.0, 20.0,
+      0.0, 0.0, 1.0, 30.0,
+    ];
+    let instance = HalaAccelerationStructureInstance {
+      transform,
+      custom_index: 0x12_3456,
+      mask: 0xA5,
+      shader_binding_table_record_offset: 0xAB_CDEF,
+      shader_binding_table_flags: HalaGeometryInstanceFlags::TRIANGLE_FACING_CULL_DISABLE | HalaGeometryInstanceFlags::FORCE_OPAQUE,
+      acceleration_structure_device_address: 0x0123_4567_89AB_CDEF,
+    };
+    let raw = instance.as_data();
+    let bytes = unsafe {
+      std::slice::from_raw_parts(&raw as *const vk::AccelerationStructureInstanceKHR as *const u8, HalaAccelerationStructureInstance::SIZE as usize)
+    };
+    let read_u32 = |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+
+    for (i, value) in transform.iter().enumerate() {
+      assert_eq!(f32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap()), *value);
+    }
+    // The 24 bits custom index in the low bits and the 8 bits mask in the high bits.
+    assert_eq!(read_u32(48), 0xA512_3456);
+    // The 24 bits record offset in the low bits and the 8 bits flags in the high bits.
+    let flags = (vk::GeometryInstanceFlagsKHR::TRIANGLE_FACING_CULL_DISABLE | vk::GeometryInstanceFlagsKHR::FORCE_OPAQUE).as_raw();
+    assert_eq!(read_u32(52), 0xAB_CDEF | (flags << 24));
+    assert_eq!(u64::from_le_bytes(bytes[56..64].try_into().unwrap()), 0x0123_4567_89AB_CDEF);
+  }
+
+  #[test]
+  fn instance_truncates_index_and_offset_to_24_bits() {
+    let instance = HalaAccelerationStructureInstance {
+      custom_index: 0xFF12_3456,
+      mask: 0x01,
+      shader_binding_table_record_offset: 0xFFAB_CDEF,
+      ..Default::default()
+    };
+    let raw = instance.as_data();
+    assert_eq!(raw.instance_custom_index_and_mask.low_24(), 0x12_3456);
+    assert_eq!(raw.instance_custom_index_and_mask.high_8(), 0x01);
+    assert_eq!(raw.instance_shader_binding_table_record_offset_and_flags.low_24(), 0xAB_CDEF);
+    assert_eq!(raw.instance_shader_binding_table_record_offset_and_flags.high_8(), 0);
+  }
+
+  fn triangle_geometry() -> HalaTriangleGeometry {
+    HalaTriangleGeometry {
+      vertex_address: 0x1000,
+      vertex_stride: 12,
+      vertex_format: HalaFormat::R32G32B32_SFLOAT,
+      vertex_count: 6,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn triangle_geometry_is_non_indexed_by_default() {
+    assert!(HalaTriangleGeometry::default().index_type == HalaIndexType::NONE);
+  }
+
+  #[test]
+  fn triangle_geometry_primitive_count() {
+    let geometry = triangle_geometry();
+    assert_eq!(geometry.primitive_count(), 2);
+    // The index count is used by the indexed geometry.
+    let geometry = HalaTriangleGeometry {
+      index_address: 0x2000,
+      index_type: HalaIndexType::UINT32,
+      index_count: 12,
+      ..triangle_geometry()
+    };
+    assert_eq!(geometry.primitive_count(), 4);
+  }
+
+  #[test]
+  fn triangle_geometry_validate_vertices() {
+    assert!(triangle_geometry().validate().is_ok());
+    assert!(HalaTriangleGeometry { vertex_address: 0, ..triangle_geometry() }.validate().is_err());
+    assert!(HalaTriangleGeometry { vertex_count: 0, ..triangle_geometry() }.validate().is_err());
+    assert!(HalaTriangleGeometry { vertex_count: 7, ..triangle_geometry() }.validate().is_err());
+    assert!(HalaTriangleGeometry { vertex_format: HalaFormat::R8G8B8A8_UNORM, ..triangle_geometry() }.validate().is_err());
+    assert!(HalaTriangleGeometry { vertex_stride: 8, ..triangle_geometry() }.validate().is_err());
+    assert!(HalaTriangleGeometry { vertex_stride: 14, ..triangle_geometry() }.validate().is_err());
+    assert!(HalaTriangleGeometry { vertex_stride: 16, ..triangle_geometry() }.validate().is_ok());
+    assert!(HalaTriangleGeometry { transform_address: 0x3008, ..triangle_geometry() }.validate().is_err());
+    assert!(HalaTriangleGeometry { transform_address: 0x3010, ..triangle_geometry() }.validate().is_ok());
+  }
+
+  #[test]
+  fn triangle_geometry_validate_indices() {
+    let geometry = HalaTriangleGeometry {
+      index_address: 0x2002,
+      index_type: HalaIndexType::UINT16,
+      index_count: 6,
+      ..triangle_geometry()
+    };
+    assert!(geometry.validate().is_ok());
+    // The vertex count of the indexed geometry is not required to be a multiple of 3.
+    assert!(HalaTriangleGeometry { vertex_count: 4, ..geometry }.validate().is_ok());
+    assert!(HalaTriangleGeometry { index_address: 0, ..geometry }.validate().is_err());
+    assert!(HalaTriangleGeometry { index_count: 0, ..geometry }.validate().is_err());
+    assert!(HalaTriangleGeometry { index_count: 5, ..geometry }.validate().is_err());
+    assert!(HalaTriangleGeometry { index_type: HalaIndexType::UINT32, ..geometry }.validate().is_err());
+    assert!(HalaTriangleGeometry { index_address: 0x2001, ..geometry }.validate().is_err());
+  }
+}
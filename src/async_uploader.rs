@@ -0,0 +1,316 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use ash::vk;
+
+use crate::{
+  HalaAccessFlags2,
+  HalaBuffer,
+  HalaBufferBarrierInfo,
+  HalaBufferUsageFlags,
+  HalaCommandBufferLevel,
+  HalaCommandBufferSet,
+  HalaCommandBufferType,
+  HalaCommandBufferUsageFlags,
+  HalaCommandPools,
+  HalaGfxError,
+  HalaImage,
+  HalaImageBarrierInfo,
+  HalaImageLayout,
+  HalaLogicalDevice,
+  HalaMemoryLocation,
+  HalaPipelineStageFlags2,
+  HalaTimelineSemaphore,
+};
+
+/// The ticket of an async upload.
+/// The upload is complete when the timeline semaphore of the uploader reaches the value.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct HalaUploadTicket {
+  pub value: u64,
+}
+
+/// The in-flight upload which keeps its resources alive until the GPU finishes it.
+struct HalaPendingUpload {
+  value: u64,
+  _command_buffers: HalaCommandBufferSet,
+  _staging_buffer: HalaBuffer,
+}
+
+/// The async uploader on the transfer queue.
+/// Each upload has its own staging buffer and command buffer, and signals the timeline semaphore when it is finished,
+/// so the caller can poll or wait the ticket later instead of waiting the queue idle.
+/// If the queue which uses the uploaded resources is in another queue family, their ownership is released by the transfer queue
+/// and must be acquired with the returned barrier. The queue must wait the timeline semaphore value or the host must check the ticket first.
+pub struct HalaAsyncUploader {
+  pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  pub(crate) command_pools: Rc<RefCell<HalaCommandPools>>,
+  pub semaphore: HalaTimelineSemaphore,
+  pub(crate) next_value: u64,
+  pending_uploads: Vec<HalaPendingUpload>,
+  pub(crate) debug_name: String,
+}
+
+/// The Drop trait implementation of the async uploader.
+impl Drop for HalaAsyncUploader {
+  fn drop(&mut self) {
+    // The staging buffers and command buffers can only be released after the GPU finishes the uploads.
+    if let Err(err) = self.semaphore.wait(self.next_value - 1, u64::MAX) {
+      log::error!("Failed to wait the uploads of HalaAsyncUploader \"{}\": {}", self.debug_name, err);
+    }
+    self.pending_uploads.clear();
+    log::debug!("A HalaAsyncUploader \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation of the async uploader.
+impl HalaAsyncUploader {
+  /// Create a new async uploader.
+  /// param logical_device: The logical device.
+  /// param command_pools: The command pools to allocate the transfer command buffers.
+  /// param debug_name: The debug name.
+  /// return: The async uploader.
+  pub fn new(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    command_pools: Rc<RefCell<HalaCommandPools>>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let semaphore = HalaTimelineSemaphore::new(
+      Rc::clone(&logical_device),
+      0,
+      &format!("{}.timeline_semaphore", debug_name),
+    )?;
+
+    log::debug!("A HalaAsyncUploader \"{}\" is created.", debug_name);
+    Ok(Self {
+      logical_device,
+      command_pools,
+      semaphore,
+      next_value: 1,
+      pending_uploads: Vec::new(),
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Begin to upload the data to the GPU only buffer.
+  /// If the destination queue family is not the transfer queue family, the ownership of the buffer is released by the transfer queue,
+  /// and the returned barrier must be acquired with HalaCommandBufferSet::acquire_queue_ownership on the destination queue
+  /// which waits the timeline semaphore value of the ticket.
+  /// param buffer: The destination buffer.
+  /// param data: The data.
+  /// param dst_queue_family_index: The queue family index of the queue which uses the buffer.
+  /// param dst_stage_mask: The destination stage mask.
+  /// param dst_access_mask: The destination access mask.
+  /// return: The upload ticket and the acquire barrier if the ownership is transferred.
+  pub fn begin_async_upload<T: Copy>(
+    &mut self,
+    buffer: &HalaBuffer,
+    data: &[T],
+    dst_queue_family_index: u32,
+    dst_stage_mask: HalaPipelineStageFlags2,
+    dst_access_mask: HalaAccessFlags2,
+  ) -> Result<(HalaUploadTicket, Option<HalaBufferBarrierInfo>), HalaGfxError> {
+    let size = std::mem::size_of_val(data) as u64;
+    if size > buffer.size {
+      return Err(HalaGfxError::new(
+        &format!("The data size {} exceeds the buffer size {}.", size, buffer.size),
+        None,
+      ));
+    }
+
+    let src_queue_family_index = self.logical_device.borrow().transfer_queue_family_index;
+    let is_ownership_transferred = src_queue_family_index != dst_queue_family_index;
+    let barrier = HalaBufferBarrierInfo {
+      src_stage_mask: HalaPipelineStageFlags2::TRANSFER,
+      src_access_mask: HalaAccessFlags2::TRANSFER_WRITE,
+      dst_stage_mask,
+      dst_access_mask,
+      size,
+      ..HalaBufferBarrierInfo::for_buffer(buffer)
+    };
+    let barrier = if is_ownership_transferred {
+      HalaBufferBarrierInfo {
+        src_queue_family_index,
+        dst_queue_family_index,
+        ..barrier
+      }
+    } else {
+      barrier
+    };
+
+    let ticket = self.submit(data, |logical_device, command_buffers, index, staging_buffer| {
+      let copy_regions = [vk::BufferCopy::default()
+        .src_offset(0)
+        .dst_offset(0)
+        .size(size)];
+      let raw_barrier = if is_ownership_transferred {
+        barrier.to_release().to_raw()
+      } else {
+        barrier.to_raw()
+      };
+      let dependency_info = vk::DependencyInfoKHR::default()
+        .buffer_memory_barriers(std::slice::from_ref(&raw_barrier));
+      unsafe {
+        logical_device.raw.cmd_copy_buffer(command_buffers.raw[index], staging_buffer.raw, buffer.raw, &copy_regions);
+        logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &dependency_info);
+      }
+    })?;
+
+    Ok((ticket, if is_ownership_transferred { Some(barrier) } else { None }))
+  }
+
+  /// Begin to upload the data to the first mip level and array layer of the GPU only image.
+  /// The image is transitioned to the destination layout after the copy.
+  /// If the destination queue family is not the transfer queue family, the ownership of the image is released by the transfer queue,
+  /// and the returned barrier must be acquired with HalaCommandBufferSet::acquire_queue_ownership on the destination queue
+  /// which waits the timeline semaphore value of the ticket.
+  /// The data size of the formats without the texel size(e.g. the block compressed formats) is not checked.
+  /// param image: The destination image.
+  /// param data: The data.
+  /// param dst_queue_family_index: The queue family index of the queue which uses the image.
+  /// param dst_stage_mask: The destination stage mask.
+  /// param dst_access_mask: The destination access mask.
+  /// param dst_layout: The destination layout.
+  /// return: The upload ticket and the acquire barrier if the ownership is transferred.
+  pub fn begin_async_upload_image<T: Copy>(
+    &mut self,
+    image: &HalaImage,
+    data: &[T],
+    dst_queue_family_index: u32,
+    dst_stage_mask: HalaPipelineStageFlags2,
+    dst_access_mask: HalaAccessFlags2,
+    dst_layout: HalaImageLayout,
+  ) -> Result<(HalaUploadTicket, Option<HalaImageBarrierInfo>), HalaGfxError> {
+    let size = std::mem::size_of_val(data) as u64;
+    if let Some(image_size) = image.first_mip_size() {
+      if size < image_size {
+        return Err(HalaGfxError::new(
+          &format!("The data size {} is smaller than the image data size {}.", size, image_size),
+          None,
+        ));
+      }
+    }
+
+    let src_queue_family_index = self.logical_device.borrow().transfer_queue_family_index;
+    let ticket = self.submit(data, |logical_device, command_buffers, index, staging_buffer| {
+      image.record_upload_from_buffer(
+        logical_device,
+        command_buffers,
+        index,
+        staging_buffer,
+        dst_stage_mask,
+        dst_access_mask,
+        dst_layout,
+        Some((src_queue_family_index, dst_queue_family_index)),
+      );
+    })?;
+
+    let acquire_barrier = if src_queue_family_index != dst_queue_family_index {
+      Some(HalaImageBarrierInfo {
+        src_stage_mask: HalaPipelineStageFlags2::TRANSFER,
+        src_access_mask: HalaAccessFlags2::TRANSFER_WRITE,
+        dst_stage_mask,
+        dst_access_mask,
+        old_layout: HalaImageLayout::TRANSFER_DST_OPTIMAL,
+        new_layout: dst_layout,
+        src_queue_family_index,
+        dst_queue_family_index,
+        // The same subresource range as the release barrier.
+        level_count: 1,
+        layer_count: 1,
+        ..HalaImageBarrierInfo::for_image(image)
+      })
+    } else {
+      None
+    };
+
+    Ok((ticket, acquire_barrier))
+  }
+
+  /// Check if the upload is complete.
+  /// The resources of the complete uploads are released.
+  /// param ticket: The upload ticket.
+  /// return: True if the upload is complete.
+  pub fn is_upload_complete(&mut self, ticket: HalaUploadTicket) -> Result<bool, HalaGfxError> {
+    let completed_value = self.collect()?;
+    Ok(ticket.value <= completed_value)
+  }
+
+  /// Wait on the host until the upload is complete.
+  /// param ticket: The upload ticket.
+  /// param timeout: The timeout in nanoseconds.
+  /// return: True if the upload is complete, false if timeout.
+  pub fn wait_upload(&mut self, ticket: HalaUploadTicket, timeout: u64) -> Result<bool, HalaGfxError> {
+    let is_complete = self.semaphore.wait(ticket.value, timeout)?;
+    self.collect()?;
+    Ok(is_complete)
+  }
+
+  /// Get the number of the uploads which are not complete or not collected.
+  /// return: The number of the pending uploads.
+  pub fn pending_count(&self) -> usize {
+    self.pending_uploads.len()
+  }
+
+  /// Release the resources of the complete uploads.
+  /// return: The completed value of the timeline semaphore.
+  pub fn collect(&mut self) -> Result<u64, HalaGfxError> {
+    let completed_value = self.semaphore.get_value()?;
+    self.pending_uploads.retain(|upload| upload.value > completed_value);
+    Ok(completed_value)
+  }
+
+  /// Copy the data to a new staging buffer, record the commands and submit them to the transfer queue.
+  /// param data: The data.
+  /// param recording_fn: The recording function.
+  /// return: The upload ticket.
+  fn submit<T, F>(&mut self, data: &[T], recording_fn: F) -> Result<HalaUploadTicket, HalaGfxError>
+    where T: Copy,
+          F: FnOnce(&HalaLogicalDevice, &HalaCommandBufferSet, usize, &HalaBuffer)
+  {
+    let size = std::mem::size_of_val(data) as u64;
+    if size == 0 {
+      return Err(HalaGfxError::new("The upload data is empty.", None));
+    }
+    self.collect()?;
+
+    let value = self.next_value;
+    let staging_buffer = HalaBuffer::new(
+      Rc::clone(&self.logical_device),
+      size,
+      HalaBufferUsageFlags::TRANSFER_SRC,
+      HalaMemoryLocation::CpuToGpu,
+      &format!("{}.staging_buffer_{}", self.debug_name, value),
+    )?;
+    staging_buffer.update_memory(0, data)?;
+
+    let command_buffers = HalaCommandBufferSet::new(
+      Rc::clone(&self.logical_device),
+      Rc::clone(&self.command_pools),
+      HalaCommandBufferType::TRANSFER,
+      HalaCommandBufferLevel::PRIMARY,
+      1,
+      &format!("{}.command_buffer_{}", self.debug_name, value),
+    )?;
+    command_buffers.begin(0, HalaCommandBufferUsageFlags::ONE_TIME_SUBMIT)?;
+    recording_fn(&self.logical_device.borrow(), &command_buffers, 0, &staging_buffer);
+    command_buffers.end(0)?;
+
+    self.logical_device.borrow().transfer_submit_with_timeline_semaphore(
+      &command_buffers,
+      0,
+      0,
+      &self.semaphore,
+      value,
+    )?;
+    self.next_value += 1;
+
+    self.pending_uploads.push(HalaPendingUpload {
+      value,
+      _command_buffers: command_buffers,
+      _staging_buffer: staging_buffer,
+    });
+
+    Ok(HalaUploadTicket { value })
+  }
+}
@@ -16,6 +16,7 @@ impl HalaImageLayout {
   pub const TRANSFER_DST_OPTIMAL: Self = Self(vk::ImageLayout::TRANSFER_DST_OPTIMAL.as_raw());
   pub const PREINITIALIZED: Self = Self(vk::ImageLayout::PREINITIALIZED.as_raw());
   pub const PRESENT_SRC: Self = Self(vk::ImageLayout::PRESENT_SRC_KHR.as_raw());
+  pub const FRAGMENT_SHADING_RATE_ATTACHMENT_OPTIMAL: Self = Self(vk::ImageLayout::FRAGMENT_SHADING_RATE_ATTACHMENT_OPTIMAL_KHR.as_raw());
 }
 
 impl std::convert::From<vk::ImageLayout> for HalaImageLayout {
@@ -56,6 +57,10 @@ impl HalaAccessFlags2 {
   pub const SHADER_SAMPLED_READ: Self = Self(vk::AccessFlags2::SHADER_SAMPLED_READ.as_raw());
   pub const SHADER_STORAGE_READ: Self = Self(vk::AccessFlags2::SHADER_STORAGE_READ.as_raw());
   pub const SHADER_STORAGE_WRITE: Self = Self(vk::AccessFlags2::SHADER_STORAGE_WRITE.as_raw());
+  pub const FRAGMENT_SHADING_RATE_ATTACHMENT_READ: Self = Self(vk::AccessFlags2::FRAGMENT_SHADING_RATE_ATTACHMENT_READ_KHR.as_raw());
+  pub const TRANSFORM_FEEDBACK_WRITE: Self = Self(vk::AccessFlags2::TRANSFORM_FEEDBACK_WRITE_EXT.as_raw());
+  pub const TRANSFORM_FEEDBACK_COUNTER_READ: Self = Self(vk::AccessFlags2::TRANSFORM_FEEDBACK_COUNTER_READ_EXT.as_raw());
+  pub const TRANSFORM_FEEDBACK_COUNTER_WRITE: Self = Self(vk::AccessFlags2::TRANSFORM_FEEDBACK_COUNTER_WRITE_EXT.as_raw());
 }
 
 impl std::convert::From<vk::AccessFlags2> for HalaAccessFlags2 {
@@ -142,6 +147,68 @@ impl Default for HalaImageBarrierInfo {
   }
 }
 
+/// The implementation of the image barrier.
+impl HalaImageBarrierInfo {
+  /// Create an image barrier covering all mip levels and array layers of the image.
+  /// The aspect mask is inferred from the image format.
+  /// param image: The image.
+  /// return: The image barrier.
+  pub fn for_image(image: &crate::HalaImage) -> Self {
+    Self {
+      aspect_mask: image.format.aspect_flags(),
+      level_count: image.mip_levels,
+      layer_count: image.array_layers,
+      image: image.raw,
+      ..Default::default()
+    }
+  }
+
+  /// Get the release half of the queue family ownership transfer.
+  /// The destination stage and access are ignored by the release barrier.
+  /// return: The release barrier which is recorded on the source queue.
+  pub fn to_release(self) -> Self {
+    Self {
+      dst_stage_mask: HalaPipelineStageFlags2::NONE,
+      dst_access_mask: HalaAccessFlags2::NONE,
+      ..self
+    }
+  }
+
+  /// Get the acquire half of the queue family ownership transfer.
+  /// The source stage and access are ignored by the acquire barrier.
+  /// return: The acquire barrier which is recorded on the destination queue.
+  pub fn to_acquire(self) -> Self {
+    Self {
+      src_stage_mask: HalaPipelineStageFlags2::NONE,
+      src_access_mask: HalaAccessFlags2::NONE,
+      ..self
+    }
+  }
+
+  /// Convert to the raw image memory barrier.
+  /// return: The raw image memory barrier.
+  pub(crate) fn to_raw(self) -> vk::ImageMemoryBarrier2<'static> {
+    vk::ImageMemoryBarrier2::default()
+      .src_stage_mask(self.src_stage_mask.into())
+      .src_access_mask(self.src_access_mask.into())
+      .dst_stage_mask(self.dst_stage_mask.into())
+      .dst_access_mask(self.dst_access_mask.into())
+      .old_layout(self.old_layout.into())
+      .new_layout(self.new_layout.into())
+      .src_queue_family_index(self.src_queue_family_index)
+      .dst_queue_family_index(self.dst_queue_family_index)
+      .image(self.image)
+      .subresource_range(
+        vk::ImageSubresourceRange::default()
+          .aspect_mask(self.aspect_mask.into())
+          .base_mip_level(self.base_mip_level)
+          .level_count(self.level_count)
+          .base_array_layer(self.base_array_layer)
+          .layer_count(self.layer_count)
+      )
+  }
+}
+
 /// The buffer barrier.
 #[derive(Clone, Copy, Default)]
 pub struct HalaBufferBarrierInfo {
@@ -163,6 +230,74 @@ impl AsRef<HalaBufferBarrierInfo> for HalaBufferBarrierInfo {
   }
 }
 
+/// The implementation of the buffer barrier.
+impl HalaBufferBarrierInfo {
+  /// Create a buffer barrier covering the whole buffer.
+  /// param buffer: The buffer.
+  /// return: The buffer barrier.
+  pub fn for_buffer(buffer: &crate::HalaBuffer) -> Self {
+    Self {
+      src_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+      dst_queue_family_index: vk::QUEUE_FAMILY_IGNORED,
+      offset: 0,
+      size: vk::WHOLE_SIZE,
+      buffer: buffer.raw,
+      ..Default::default()
+    }
+  }
+
+  /// Create a buffer barrier from the compute shader write to the vertex attribute read.
+  /// It is used when a compute shader(e.g. GPU skinning) writes the vertices which are then drawn.
+  /// param buffer: The buffer.
+  /// return: The buffer barrier.
+  pub fn compute_write_to_vertex_read(buffer: &crate::HalaBuffer) -> Self {
+    Self {
+      src_stage_mask: HalaPipelineStageFlags2::COMPUTE_SHADER,
+      src_access_mask: HalaAccessFlags2::SHADER_WRITE,
+      dst_stage_mask: HalaPipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT,
+      dst_access_mask: HalaAccessFlags2::VERTEX_ATTRIBUTE_READ,
+      ..Self::for_buffer(buffer)
+    }
+  }
+
+  /// Get the release half of the queue family ownership transfer.
+  /// The destination stage and access are ignored by the release barrier.
+  /// return: The release barrier which is recorded on the source queue.
+  pub fn to_release(self) -> Self {
+    Self {
+      dst_stage_mask: HalaPipelineStageFlags2::NONE,
+      dst_access_mask: HalaAccessFlags2::NONE,
+      ..self
+    }
+  }
+
+  /// Get the acquire half of the queue family ownership transfer.
+  /// The source stage and access are ignored by the acquire barrier.
+  /// return: The acquire barrier which is recorded on the destination queue.
+  pub fn to_acquire(self) -> Self {
+    Self {
+      src_stage_mask: HalaPipelineStageFlags2::NONE,
+      src_access_mask: HalaAccessFlags2::NONE,
+      ..self
+    }
+  }
+
+  /// Convert to the raw buffer memory barrier.
+  /// return: The raw buffer memory barrier.
+  pub(crate) fn to_raw(self) -> vk::BufferMemoryBarrier2<'static> {
+    vk::BufferMemoryBarrier2::default()
+      .src_stage_mask(self.src_stage_mask.into())
+      .src_access_mask(self.src_access_mask.into())
+      .dst_stage_mask(self.dst_stage_mask.into())
+      .dst_access_mask(self.dst_access_mask.into())
+      .src_queue_family_index(self.src_queue_family_index)
+      .dst_queue_family_index(self.dst_queue_family_index)
+      .buffer(self.buffer)
+      .offset(self.offset)
+      .size(self.size)
+  }
+}
+
 /// The barrier.
 #[derive(Clone, Copy, Default)]
 pub struct HalaMemoryBarrierInfo {
@@ -177,4 +312,109 @@ impl AsRef<HalaMemoryBarrierInfo> for HalaMemoryBarrierInfo {
   fn as_ref(&self) -> &Self {
     self
   }
-}
\ No newline at end of file
+}
+
+/// The implementation of the memory barrier.
+impl HalaMemoryBarrierInfo {
+  /// Convert to the raw memory barrier.
+  /// return: The raw memory barrier.
+  pub(crate) fn to_raw(self) -> vk::MemoryBarrier2<'static> {
+    vk::MemoryBarrier2::default()
+      .src_stage_mask(self.src_stage_mask.into())
+      .src_access_mask(self.src_access_mask.into())
+      .dst_stage_mask(self.dst_stage_mask.into())
+      .dst_access_mask(self.dst_access_mask.into())
+  }
+}
+
+/// The shader stages which can read the images in the SHADER_READ_ONLY_OPTIMAL layout.
+pub(crate) const SHADER_READ_STAGES: HalaPipelineStageFlags2 = HalaPipelineStageFlags2::from_raw(
+  vk::PipelineStageFlags2::VERTEX_SHADER.as_raw()
+    | vk::PipelineStageFlags2::FRAGMENT_SHADER.as_raw()
+    | vk::PipelineStageFlags2::COMPUTE_SHADER.as_raw()
+);
+
+/// Get the default stages and accesses of the image layout transition from the well-known table.
+/// e.g. UNDEFINED -> TRANSFER_DST_OPTIMAL waits nothing and blocks the transfer writes,
+/// COLOR_ATTACHMENT_OPTIMAL -> PRESENT_SRC waits the color attachment writes.
+/// The layouts which are not in the table fall back to ALL_COMMANDS and MEMORY_READ | MEMORY_WRITE.
+/// param old_layout: The old layout.
+/// param new_layout: The new layout.
+/// return: The source stage, source access, destination stage and destination access.
+pub fn default_barrier_params(
+  old_layout: HalaImageLayout,
+  new_layout: HalaImageLayout,
+) -> (HalaPipelineStageFlags2, HalaAccessFlags2, HalaPipelineStageFlags2, HalaAccessFlags2) {
+  let fallback = |layout: HalaImageLayout| {
+    log::debug!(
+      "The layout transition {:?} -> {:?} is not in the barrier table, fall back to the full barrier of {:?}.",
+      vk::ImageLayout::from(old_layout), vk::ImageLayout::from(new_layout), vk::ImageLayout::from(layout),
+    );
+    (HalaPipelineStageFlags2::ALL_COMMANDS, HalaAccessFlags2::MEMORY_READ | HalaAccessFlags2::MEMORY_WRITE)
+  };
+  let (src_stage_mask, src_access_mask) = src_stage_access_for_layout(old_layout)
+    .unwrap_or_else(|| fallback(old_layout));
+  let (dst_stage_mask, dst_access_mask) = dst_stage_access_for_layout(new_layout)
+    .unwrap_or_else(|| fallback(new_layout));
+  (src_stage_mask, src_access_mask, dst_stage_mask, dst_access_mask)
+}
+
+/// Get the stage and access which must be finished before the image leaves the layout.
+/// param layout: The old layout.
+/// return: The source stage and access, None if the layout is not in the table.
+fn src_stage_access_for_layout(layout: HalaImageLayout) -> Option<(HalaPipelineStageFlags2, HalaAccessFlags2)> {
+  match layout {
+    HalaImageLayout::UNDEFINED => Some((HalaPipelineStageFlags2::NONE, HalaAccessFlags2::NONE)),
+    HalaImageLayout::PREINITIALIZED => Some((HalaPipelineStageFlags2::HOST, HalaAccessFlags2::HOST_WRITE)),
+    HalaImageLayout::GENERAL => Some((HalaPipelineStageFlags2::ALL_COMMANDS, HalaAccessFlags2::MEMORY_WRITE)),
+    HalaImageLayout::COLOR_ATTACHMENT_OPTIMAL => Some((
+      HalaPipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+      HalaAccessFlags2::COLOR_ATTACHMENT_WRITE,
+    )),
+    HalaImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL => Some((
+      HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS,
+      HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+    )),
+    // The read only layouts have no writes to make available, only the reads must be finished.
+    HalaImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL => Some((
+      HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS | SHADER_READ_STAGES,
+      HalaAccessFlags2::NONE,
+    )),
+    HalaImageLayout::SHADER_READ_ONLY_OPTIMAL => Some((SHADER_READ_STAGES, HalaAccessFlags2::NONE)),
+    HalaImageLayout::TRANSFER_SRC_OPTIMAL => Some((HalaPipelineStageFlags2::ALL_TRANSFER, HalaAccessFlags2::NONE)),
+    HalaImageLayout::TRANSFER_DST_OPTIMAL => Some((HalaPipelineStageFlags2::ALL_TRANSFER, HalaAccessFlags2::TRANSFER_WRITE)),
+    // The presentation engine is synchronized by the acquire semaphore.
+    HalaImageLayout::PRESENT_SRC => Some((HalaPipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT, HalaAccessFlags2::NONE)),
+    _ => None,
+  }
+}
+
+/// Get the stage and access which wait for the image entering the layout.
+/// param layout: The new layout.
+/// return: The destination stage and access, None if the layout is not in the table.
+fn dst_stage_access_for_layout(layout: HalaImageLayout) -> Option<(HalaPipelineStageFlags2, HalaAccessFlags2)> {
+  match layout {
+    HalaImageLayout::GENERAL => Some((
+      HalaPipelineStageFlags2::ALL_COMMANDS,
+      HalaAccessFlags2::MEMORY_READ | HalaAccessFlags2::MEMORY_WRITE,
+    )),
+    HalaImageLayout::COLOR_ATTACHMENT_OPTIMAL => Some((
+      HalaPipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+      HalaAccessFlags2::COLOR_ATTACHMENT_READ | HalaAccessFlags2::COLOR_ATTACHMENT_WRITE,
+    )),
+    HalaImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL => Some((
+      HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS,
+      HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ | HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+    )),
+    HalaImageLayout::DEPTH_STENCIL_READ_ONLY_OPTIMAL => Some((
+      HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS | SHADER_READ_STAGES,
+      HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ | HalaAccessFlags2::SHADER_READ,
+    )),
+    HalaImageLayout::SHADER_READ_ONLY_OPTIMAL => Some((SHADER_READ_STAGES, HalaAccessFlags2::SHADER_READ)),
+    HalaImageLayout::TRANSFER_SRC_OPTIMAL => Some((HalaPipelineStageFlags2::ALL_TRANSFER, HalaAccessFlags2::TRANSFER_READ)),
+    HalaImageLayout::TRANSFER_DST_OPTIMAL => Some((HalaPipelineStageFlags2::ALL_TRANSFER, HalaAccessFlags2::TRANSFER_WRITE)),
+    // The presentation engine waits for the semaphore, so no stage waits for the barrier.
+    HalaImageLayout::PRESENT_SRC => Some((HalaPipelineStageFlags2::NONE, HalaAccessFlags2::NONE)),
+    _ => None,
+  }
+}
@@ -25,6 +25,8 @@ impl HalaBufferUsageFlags {
   pub const ACCELERATION_STRUCTURE_STORAGE: Self = Self(vk::BufferUsageFlags::ACCELERATION_STRUCTURE_STORAGE_KHR.as_raw());
   pub const ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY: Self = Self(vk::BufferUsageFlags::ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_KHR.as_raw());
   pub const SHADER_BINDING_TABLE: Self = Self(vk::BufferUsageFlags::SHADER_BINDING_TABLE_KHR.as_raw());
+  pub const TRANSFORM_FEEDBACK_BUFFER: Self = Self(vk::BufferUsageFlags::TRANSFORM_FEEDBACK_BUFFER_EXT.as_raw());
+  pub const TRANSFORM_FEEDBACK_COUNTER_BUFFER: Self = Self(vk::BufferUsageFlags::TRANSFORM_FEEDBACK_COUNTER_BUFFER_EXT.as_raw());
 }
 
 impl std::convert::From<vk::BufferUsageFlags> for HalaBufferUsageFlags {
@@ -106,6 +108,30 @@ impl HalaBuffer {
     Self::new_impl(logical_device, size, usage_flags, memory_location, true, debug_name)
   }
 
+  /// Create a GPU only buffer which can be written by the compute shader and read as the vertex buffer.
+  /// It is used for GPU skinning, the barrier between them is HalaBufferBarrierInfo::compute_write_to_vertex_read.
+  /// param logical_device: The logical device.
+  /// param size: The size of the buffer.
+  /// param debug_name: The debug name of the buffer.
+  /// return: The result.
+  pub fn new_storage_vertex_buffer(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    size: u64,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_impl(
+      logical_device,
+      size,
+      HalaBufferUsageFlags::STORAGE_BUFFER
+        | HalaBufferUsageFlags::VERTEX_BUFFER
+        | HalaBufferUsageFlags::TRANSFER_DST
+        | HalaBufferUsageFlags::SHADER_DEVICE_ADDRESS,
+      HalaMemoryLocation::GpuOnly,
+      false,
+      debug_name,
+    )
+  }
+
   /// Create a buffer.
   /// param logical_device: The logical device.
   /// param size: The size of the buffer.
@@ -192,11 +218,13 @@ impl HalaBuffer {
   /// return: The result.
   pub fn update_memory_raw(&self, offset: usize, data: *const u8, size: usize) -> Result<(), HalaGfxError> {
     if self.memory_location != gpu_allocator::MemoryLocation::GpuOnly {
+      self.check_mapped_range(offset, size)?;
       let src = data;
-      let src_bytes = size;
-      let dst = self.allocation.mapped_ptr().unwrap().as_ptr() as *mut u8;
-      let dst_bytes = self.size as usize;
-      unsafe { std::ptr::copy_nonoverlapping(src, dst.add(offset), std::cmp::min(src_bytes, dst_bytes)) };
+      let dst = self.allocation.mapped_ptr()
+        .ok_or(HalaGfxError::new("The buffer is not host visible.", None))?
+        .as_ptr() as *mut u8;
+      unsafe { std::ptr::copy_nonoverlapping(src, dst.add(offset), size) };
+      self.flush(offset as u64, size as u64)?;
     } else {
       return Err(HalaGfxError::new("Cannot update memory of a GPU only buffer.", None));
     }
@@ -243,7 +271,9 @@ impl HalaBuffer {
 
       let dst = staging_buffer.allocation.mapped_ptr().unwrap().as_ptr() as *mut u8;
       let dst_bytes = staging_buffer.size as usize;
-      unsafe { std::ptr::copy_nonoverlapping(src, dst, std::cmp::min(src_bytes, dst_bytes)) };
+      let copy_bytes = std::cmp::min(src_bytes, dst_bytes);
+      unsafe { std::ptr::copy_nonoverlapping(src, dst, copy_bytes) };
+      staging_buffer.flush(0, copy_bytes as u64)?;
 
       unsafe {
         let logical_device = self.logical_device.borrow();
@@ -313,11 +343,13 @@ impl HalaBuffer {
   /// return: The result.
   pub fn download_memory_raw(&self, offset: usize, data: *mut u8, size: usize) -> Result<(), HalaGfxError> {
     if self.memory_location != gpu_allocator::MemoryLocation::GpuOnly {
-      let src = self.allocation.mapped_ptr().unwrap().as_ptr() as *const u8;
-      let src_bytes = self.size as usize;
+      self.check_mapped_range(offset, size)?;
+      let src = self.allocation.mapped_ptr()
+        .ok_or(HalaGfxError::new("The buffer is not host visible.", None))?
+        .as_ptr() as *const u8;
       let dst = data;
-      let dst_bytes = size;
-      unsafe { std::ptr::copy_nonoverlapping(src.add(offset), dst, std::cmp::min(src_bytes, dst_bytes)) };
+      self.invalidate(offset as u64, size as u64)?;
+      unsafe { std::ptr::copy_nonoverlapping(src.add(offset), dst, size) };
     } else {
       return Err(HalaGfxError::new("Cannot download memory of a GPU only buffer.", None));
     }
@@ -382,7 +414,9 @@ impl HalaBuffer {
 
       let dst = data;
       let dst_bytes = size;
-      unsafe { std::ptr::copy_nonoverlapping(src, dst, std::cmp::min(src_bytes, dst_bytes)) };
+      let copy_bytes = std::cmp::min(src_bytes, dst_bytes);
+      staging_buffer.invalidate(0, copy_bytes as u64)?;
+      unsafe { std::ptr::copy_nonoverlapping(src, dst, copy_bytes) };
     } else {
       return Err(HalaGfxError::new("Cannot update GPU memory of a non GPU only buffer.", None));
     }
@@ -413,6 +447,92 @@ impl HalaBuffer {
     Ok(())
   }
 
+  /// Check if the memory of the buffer is host coherent.
+  /// The host writes to the non-coherent memory must be flushed and the device writes must be invalidated before the host reads.
+  /// return: True if the memory is host coherent.
+  pub fn is_host_coherent(&self) -> bool {
+    self.allocation.memory_properties().contains(vk::MemoryPropertyFlags::HOST_COHERENT)
+  }
+
+  /// Check the range is in the buffer before copying the mapped memory.
+  /// param offset: The offset in the buffer.
+  /// param size: The size of the range.
+  /// return: The result.
+  fn check_mapped_range(&self, offset: usize, size: usize) -> Result<(), HalaGfxError> {
+    if offset.checked_add(size).is_none_or(|end| end as u64 > self.size) {
+      return Err(HalaGfxError::new(
+        &format!("The range [{}, {}+{}) is out of the buffer \"{}\" with size {}.", offset, offset, size, self.debug_name, self.size),
+        None,
+      ));
+    }
+
+    Ok(())
+  }
+
+  /// Flush the host writes of the mapped memory range to make them visible to the device.
+  /// It does nothing if the memory is host coherent.
+  /// param offset: The offset in the buffer.
+  /// param size: The size of the range.
+  /// return: The result.
+  pub fn flush(&self, offset: u64, size: u64) -> Result<(), HalaGfxError> {
+    if let Some(range) = self.get_mapped_memory_range(offset, size)? {
+      unsafe {
+        self.logical_device.borrow().raw.flush_mapped_memory_ranges(std::slice::from_ref(&range))
+          .map_err(|err| HalaGfxError::new("Failed to flush buffer memory.", Some(Box::new(err))))?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Invalidate the mapped memory range to make the device writes visible to the host.
+  /// It does nothing if the memory is host coherent.
+  /// param offset: The offset in the buffer.
+  /// param size: The size of the range.
+  /// return: The result.
+  pub fn invalidate(&self, offset: u64, size: u64) -> Result<(), HalaGfxError> {
+    if let Some(range) = self.get_mapped_memory_range(offset, size)? {
+      unsafe {
+        self.logical_device.borrow().raw.invalidate_mapped_memory_ranges(std::slice::from_ref(&range))
+          .map_err(|err| HalaGfxError::new("Failed to invalidate buffer memory.", Some(Box::new(err))))?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Get the memory range of the buffer range which is aligned to the non-coherent atom size.
+  /// param offset: The offset in the buffer.
+  /// param size: The size of the range.
+  /// return: The memory range, None if the memory is host coherent or the range is empty.
+  fn get_mapped_memory_range(&self, offset: u64, size: u64) -> Result<Option<vk::MappedMemoryRange<'static>>, HalaGfxError> {
+    if self.memory_location == gpu_allocator::MemoryLocation::GpuOnly {
+      return Err(HalaGfxError::new("Cannot flush or invalidate memory of a GPU only buffer.", None));
+    }
+    if offset.saturating_add(size) > self.size {
+      return Err(HalaGfxError::new(
+        &format!("The range [{}, {}) exceeds the buffer size {}.", offset, offset.saturating_add(size), self.size),
+        None,
+      ));
+    }
+    if size == 0 || self.is_host_coherent() {
+      return Ok(None);
+    }
+
+    let atom_size = self.logical_device.borrow().limits.non_coherent_atom_size.max(1);
+    let allocation_begin = self.allocation.offset();
+    let allocation_end = allocation_begin + self.allocation.size();
+    let begin = (allocation_begin + offset) / atom_size * atom_size;
+    let end = (allocation_begin + offset + size).div_ceil(atom_size) * atom_size;
+    let range = vk::MappedMemoryRange::default()
+      .memory(unsafe { self.allocation.memory() })
+      .offset(begin)
+      // The aligned end may exceed the allocation at the end of the memory, flush to the end of the memory instead.
+      .size(if end > allocation_end { vk::WHOLE_SIZE } else { end - begin });
+
+    Ok(Some(range))
+  }
+
   /// Get the device address of the buffer.
   /// return: The device address.
   pub fn get_device_address(&self) -> u64 {
@@ -423,4 +543,23 @@ impl HalaBuffer {
       logical_device.raw.get_buffer_device_address(&buffer_device_address_info)
     }
   }
+
+  /// Set the debug name of the buffer.
+  /// The memory is also renamed if it is dedicated to the buffer, the shared memory block of the allocator is not renamed.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    logical_device.set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name of buffer.", Some(Box::new(err))))?;
+    if self.allocation.is_dedicated() {
+      unsafe {
+        logical_device.set_debug_name(self.allocation.memory(), debug_name)
+          .map_err(|err| HalaGfxError::new("Failed to set debug name of buffer memory.", Some(Box::new(err))))?;
+      }
+    }
+    drop(logical_device);
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
 }
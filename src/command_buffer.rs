@@ -4,7 +4,7 @@ use std::cell::RefCell;
 use ash::vk;
 
 use crate::{
-  HalaAttachmentLoadOp, HalaAttachmentStoreOp, HalaBuffer, HalaCommandPools, HalaFormat, HalaFrameBufferSet, HalaGfxError, HalaImage, HalaImageBarrierInfo, HalaImageLayout, HalaLogicalDevice, HalaPipelineStageFlags2, HalaQueryPool, HalaRenderPass, HalaResolveModeFlags, HalaSwapchain
+  HalaAttachmentLoadOp, HalaAttachmentStoreOp, HalaBuffer, HalaCommandPools, HalaFormat, HalaFrameBufferSet, HalaGfxError, HalaImage, HalaImageBarrierInfo, HalaImageLayout, HalaLogicalDevice, HalaPipelineStageFlags2, HalaQueryPool, HalaQueryResultFlags, HalaRenderPass, HalaResolveModeFlags, HalaSwapchain
 };
 
 pub type HalaIndirectDrawCommand = vk::DrawIndirectCommand;
@@ -214,6 +214,29 @@ impl std::convert::From<HalaSubpassContents> for vk::SubpassContents {
   }
 }
 
+/// The combiner operation of the fragment shading rates.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HalaFragmentShadingRateCombinerOp(i32);
+impl HalaFragmentShadingRateCombinerOp {
+  pub const KEEP: Self = Self(vk::FragmentShadingRateCombinerOpKHR::KEEP.as_raw());
+  pub const REPLACE: Self = Self(vk::FragmentShadingRateCombinerOpKHR::REPLACE.as_raw());
+  pub const MIN: Self = Self(vk::FragmentShadingRateCombinerOpKHR::MIN.as_raw());
+  pub const MAX: Self = Self(vk::FragmentShadingRateCombinerOpKHR::MAX.as_raw());
+  pub const MUL: Self = Self(vk::FragmentShadingRateCombinerOpKHR::MUL.as_raw());
+}
+
+impl std::convert::From<vk::FragmentShadingRateCombinerOpKHR> for HalaFragmentShadingRateCombinerOp {
+  fn from(op: vk::FragmentShadingRateCombinerOpKHR) -> Self {
+    Self(op.as_raw())
+  }
+}
+
+impl std::convert::From<HalaFragmentShadingRateCombinerOp> for vk::FragmentShadingRateCombinerOpKHR {
+  fn from(op: HalaFragmentShadingRateCombinerOp) -> Self {
+    vk::FragmentShadingRateCombinerOpKHR::from_raw(op.0)
+  }
+}
+
 /// The command buffer set.
 pub struct HalaCommandBufferSet {
   pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
@@ -366,6 +389,124 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Begin the query.
+  /// param index: The index of the command buffer.
+  /// param query_pool: The query pool.
+  /// param query: The query.
+  /// param precise: Whether the occlusion query is precise.
+  pub fn begin_query(&self, index: usize, query_pool: &HalaQueryPool, query: u32, precise: bool) {
+    assert!(
+      !precise || query_pool.query_type == crate::HalaQueryType::OCCLUSION,
+      "The precise flag is only valid for the occlusion query."
+    );
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_begin_query(
+        self.raw[index],
+        query_pool.raw,
+        query,
+        if precise { vk::QueryControlFlags::PRECISE } else { vk::QueryControlFlags::empty() },
+      );
+    }
+  }
+
+  /// End the query.
+  /// param index: The index of the command buffer.
+  /// param query_pool: The query pool.
+  /// param query: The query.
+  pub fn end_query(&self, index: usize, query_pool: &HalaQueryPool, query: u32) {
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_end_query(self.raw[index], query_pool.raw, query);
+    }
+  }
+
+  /// Begin the conditional rendering.
+  /// The following commands are discarded if the 32-bit value in the buffer is zero(or non-zero if inverted).
+  /// param index: The index of the command buffer.
+  /// param buffer: The buffer containing the predicate.
+  /// param offset: The offset of the predicate in the buffer, must be a multiple of 4.
+  /// param inverted: Whether to invert the condition.
+  /// return: The result.
+  pub fn begin_conditional_rendering(&self, index: usize, buffer: &HalaBuffer, offset: u64, inverted: bool) -> Result<(), HalaGfxError> {
+    assert!(offset.is_multiple_of(4), "The conditional rendering offset must be a multiple of 4.");
+    let logical_device = self.logical_device.borrow();
+    let conditional_rendering_loader = logical_device.conditional_rendering_loader.as_ref()
+      .ok_or(HalaGfxError::new("The conditional rendering extension is not supported.", None))?;
+    let begin_info = vk::ConditionalRenderingBeginInfoEXT::default()
+      .buffer(buffer.raw)
+      .offset(offset)
+      .flags(if inverted { vk::ConditionalRenderingFlagsEXT::INVERTED } else { vk::ConditionalRenderingFlagsEXT::empty() });
+    unsafe {
+      (conditional_rendering_loader.fp().cmd_begin_conditional_rendering_ext)(self.raw[index], &begin_info);
+    }
+
+    Ok(())
+  }
+
+  /// End the conditional rendering.
+  /// param index: The index of the command buffer.
+  /// return: The result.
+  pub fn end_conditional_rendering(&self, index: usize) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let conditional_rendering_loader = logical_device.conditional_rendering_loader.as_ref()
+      .ok_or(HalaGfxError::new("The conditional rendering extension is not supported.", None))?;
+    unsafe {
+      (conditional_rendering_loader.fp().cmd_end_conditional_rendering_ext)(self.raw[index]);
+    }
+
+    Ok(())
+  }
+
+  /// Copy the query pool results to the buffer.
+  /// param index: The index of the command buffer.
+  /// param query_pool: The query pool.
+  /// param first_query: The first query.
+  /// param query_count: The query count.
+  /// param dst_buffer: The destination buffer.
+  /// param dst_offset: The offset in the destination buffer.
+  /// param stride: The stride between the results of each query.
+  /// param flags: The query result flags.
+  /// return: The result.
+  #[allow(clippy::too_many_arguments)]
+  pub fn copy_query_pool_results(
+    &self,
+    index: usize,
+    query_pool: &HalaQueryPool,
+    first_query: u32,
+    query_count: u32,
+    dst_buffer: &HalaBuffer,
+    dst_offset: u64,
+    stride: u64,
+    flags: HalaQueryResultFlags,
+  ) -> Result<(), HalaGfxError> {
+    check_query_results_copy(
+      query_pool.size,
+      first_query,
+      query_count,
+      query_result_size(query_pool.values_per_query(flags.contains(HalaQueryResultFlags::WITH_AVAILABILITY)), flags),
+      dst_buffer.size,
+      dst_offset,
+      stride,
+    )?;
+
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_copy_query_pool_results(
+        self.raw[index],
+        query_pool.raw,
+        first_query,
+        query_count,
+        dst_buffer.raw,
+        dst_offset,
+        stride,
+        flags.into(),
+      );
+    }
+
+    Ok(())
+  }
+
   /// Begin the render pass.
   /// param index: The index of the command buffer.
   /// param render_pass: The render pass.
@@ -608,6 +749,8 @@ impl HalaCommandBufferSet {
   }
 
   /// Begin rendering with the specified render targets.
+  /// The attachments are cleared if the clear values are given, otherwise their contents are undefined.
+  /// The color, depth and stencil are stored, use begin_rendering_with_ex to specify the load and store operations.
   /// param index: The index of the command buffer.
   /// param color_images: The color images.
   /// param depth_image: The depth image.
@@ -638,12 +781,13 @@ impl HalaCommandBufferSet {
       depth_clear_value,
       stencil_clear_value,
       HalaAttachmentStoreOp::STORE,
-      HalaAttachmentStoreOp::DONT_CARE,
-      HalaAttachmentStoreOp::DONT_CARE,
+      HalaAttachmentStoreOp::STORE,
+      HalaAttachmentStoreOp::STORE,
     );
   }
 
   /// Begin rendering with the specified render targets.
+  /// The attachments are cleared if the clear values are given, otherwise their contents are undefined.
   /// param index: The index of the command buffer.
   /// param color_images: The color images.
   /// param depth_image: The depth image.
@@ -671,23 +815,11 @@ impl HalaCommandBufferSet {
   )
     where T: AsRef<HalaImage>
   {
-    let color_load_ops = color_clear_values.iter().map(|clear_value| {
-      if clear_value.is_some() {
-        HalaAttachmentLoadOp::CLEAR
-      } else {
-        HalaAttachmentLoadOp::DONT_CARE
-      }
-    }).collect::<Vec<_>>();
-    let depth_load_op = if depth_clear_value.is_some() {
-      HalaAttachmentLoadOp::CLEAR
-    } else {
-      HalaAttachmentLoadOp::DONT_CARE
-    };
-    let stencil_load_op = if stencil_clear_value.is_some() {
-      HalaAttachmentLoadOp::CLEAR
-    } else {
-      HalaAttachmentLoadOp::DONT_CARE
-    };
+    let (color_load_ops, depth_load_op, stencil_load_op) = Self::get_clear_load_ops(
+      color_clear_values,
+      depth_clear_value,
+      stencil_clear_value,
+    );
     let color_store_ops = vec![color_store_op; color_images.len()];
     self.begin_rendering_with_ex(
       index,
@@ -703,6 +835,81 @@ impl HalaCommandBufferSet {
       color_store_ops.as_slice(),
       depth_store_op,
       stencil_store_op,
+      1,
+      None,
+    )
+  }
+
+  /// Begin multiview rendering with the specified render targets.
+  /// The attachments are cleared if the clear values are given, otherwise their contents are undefined.
+  /// The color, depth and stencil are stored.
+  /// Each view is rendered to the array layer of the same index, so the images must have enough array layers.
+  /// param index: The index of the command buffer.
+  /// param color_images: The color images.
+  /// param depth_image: The depth image.
+  /// param render_area: The render area(x, y, width, height).
+  /// param color_clear_values: The color clear values.
+  /// param depth_clear_value: The depth clear value.
+  /// param stencil_clear_value: The stencil clear value.
+  /// param view_mask: The view mask, e.g. 0b11 for the stereo rendering.
+  #[allow(clippy::too_many_arguments)]
+  pub fn begin_rendering_with_rt_multiview<T>(
+    &self,
+    index: usize,
+    color_images: &[T],
+    depth_image: Option<T>,
+    render_area: (i32, i32, u32, u32),
+    color_clear_values: &[Option<[f32; 4]>],
+    depth_clear_value: Option<f32>,
+    stencil_clear_value: Option<u32>,
+    view_mask: u32,
+  )
+    where T: AsRef<HalaImage>
+  {
+    let (color_load_ops, depth_load_op, stencil_load_op) = Self::get_clear_load_ops(
+      color_clear_values,
+      depth_clear_value,
+      stencil_clear_value,
+    );
+    let color_store_ops = vec![HalaAttachmentStoreOp::STORE; color_images.len()];
+    self.begin_rendering_with_ex(
+      index,
+      color_images,
+      depth_image,
+      render_area,
+      color_clear_values,
+      depth_clear_value,
+      stencil_clear_value,
+      color_load_ops.as_slice(),
+      depth_load_op,
+      stencil_load_op,
+      color_store_ops.as_slice(),
+      HalaAttachmentStoreOp::STORE,
+      HalaAttachmentStoreOp::STORE,
+      1,
+      Some(view_mask),
+    )
+  }
+
+  /// Get the load operations which clear the attachments with the clear values.
+  /// param color_clear_values: The color clear values.
+  /// param depth_clear_value: The depth clear value.
+  /// param stencil_clear_value: The stencil clear value.
+  /// return: The color, depth and stencil load operations.
+  fn get_clear_load_ops(
+    color_clear_values: &[Option<[f32; 4]>],
+    depth_clear_value: Option<f32>,
+    stencil_clear_value: Option<u32>,
+  ) -> (Vec<HalaAttachmentLoadOp>, HalaAttachmentLoadOp, HalaAttachmentLoadOp) {
+    let to_load_op = |is_clear: bool| if is_clear {
+      HalaAttachmentLoadOp::CLEAR
+    } else {
+      HalaAttachmentLoadOp::DONT_CARE
+    };
+    (
+      color_clear_values.iter().map(|clear_value| to_load_op(clear_value.is_some())).collect(),
+      to_load_op(depth_clear_value.is_some()),
+      to_load_op(stencil_clear_value.is_some()),
     )
   }
 
@@ -720,6 +927,8 @@ impl HalaCommandBufferSet {
   /// param color_store_ops: The color store operations.
   /// param depth_store_op: The depth store operation.
   /// param stencil_store_op: The stencil store operation.
+  /// param layer_count: The number of the layers to render, it is ignored if the view mask is given.
+  /// param view_mask: The view mask of the multiview rendering.
   #[allow(clippy::too_many_arguments)]
   pub fn begin_rendering_with_ex<T>(
     &self,
@@ -736,13 +945,143 @@ impl HalaCommandBufferSet {
     color_store_ops: &[HalaAttachmentStoreOp],
     depth_store_op: HalaAttachmentStoreOp,
     stencil_store_op: HalaAttachmentStoreOp,
+    layer_count: u32,
+    view_mask: Option<u32>,
+  )
+    where T: AsRef<HalaImage>
+  {
+    self.begin_rendering_with_ex_impl(
+      index,
+      color_images,
+      depth_image,
+      render_area,
+      color_clear_values,
+      depth_clear_value,
+      stencil_clear_value,
+      color_load_ops,
+      depth_load_op,
+      stencil_load_op,
+      color_store_ops,
+      depth_store_op,
+      stencil_store_op,
+      layer_count,
+      view_mask,
+      None,
+    )
+  }
+
+  /// Begin rendering with the specified render targets and the fragment shading rate attachment.
+  /// The attachments are cleared if the clear values are given, otherwise their contents are undefined.
+  /// The color, depth and stencil are stored.
+  /// The pipelines must be created with HalaPipelineCreateFlags::RENDERING_FRAGMENT_SHADING_RATE_ATTACHMENT,
+  /// and the shading rate image must be in the FRAGMENT_SHADING_RATE_ATTACHMENT_OPTIMAL layout.
+  /// param index: The index of the command buffer.
+  /// param color_images: The color images.
+  /// param depth_image: The depth image.
+  /// param render_area: The render area(x, y, width, height).
+  /// param color_clear_values: The color clear values.
+  /// param depth_clear_value: The depth clear value.
+  /// param stencil_clear_value: The stencil clear value.
+  /// param shading_rate_image: The R8_UINT image which holds the shading rate of each texel.
+  /// param shading_rate_texel_size: The size(width, height) of the framebuffer area covered by a texel of the shading rate image.
+  /// return: The result.
+  #[allow(clippy::too_many_arguments)]
+  pub fn begin_rendering_with_shading_rate<T>(
+    &self,
+    index: usize,
+    color_images: &[T],
+    depth_image: Option<T>,
+    render_area: (i32, i32, u32, u32),
+    color_clear_values: &[Option<[f32; 4]>],
+    depth_clear_value: Option<f32>,
+    stencil_clear_value: Option<u32>,
+    shading_rate_image: &HalaImage,
+    shading_rate_texel_size: (u32, u32),
+  ) -> Result<(), HalaGfxError>
+    where T: AsRef<HalaImage>
+  {
+    {
+      let logical_device = self.logical_device.borrow();
+      if !logical_device.attachment_fragment_shading_rate_supported {
+        return Err(HalaGfxError::new("The attachment fragment shading rate feature is not supported.", None));
+      }
+      let min_size = logical_device.min_fragment_shading_rate_attachment_texel_size;
+      let max_size = logical_device.max_fragment_shading_rate_attachment_texel_size;
+      let (width, height) = shading_rate_texel_size;
+      if !width.is_power_of_two() || !height.is_power_of_two()
+        || width < min_size.width || width > max_size.width
+        || height < min_size.height || height > max_size.height
+      {
+        return Err(HalaGfxError::new(
+          &format!(
+            "The shading rate texel size {}x{} is not a power of two in [{}x{}, {}x{}].",
+            width, height, min_size.width, min_size.height, max_size.width, max_size.height,
+          ),
+          None,
+        ));
+      }
+    }
+
+    let (color_load_ops, depth_load_op, stencil_load_op) = Self::get_clear_load_ops(
+      color_clear_values,
+      depth_clear_value,
+      stencil_clear_value,
+    );
+    let color_store_ops = vec![HalaAttachmentStoreOp::STORE; color_images.len()];
+    self.begin_rendering_with_ex_impl(
+      index,
+      color_images,
+      depth_image,
+      render_area,
+      color_clear_values,
+      depth_clear_value,
+      stencil_clear_value,
+      color_load_ops.as_slice(),
+      depth_load_op,
+      stencil_load_op,
+      color_store_ops.as_slice(),
+      HalaAttachmentStoreOp::STORE,
+      HalaAttachmentStoreOp::STORE,
+      1,
+      None,
+      Some((shading_rate_image.view, shading_rate_texel_size)),
+    );
+
+    Ok(())
+  }
+
+  /// Begin rendering with the specified render targets and the optional fragment shading rate attachment.
+  /// The other parameters are the same as begin_rendering_with_ex.
+  /// param fragment_shading_rate_attachment: The view of the shading rate image and its texel size.
+  #[allow(clippy::too_many_arguments)]
+  fn begin_rendering_with_ex_impl<T>(
+    &self,
+    index: usize,
+    color_images: &[T],
+    depth_image: Option<T>,
+    render_area: (i32, i32, u32, u32),
+    color_clear_values: &[Option<[f32; 4]>],
+    depth_clear_value: Option<f32>,
+    stencil_clear_value: Option<u32>,
+    color_load_ops: &[HalaAttachmentLoadOp],
+    depth_load_op: HalaAttachmentLoadOp,
+    stencil_load_op: HalaAttachmentLoadOp,
+    color_store_ops: &[HalaAttachmentStoreOp],
+    depth_store_op: HalaAttachmentStoreOp,
+    stencil_store_op: HalaAttachmentStoreOp,
+    layer_count: u32,
+    view_mask: Option<u32>,
+    fragment_shading_rate_attachment: Option<(vk::ImageView, (u32, u32))>,
   )
     where T: AsRef<HalaImage>
   {
     assert!(color_images.len() == color_clear_values.len() && color_images.len() == color_load_ops.len() && color_images.len() == color_store_ops.len());
+    if let Some(view_mask) = view_mask {
+      self.check_view_mask(view_mask);
+    }
 
     let has_depth = depth_image.is_some();
-    let has_stencil = depth_image.as_ref().map_or(false, |image| image.as_ref().format == HalaFormat::D16_UNORM_S8_UINT || image.as_ref().format == HalaFormat::D24_UNORM_S8_UINT || image.as_ref().format == HalaFormat::D32_SFLOAT_S8_UINT);
+    let has_stencil = depth_image.as_ref().is_some_and(|image| image.as_ref().format.has_stencil());
 
     let color_attachment_info = color_images.iter().zip(color_clear_values).zip(color_load_ops).zip(color_store_ops).map(|(((image, clear_value), load_op), store_op)| {
       vk::RenderingAttachmentInfo::default()
@@ -785,7 +1124,8 @@ impl HalaCommandBufferSet {
         offset: vk::Offset2D { x: render_area.0, y: render_area.1 },
         extent: vk::Extent2D { width: render_area.2, height: render_area.3 },
       })
-      .layer_count(1)
+      .layer_count(layer_count)
+      .view_mask(view_mask.unwrap_or(0))
       .color_attachments(color_attachment_info.as_slice());
     let rendering_info = if has_depth {
       rendering_info.depth_attachment(&depth_attachment_info)
@@ -797,6 +1137,17 @@ impl HalaCommandBufferSet {
     } else {
       rendering_info
     };
+    let mut fragment_shading_rate_info = fragment_shading_rate_attachment.map(|(image_view, (width, height))| {
+      vk::RenderingFragmentShadingRateAttachmentInfoKHR::default()
+        .image_view(image_view)
+        .image_layout(vk::ImageLayout::FRAGMENT_SHADING_RATE_ATTACHMENT_OPTIMAL_KHR)
+        .shading_rate_attachment_texel_size(vk::Extent2D { width, height })
+    });
+    let rendering_info = if let Some(fragment_shading_rate_info) = fragment_shading_rate_info.as_mut() {
+      rendering_info.push_next(fragment_shading_rate_info)
+    } else {
+      rendering_info
+    };
 
     unsafe {
       let logical_device = self.logical_device.borrow();
@@ -818,6 +1169,8 @@ impl HalaCommandBufferSet {
   /// param color_store_ops: The color store operations.
   /// param depth_store_op: The depth store operation.
   /// param stencil_store_op: The stencil store operation.
+  /// param layer_count: The number of the layers to render, it is ignored if the view mask is given.
+  /// param view_mask: The view mask of the multiview rendering.
   #[allow(clippy::too_many_arguments)]
   pub fn begin_rendering_with_view_ex(
     &self,
@@ -832,8 +1185,13 @@ impl HalaCommandBufferSet {
     depth_load_op: HalaAttachmentLoadOp,
     color_store_ops: &[HalaAttachmentStoreOp],
     depth_store_op: HalaAttachmentStoreOp,
+    layer_count: u32,
+    view_mask: Option<u32>,
   ) {
     assert!(color_views.len() == color_clear_values.len() && color_views.len() == color_load_ops.len() && color_views.len() == color_store_ops.len());
+    if let Some(view_mask) = view_mask {
+      self.check_view_mask(view_mask);
+    }
 
     let has_depth = depth_view.is_some();
 
@@ -867,7 +1225,8 @@ impl HalaCommandBufferSet {
         offset: vk::Offset2D { x: render_area.0, y: render_area.1 },
         extent: vk::Extent2D { width: render_area.2, height: render_area.3 },
       })
-      .layer_count(1)
+      .layer_count(layer_count)
+      .view_mask(view_mask.unwrap_or(0))
       .color_attachments(color_attachment_info.as_slice());
     let rendering_info = if has_depth {
       rendering_info.depth_attachment(&depth_attachment_info)
@@ -967,7 +1326,7 @@ impl HalaCommandBufferSet {
     assert!(depth_image.is_some() == depth_stencil_multisample_image.is_some());
 
     let has_depth = depth_image.is_some();
-    let has_stencil = depth_image.as_ref().map_or(false, |image| image.as_ref().format == HalaFormat::D16_UNORM_S8_UINT || image.as_ref().format == HalaFormat::D24_UNORM_S8_UINT || image.as_ref().format == HalaFormat::D32_SFLOAT_S8_UINT);
+    let has_stencil = depth_image.as_ref().is_some_and(|image| image.as_ref().format.has_stencil());
 
     let color_attachment_info = color_images.iter().zip(color_multisample_images).zip(color_clear_values).map(|((image, multisample_image), clear_value)| {
       vk::RenderingAttachmentInfo::default()
@@ -1039,33 +1398,209 @@ impl HalaCommandBufferSet {
     }
   }
 
-  /// End rendering.
-  /// param index: The index of the command buffer.
-  pub fn end_rendering(&self, index: usize) {
-    let logical_device = self.logical_device.borrow();
-    unsafe {
-      logical_device.raw.cmd_end_rendering(self.raw[index]);
-    }
-  }
-
-  /// Set the viewports.
+  /// Begin rendering with the specified multisample render targets which resolve to the optional resolve images.
+  /// The color attachments without a resolve image are not resolved.
   /// param index: The index of the command buffer.
-  /// param first_viewport: The first viewport.
-  /// param viewports: The viewports(x, y, width, height, min_depth, max_depth).
-  pub fn set_viewport(
+  /// param color_images: The color multisample images.
+  /// param depth_image: The depth stencil multisample image.
+  /// param render_area: The render area(x, y, width, height).
+  /// param color_clear_values: The color clear values.
+  /// param depth_clear_value: The depth clear value.
+  /// param stencil_clear_value: The stencil clear value.
+  /// param color_store_op: The color store operation of the multisample images.
+  /// param depth_store_op: The depth store operation of the multisample image.
+  /// param stencil_store_op: The stencil store operation of the multisample image.
+  /// param color_resolve_images: The color resolve images, one per color image.
+  /// param color_resolve_mode: The color resolve mode.
+  /// param depth_resolve_image: The depth stencil resolve image.
+  /// param depth_resolve_mode: The depth resolve mode, it must be one of the supported depth resolve modes.
+  /// param stencil_resolve_mode: The stencil resolve mode, it must be one of the supported stencil resolve modes.
+  /// return: The result.
+  #[allow(clippy::too_many_arguments)]
+  pub fn begin_rendering_with_resolve<T>(
     &self,
     index: usize,
-    first_viewport: u32,
-    viewports: &[(f32, f32, f32, f32, f32, f32)],
-  ) {
-    let logical_device = self.logical_device.borrow();
-    let viewports = viewports.iter().map(|(x, y, width, height, min_depth, max_depth)| {
-      vk::Viewport {
-        x: *x,
-        y: *y,
-        width: *width,
-        height: *height,
-        min_depth: *min_depth,
+    color_images: &[T],
+    depth_image: Option<T>,
+    render_area: (i32, i32, u32, u32),
+    color_clear_values: &[Option<[f32; 4]>],
+    depth_clear_value: Option<f32>,
+    stencil_clear_value: Option<u32>,
+    color_store_op: HalaAttachmentStoreOp,
+    depth_store_op: HalaAttachmentStoreOp,
+    stencil_store_op: HalaAttachmentStoreOp,
+    color_resolve_images: &[Option<T>],
+    color_resolve_mode: HalaResolveModeFlags,
+    depth_resolve_image: Option<T>,
+    depth_resolve_mode: HalaResolveModeFlags,
+    stencil_resolve_mode: HalaResolveModeFlags,
+  ) -> Result<(), HalaGfxError>
+    where T: AsRef<HalaImage>
+  {
+    if color_images.len() != color_clear_values.len() || color_images.len() != color_resolve_images.len() {
+      return Err(HalaGfxError::new("The count of the color images, clear values and resolve images must be the same.", None));
+    }
+    if depth_image.is_none() && depth_resolve_image.is_some() {
+      return Err(HalaGfxError::new("The depth resolve image requires a depth image.", None));
+    }
+
+    let has_depth = depth_image.is_some();
+    let has_stencil = depth_image.as_ref().is_some_and(|image| image.as_ref().format.has_stencil());
+
+    let (depth_resolve_mode, stencil_resolve_mode) = if depth_resolve_image.is_some() {
+      let depth_resolve_mode = vk::ResolveModeFlags::from(depth_resolve_mode);
+      let stencil_resolve_mode = if has_stencil {
+        vk::ResolveModeFlags::from(stencil_resolve_mode)
+      } else {
+        vk::ResolveModeFlags::NONE
+      };
+      let logical_device = self.logical_device.borrow();
+      if depth_resolve_mode != vk::ResolveModeFlags::NONE
+        && !logical_device.supported_depth_resolve_modes.contains(depth_resolve_mode)
+      {
+        return Err(HalaGfxError::new(&format!("The depth resolve mode {:?} is not supported.", depth_resolve_mode), None));
+      }
+      if stencil_resolve_mode != vk::ResolveModeFlags::NONE
+        && !logical_device.supported_stencil_resolve_modes.contains(stencil_resolve_mode)
+      {
+        return Err(HalaGfxError::new(&format!("The stencil resolve mode {:?} is not supported.", stencil_resolve_mode), None));
+      }
+      // Without the independent resolve, the modes must be identical, or one of them is NONE with the independent resolve none.
+      if has_stencil && !logical_device.independent_resolve && depth_resolve_mode != stencil_resolve_mode {
+        let is_one_none = depth_resolve_mode == vk::ResolveModeFlags::NONE || stencil_resolve_mode == vk::ResolveModeFlags::NONE;
+        if !(logical_device.independent_resolve_none && is_one_none) {
+          return Err(HalaGfxError::new(
+            &format!(
+              "The depth resolve mode {:?} and the stencil resolve mode {:?} must be identical without the independent resolve.",
+              depth_resolve_mode, stencil_resolve_mode,
+            ),
+            None,
+          ));
+        }
+      }
+      (depth_resolve_mode, stencil_resolve_mode)
+    } else {
+      (vk::ResolveModeFlags::NONE, vk::ResolveModeFlags::NONE)
+    };
+
+    let color_attachment_info = color_images.iter().zip(color_resolve_images).zip(color_clear_values).map(|((image, resolve_image), clear_value)| {
+      let attachment_info = vk::RenderingAttachmentInfo::default()
+        .image_view(image.as_ref().view)
+        .image_layout(vk::ImageLayout::ATTACHMENT_OPTIMAL)
+        .load_op(if clear_value.is_some() { vk::AttachmentLoadOp::CLEAR } else { vk::AttachmentLoadOp::DONT_CARE })
+        .store_op(color_store_op.into())
+        .clear_value(vk::ClearValue {
+          color: vk::ClearColorValue {
+            float32: clear_value.unwrap_or([0f32; 4]),
+          },
+        });
+      if let Some(resolve_image) = resolve_image {
+        attachment_info
+          .resolve_image_view(resolve_image.as_ref().view)
+          .resolve_image_layout(vk::ImageLayout::ATTACHMENT_OPTIMAL)
+          .resolve_mode(color_resolve_mode.into())
+      } else {
+        attachment_info
+      }
+    }).collect::<Vec<_>>();
+    let depth_image_view = depth_image.as_ref().map_or(vk::ImageView::null(), |image| image.as_ref().view);
+    let depth_resolve_image_view = depth_resolve_image.as_ref().map_or(vk::ImageView::null(), |image| image.as_ref().view);
+    let depth_attachment_info = vk::RenderingAttachmentInfo::default()
+      .resolve_image_view(depth_resolve_image_view)
+      .resolve_image_layout(vk::ImageLayout::ATTACHMENT_OPTIMAL)
+      .resolve_mode(depth_resolve_mode)
+      .image_view(depth_image_view)
+      .image_layout(vk::ImageLayout::ATTACHMENT_OPTIMAL)
+      .load_op(if depth_clear_value.is_some() { vk::AttachmentLoadOp::CLEAR } else { vk::AttachmentLoadOp::DONT_CARE })
+      .store_op(depth_store_op.into())
+      .clear_value(vk::ClearValue {
+        depth_stencil: vk::ClearDepthStencilValue {
+          depth: depth_clear_value.unwrap_or(1.0),
+          stencil: stencil_clear_value.unwrap_or(0),
+        },
+      });
+    let stencil_attachment_info = vk::RenderingAttachmentInfo::default()
+      .resolve_image_view(depth_resolve_image_view)
+      .resolve_image_layout(vk::ImageLayout::ATTACHMENT_OPTIMAL)
+      .resolve_mode(stencil_resolve_mode)
+      .image_view(depth_image_view)
+      .image_layout(vk::ImageLayout::ATTACHMENT_OPTIMAL)
+      .load_op(if stencil_clear_value.is_some() { vk::AttachmentLoadOp::CLEAR } else { vk::AttachmentLoadOp::DONT_CARE })
+      .store_op(stencil_store_op.into())
+      .clear_value(vk::ClearValue {
+        depth_stencil: vk::ClearDepthStencilValue {
+          depth: depth_clear_value.unwrap_or(1.0),
+          stencil: stencil_clear_value.unwrap_or(0),
+        },
+      });
+
+    let rendering_info = vk::RenderingInfo::default()
+      .render_area(vk::Rect2D {
+        offset: vk::Offset2D { x: render_area.0, y: render_area.1 },
+        extent: vk::Extent2D { width: render_area.2, height: render_area.3 },
+      })
+      .layer_count(1)
+      .color_attachments(color_attachment_info.as_slice());
+    let rendering_info = if has_depth {
+      rendering_info.depth_attachment(&depth_attachment_info)
+    } else {
+      rendering_info
+    };
+    let rendering_info = if has_stencil {
+      rendering_info.stencil_attachment(&stencil_attachment_info)
+    } else {
+      rendering_info
+    };
+
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.cmd_begin_rendering(self.raw[index], &rendering_info);
+    }
+
+    Ok(())
+  }
+
+  /// Check the view mask of the multiview rendering.
+  /// param view_mask: The view mask.
+  fn check_view_mask(&self, view_mask: u32) {
+    let logical_device = self.logical_device.borrow();
+    assert!(logical_device.multiview_enabled, "The multiview feature is not enabled.");
+    // The index of the most significant bit of the view mask must be less than the max multiview view count.
+    assert!(
+      32 - view_mask.leading_zeros() <= logical_device.max_multiview_view_count,
+      "The view mask {:#b} exceeds the max multiview view count {}.",
+      view_mask,
+      logical_device.max_multiview_view_count,
+    );
+  }
+
+  /// End rendering.
+  /// param index: The index of the command buffer.
+  pub fn end_rendering(&self, index: usize) {
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_end_rendering(self.raw[index]);
+    }
+  }
+
+  /// Set the viewports.
+  /// param index: The index of the command buffer.
+  /// param first_viewport: The first viewport.
+  /// param viewports: The viewports(x, y, width, height, min_depth, max_depth).
+  pub fn set_viewport(
+    &self,
+    index: usize,
+    first_viewport: u32,
+    viewports: &[(f32, f32, f32, f32, f32, f32)],
+  ) {
+    let logical_device = self.logical_device.borrow();
+    let viewports = viewports.iter().map(|(x, y, width, height, min_depth, max_depth)| {
+      vk::Viewport {
+        x: *x,
+        y: *y,
+        width: *width,
+        height: *height,
+        min_depth: *min_depth,
         max_depth: *max_depth,
       }
     }).collect::<Vec<_>>();
@@ -1096,6 +1631,242 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Set the line stipple.
+  /// The pipeline must use the LINE_STIPPLE_EXT dynamic state and enable the stippled line rasterization.
+  /// param index: The index of the command buffer.
+  /// param stipple_factor: The repeat factor of each bit in the pattern, it is in [1, 256].
+  /// param stipple_pattern: The bit pattern, the lowest bit is used first.
+  /// return: The result.
+  pub fn set_line_stipple(&self, index: usize, stipple_factor: u32, stipple_pattern: u16) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let line_rasterization_loader = logical_device.line_rasterization_loader.as_ref()
+      .ok_or(HalaGfxError::new("The line rasterization extension is not supported.", None))?;
+    if !(1..=256).contains(&stipple_factor) {
+      return Err(HalaGfxError::new(&format!("The line stipple factor {} is not in [1, 256].", stipple_factor), None));
+    }
+    unsafe {
+      (line_rasterization_loader.fp().cmd_set_line_stipple_ext)(self.raw[index], stipple_factor, stipple_pattern);
+    }
+
+    Ok(())
+  }
+
+  /// Set the pipeline fragment shading rate.
+  /// The pipeline must use the FRAGMENT_SHADING_RATE_KHR dynamic state.
+  /// param index: The index of the command buffer.
+  /// param fragment_size: The fragment size(width, height), each of them is 1, 2 or 4.
+  /// param combiner_ops: The combiner operations with the primitive rate and the attachment rate.
+  /// return: The result.
+  pub fn set_fragment_shading_rate(
+    &self,
+    index: usize,
+    fragment_size: (u32, u32),
+    combiner_ops: [HalaFragmentShadingRateCombinerOp; 2],
+  ) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let fragment_shading_rate_loader = logical_device.fragment_shading_rate_loader.as_ref()
+      .ok_or(HalaGfxError::new("The fragment shading rate extension is not enabled.", None))?;
+    let is_valid_size = |size: u32| size == 1 || size == 2 || size == 4;
+    if !is_valid_size(fragment_size.0) || !is_valid_size(fragment_size.1) {
+      return Err(HalaGfxError::new(
+        &format!("The fragment size {:?} is invalid, each of them must be 1, 2 or 4.", fragment_size),
+        None,
+      ));
+    }
+    unsafe {
+      (fragment_shading_rate_loader.fp().cmd_set_fragment_shading_rate_khr)(
+        self.raw[index],
+        &vk::Extent2D { width: fragment_size.0, height: fragment_size.1 },
+        &[combiner_ops[0].into(), combiner_ops[1].into()],
+      );
+    }
+
+    Ok(())
+  }
+
+  /// Set the viewports and the viewport count.
+  /// The pipeline must use the VIEWPORT_WITH_COUNT_EXT dynamic state.
+  /// param index: The index of the command buffer.
+  /// param viewports: The viewports(x, y, width, height, min_depth, max_depth).
+  /// return: The result.
+  pub fn set_viewport_with_count(
+    &self,
+    index: usize,
+    viewports: &[(f32, f32, f32, f32, f32, f32)],
+  ) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let extended_dynamic_state_loader = logical_device.extended_dynamic_state_loader.as_ref()
+      .ok_or(HalaGfxError::new("The extended dynamic state extension is not supported.", None))?;
+    let viewports = viewports.iter().map(|(x, y, width, height, min_depth, max_depth)| {
+      vk::Viewport {
+        x: *x,
+        y: *y,
+        width: *width,
+        height: *height,
+        min_depth: *min_depth,
+        max_depth: *max_depth,
+      }
+    }).collect::<Vec<_>>();
+    unsafe {
+      extended_dynamic_state_loader.cmd_set_viewport_with_count(self.raw[index], viewports.as_slice());
+    }
+
+    Ok(())
+  }
+
+  /// Set the scissors and the scissor count.
+  /// The pipeline must use the SCISSOR_WITH_COUNT_EXT dynamic state.
+  /// param index: The index of the command buffer.
+  /// param scissors: The scissors(x, y, width, height).
+  /// return: The result.
+  pub fn set_scissor_with_count(
+    &self,
+    index: usize,
+    scissors: &[(i32, i32, u32, u32)],
+  ) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let extended_dynamic_state_loader = logical_device.extended_dynamic_state_loader.as_ref()
+      .ok_or(HalaGfxError::new("The extended dynamic state extension is not supported.", None))?;
+    let scissors = scissors.iter().map(|(x, y, width, height)| {
+      vk::Rect2D {
+        offset: vk::Offset2D { x: *x, y: *y },
+        extent: vk::Extent2D { width: *width, height: *height },
+      }
+    }).collect::<Vec<_>>();
+    unsafe {
+      extended_dynamic_state_loader.cmd_set_scissor_with_count(self.raw[index], scissors.as_slice());
+    }
+
+    Ok(())
+  }
+
+  /// Set the line width.
+  /// The pipeline must use the LINE_WIDTH dynamic state.
+  /// param index: The index of the command buffer.
+  /// param line_width: The line width.
+  pub fn set_line_width(&self, index: usize, line_width: f32) {
+    let logical_device = self.logical_device.borrow();
+    debug_assert!(
+      line_width == 1.0 || logical_device.physical_device_features.wide_lines == vk::TRUE,
+      "The line width {} is not 1.0, but the wide lines feature is not supported.",
+      line_width,
+    );
+    unsafe {
+      logical_device.raw.cmd_set_line_width(self.raw[index], line_width)
+    }
+  }
+
+  /// Set the blend constants.
+  /// The pipeline must use the BLEND_CONSTANTS dynamic state.
+  /// param index: The index of the command buffer.
+  /// param blend_constants: The blend constants(r, g, b, a).
+  pub fn set_blend_constants(&self, index: usize, blend_constants: [f32; 4]) {
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_set_blend_constants(self.raw[index], &blend_constants)
+    }
+  }
+
+  /// Set the cull mode.
+  /// The pipeline must use the CULL_MODE_EXT dynamic state.
+  /// param index: The index of the command buffer.
+  /// param cull_mode: The cull mode.
+  pub fn set_cull_mode(&self, index: usize, cull_mode: crate::HalaCullModeFlags) {
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_set_cull_mode(self.raw[index], cull_mode.into())
+    }
+  }
+
+  /// Set the front face.
+  /// The pipeline must use the FRONT_FACE_EXT dynamic state.
+  /// param index: The index of the command buffer.
+  /// param front_face: The front face.
+  pub fn set_front_face(&self, index: usize, front_face: crate::HalaFrontFace) {
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_set_front_face(self.raw[index], front_face.into())
+    }
+  }
+
+  /// Set the primitive topology.
+  /// The pipeline must use the PRIMITIVE_TOPOLOGY_EXT dynamic state,
+  /// and the topology must be in the same topology class as the pipeline.
+  /// param index: The index of the command buffer.
+  /// param primitive_topology: The primitive topology.
+  pub fn set_primitive_topology(&self, index: usize, primitive_topology: crate::HalaPrimitiveTopology) {
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_set_primitive_topology(self.raw[index], primitive_topology.into())
+    }
+  }
+
+  /// Set the vertex input bindings and attributes.
+  /// The pipeline must use the VERTEX_INPUT_EXT dynamic state, so it can be used with the meshes of the different vertex layouts.
+  /// param index: The index of the command buffer.
+  /// param bindings: The vertex input binding descriptions.
+  /// param attributes: The vertex input attribute descriptions.
+  /// return: The result.
+  pub fn set_vertex_input(
+    &self,
+    index: usize,
+    bindings: &[crate::HalaVertexInputBindingDescription2],
+    attributes: &[crate::HalaVertexInputAttributeDescription2],
+  ) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let vertex_input_dynamic_state_loader = logical_device.vertex_input_dynamic_state_loader.as_ref()
+      .ok_or(HalaGfxError::new("The vertex input dynamic state feature is not supported.", None))?;
+    let bindings = bindings.iter().map(|binding| binding.into()).collect::<Vec<_>>();
+    let attributes = attributes.iter().map(|attribute| attribute.into()).collect::<Vec<_>>();
+    unsafe {
+      vertex_input_dynamic_state_loader.cmd_set_vertex_input(self.raw[index], &bindings, &attributes);
+    }
+
+    Ok(())
+  }
+
+  /// Enable the depth bias.
+  /// The pipeline must use the DEPTH_BIAS_ENABLE_EXT dynamic state.
+  /// param index: The index of the command buffer.
+  /// param enable: Whether to enable the depth bias.
+  pub fn set_depth_bias_enable(&self, index: usize, enable: bool) {
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      logical_device.raw.cmd_set_depth_bias_enable(self.raw[index], enable)
+    }
+  }
+
+  /// Set the depth bounds.
+  /// The pipeline must use the DEPTH_BOUNDS dynamic state.
+  /// param index: The index of the command buffer.
+  /// param min_depth_bounds: The min depth bounds.
+  /// param max_depth_bounds: The max depth bounds.
+  pub fn set_depth_bounds(&self, index: usize, min_depth_bounds: f32, max_depth_bounds: f32) {
+    let logical_device = self.logical_device.borrow();
+    debug_assert!(
+      logical_device.physical_device_features.depth_bounds == vk::TRUE,
+      "The depth bounds feature is not supported.",
+    );
+    unsafe {
+      logical_device.raw.cmd_set_depth_bounds(self.raw[index], min_depth_bounds, max_depth_bounds)
+    }
+  }
+
+  /// Enable the depth bounds test.
+  /// The pipeline must use the DEPTH_BOUNDS_TEST_ENABLE_EXT dynamic state.
+  /// param index: The index of the command buffer.
+  /// param enable: Whether to enable the depth bounds test.
+  pub fn set_depth_bounds_test_enable(&self, index: usize, enable: bool) {
+    let logical_device = self.logical_device.borrow();
+    debug_assert!(
+      !enable || logical_device.physical_device_features.depth_bounds == vk::TRUE,
+      "The depth bounds feature is not supported.",
+    );
+    unsafe {
+      logical_device.raw.cmd_set_depth_bounds_test_enable(self.raw[index], enable)
+    }
+  }
+
   /// Enable the depth test.
   /// param index: The index of the command buffer.
   /// param enable: Whether to enable the depth test.
@@ -1213,6 +1984,11 @@ impl HalaCommandBufferSet {
     data: &[u8],
   ) {
     let logical_device = self.logical_device.borrow();
+    debug_assert!(
+      offset as usize + data.len() <= logical_device.limits.max_push_constants_size as usize,
+      "The push constants range [{}, {}) exceeds the max push constants size {}.",
+      offset, offset as usize + data.len(), logical_device.limits.max_push_constants_size,
+    );
     unsafe {
       logical_device.raw.cmd_push_constants(
         self.raw[index],
@@ -1224,6 +2000,45 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Push constants from a plain struct.
+  /// param index: The index of the command buffer.
+  /// param pipeline_layout: The pipeline layout.
+  /// param shader_stage: The shader stage.
+  /// param offset: The offset in bytes.
+  /// param value: The struct value.
+  #[cfg(feature = "bytemuck")]
+  pub fn push_constants_struct<T: bytemuck::Pod>(
+    &self,
+    index: usize,
+    pipeline_layout: vk::PipelineLayout,
+    shader_stage: crate::HalaShaderStageFlags,
+    offset: u32,
+    value: &T,
+  ) {
+    self.push_constants(index, pipeline_layout, shader_stage, offset, bytemuck::bytes_of(value));
+  }
+
+  /// Push constants from a struct without checking its layout.
+  /// # Safety
+  /// T must be #[repr(C)] and must not contain any padding bytes,
+  /// and its layout must match the push constant block in the shader.
+  /// param index: The index of the command buffer.
+  /// param pipeline_layout: The pipeline layout.
+  /// param shader_stage: The shader stage.
+  /// param offset: The offset in bytes.
+  /// param value: The struct value.
+  pub unsafe fn push_constants_struct_unchecked<T: Copy>(
+    &self,
+    index: usize,
+    pipeline_layout: vk::PipelineLayout,
+    shader_stage: crate::HalaShaderStageFlags,
+    offset: u32,
+    value: &T,
+  ) {
+    let data = std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>());
+    self.push_constants(index, pipeline_layout, shader_stage, offset, data);
+  }
+
   /// Push constants as f32.
   /// param index: The index of the command buffer.
   /// param pipeline_layout: The pipeline layout.
@@ -1239,6 +2054,11 @@ impl HalaCommandBufferSet {
     data: &[f32],
   ) {
     let logical_device = self.logical_device.borrow();
+    debug_assert!(
+      offset as usize + std::mem::size_of_val(data) <= logical_device.limits.max_push_constants_size as usize,
+      "The push constants range [{}, {}) exceeds the max push constants size {}.",
+      offset, offset as usize + std::mem::size_of_val(data), logical_device.limits.max_push_constants_size,
+    );
     unsafe {
       let data = std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data));
       logical_device.raw.cmd_push_constants(
@@ -1299,6 +2119,99 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Draw multiple vertex ranges with the same instances.
+  /// The draws are issued in one command with the multi draw extension, otherwise they are issued one by one.
+  /// param index: The index of the command buffer.
+  /// param draws: The draws(first vertex, vertex count).
+  /// param instance_count: The instance count.
+  /// param first_instance: The first instance.
+  pub fn draw_multi(
+    &self,
+    index: usize,
+    draws: &[(u32, u32)],
+    instance_count: u32,
+    first_instance: u32,
+  ) {
+    let logical_device = self.logical_device.borrow();
+    if let Some(multi_draw_loader) = logical_device.multi_draw_loader.as_ref() {
+      let infos = draws.iter().map(|&(first_vertex, vertex_count)| {
+        vk::MultiDrawInfoEXT {
+          first_vertex,
+          vertex_count,
+        }
+      }).collect::<Vec<_>>();
+      for chunk in infos.chunks(logical_device.max_multi_draw_count.max(1) as usize) {
+        unsafe {
+          (multi_draw_loader.fp().cmd_draw_multi_ext)(
+            self.raw[index],
+            chunk.len() as u32,
+            chunk.as_ptr(),
+            instance_count,
+            first_instance,
+            std::mem::size_of::<vk::MultiDrawInfoEXT>() as u32,
+          );
+        }
+      }
+    } else {
+      for &(first_vertex, vertex_count) in draws {
+        unsafe {
+          logical_device.raw.cmd_draw(self.raw[index], vertex_count, instance_count, first_vertex, first_instance);
+        }
+      }
+    }
+  }
+
+  /// Draw multiple index ranges with the same instances.
+  /// The draws are issued in one command with the multi draw extension, otherwise they are issued one by one.
+  /// param index: The index of the command buffer.
+  /// param draws: The draws(first index, index count, vertex offset).
+  /// param instance_count: The instance count.
+  /// param first_instance: The first instance.
+  pub fn draw_multi_indexed(
+    &self,
+    index: usize,
+    draws: &[(u32, u32, i32)],
+    instance_count: u32,
+    first_instance: u32,
+  ) {
+    let logical_device = self.logical_device.borrow();
+    if let Some(multi_draw_loader) = logical_device.multi_draw_loader.as_ref() {
+      let infos = draws.iter().map(|&(first_index, index_count, vertex_offset)| {
+        vk::MultiDrawIndexedInfoEXT {
+          first_index,
+          index_count,
+          vertex_offset,
+        }
+      }).collect::<Vec<_>>();
+      for chunk in infos.chunks(logical_device.max_multi_draw_count.max(1) as usize) {
+        unsafe {
+          // The vertex offset of each draw is used if the vertex offset pointer is null.
+          (multi_draw_loader.fp().cmd_draw_multi_indexed_ext)(
+            self.raw[index],
+            chunk.len() as u32,
+            chunk.as_ptr(),
+            instance_count,
+            first_instance,
+            std::mem::size_of::<vk::MultiDrawIndexedInfoEXT>() as u32,
+            std::ptr::null(),
+          );
+        }
+      }
+    } else {
+      for &(first_index, index_count, vertex_offset) in draws {
+        unsafe {
+          logical_device.raw.cmd_draw_indexed(
+            self.raw[index],
+            index_count,
+            instance_count,
+            first_index,
+            vertex_offset,
+            first_instance);
+        }
+      }
+    }
+  }
+
   /// Draw indirect.
   /// param index: The index of the command buffer.
   /// param buffer: The buffer.
@@ -1413,7 +2326,46 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Draw the vertices whose count is derived from the byte count in the counter buffer, e.g. the captured transform feedback data.
+  /// param index: The index of the command buffer.
+  /// param instance_count: The instance count.
+  /// param first_instance: The first instance.
+  /// param counter_buffer: The counter buffer which holds the byte count.
+  /// param counter_buffer_offset: The offset of the byte count in the counter buffer.
+  /// param counter_offset: The byte offset subtracted from the byte count.
+  /// param vertex_stride: The stride of the vertices in bytes.
+  /// return: The result.
+  #[allow(clippy::too_many_arguments)]
+  pub fn draw_indirect_byte_count(
+    &self,
+    index: usize,
+    instance_count: u32,
+    first_instance: u32,
+    counter_buffer: &HalaBuffer,
+    counter_buffer_offset: u64,
+    counter_offset: u32,
+    vertex_stride: u32,
+  ) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let transform_feedback_loader = logical_device.transform_feedback_loader.as_ref()
+      .ok_or(HalaGfxError::new("The transform feedback feature is not supported.", None))?;
+    unsafe {
+      (transform_feedback_loader.fp().cmd_draw_indirect_byte_count_ext)(
+        self.raw[index],
+        instance_count,
+        first_instance,
+        counter_buffer.raw,
+        counter_buffer_offset,
+        counter_offset,
+        vertex_stride,
+      );
+    }
+
+    Ok(())
+  }
+
   /// Draw mesh tasks.
+  /// The group count is checked against the device limits in the debug build.
   /// param index: The index of the command buffer.
   /// param group_count_x: The group count x.
   /// param group_count_y: The group count y.
@@ -1426,12 +2378,35 @@ impl HalaCommandBufferSet {
     group_count_z: u32,
   ) {
     let logical_device = self.logical_device.borrow();
+    #[cfg(debug_assertions)]
+    if let Err(err) = logical_device.check_task_group_count([group_count_x, group_count_y, group_count_z]) {
+      panic!("{}", err);
+    }
     unsafe {
       logical_device.mesh_shader_loader.cmd_draw_mesh_tasks(self.raw[index], group_count_x, group_count_y, group_count_z);
     }
   }
 
+  /// Draw mesh tasks after checking the group count against the device limits.
+  /// param index: The index of the command buffer.
+  /// param group_count_x: The group count x.
+  /// param group_count_y: The group count y.
+  /// param group_count_z: The group count z.
+  /// return: The result.
+  pub fn draw_mesh_tasks_checked(
+    &self,
+    index: usize,
+    group_count_x: u32,
+    group_count_y: u32,
+    group_count_z: u32,
+  ) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().check_task_group_count([group_count_x, group_count_y, group_count_z])?;
+    self.draw_mesh_tasks(index, group_count_x, group_count_y, group_count_z);
+    Ok(())
+  }
+
   /// Draw mesh tasks indirect.
+  /// The group counts in the buffer can not be checked when recording, they must not exceed HalaLogicalDevice::max_task_work_group_count.
   /// param index: The index of the command buffer.
   /// param buffer: The buffer.
   /// param offset: The offset.
@@ -1452,6 +2427,7 @@ impl HalaCommandBufferSet {
   }
 
   /// Draw mesh tasks indirect count.
+  /// The group counts in the buffer can not be checked when recording, they must not exceed HalaLogicalDevice::max_task_work_group_count.
   /// param index: The index of the command buffer.
   /// param buffer: The buffer.
   /// param offset: The offset.
@@ -1477,18 +2453,78 @@ impl HalaCommandBufferSet {
   }
 
   /// Dispatch compute.
+  /// The group count is checked against the device limits in the debug build.
   /// param index: The index of the command buffer.
   /// param group_count_x: The group count x.
   /// param group_count_y: The group count y.
   /// param group_count_z: The group count z.
   pub fn dispatch(&self, index: usize, group_count_x: u32, group_count_y: u32, group_count_z: u32) {
     let logical_device = self.logical_device.borrow();
+    #[cfg(debug_assertions)]
+    if let Err(err) = logical_device.check_compute_group_count([group_count_x, group_count_y, group_count_z]) {
+      panic!("{}", err);
+    }
     unsafe {
       logical_device.raw.cmd_dispatch(self.raw[index], group_count_x, group_count_y, group_count_z);
     }
   }
 
+  /// Dispatch compute after checking the group count against the device limits.
+  /// param index: The index of the command buffer.
+  /// param group_count_x: The group count x.
+  /// param group_count_y: The group count y.
+  /// param group_count_z: The group count z.
+  /// return: The result.
+  pub fn dispatch_checked(&self, index: usize, group_count_x: u32, group_count_y: u32, group_count_z: u32) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().check_compute_group_count([group_count_x, group_count_y, group_count_z])?;
+    self.dispatch(index, group_count_x, group_count_y, group_count_z);
+    Ok(())
+  }
+
+  /// Dispatch compute with a base workgroup, the built-in workgroup ID in the shader starts from the base.
+  /// The bound compute pipeline must be created with HalaPipelineCreateFlags::DISPATCH_BASE.
+  /// param index: The index of the command buffer.
+  /// param base_group_x: The base group x.
+  /// param base_group_y: The base group y.
+  /// param base_group_z: The base group z.
+  /// param group_count_x: The group count x.
+  /// param group_count_y: The group count y.
+  /// param group_count_z: The group count z.
+  #[allow(clippy::too_many_arguments)]
+  pub fn dispatch_base(
+    &self,
+    index: usize,
+    base_group_x: u32,
+    base_group_y: u32,
+    base_group_z: u32,
+    group_count_x: u32,
+    group_count_y: u32,
+    group_count_z: u32,
+  ) {
+    let logical_device = self.logical_device.borrow();
+    #[cfg(debug_assertions)]
+    if let Err(err) = logical_device.check_compute_group_count([
+      base_group_x.saturating_add(group_count_x),
+      base_group_y.saturating_add(group_count_y),
+      base_group_z.saturating_add(group_count_z),
+    ]) {
+      panic!("{}", err);
+    }
+    unsafe {
+      logical_device.raw.cmd_dispatch_base(
+        self.raw[index],
+        base_group_x,
+        base_group_y,
+        base_group_z,
+        group_count_x,
+        group_count_y,
+        group_count_z,
+      );
+    }
+  }
+
   /// Dispatch indirect.
+  /// The group count in the buffer can not be checked when recording, it must not exceed HalaLogicalDevice::limits.max_compute_work_group_count.
   /// param index: The index of the command buffer.
   /// param buffer: The buffer.
   /// param offset: The offset.
@@ -1557,6 +2593,7 @@ impl HalaCommandBufferSet {
     let logical_device = self.logical_device.borrow();
     let descriptor_sets: Vec<vk::DescriptorSet> = descriptor_sets.iter().map(|set| {
       let set = set.as_ref();
+      debug_assert!(set.is_valid(), "The descriptor set \"{}\" is used after its pool is reset.", set.debug_name);
       if set.is_static {
         set.raw[0]
       } else {
@@ -1594,6 +2631,7 @@ impl HalaCommandBufferSet {
     let logical_device = self.logical_device.borrow();
     let descriptor_sets: Vec<vk::DescriptorSet> = descriptor_sets.iter().map(|set| {
       let set = set.as_ref();
+      debug_assert!(set.is_valid(), "The descriptor set \"{}\" is used after its pool is reset.", set.debug_name);
       if set.is_static {
         set.raw[0]
       } else {
@@ -1631,6 +2669,7 @@ impl HalaCommandBufferSet {
     let logical_device = self.logical_device.borrow();
     let descriptor_sets: Vec<vk::DescriptorSet> = descriptor_sets.iter().map(|set| {
       let set = set.as_ref();
+      debug_assert!(set.is_valid(), "The descriptor set \"{}\" is used after its pool is reset.", set.debug_name);
       if set.is_static {
         set.raw[0]
       } else {
@@ -1649,6 +2688,38 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Push the descriptors to the command buffer without allocating a descriptor set.
+  /// The descriptor set layout of the set must be created by HalaDescriptorSetLayout::new_push_descriptor.
+  /// param index: The index of the command buffer.
+  /// param bind_point: The pipeline bind point.
+  /// param pipeline_layout: The pipeline layout.
+  /// param set: The set number.
+  /// param writes: The descriptor writes.
+  /// return: The result.
+  pub fn push_descriptor_set(
+    &self,
+    index: usize,
+    bind_point: crate::HalaPipelineBindPoint,
+    pipeline_layout: vk::PipelineLayout,
+    set: u32,
+    writes: &[crate::HalaWriteDescriptor],
+  ) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let push_descriptor_loader = logical_device.push_descriptor_loader.as_ref()
+      .ok_or(HalaGfxError::new("The push descriptor extension is not supported.", None))?;
+    crate::HalaWriteDescriptor::with_raw_writes(vk::DescriptorSet::null(), writes, |descriptor_writes| unsafe {
+      push_descriptor_loader.cmd_push_descriptor_set(
+        self.raw[index],
+        bind_point.into(),
+        pipeline_layout,
+        set,
+        descriptor_writes,
+      );
+    });
+
+    Ok(())
+  }
+
   /// Bind the vertex buffers.
   /// param index: The index of the command buffer.
   /// param first_binding: The first binding.
@@ -1675,6 +2746,105 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Bind the transform feedback buffers which capture the vertex outputs.
+  /// param index: The index of the command buffer.
+  /// param first_binding: The first binding.
+  /// param buffers: The buffers which are created with HalaBufferUsageFlags::TRANSFORM_FEEDBACK_BUFFER.
+  /// param offsets: The offsets.
+  /// param sizes: The sizes, vk::WHOLE_SIZE for the remaining sizes of the buffers.
+  /// return: The result.
+  pub fn bind_transform_feedback_buffers<B>(
+    &self,
+    index: usize,
+    first_binding: u32,
+    buffers: &[B],
+    offsets: &[u64],
+    sizes: &[u64],
+  ) -> Result<(), HalaGfxError>
+    where B: AsRef<crate::HalaBuffer>
+  {
+    assert!(buffers.len() == offsets.len() && buffers.len() == sizes.len());
+    let logical_device = self.logical_device.borrow();
+    let transform_feedback_loader = logical_device.transform_feedback_loader.as_ref()
+      .ok_or(HalaGfxError::new("The transform feedback feature is not supported.", None))?;
+    let buffers: Vec<vk::Buffer> = buffers.iter().map(|buffer| buffer.as_ref().raw).collect();
+    unsafe {
+      (transform_feedback_loader.fp().cmd_bind_transform_feedback_buffers_ext)(
+        self.raw[index],
+        first_binding,
+        buffers.len() as u32,
+        buffers.as_ptr(),
+        offsets.as_ptr(),
+        sizes.as_ptr(),
+      );
+    }
+
+    Ok(())
+  }
+
+  /// Begin the transform feedback.
+  /// The counter buffers resume the capture from the byte counts written by the last end transform feedback.
+  /// param index: The index of the command buffer.
+  /// param counter_buffers: The counter buffers, empty to start the capture from the beginning of the transform feedback buffers.
+  /// param counter_buffer_offsets: The offsets of the byte counts in the counter buffers.
+  /// return: The result.
+  pub fn begin_transform_feedback<B>(
+    &self,
+    index: usize,
+    counter_buffers: &[B],
+    counter_buffer_offsets: &[u64],
+  ) -> Result<(), HalaGfxError>
+    where B: AsRef<crate::HalaBuffer>
+  {
+    assert!(counter_buffers.len() == counter_buffer_offsets.len());
+    let logical_device = self.logical_device.borrow();
+    let transform_feedback_loader = logical_device.transform_feedback_loader.as_ref()
+      .ok_or(HalaGfxError::new("The transform feedback feature is not supported.", None))?;
+    let counter_buffers: Vec<vk::Buffer> = counter_buffers.iter().map(|buffer| buffer.as_ref().raw).collect();
+    unsafe {
+      (transform_feedback_loader.fp().cmd_begin_transform_feedback_ext)(
+        self.raw[index],
+        0,
+        counter_buffers.len() as u32,
+        counter_buffers.as_ptr(),
+        counter_buffer_offsets.as_ptr(),
+      );
+    }
+
+    Ok(())
+  }
+
+  /// End the transform feedback.
+  /// param index: The index of the command buffer.
+  /// param counter_buffers: The counter buffers to write the captured byte counts, empty if the counts are not needed.
+  /// param counter_buffer_offsets: The offsets of the byte counts in the counter buffers.
+  /// return: The result.
+  pub fn end_transform_feedback<B>(
+    &self,
+    index: usize,
+    counter_buffers: &[B],
+    counter_buffer_offsets: &[u64],
+  ) -> Result<(), HalaGfxError>
+    where B: AsRef<crate::HalaBuffer>
+  {
+    assert!(counter_buffers.len() == counter_buffer_offsets.len());
+    let logical_device = self.logical_device.borrow();
+    let transform_feedback_loader = logical_device.transform_feedback_loader.as_ref()
+      .ok_or(HalaGfxError::new("The transform feedback feature is not supported.", None))?;
+    let counter_buffers: Vec<vk::Buffer> = counter_buffers.iter().map(|buffer| buffer.as_ref().raw).collect();
+    unsafe {
+      (transform_feedback_loader.fp().cmd_end_transform_feedback_ext)(
+        self.raw[index],
+        0,
+        counter_buffers.len() as u32,
+        counter_buffers.as_ptr(),
+        counter_buffer_offsets.as_ptr(),
+      );
+    }
+
+    Ok(())
+  }
+
   /// Bind the index buffers.
   /// param index: The index of the command buffer.
   /// param buffers: The buffers.
@@ -1848,27 +3018,7 @@ impl HalaCommandBufferSet {
     where IBI: AsRef<crate::HalaImageBarrierInfo>
   {
     let barriers = barriers.iter().map(
-      |barrier_info| {
-        let barrier_info = barrier_info.as_ref();
-        vk::ImageMemoryBarrier2::default()
-          .src_stage_mask(barrier_info.src_stage_mask.into())
-          .src_access_mask(barrier_info.src_access_mask.into())
-          .dst_stage_mask(barrier_info.dst_stage_mask.into())
-          .dst_access_mask(barrier_info.dst_access_mask.into())
-          .old_layout(barrier_info.old_layout.into())
-          .new_layout(barrier_info.new_layout.into())
-          .src_queue_family_index(barrier_info.src_queue_family_index)
-          .dst_queue_family_index(barrier_info.dst_queue_family_index)
-          .image(barrier_info.image)
-          .subresource_range(
-            vk::ImageSubresourceRange::default()
-              .aspect_mask(barrier_info.aspect_mask.into())
-              .base_mip_level(barrier_info.base_mip_level)
-              .level_count(barrier_info.level_count)
-              .base_array_layer(barrier_info.base_array_layer)
-              .layer_count(barrier_info.layer_count)
-          )
-      }
+      |barrier_info| barrier_info.as_ref().to_raw()
     ).collect::<Vec<_>>();
 
     let dependency_info = vk::DependencyInfoKHR::default()
@@ -1894,19 +3044,7 @@ impl HalaCommandBufferSet {
     where BBI: AsRef<crate::HalaBufferBarrierInfo>
   {
     let barriers = barriers.iter().map(
-      |barrier_info| {
-        let barrier_info = barrier_info.as_ref();
-        vk::BufferMemoryBarrier2KHR::default()
-          .src_stage_mask(barrier_info.src_stage_mask.into())
-          .src_access_mask(barrier_info.src_access_mask.into())
-          .dst_stage_mask(barrier_info.dst_stage_mask.into())
-          .dst_access_mask(barrier_info.dst_access_mask.into())
-          .src_queue_family_index(barrier_info.src_queue_family_index)
-          .dst_queue_family_index(barrier_info.dst_queue_family_index)
-          .buffer(barrier_info.buffer)
-          .offset(barrier_info.offset)
-          .size(barrier_info.size)
-      }
+      |barrier_info| barrier_info.as_ref().to_raw()
     ).collect::<Vec<_>>();
 
     let dependency_info = vk::DependencyInfoKHR::default()
@@ -1921,6 +3059,76 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Release the ownership of the images and buffers to another queue family.
+  /// A queue family ownership transfer is two-sided: the same barriers(with the same layouts and queue family indices)
+  /// must be recorded by the release on the source queue and by the acquire on the destination queue,
+  /// and the acquire submission must wait for a semaphore signaled by the release submission.
+  /// param index: The index of the command buffer.
+  /// param image_barriers: The image barriers.
+  /// param buffer_barriers: The buffer barriers.
+  pub fn release_queue_ownership<IBI, BBI>(
+    &self,
+    index: usize,
+    image_barriers: &[IBI],
+    buffer_barriers: &[BBI],
+  )
+    where IBI: AsRef<crate::HalaImageBarrierInfo>,
+          BBI: AsRef<crate::HalaBufferBarrierInfo>
+  {
+    let image_barriers = image_barriers.iter().map(
+      |barrier_info| barrier_info.as_ref().to_release().to_raw()
+    ).collect::<Vec<_>>();
+    let buffer_barriers = buffer_barriers.iter().map(
+      |barrier_info| barrier_info.as_ref().to_release().to_raw()
+    ).collect::<Vec<_>>();
+
+    let dependency_info = vk::DependencyInfoKHR::default()
+      .image_memory_barriers(image_barriers.as_slice())
+      .buffer_memory_barriers(buffer_barriers.as_slice());
+
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.cmd_pipeline_barrier2(
+        self.raw[index],
+        &dependency_info,
+      );
+    }
+  }
+
+  /// Acquire the ownership of the images and buffers from another queue family.
+  /// The barriers must be the same as the ones passed to the release on the source queue.
+  /// param index: The index of the command buffer.
+  /// param image_barriers: The image barriers.
+  /// param buffer_barriers: The buffer barriers.
+  pub fn acquire_queue_ownership<IBI, BBI>(
+    &self,
+    index: usize,
+    image_barriers: &[IBI],
+    buffer_barriers: &[BBI],
+  )
+    where IBI: AsRef<crate::HalaImageBarrierInfo>,
+          BBI: AsRef<crate::HalaBufferBarrierInfo>
+  {
+    let image_barriers = image_barriers.iter().map(
+      |barrier_info| barrier_info.as_ref().to_acquire().to_raw()
+    ).collect::<Vec<_>>();
+    let buffer_barriers = buffer_barriers.iter().map(
+      |barrier_info| barrier_info.as_ref().to_acquire().to_raw()
+    ).collect::<Vec<_>>();
+
+    let dependency_info = vk::DependencyInfoKHR::default()
+      .image_memory_barriers(image_barriers.as_slice())
+      .buffer_memory_barriers(buffer_barriers.as_slice());
+
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.cmd_pipeline_barrier2(
+        self.raw[index],
+        &dependency_info,
+      );
+    }
+  }
+
   /// Set memory barriers.
   /// param index: The index of the command buffer.
   /// param barriers: The barriers.
@@ -1932,14 +3140,7 @@ impl HalaCommandBufferSet {
     where MBI: AsRef<crate::HalaMemoryBarrierInfo>
   {
     let barriers = barriers.iter().map(
-      |barrier_info| {
-        let barrier_info = barrier_info.as_ref();
-        vk::MemoryBarrier2KHR::default()
-          .src_stage_mask(barrier_info.src_stage_mask.into())
-          .src_access_mask(barrier_info.src_access_mask.into())
-          .dst_stage_mask(barrier_info.dst_stage_mask.into())
-          .dst_access_mask(barrier_info.dst_access_mask.into())
-      }
+      |barrier_info| barrier_info.as_ref().to_raw()
     ).collect::<Vec<_>>();
 
     let dependency_info = vk::DependencyInfoKHR::default()
@@ -1954,6 +3155,152 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Set a global memory barrier.
+  /// param index: The index of the command buffer.
+  /// param src_stage_mask: The source stage mask.
+  /// param src_access_mask: The source access mask.
+  /// param dst_stage_mask: The destination stage mask.
+  /// param dst_access_mask: The destination access mask.
+  pub fn memory_barrier(
+    &self,
+    index: usize,
+    src_stage_mask: HalaPipelineStageFlags2,
+    src_access_mask: crate::HalaAccessFlags2,
+    dst_stage_mask: HalaPipelineStageFlags2,
+    dst_access_mask: crate::HalaAccessFlags2,
+  ) {
+    self.set_memory_barriers(
+      index,
+      &[crate::HalaMemoryBarrierInfo {
+        src_stage_mask,
+        src_access_mask,
+        dst_stage_mask,
+        dst_access_mask,
+      }],
+    );
+  }
+
+  /// Set a memory barrier between the compute shader writes and the following compute shader reads.
+  /// param index: The index of the command buffer.
+  pub fn compute_to_compute_barrier(&self, index: usize) {
+    self.memory_barrier(
+      index,
+      HalaPipelineStageFlags2::COMPUTE_SHADER,
+      crate::HalaAccessFlags2::SHADER_WRITE,
+      HalaPipelineStageFlags2::COMPUTE_SHADER,
+      crate::HalaAccessFlags2::SHADER_READ | crate::HalaAccessFlags2::SHADER_WRITE,
+    );
+  }
+
+  /// Set a memory barrier between the compute shader writes and the following graphics reads,
+  /// including the indirect command, index and vertex attribute reads.
+  /// param index: The index of the command buffer.
+  pub fn compute_to_graphics_barrier(&self, index: usize) {
+    self.memory_barrier(
+      index,
+      HalaPipelineStageFlags2::COMPUTE_SHADER,
+      crate::HalaAccessFlags2::SHADER_WRITE,
+      HalaPipelineStageFlags2::DRAW_INDIRECT
+        | HalaPipelineStageFlags2::VERTEX_INPUT
+        | HalaPipelineStageFlags2::ALL_GRAPHICS,
+      crate::HalaAccessFlags2::INDIRECT_COMMAND_READ
+        | crate::HalaAccessFlags2::INDEX_READ
+        | crate::HalaAccessFlags2::VERTEX_ATTRIBUTE_READ
+        | crate::HalaAccessFlags2::SHADER_READ,
+    );
+  }
+
+  /// Set the event with the dependency for the split barrier.
+  /// param index: The index of the command buffer.
+  /// param event: The event.
+  /// param image_barriers: The image barriers.
+  /// param buffer_barriers: The buffer barriers.
+  /// param memory_barriers: The memory barriers.
+  pub fn set_event(
+    &self,
+    index: usize,
+    event: &crate::HalaEvent,
+    image_barriers: &[crate::HalaImageBarrierInfo],
+    buffer_barriers: &[crate::HalaBufferBarrierInfo],
+    memory_barriers: &[crate::HalaMemoryBarrierInfo],
+  ) {
+    let image_barriers = image_barriers.iter().map(|barrier_info| barrier_info.to_raw()).collect::<Vec<_>>();
+    let buffer_barriers = buffer_barriers.iter().map(|barrier_info| barrier_info.to_raw()).collect::<Vec<_>>();
+    let memory_barriers = memory_barriers.iter().map(|barrier_info| barrier_info.to_raw()).collect::<Vec<_>>();
+
+    let dependency_info = vk::DependencyInfoKHR::default()
+      .image_memory_barriers(image_barriers.as_slice())
+      .buffer_memory_barriers(buffer_barriers.as_slice())
+      .memory_barriers(memory_barriers.as_slice());
+
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.cmd_set_event2(
+        self.raw[index],
+        event.raw,
+        &dependency_info,
+      );
+    }
+  }
+
+  /// Reset the event.
+  /// param index: The index of the command buffer.
+  /// param event: The event.
+  /// param stage_mask: The stage mask after which the event is reset.
+  pub fn reset_event(
+    &self,
+    index: usize,
+    event: &crate::HalaEvent,
+    stage_mask: HalaPipelineStageFlags2,
+  ) {
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.cmd_reset_event2(
+        self.raw[index],
+        event.raw,
+        stage_mask.into(),
+      );
+    }
+  }
+
+  /// Wait the events for the split barrier.
+  /// The dependency must be the same as the one used to set each event.
+  /// param index: The index of the command buffer.
+  /// param events: The events.
+  /// param image_barriers: The image barriers.
+  /// param buffer_barriers: The buffer barriers.
+  /// param memory_barriers: The memory barriers.
+  pub fn wait_events<E>(
+    &self,
+    index: usize,
+    events: &[E],
+    image_barriers: &[crate::HalaImageBarrierInfo],
+    buffer_barriers: &[crate::HalaBufferBarrierInfo],
+    memory_barriers: &[crate::HalaMemoryBarrierInfo],
+  )
+    where E: AsRef<crate::HalaEvent>
+  {
+    let image_barriers = image_barriers.iter().map(|barrier_info| barrier_info.to_raw()).collect::<Vec<_>>();
+    let buffer_barriers = buffer_barriers.iter().map(|barrier_info| barrier_info.to_raw()).collect::<Vec<_>>();
+    let memory_barriers = memory_barriers.iter().map(|barrier_info| barrier_info.to_raw()).collect::<Vec<_>>();
+
+    let dependency_info = vk::DependencyInfoKHR::default()
+      .image_memory_barriers(image_barriers.as_slice())
+      .buffer_memory_barriers(buffer_barriers.as_slice())
+      .memory_barriers(memory_barriers.as_slice());
+    let events = events.iter().map(|event| event.as_ref().raw).collect::<Vec<_>>();
+    let dependency_infos = vec![dependency_info; events.len()];
+
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.cmd_wait_events2(
+        self.raw[index],
+        events.as_slice(),
+        dependency_infos.as_slice(),
+      );
+    }
+  }
+
   /// Copy image to swapchain.
   /// param index: The index of the command buffer.
   /// param src_image: The source image.
@@ -2098,7 +3445,7 @@ impl HalaCommandBufferSet {
   ) {
     let region = vk::BufferImageCopy2::default()
       .image_subresource(vk::ImageSubresourceLayers::default()
-        .aspect_mask(vk::ImageAspectFlags::COLOR)
+        .aspect_mask(HalaImage::single_aspect_for_format(src_image.format))
         .mip_level(0)
         .base_array_layer(0)
         .layer_count(1)
@@ -2161,7 +3508,7 @@ impl HalaCommandBufferSet {
     name: &str,
     color: [f32; 4],
   ) {
-    let name = std::ffi::CString::new(name).unwrap();
+    let name = crate::logical_device::debug_cstring(name);
     let logical_device = self.logical_device.borrow();
     let label = vk::DebugUtilsLabelEXT::default()
       .label_name(&name)
@@ -2189,4 +3536,150 @@ impl HalaCommandBufferSet {
     }
   }
 
+  /// Begin a debug label which is ended when the returned scope is dropped.
+  /// param index: The index of the command buffer.
+  /// param name: The name of the label.
+  /// param color: The color of the label.
+  /// return: The debug label scope.
+  pub fn scoped_debug_label(
+    &self,
+    index: usize,
+    name: &str,
+    color: [f32; 4],
+  ) -> HalaDebugLabelScope<'_> {
+    self.begin_debug_label(index, name, color);
+    HalaDebugLabelScope {
+      command_buffers: self,
+      index,
+    }
+  }
+
+}
+
+/// The scope of a debug label.
+/// The label is ended when the scope is dropped, so it is balanced on the early returns.
+/// The scope borrows the command buffer set, so it must be dropped before the command buffer is ended.
+#[must_use = "The debug label is ended immediately if the scope is not bound to a variable."]
+pub struct HalaDebugLabelScope<'a> {
+  command_buffers: &'a HalaCommandBufferSet,
+  index: usize,
+}
+
+/// The Drop trait implementation of the debug label scope.
+impl Drop for HalaDebugLabelScope<'_> {
+  fn drop(&mut self) {
+    self.command_buffers.end_debug_label(self.index);
+  }
+}
+
+/// Get the size of the results of a query copied to a buffer.
+/// param values_per_query: The number of the values written for each query, including the availability value.
+/// param flags: The query result flags.
+/// return: The size of a value and the size of the results of a query in bytes.
+pub(crate) fn query_result_size(values_per_query: usize, flags: HalaQueryResultFlags) -> (u64, u64) {
+  let value_size = if flags.contains(HalaQueryResultFlags::TYPE_64) { 8 } else { 4 };
+  (value_size, values_per_query as u64 * value_size)
+}
+
+/// Check the query results copy against the query pool and the destination buffer.
+/// param pool_size: The query count of the query pool.
+/// param first_query: The first query.
+/// param query_count: The query count.
+/// param (value_size, result_size): The size of each value and the size of the results of each query.
+/// param dst_size: The size of the destination buffer.
+/// param dst_offset: The offset in the destination buffer.
+/// param stride: The stride between the results of each query.
+/// return: The result.
+pub(crate) fn check_query_results_copy(
+  pool_size: u32,
+  first_query: u32,
+  query_count: u32,
+  (value_size, result_size): (u64, u64),
+  dst_size: u64,
+  dst_offset: u64,
+  stride: u64,
+) -> Result<(), HalaGfxError> {
+  if first_query.checked_add(query_count).is_none_or(|end| end > pool_size) {
+    return Err(HalaGfxError::new("The query range is out of range.", None));
+  }
+  if !dst_offset.is_multiple_of(value_size) {
+    return Err(HalaGfxError::new("The destination offset must be a multiple of the result size.", None));
+  }
+  if !stride.is_multiple_of(value_size) {
+    return Err(HalaGfxError::new("The stride must be a multiple of the result size.", None));
+  }
+  if stride < result_size {
+    return Err(HalaGfxError::new("The stride is too small to hold the results of a query.", None));
+  }
+  if query_count > 0 {
+    let end = (query_count as u64 - 1).checked_mul(stride)
+      .and_then(|size| size.checked_add(dst_offset))
+      .and_then(|size| size.checked_add(result_size));
+    if end.is_none_or(|end| end > dst_size) {
+      return Err(HalaGfxError::new("The destination buffer is too small to hold the results.", None));
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn query_result_size_of_64bit_values() {
+    assert_eq!(query_result_size(1, HalaQueryResultFlags::TYPE_64), (8, 8));
+    assert_eq!(query_result_size(1, HalaQueryResultFlags::TYPE_64 | HalaQueryResultFlags::WAIT), (8, 8));
+  }
+
+  #[test]
+  fn query_result_size_of_32bit_values() {
+    assert_eq!(query_result_size(1, HalaQueryResultFlags::empty()), (4, 4));
+    assert_eq!(query_result_size(1, HalaQueryResultFlags::WAIT), (4, 4));
+  }
+
+  #[test]
+  fn query_result_size_with_availability() {
+    // The availability value is appended to the results of each query with the same value size.
+    assert_eq!(query_result_size(2, HalaQueryResultFlags::TYPE_64 | HalaQueryResultFlags::WITH_AVAILABILITY), (8, 16));
+    assert_eq!(query_result_size(2, HalaQueryResultFlags::WITH_AVAILABILITY), (4, 8));
+    // The pipeline statistics query with 3 statistics and the availability value.
+    assert_eq!(query_result_size(4, HalaQueryResultFlags::TYPE_64 | HalaQueryResultFlags::WITH_AVAILABILITY), (8, 32));
+  }
+
+  #[test]
+  fn query_results_copy_in_range() {
+    assert!(check_query_results_copy(4, 0, 4, (8, 8), 32, 0, 8).is_ok());
+    assert!(check_query_results_copy(4, 2, 2, (4, 8), 40, 8, 16).is_ok());
+    // No result is written for an empty range.
+    assert!(check_query_results_copy(4, 4, 0, (8, 8), 0, 0, 8).is_ok());
+  }
+
+  #[test]
+  fn query_results_copy_out_of_query_range() {
+    assert!(check_query_results_copy(4, 2, 3, (8, 8), 1024, 0, 8).is_err());
+    // The end of the range overflows the u32.
+    assert!(check_query_results_copy(4, u32::MAX, 2, (8, 8), 1024, 0, 8).is_err());
+  }
+
+  #[test]
+  fn query_results_copy_misaligned() {
+    // The 64-bit values need 8 bytes aligned offset and stride.
+    assert!(check_query_results_copy(4, 0, 1, (8, 8), 1024, 4, 8).is_err());
+    assert!(check_query_results_copy(4, 0, 1, (8, 8), 1024, 0, 12).is_err());
+    assert!(check_query_results_copy(4, 0, 1, (4, 4), 1024, 4, 12).is_ok());
+  }
+
+  #[test]
+  fn query_results_copy_stride_too_small() {
+    assert!(check_query_results_copy(4, 0, 2, (8, 16), 1024, 0, 8).is_err());
+  }
+
+  #[test]
+  fn query_results_copy_buffer_too_small() {
+    assert!(check_query_results_copy(4, 0, 4, (8, 8), 24, 0, 8).is_err());
+    assert!(check_query_results_copy(4, 0, 4, (8, 8), 32, 8, 8).is_err());
+    // The end of the results overflows the u64.
+    assert!(check_query_results_copy(4, 0, 4, (8, 8), u64::MAX, 0, u64::MAX - 7).is_err());
+  }
 }
@@ -9,6 +9,7 @@ use crate::{
   HalaSurface,
   HalaLogicalDevice,
   HalaSwapchain,
+  HalaSwapchainStatus,
   HalaCommandPools,
   HalaCommandBufferSet,
   HalaPipelineStageFlags2,
@@ -17,14 +18,20 @@ use crate::{
   HalaGfxError,
   HalaFormat,
   HalaSampleCountFlags,
+  HalaStagingBufferPool,
 };
 
 /// The context of the hala-gfx crate.
+/// The fields are dropped in the declaration order after the device is idle,
+/// so the objects depending on the logical device must be declared before it.
 pub struct HalaContext {
   pub name: String,
   pub gpu_req: HalaGPURequirements,
 
+  /// The frames in flight which are managed by the context, it is created by init_frames_in_flight.
+  pub frame_context: Option<crate::HalaFrameContext>,
   pub timestamp_query_pool: HalaQueryPool,
+  pub staging_buffer_pool: Rc<RefCell<HalaStagingBufferPool>>,
   pub short_time_command_pools: Rc<RefCell<HalaCommandPools>>,
   pub command_pools: Rc<RefCell<HalaCommandPools>>,
   pub swapchain: std::mem::ManuallyDrop<HalaSwapchain>,
@@ -39,6 +46,10 @@ pub struct HalaContext {
 /// The Drop trait implementation of the context of the hala-gfx crate.
 impl Drop for HalaContext {
   fn drop(&mut self) {
+    // Wait for all the submitted work to finish before destroying any object in use.
+    if let Err(err) = self.logical_device.borrow().wait_idle() {
+      log::warn!("Failed to wait the device idle before dropping the context: {}", err);
+    }
     unsafe {
       std::mem::ManuallyDrop::drop(&mut self.swapchain);
     }
@@ -48,19 +59,34 @@ impl Drop for HalaContext {
 
 /// The implementation of the context of the hala-gfx crate.
 impl HalaContext {
-  /// Create a new context.
+  /// Create a new context with the default validation configuration.
   /// param name: The name of the context.
   /// param gpu_req: The GPU requirements.
   /// param window: The window.
   /// return: The context.
   pub fn new(name: &str, gpu_req: &HalaGPURequirements, window: &winit::window::Window) -> Result<Self, HalaGfxError> {
+    Self::new_with_validation(name, gpu_req, crate::HalaValidationConfig::default(), window)
+  }
+
+  /// Create a new context.
+  /// param name: The name of the context.
+  /// param gpu_req: The GPU requirements.
+  /// param validation_config: The validation configuration.
+  /// param window: The window.
+  /// return: The context.
+  pub fn new_with_validation(
+    name: &str,
+    gpu_req: &HalaGPURequirements,
+    validation_config: crate::HalaValidationConfig,
+    window: &winit::window::Window,
+  ) -> Result<Self, HalaGfxError> {
     // Validate the GPU requirements.
     if gpu_req.require_10bits_output && gpu_req.require_srgb_surface {
       return Err(HalaGfxError::new("10bits output and sRGB surface can't be required at the same time.", None));
     }
 
     // Create instance.
-    let instance = crate::HalaInstance::new(name, gpu_req)?;
+    let instance = crate::HalaInstance::new(name, gpu_req, validation_config)?;
 
     // Create physical device.
     let physical_device = crate::HalaPhysicalDevice::new(gpu_req, &instance)?;
@@ -111,6 +137,16 @@ impl HalaContext {
       "timestamp.query_pool",
     )?;
 
+    // Create staging buffer pool.
+    let staging_buffer_pool = Rc::new(
+      RefCell::new(
+        HalaStagingBufferPool::new(
+          Rc::clone(&logical_device),
+          "main.staging_buffer_pool",
+        )
+      )
+    );
+
     log::debug!("A HalaContext is created.");
     Ok(
       Self {
@@ -123,27 +159,341 @@ impl HalaContext {
         logical_device,
         command_pools,
         short_time_command_pools,
+        frame_context: None,
         timestamp_query_pool,
+        staging_buffer_pool,
         multisample_count: HalaSampleCountFlags::TYPE_1,
       }
     )
   }
 
+  /// Create an async uploader on the transfer queue.
+  /// param debug_name: The debug name.
+  /// return: The async uploader.
+  pub fn create_async_uploader(&self, debug_name: &str) -> Result<crate::HalaAsyncUploader, HalaGfxError> {
+    crate::HalaAsyncUploader::new(
+      Rc::clone(&self.logical_device),
+      Rc::clone(&self.short_time_command_pools),
+      debug_name,
+    )
+  }
+
+  /// Create a storage image for the compute shader.
+  /// The image is created with storage, sampled and transfer source usage and transitioned to the general layout.
+  /// param format: The image format.
+  /// param width: The image width.
+  /// param height: The image height.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  pub fn create_storage_image(
+    &self,
+    format: HalaFormat,
+    width: u32,
+    height: u32,
+    debug_name: &str,
+  ) -> Result<HalaImage, HalaGfxError> {
+    let format_properties = self.physical_device.format_properties(&self.instance, format);
+    if !format_properties.optimal_tiling_features.contains(crate::HalaFormatFeatureFlags::STORAGE_IMAGE) {
+      return Err(HalaGfxError::new(
+        &format!("The format {} does not support the storage image.", format),
+        None,
+      ));
+    }
+
+    let image = HalaImage::new_2d(
+      Rc::clone(&self.logical_device),
+      crate::HalaImageUsageFlags::STORAGE | crate::HalaImageUsageFlags::SAMPLED | crate::HalaImageUsageFlags::TRANSFER_SRC,
+      format,
+      width,
+      height,
+      1,
+      1,
+      crate::HalaMemoryLocation::GpuOnly,
+      debug_name,
+    )?;
+
+    let command_buffers = HalaCommandBufferSet::new(
+      Rc::clone(&self.logical_device),
+      Rc::clone(&self.short_time_command_pools),
+      crate::HalaCommandBufferType::GRAPHICS,
+      crate::HalaCommandBufferLevel::PRIMARY,
+      1,
+      &format!("{}.command_buffer", debug_name),
+    )?;
+    self.logical_device.borrow().graphics_execute_and_submit(
+      &command_buffers,
+      0,
+      |_logical_device, command_buffers, index| {
+        command_buffers.set_image_barriers(
+          index,
+          &[crate::HalaImageBarrierInfo {
+            old_layout: crate::HalaImageLayout::UNDEFINED,
+            new_layout: crate::HalaImageLayout::GENERAL,
+            src_access_mask: crate::HalaAccessFlags2::NONE,
+            dst_access_mask: crate::HalaAccessFlags2::SHADER_READ | crate::HalaAccessFlags2::SHADER_WRITE,
+            src_stage_mask: crate::HalaPipelineStageFlags2::TOP_OF_PIPE,
+            dst_stage_mask: crate::HalaPipelineStageFlags2::ALL_COMMANDS,
+            ..crate::HalaImageBarrierInfo::for_image(&image)
+          }],
+        );
+      },
+      0,
+    )?;
+
+    Ok(image)
+  }
+
+  /// Upload data to the GPU only buffer through the staging buffer pool.
+  /// This is expensive and should not be done in a hot loop.
+  /// param buffer: The GPU only buffer.
+  /// param data: The data to be uploaded.
+  /// return: The result.
+  pub fn stage_and_upload_buffer<T: Copy>(
+    &self,
+    buffer: &crate::HalaBuffer,
+    data: &[T],
+  ) -> Result<(), HalaGfxError> {
+    // The whole buffer is copied from the staging buffer.
+    let size = buffer.size.max(std::mem::size_of_val(data) as u64);
+    let command_buffers = self.create_upload_command_buffers(&buffer.debug_name)?;
+    let mut staging_buffer_pool = self.staging_buffer_pool.borrow_mut();
+    let staging_buffer = staging_buffer_pool.acquire(size)?;
+    buffer.update_gpu_memory_with_buffer(data, staging_buffer, &command_buffers)
+  }
+
+  /// Upload data to the GPU only image through the staging buffer pool.
+  /// This is expensive and should not be done in a hot loop.
+  /// param image: The GPU only image.
+  /// param data: The data to be uploaded.
+  /// param dst_stage_mask: The destination stage mask.
+  /// param dst_access_mask: The destination access mask.
+  /// param dst_layout: The destination layout.
+  /// return: The result.
+  pub fn stage_and_upload_image<T: Copy>(
+    &self,
+    image: &HalaImage,
+    data: &[T],
+    dst_stage_mask: HalaPipelineStageFlags2,
+    dst_access_mask: crate::HalaAccessFlags2,
+    dst_layout: crate::HalaImageLayout,
+  ) -> Result<(), HalaGfxError> {
+    let command_buffers = self.create_upload_command_buffers(&image.debug_name)?;
+    let mut staging_buffer_pool = self.staging_buffer_pool.borrow_mut();
+    let staging_buffer = staging_buffer_pool.acquire(std::mem::size_of_val(data) as u64)?;
+    image.update_gpu_memory_with_buffer(
+      data,
+      dst_stage_mask,
+      dst_access_mask,
+      dst_layout,
+      staging_buffer,
+      &command_buffers,
+    )
+  }
+
+  /// Create the command buffer set for the uploads.
+  /// param debug_name: The debug name of the uploaded resource.
+  /// return: The command buffer set.
+  fn create_upload_command_buffers(&self, debug_name: &str) -> Result<HalaCommandBufferSet, HalaGfxError> {
+    HalaCommandBufferSet::new(
+      Rc::clone(&self.logical_device),
+      Rc::clone(&self.short_time_command_pools),
+      crate::HalaCommandBufferType::GRAPHICS,
+      crate::HalaCommandBufferLevel::PRIMARY,
+      1,
+      &format!("{}.upload_command_buffer", debug_name),
+    )
+  }
+
+  /// Create the font atlas image of the UI overlay.
+  /// The image is uploaded and transitioned to the shader read only layout.
+  /// param pixels: The RGBA8 pixels.
+  /// param width: The atlas width.
+  /// param height: The atlas height.
+  /// param debug_name: The debug name.
+  /// return: The font atlas image.
+  pub fn create_ui_font_atlas(
+    &self,
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+    debug_name: &str,
+  ) -> Result<HalaImage, HalaGfxError> {
+    let size = (width as usize).checked_mul(height as usize)
+      .and_then(|size| size.checked_mul(4));
+    if size != Some(pixels.len()) {
+      return Err(HalaGfxError::new("The size of the font atlas pixels does not match the atlas size.", None));
+    }
+
+    let image = HalaImage::new_2d(
+      Rc::clone(&self.logical_device),
+      crate::HalaImageUsageFlags::SAMPLED | crate::HalaImageUsageFlags::TRANSFER_DST,
+      HalaFormat::R8G8B8A8_UNORM,
+      width,
+      height,
+      1,
+      1,
+      crate::HalaMemoryLocation::GpuOnly,
+      debug_name,
+    )?;
+    self.stage_and_upload_image(
+      &image,
+      pixels,
+      HalaPipelineStageFlags2::FRAGMENT_SHADER,
+      crate::HalaAccessFlags2::SHADER_READ,
+      crate::HalaImageLayout::SHADER_READ_ONLY_OPTIMAL,
+    )?;
+
+    Ok(image)
+  }
+
+  /// Create the descriptor set layout of the UI overlay.
+  /// It has a combined image sampler of the texture at binding 0 for the fragment shader.
+  /// param debug_name: The debug name.
+  /// return: The descriptor set layout.
+  pub fn create_ui_descriptor_set_layout(&self, debug_name: &str) -> Result<crate::HalaDescriptorSetLayout, HalaGfxError> {
+    crate::HalaDescriptorSetLayout::new(
+      Rc::clone(&self.logical_device),
+      &[
+        crate::HalaDescriptorSetLayoutBinding::new(
+          0,
+          crate::HalaDescriptorType::COMBINED_IMAGE_SAMPLER,
+          1,
+          crate::HalaShaderStageFlags::FRAGMENT,
+          crate::HalaDescriptorBindingFlags::empty(),
+        ),
+      ],
+      debug_name,
+    )
+  }
+
+  /// Create the pipeline of the UI overlay.
+  /// It draws textured, alpha-blended and scissored triangles of HalaUiVertex without depth test,
+  /// and the vertex shader gets the orthographic projection by HalaUiPushConstants.
+  /// The viewport and scissor are dynamic states.
+  /// param color_format: The format of the render target.
+  /// param descriptor_set_layout: The descriptor set layout created by create_ui_descriptor_set_layout.
+  /// param vertex_shader: The vertex shader.
+  /// param fragment_shader: The fragment shader.
+  /// param debug_name: The debug name.
+  /// return: The graphics pipeline.
+  pub fn create_ui_pipeline(
+    &self,
+    color_format: HalaFormat,
+    descriptor_set_layout: &crate::HalaDescriptorSetLayout,
+    vertex_shader: &crate::HalaShader,
+    fragment_shader: &crate::HalaShader,
+    debug_name: &str,
+  ) -> Result<crate::HalaGraphicsPipeline, HalaGfxError> {
+    crate::HalaGraphicsPipeline::with_format_and_size(
+      Rc::clone(&self.logical_device),
+      &[color_format],
+      None,
+      self.gpu_req.width,
+      self.gpu_req.height,
+      &[descriptor_set_layout],
+      crate::HalaPipelineCreateFlags::default(),
+      &crate::HalaUiVertex::attribute_descriptions(),
+      &crate::HalaUiVertex::binding_descriptions(),
+      &[crate::HalaUiPushConstants::range()],
+      crate::HalaPrimitiveTopology::TRIANGLE_LIST,
+      &[crate::HalaBlendState::new(crate::HalaBlendFactor::SRC_ALPHA, crate::HalaBlendFactor::ONE_MINUS_SRC_ALPHA, crate::HalaBlendOp::ADD)],
+      &[crate::HalaBlendState::new(crate::HalaBlendFactor::ONE, crate::HalaBlendFactor::ONE_MINUS_SRC_ALPHA, crate::HalaBlendOp::ADD)],
+      &crate::HalaRasterizerState::default(),
+      &crate::HalaMultisampleState::default(),
+      &crate::HalaDepthState::new(false, false, crate::HalaCompareOp::ALWAYS),
+      None,
+      &[vertex_shader, fragment_shader],
+      &[crate::HalaDynamicState::VIEWPORT, crate::HalaDynamicState::SCISSOR],
+      None,
+      debug_name,
+    )
+  }
+
+  /// Create the vertex and index buffer ring of the UI overlay with one slot for each frame in flight.
+  /// The slot is indexed by the frame index of the frame context, not the swapchain image index.
+  /// param frames_in_flight: The number of the frames in flight.
+  /// param debug_name: The debug name.
+  /// return: The UI buffer ring.
+  pub fn create_ui_buffer_ring(&self, frames_in_flight: usize, debug_name: &str) -> Result<crate::HalaUiBufferRing, HalaGfxError> {
+    crate::HalaUiBufferRing::new(
+      Rc::clone(&self.logical_device),
+      frames_in_flight,
+      4096,
+      8192,
+      debug_name,
+    )
+  }
+
+  /// Create a frame context which manages the frames in flight with the main command pools.
+  /// param frames_in_flight: The number of the frames in flight.
+  /// return: The frame context.
+  pub fn create_frame_context(&self, frames_in_flight: usize) -> Result<crate::HalaFrameContext, HalaGfxError> {
+    crate::HalaFrameContext::new(
+      Rc::clone(&self.logical_device),
+      Rc::clone(&self.command_pools),
+      frames_in_flight,
+      "main.frame_context",
+    )
+  }
+
+  /// Initialize the frames in flight managed by the context.
+  /// The previous frames in flight are dropped after the device is idle.
+  /// param frames_in_flight: The number of the frames in flight, usually 2 or 3.
+  /// return: The result.
+  pub fn init_frames_in_flight(&mut self, frames_in_flight: usize) -> Result<(), HalaGfxError> {
+    if self.frame_context.is_some() {
+      self.logical_device.borrow().wait_idle()?;
+      self.frame_context = None;
+    }
+    self.frame_context = Some(self.create_frame_context(frames_in_flight)?);
+    Ok(())
+  }
+
+  /// Begin a frame in flight.
+  /// It waits for the in-flight fence of the frame and acquires the next swapchain image.
+  /// The command buffer of the frame is frame_context.command_buffers at the frame index of the token.
+  /// If the status of the token is out of date, the swapchain should be reset and the frame should be skipped.
+  /// return: The frame token.
+  pub fn begin_frame(&mut self) -> Result<crate::HalaFrameToken, HalaGfxError> {
+    self.instance.take_validation_error()?;
+    let frame_context = self.frame_context.as_mut()
+      .ok_or(HalaGfxError::new("The frames in flight are not initialized.", None))?;
+    frame_context.begin_frame(&self.swapchain)
+  }
+
+  /// End a frame in flight.
+  /// It submits the command buffer of the frame with the semaphores of the frame and presents the swapchain image.
+  /// param token: The frame token returned by the begin frame.
+  /// return: The swapchain status, the swapchain should be reset if it is not optimal.
+  pub fn end_frame(&mut self, token: crate::HalaFrameToken) -> Result<HalaSwapchainStatus, HalaGfxError> {
+    self.instance.take_validation_error()?;
+    let frame_context = self.frame_context.as_mut()
+      .ok_or(HalaGfxError::new("The frames in flight are not initialized.", None))?;
+    frame_context.end_frame(&self.swapchain, token)
+  }
+
   /// Prepare some sync signals for this frame.
-  pub fn prepare_frame(&self) -> Result<usize, HalaGfxError> {
-    let image_index = self.swapchain.acquire_next_image()?;
+  /// If the swapchain is out of date, the image index is invalid and the swapchain should be reset.
+  /// return: The index of the frame image and the swapchain status.
+  pub fn prepare_frame(&self) -> Result<(usize, HalaSwapchainStatus), HalaGfxError> {
+    self.instance.take_validation_error()?;
+    let (image_index, status) = self.swapchain.acquire_next_frame_image()?;
+    if status == HalaSwapchainStatus::OutOfDate {
+      return Ok((image_index, status));
+    }
     self.swapchain.wait_for_fence(image_index)?;
     self.swapchain.reset_fence(image_index)?;
-    Ok(image_index)
+    Ok((image_index, status))
   }
 
   /// Submit and present the frame.
   /// param index: The index of the frame image.
   /// param command_buffers: The graphics command buffer set.
-  pub fn submit_and_present_frame(&mut self, index: usize, command_buffers: &HalaCommandBufferSet) -> Result<(), HalaGfxError> {
+  /// return: The swapchain status.
+  pub fn submit_and_present_frame(&mut self, index: usize, command_buffers: &HalaCommandBufferSet) -> Result<HalaSwapchainStatus, HalaGfxError> {
+    self.instance.take_validation_error()?;
     self.swapchain.submit(command_buffers, index, 0)?;
-    self.swapchain.present(index as u32)?;
-    Ok(())
+    self.swapchain.present_frame(index as u32)
   }
 
   /// Get GPU frame time.
@@ -178,6 +528,9 @@ impl HalaContext {
       &self.surface)?;
 
     self.swapchain = std::mem::ManuallyDrop::new(swapchain);
+    if let Some(frame_context) = self.frame_context.as_mut() {
+      frame_context.reset_image_tracking();
+    }
 
     Ok(())
   }
@@ -285,7 +638,7 @@ impl HalaContext {
             dst_access_mask: crate::HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
             src_stage_mask: crate::HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | crate::HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS,
             dst_stage_mask: crate::HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | crate::HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS,
-            aspect_mask: crate::HalaImageAspectFlags::DEPTH | if self.swapchain.has_stencil { crate::HalaImageAspectFlags::STENCIL } else { crate::HalaImageAspectFlags::empty() },
+            aspect_mask: self.swapchain.depth_stencil_format.aspect_flags(),
             image: self.swapchain.depth_stencil_image,
             ..Default::default()
           }
@@ -317,7 +670,7 @@ impl HalaContext {
           dst_access_mask: crate::HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
           src_stage_mask: crate::HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | crate::HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS,
           dst_stage_mask: crate::HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | crate::HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS,
-          aspect_mask: crate::HalaImageAspectFlags::DEPTH | if self.swapchain.has_stencil { crate::HalaImageAspectFlags::STENCIL } else { crate::HalaImageAspectFlags::empty() },
+          aspect_mask: self.swapchain.depth_stencil_format.aspect_flags(),
           ..Default::default()
         }
       );
@@ -12,6 +12,10 @@ use crate::{
 pub struct HalaDescriptorPool {
   pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
   pub raw: vk::DescriptorPool,
+  /// The sets of a transient pool are not freed individually, they are recycled by resetting the pool.
+  pub is_transient: bool,
+  /// The generation is increased when the pool is reset, the sets allocated in the old generations are invalid.
+  pub(crate) generation: u64,
   debug_name: String,
 }
 
@@ -39,6 +43,60 @@ impl HalaDescriptorPool {
     descriptor_sizes: &[(crate::HalaDescriptorType, usize)],
     size: usize,
     debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_impl(logical_device, descriptor_sizes, size, false, debug_name)
+  }
+
+  /// Create a new transient descriptor pool.
+  /// The sets allocated from the pool are not freed individually, reset the pool to recycle all of them at once,
+  /// e.g. allocate the per-frame sets and reset the pool when the frame begins.
+  /// param logical_device: The logical device.
+  /// param descriptor_sizes: The descriptor sizes(description type, count).
+  /// param size: The size of the descriptor pool.
+  /// param debug_name: The debug name.
+  /// return: The descriptor pool.
+  pub fn new_transient(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    descriptor_sizes: &[(crate::HalaDescriptorType, usize)],
+    size: usize,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_impl(logical_device, descriptor_sizes, size, true, debug_name)
+  }
+
+  /// Reset the descriptor pool and recycle all the sets allocated from it.
+  /// The sets allocated before the reset become invalid and must not be used by the GPU any more.
+  /// return: The result.
+  pub fn reset(&mut self) -> Result<(), HalaGfxError> {
+    unsafe {
+      self.logical_device.borrow().raw.reset_descriptor_pool(self.raw, vk::DescriptorPoolResetFlags::empty())
+        .map_err(|err| HalaGfxError::new("Failed to reset descriptor pool.", Some(Box::new(err))))?;
+    }
+    self.generation += 1;
+
+    log::debug!("The HalaDescriptorPool \"{}\" is reset to generation {}.", self.debug_name, self.generation);
+    Ok(())
+  }
+
+  /// Get the generation of the descriptor pool.
+  /// return: The generation.
+  pub fn generation(&self) -> u64 {
+    self.generation
+  }
+
+  /// Create a new descriptor pool.
+  /// param logical_device: The logical device.
+  /// param descriptor_sizes: The descriptor sizes(description type, count).
+  /// param size: The size of the descriptor pool.
+  /// param is_transient: Whether the sets are only recycled by resetting the pool.
+  /// param debug_name: The debug name.
+  /// return: The descriptor pool.
+  fn new_impl(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    descriptor_sizes: &[(crate::HalaDescriptorType, usize)],
+    size: usize,
+    is_transient: bool,
+    debug_name: &str,
   ) -> Result<Self, HalaGfxError> {
     let raw = {
       let pool_sizes = descriptor_sizes.iter().map(|(descriptor_type, count)| {
@@ -47,9 +105,14 @@ impl HalaDescriptorPool {
           .descriptor_count(*count as u32)
       }).collect::<Vec<_>>();
       let logical_device = logical_device.borrow();
+      let flags = if is_transient {
+        vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND
+      } else {
+        vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET | vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND
+      };
       let create_info = vk::DescriptorPoolCreateInfo::default()
         .pool_sizes(&pool_sizes)
-        .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET | vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND)
+        .flags(flags)
         .max_sets(size as u32);
       unsafe {
         logical_device.raw.create_descriptor_pool(&create_info, None)
@@ -66,6 +129,8 @@ impl HalaDescriptorPool {
       Self {
         logical_device,
         raw,
+        is_transient,
+        generation: 0,
         debug_name: debug_name.to_string(),
       }
     )
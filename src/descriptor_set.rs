@@ -187,12 +187,39 @@ impl HalaDescriptorSetLayoutBinding {
     }
   }
 
+  /// Create a new bindless descriptor set layout binding.
+  /// The binding is partially bound, update after bind and has a variable descriptor count.
+  /// It must be the binding with the largest binding index in the layout.
+  /// param binding_index: The binding index.
+  /// param descriptor_type: The descriptor type.
+  /// param max_descriptor_count: The max descriptor count.
+  /// param stage_flags: The stage flags.
+  /// return: The descriptor set layout binding.
+  pub fn new_bindless(
+    binding_index: u32,
+    descriptor_type: HalaDescriptorType,
+    max_descriptor_count: u32,
+    stage_flags: HalaShaderStageFlags,
+  ) -> Self {
+    Self::new(
+      binding_index,
+      descriptor_type,
+      max_descriptor_count,
+      stage_flags,
+      HalaDescriptorBindingFlags::PARTIALLY_BOUND
+        | HalaDescriptorBindingFlags::UPDATE_AFTER_BIND
+        | HalaDescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT,
+    )
+  }
+
 }
 
 /// The descriptor set layout.
 pub struct HalaDescriptorSetLayout {
   pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
   pub raw: vk::DescriptorSetLayout,
+  pub max_variable_descriptor_count: u32,
+  pub is_push_descriptor: bool,
 
   pub(crate) debug_name: String,
 }
@@ -228,9 +255,68 @@ impl HalaDescriptorSetLayout {
   ) -> Result<Self, HalaGfxError>
   where DSLB: AsRef<HalaDescriptorSetLayoutBinding>
   {
+    Self::new_impl(logical_device, bindings, false, debug_name)
+  }
+
+  /// Create a new push descriptor set layout.
+  /// The descriptors are pushed by the command buffer and no descriptor set is allocated from the pool.
+  /// param logical_device: The logical device.
+  /// param bindings: The bindings(binding, description type, count, stage flags, binding flags).
+  /// param debug_name: The debug name.
+  /// return: The descriptor set layout.
+  pub fn new_push_descriptor<DSLB>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    bindings: &[DSLB],
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError>
+  where DSLB: AsRef<HalaDescriptorSetLayoutBinding>
+  {
+    if logical_device.borrow().push_descriptor_loader.is_none() {
+      return Err(HalaGfxError::new("The push descriptor extension is not supported.", None));
+    }
+    if bindings.iter().any(|binding| !binding.as_ref().binding_flags.is_empty()) {
+      return Err(HalaGfxError::new("The push descriptor set layout can not have binding flags.", None));
+    }
+    Self::new_impl(logical_device, bindings, true, debug_name)
+  }
+
+  /// Create a new descriptor set layout.
+  /// param logical_device: The logical device.
+  /// param bindings: The bindings(binding, description type, count, stage flags, binding flags).
+  /// param is_push_descriptor: Whether the layout is used for push descriptors.
+  /// param debug_name: The debug name.
+  /// return: The descriptor set layout.
+  fn new_impl<DSLB>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    bindings: &[DSLB],
+    is_push_descriptor: bool,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError>
+  where DSLB: AsRef<HalaDescriptorSetLayoutBinding>
+  {
+    // The update after bind limits only apply to the layout with the update after bind bindings.
+    let is_update_after_bind = bindings.iter()
+      .any(|binding| binding.as_ref().binding_flags.contains(HalaDescriptorBindingFlags::UPDATE_AFTER_BIND));
+    {
+      let logical_device = logical_device.borrow();
+      Self::check_limits(
+        &logical_device.limits,
+        &logical_device.descriptor_indexing_properties,
+        is_update_after_bind,
+        bindings,
+      )?;
+    }
+
+    let mut max_variable_descriptor_count = 0;
     let mut descriptor_set_layout_bindings = Vec::new();
     let mut descriptor_set_layout_bindings_flags = Vec::new();
     for binding in bindings {
+      if binding.as_ref().binding_flags.contains(HalaDescriptorBindingFlags::VARIABLE_DESCRIPTOR_COUNT) {
+        if bindings.iter().any(|other| other.as_ref().binding_index > binding.as_ref().binding_index) {
+          return Err(HalaGfxError::new("The variable descriptor count binding must be the last binding.", None));
+        }
+        max_variable_descriptor_count = binding.as_ref().descriptor_count;
+      }
       descriptor_set_layout_bindings.push(vk::DescriptorSetLayoutBinding::default()
         .binding(binding.as_ref().binding_index)
         .descriptor_type(vk::DescriptorType::from(binding.as_ref().descriptor_type))
@@ -244,7 +330,13 @@ impl HalaDescriptorSetLayout {
 
     let descriptor_set_layout_create_info = vk::DescriptorSetLayoutCreateInfo::default()
       .bindings(&descriptor_set_layout_bindings)
-      .flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL)
+      .flags(if is_push_descriptor {
+        vk::DescriptorSetLayoutCreateFlags::PUSH_DESCRIPTOR_KHR
+      } else if is_update_after_bind {
+        vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL
+      } else {
+        vk::DescriptorSetLayoutCreateFlags::empty()
+      })
       .push_next(&mut binding_flags_create_info);
 
     let raw = unsafe {
@@ -260,9 +352,229 @@ impl HalaDescriptorSetLayout {
     Ok(Self {
       logical_device,
       raw,
+      max_variable_descriptor_count,
+      is_push_descriptor,
       debug_name: debug_name.to_string(),
     })
   }
+
+  /// Check the descriptor counts against the limits of the device.
+  /// param limits: The device limits.
+  /// param properties: The descriptor indexing properties with the update after bind limits.
+  /// param is_update_after_bind: Whether the layout has the update after bind bindings.
+  /// param bindings: The bindings.
+  /// return: The result.
+  fn check_limits<DSLB>(
+    limits: &crate::HalaDeviceLimits,
+    properties: &vk::PhysicalDeviceDescriptorIndexingProperties,
+    is_update_after_bind: bool,
+    bindings: &[DSLB],
+  ) -> Result<(), HalaGfxError>
+  where DSLB: AsRef<HalaDescriptorSetLayoutBinding>
+  {
+    let count_of = |descriptor_types: &[HalaDescriptorType]| -> u32 {
+      bindings.iter()
+        .filter(|binding| descriptor_types.contains(&binding.as_ref().descriptor_type))
+        .map(|binding| binding.as_ref().descriptor_count)
+        .sum()
+    };
+    let max_counts = if is_update_after_bind {
+      [
+        properties.max_descriptor_set_update_after_bind_samplers,
+        properties.max_descriptor_set_update_after_bind_uniform_buffers,
+        properties.max_descriptor_set_update_after_bind_uniform_buffers_dynamic,
+        properties.max_descriptor_set_update_after_bind_storage_buffers,
+        properties.max_descriptor_set_update_after_bind_storage_buffers_dynamic,
+        properties.max_descriptor_set_update_after_bind_sampled_images,
+        properties.max_descriptor_set_update_after_bind_storage_images,
+        properties.max_descriptor_set_update_after_bind_input_attachments,
+      ]
+    } else {
+      [
+        limits.max_descriptor_set_samplers,
+        limits.max_descriptor_set_uniform_buffers,
+        limits.max_descriptor_set_uniform_buffers_dynamic,
+        limits.max_descriptor_set_storage_buffers,
+        limits.max_descriptor_set_storage_buffers_dynamic,
+        limits.max_descriptor_set_sampled_images,
+        limits.max_descriptor_set_storage_images,
+        limits.max_descriptor_set_input_attachments,
+      ]
+    };
+    let counts = [
+      ("samplers", count_of(&[HalaDescriptorType::SAMPLER, HalaDescriptorType::COMBINED_IMAGE_SAMPLER])),
+      ("uniform buffers", count_of(&[HalaDescriptorType::UNIFORM_BUFFER, HalaDescriptorType::UNIFORM_BUFFER_DYNAMIC])),
+      ("dynamic uniform buffers", count_of(&[HalaDescriptorType::UNIFORM_BUFFER_DYNAMIC])),
+      ("storage buffers", count_of(&[HalaDescriptorType::STORAGE_BUFFER, HalaDescriptorType::STORAGE_BUFFER_DYNAMIC])),
+      ("dynamic storage buffers", count_of(&[HalaDescriptorType::STORAGE_BUFFER_DYNAMIC])),
+      ("sampled images", count_of(&[HalaDescriptorType::SAMPLED_IMAGE, HalaDescriptorType::COMBINED_IMAGE_SAMPLER, HalaDescriptorType::UNIFORM_TEXEL_BUFFER])),
+      ("storage images", count_of(&[HalaDescriptorType::STORAGE_IMAGE, HalaDescriptorType::STORAGE_TEXEL_BUFFER])),
+      ("input attachments", count_of(&[HalaDescriptorType::INPUT_ATTACHMENT])),
+    ];
+    for ((name, count), limit) in counts.iter().zip(max_counts.iter()) {
+      if count > limit {
+        return Err(HalaGfxError::new(
+          &format!("The descriptor count {} of {} exceeds the device limit {}.", count, name, limit),
+          None,
+        ));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for descriptor set layout.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
+
+/// The descriptor write for the batched update.
+pub enum HalaWriteDescriptor<'a> {
+  UniformBuffer {
+    binding: u32,
+    buffers: &'a [&'a crate::HalaBuffer],
+  },
+  UniformBufferDynamic {
+    binding: u32,
+    buffers: &'a [&'a crate::HalaBuffer],
+    range: u64,
+  },
+  StorageBuffer {
+    binding: u32,
+    buffers: &'a [&'a crate::HalaBuffer],
+  },
+  CombinedImageSampler {
+    binding: u32,
+    images_and_samplers: &'a [(&'a crate::HalaImage, &'a crate::HalaSampler)],
+  },
+  StorageImage {
+    binding: u32,
+    images: &'a [&'a crate::HalaImage],
+  },
+  AccelerationStructure {
+    binding: u32,
+    acceleration_structures: &'a [&'a crate::HalaAccelerationStructure],
+  },
+}
+
+/// The implementation of the descriptor write.
+impl HalaWriteDescriptor<'_> {
+  /// Build the raw descriptor writes and pass them to the callback.
+  /// param dst_set: The destination descriptor set, null for push descriptors.
+  /// param writes: The descriptor writes.
+  /// param f: The callback with the raw descriptor writes.
+  /// return: The result of the callback.
+  pub(crate) fn with_raw_writes<R, F>(dst_set: vk::DescriptorSet, writes: &[Self], f: F) -> R
+    where F: FnOnce(&[vk::WriteDescriptorSet]) -> R
+  {
+    let mut buffer_infos = Vec::new();
+    let mut image_infos = Vec::new();
+    let mut acceleration_structures = Vec::new();
+    for write in writes.iter() {
+      match write {
+        Self::UniformBuffer { buffers, .. } | Self::StorageBuffer { buffers, .. } => {
+          buffer_infos.push(
+            buffers.iter()
+              .map(|buffer| vk::DescriptorBufferInfo::default()
+                .buffer(buffer.raw)
+                .range(vk::WHOLE_SIZE))
+              .collect::<Vec<_>>()
+          );
+        },
+        Self::UniformBufferDynamic { buffers, range, .. } => {
+          buffer_infos.push(
+            buffers.iter()
+              .map(|buffer| vk::DescriptorBufferInfo::default()
+                .buffer(buffer.raw)
+                .range(*range))
+              .collect::<Vec<_>>()
+          );
+        },
+        Self::CombinedImageSampler { images_and_samplers, .. } => {
+          image_infos.push(
+            images_and_samplers.iter()
+              .map(|(image, sampler)| vk::DescriptorImageInfo::default()
+                .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .image_view(image.view)
+                .sampler(sampler.raw))
+              .collect::<Vec<_>>()
+          );
+        },
+        Self::StorageImage { images, .. } => {
+          image_infos.push(
+            images.iter()
+              .map(|image| vk::DescriptorImageInfo::default()
+                .image_view(image.view)
+                .image_layout(vk::ImageLayout::GENERAL))
+              .collect::<Vec<_>>()
+          );
+        },
+        Self::AccelerationStructure { acceleration_structures: structures, .. } => {
+          acceleration_structures.push(
+            structures.iter()
+              .map(|acceleration_structure| acceleration_structure.raw)
+              .collect::<Vec<_>>()
+          );
+        },
+      }
+    }
+    let mut acceleration_structure_infos = acceleration_structures
+      .iter()
+      .map(|acceleration_structures| vk::WriteDescriptorSetAccelerationStructureKHR::default()
+        .acceleration_structures(acceleration_structures.as_slice()))
+      .collect::<Vec<_>>();
+
+    let mut buffer_info_iter = buffer_infos.iter();
+    let mut image_info_iter = image_infos.iter();
+    let mut acceleration_structure_info_iter = acceleration_structure_infos.iter_mut();
+    let descriptor_writes = writes
+      .iter()
+      .map(|write| {
+        let descriptor_write = vk::WriteDescriptorSet::default()
+          .dst_set(dst_set);
+        match write {
+          Self::UniformBuffer { binding, .. } => descriptor_write
+            .dst_binding(*binding)
+            .descriptor_type(vk::DescriptorType::UNIFORM_BUFFER)
+            .buffer_info(buffer_info_iter.next().unwrap()),
+          Self::UniformBufferDynamic { binding, .. } => descriptor_write
+            .dst_binding(*binding)
+            .descriptor_type(vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC)
+            .buffer_info(buffer_info_iter.next().unwrap()),
+          Self::StorageBuffer { binding, .. } => descriptor_write
+            .dst_binding(*binding)
+            .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+            .buffer_info(buffer_info_iter.next().unwrap()),
+          Self::CombinedImageSampler { binding, .. } => descriptor_write
+            .dst_binding(*binding)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .image_info(image_info_iter.next().unwrap()),
+          Self::StorageImage { binding, .. } => descriptor_write
+            .dst_binding(*binding)
+            .descriptor_type(vk::DescriptorType::STORAGE_IMAGE)
+            .image_info(image_info_iter.next().unwrap()),
+          Self::AccelerationStructure { binding, .. } => {
+            let acceleration_structure_info = acceleration_structure_info_iter.next().unwrap();
+            let descriptor_count = acceleration_structure_info.acceleration_structure_count;
+            let mut descriptor_write = descriptor_write
+              .dst_binding(*binding)
+              .descriptor_type(vk::DescriptorType::ACCELERATION_STRUCTURE_KHR)
+              .push_next(acceleration_structure_info);
+            descriptor_write.descriptor_count = descriptor_count;
+            descriptor_write
+          },
+        }
+      })
+      .collect::<Vec<_>>();
+
+    f(&descriptor_writes)
+  }
 }
 
 /// The descriptor set.
@@ -272,6 +584,8 @@ pub struct HalaDescriptorSet {
   pub layout: HalaDescriptorSetLayout,
   pub raw: Vec<vk::DescriptorSet>,
   pub is_static: bool,
+  /// The generation of the descriptor pool when the sets are allocated.
+  pub(crate) pool_generation: u64,
   pub(crate) debug_name: String,
 }
 
@@ -285,11 +599,15 @@ impl AsRef<HalaDescriptorSet> for HalaDescriptorSet {
 /// The Drop trait implementation of the descriptor set.
 impl Drop for HalaDescriptorSet {
   fn drop(&mut self) {
-    unsafe {
-      self.logical_device.borrow().raw.free_descriptor_sets(
-        self.descriptor_pool.borrow().raw,
-        &self.raw,
-      ).unwrap();
+    // The sets of a transient pool or a reset pool are recycled by the pool.
+    let descriptor_pool = self.descriptor_pool.borrow();
+    if !descriptor_pool.is_transient && descriptor_pool.generation == self.pool_generation {
+      unsafe {
+        self.logical_device.borrow().raw.free_descriptor_sets(
+          descriptor_pool.raw,
+          &self.raw,
+        ).unwrap();
+      }
     }
     log::debug!("A HalaDescriptorSet \"{}\" is dropped.", self.debug_name);
   }
@@ -313,6 +631,13 @@ impl HalaDescriptorSet {
     variable_descriptor_count: u32,
     debug_name: &str,
   ) -> Result<Self, HalaGfxError> {
+    // The variable descriptor count is ignored if the layout has no variable descriptor count binding.
+    if layout.max_variable_descriptor_count > 0 && variable_descriptor_count > layout.max_variable_descriptor_count {
+      return Err(HalaGfxError::new(
+        &format!("The variable descriptor count {} exceeds the max count {} of the layout.", variable_descriptor_count, layout.max_variable_descriptor_count),
+        None,
+      ));
+    }
     let variable_descriptor_counts = vec![variable_descriptor_count; count];
     let mut variable_descriptor_count_allocate_info: vk::DescriptorSetVariableDescriptorCountAllocateInfo = vk::DescriptorSetVariableDescriptorCountAllocateInfo::default()
       .descriptor_counts(&variable_descriptor_counts);
@@ -327,6 +652,7 @@ impl HalaDescriptorSet {
       logical_device.borrow().raw.allocate_descriptor_sets(&descriptor_set_allocate_info)
         .map_err(|err| HalaGfxError::new("Failed to allocate descriptor sets.", Some(Box::new(err))))?
     };
+    let pool_generation = descriptor_pool.borrow().generation;
     for (index, &descriptor_set) in raw.iter().enumerate() {
       logical_device.borrow().set_debug_name(
         descriptor_set,
@@ -341,10 +667,40 @@ impl HalaDescriptorSet {
       layout,
       raw,
       is_static: false,
+      pool_generation,
       debug_name: debug_name.to_string(),
     })
   }
 
+  /// Create a new transient descriptor set from a transient descriptor pool.
+  /// The sets are not freed individually, they are recycled when the pool is reset.
+  /// param logical_device: The logical device.
+  /// param descriptor_pool: The transient descriptor pool.
+  /// param layout: The descriptor set layout.
+  /// param count: The count of the descriptor set.
+  /// param variable_descriptor_count: The variable descriptor count.
+  /// param debug_name: The debug name.
+  /// return: The descriptor set.
+  pub fn new_transient(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    descriptor_pool: Rc<RefCell<HalaDescriptorPool>>,
+    layout: HalaDescriptorSetLayout,
+    count: usize,
+    variable_descriptor_count: u32,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    if !descriptor_pool.borrow().is_transient {
+      return Err(HalaGfxError::new("The transient descriptor set must be allocated from a transient descriptor pool.", None));
+    }
+    Self::new(logical_device, descriptor_pool, layout, count, variable_descriptor_count, debug_name)
+  }
+
+  /// Check if the descriptor set is still valid, it is invalidated when the descriptor pool is reset.
+  /// return: True if the descriptor set is valid.
+  pub fn is_valid(&self) -> bool {
+    self.descriptor_pool.borrow().generation == self.pool_generation
+  }
+
   /// Get the handle of the descriptor set.
   /// param index: The index.
   /// return: The handle.
@@ -378,6 +734,15 @@ impl HalaDescriptorSet {
     Ok(self_)
   }
 
+  /// Update the descriptors in one call.
+  /// param index: The index.
+  /// param writes: The descriptor writes.
+  pub fn update_batch(&self, index: usize, writes: &[HalaWriteDescriptor]) {
+    HalaWriteDescriptor::with_raw_writes(self.raw[index], writes, |descriptor_writes| unsafe {
+      self.logical_device.borrow().raw.update_descriptor_sets(descriptor_writes, &[]);
+    });
+  }
+
   /// Update the uniform buffer.
   /// param index: The index.
   /// param binding: The binding.
@@ -453,6 +818,27 @@ impl HalaDescriptorSet {
     }
   }
 
+  /// Update the storage image with the view of one mip level.
+  /// param index: The index.
+  /// param binding: The binding.
+  /// param image: The image created with seperate views.
+  /// param mip_level: The mip level.
+  pub fn update_storage_image_mip(&self, index: usize, binding: u32, image: &crate::HalaImage, mip_level: u32) {
+    let image_info = vk::DescriptorImageInfo::default()
+      .image_view(image.get_mip_view(mip_level))
+      .image_layout(vk::ImageLayout::GENERAL);
+
+    let descriptor_write = vk::WriteDescriptorSet::default()
+      .dst_set(self.raw[index])
+      .dst_binding(binding)
+      .descriptor_type(vk::DescriptorType::STORAGE_IMAGE)
+      .image_info(std::slice::from_ref(&image_info));
+
+    unsafe {
+      self.logical_device.borrow().raw.update_descriptor_sets(&[descriptor_write], &[]);
+    }
+  }
+
   /// Update the sampled images.
   /// param index: The index.
   /// param binding: The binding.
@@ -478,6 +864,33 @@ impl HalaDescriptorSet {
     }
   }
 
+  /// Update a range of the sampled image array, e.g. the bindless texture table.
+  /// param index: The index.
+  /// param binding: The binding.
+  /// param first_index: The first array element to update.
+  /// param images: The images.
+  pub fn update_image_array<T>(&self, index: usize, binding: u32, first_index: u32, images: &[T])
+    where T: AsRef<crate::HalaImage>
+  {
+    let image_infos = images
+      .iter()
+      .map(|image| vk::DescriptorImageInfo::default()
+        .image_view(image.as_ref().view)
+        .image_layout(vk::ImageLayout::GENERAL))
+      .collect::<Vec<_>>();
+
+    let descriptor_write = vk::WriteDescriptorSet::default()
+      .dst_set(self.raw[index])
+      .dst_binding(binding)
+      .dst_array_element(first_index)
+      .descriptor_type(vk::DescriptorType::SAMPLED_IMAGE)
+      .image_info(image_infos.as_slice());
+
+    unsafe {
+      self.logical_device.borrow().raw.update_descriptor_sets(&[descriptor_write], &[]);
+    }
+  }
+
   /// Update the sampled images with view.
   /// param index: The index.
   /// param binding: The binding.
@@ -639,4 +1052,48 @@ impl HalaDescriptorSet {
     }
   }
 
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn storage_buffers(count: u32, binding_flags: HalaDescriptorBindingFlags) -> HalaDescriptorSetLayoutBinding {
+    HalaDescriptorSetLayoutBinding {
+      binding_index: 0,
+      descriptor_type: HalaDescriptorType::STORAGE_BUFFER,
+      descriptor_count: count,
+      stage_flags: crate::HalaShaderStageFlags::COMPUTE,
+      binding_flags,
+    }
+  }
+
+  fn limits() -> (crate::HalaDeviceLimits, vk::PhysicalDeviceDescriptorIndexingProperties<'static>) {
+    let limits = crate::HalaDeviceLimits {
+      max_descriptor_set_storage_buffers: 8,
+      ..Default::default()
+    };
+    let properties = vk::PhysicalDeviceDescriptorIndexingProperties {
+      max_descriptor_set_update_after_bind_storage_buffers: 64,
+      ..Default::default()
+    };
+    (limits, properties)
+  }
+
+  #[test]
+  fn check_limits_uses_non_update_after_bind_limits() {
+    let (limits, properties) = limits();
+    let bindings = [storage_buffers(8, HalaDescriptorBindingFlags::PARTIALLY_BOUND)];
+    assert!(HalaDescriptorSetLayout::check_limits(&limits, &properties, false, &bindings).is_ok());
+    let bindings = [storage_buffers(9, HalaDescriptorBindingFlags::PARTIALLY_BOUND)];
+    assert!(HalaDescriptorSetLayout::check_limits(&limits, &properties, false, &bindings).is_err());
+  }
+
+  #[test]
+  fn check_limits_uses_update_after_bind_limits() {
+    let (limits, properties) = limits();
+    let bindings = [storage_buffers(64, HalaDescriptorBindingFlags::UPDATE_AFTER_BIND)];
+    assert!(HalaDescriptorSetLayout::check_limits(&limits, &properties, true, &bindings).is_ok());
+    let bindings = [storage_buffers(65, HalaDescriptorBindingFlags::UPDATE_AFTER_BIND)];
+    assert!(HalaDescriptorSetLayout::check_limits(&limits, &properties, true, &bindings).is_err());
+  }
+}
@@ -27,6 +27,14 @@ impl HalaGfxError {
     }
     false
   }
+  pub fn is_out_of_date(&self) -> bool {
+    if let Some(ref source) = self.source {
+      if let Some(err) = source.downcast_ref::<ash::vk::Result>() {
+        return matches!(err, &ash::vk::Result::ERROR_OUT_OF_DATE_KHR);
+      }
+    }
+    false
+  }
 }
 
 /// The implementation Display trait for the error type of the hala-gfx crate.
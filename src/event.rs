@@ -0,0 +1,74 @@
+use ash::vk;
+
+use crate::{
+  HalaGfxError,
+  HalaLogicalDevice,
+};
+
+/// The event for the split barrier.
+pub struct HalaEvent {
+  pub(crate) logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+  pub raw: vk::Event,
+  pub(crate) debug_name: String,
+}
+
+/// The AsRef implementation for event.
+impl AsRef<HalaEvent> for HalaEvent {
+  fn as_ref(&self) -> &HalaEvent {
+    self
+  }
+}
+
+/// The Drop implementation for event.
+impl Drop for HalaEvent {
+  fn drop(&mut self) {
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.destroy_event(self.raw, None);
+    }
+    log::debug!("The HalaEvent \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation for event.
+impl HalaEvent {
+  /// Create a new event.
+  /// The event can only be set, reset and waited by the command buffer.
+  /// param logical_device: The logical device.
+  /// param debug_name: The debug name.
+  /// return: The event.
+  pub fn new(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let event_info = vk::EventCreateInfo::default()
+      .flags(vk::EventCreateFlags::DEVICE_ONLY);
+    let raw = unsafe {
+      let logical_device = logical_device.borrow();
+      let event = logical_device.raw.create_event(&event_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create event.", Some(Box::new(err))))?;
+      logical_device.set_debug_name(
+        event,
+        debug_name,
+      ).map_err(|err| HalaGfxError::new("Failed to set debug name for event.", Some(Box::new(err))))?;
+      event
+    };
+
+    log::debug!("A HalaEvent \"{}\" is created.", debug_name);
+    Ok(Self {
+      logical_device,
+      raw,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for event.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
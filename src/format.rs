@@ -196,6 +196,122 @@ impl HalaFormat {
   pub const ASTC_12X12_SRGB_BLOCK: Self = Self(vk::Format::ASTC_12X12_SRGB_BLOCK.as_raw());
 }
 
+/// The implementation of the format.
+impl HalaFormat {
+  /// Check if the format is a depth only format.
+  /// return: True if the format is a depth only format.
+  pub fn is_depth(&self) -> bool {
+    matches!(*self, HalaFormat::D16_UNORM | HalaFormat::X8_D24_UNORM_PACK32 | HalaFormat::D32_SFLOAT)
+  }
+
+  /// Check if the format is a stencil only format.
+  /// return: True if the format is a stencil only format.
+  pub fn is_stencil(&self) -> bool {
+    *self == HalaFormat::S8_UINT
+  }
+
+  /// Check if the format is a combined depth stencil format.
+  /// return: True if the format is a combined depth stencil format.
+  pub fn is_depth_stencil(&self) -> bool {
+    matches!(*self, HalaFormat::D16_UNORM_S8_UINT | HalaFormat::D24_UNORM_S8_UINT | HalaFormat::D32_SFLOAT_S8_UINT)
+  }
+
+  /// Check if the format has the depth component.
+  /// return: True if the format has the depth component.
+  pub fn has_depth(&self) -> bool {
+    self.is_depth() || self.is_depth_stencil()
+  }
+
+  /// Check if the format has the stencil component.
+  /// return: True if the format has the stencil component.
+  pub fn has_stencil(&self) -> bool {
+    self.is_stencil() || self.is_depth_stencil()
+  }
+
+  /// Get the default depth stencil clear value.
+  /// param reverse_z: Whether the reverse-Z is used, the depth is cleared to 0.0 instead of 1.0.
+  /// return: The depth stencil clear value.
+  pub fn default_depth_clear(reverse_z: bool) -> crate::HalaClearDepthStencilValue {
+    crate::HalaClearDepthStencilValue {
+      depth: if reverse_z { 0.0 } else { 1.0 },
+      stencil: 0,
+    }
+  }
+
+  /// Get the image aspect flags of the format.
+  /// return: The image aspect flags.
+  pub fn aspect_flags(&self) -> crate::HalaImageAspectFlags {
+    let mut aspect_flags = crate::HalaImageAspectFlags::empty();
+    if self.has_depth() {
+      aspect_flags |= crate::HalaImageAspectFlags::DEPTH;
+    }
+    if self.has_stencil() {
+      aspect_flags |= crate::HalaImageAspectFlags::STENCIL;
+    }
+    if aspect_flags.is_empty() {
+      crate::HalaImageAspectFlags::COLOR
+    } else {
+      aspect_flags
+    }
+  }
+
+  /// Get the size in bytes of one texel when the image is copied to a buffer.
+  /// The combined depth stencil format returns the size of the depth aspect.
+  /// return: The texel size, None for the undefined and block compressed formats.
+  pub fn texel_size(&self) -> Option<u32> {
+    match *self {
+      HalaFormat::R4G4_UNORM_PACK8 | HalaFormat::R8_UNORM | HalaFormat::R8_SNORM | HalaFormat::R8_USCALED |
+      HalaFormat::R8_SSCALED | HalaFormat::R8_UINT | HalaFormat::R8_SINT | HalaFormat::R8_SRGB |
+      HalaFormat::S8_UINT => Some(1),
+      HalaFormat::R4G4B4A4_UNORM_PACK16 | HalaFormat::B4G4R4A4_UNORM_PACK16 |
+      HalaFormat::R5G6B5_UNORM_PACK16 | HalaFormat::B5G6R5_UNORM_PACK16 |
+      HalaFormat::R5G5B5A1_UNORM_PACK16 | HalaFormat::B5G5R5A1_UNORM_PACK16 |
+      HalaFormat::A1R5G5B5_UNORM_PACK16 | HalaFormat::R8G8_UNORM | HalaFormat::R8G8_SNORM |
+      HalaFormat::R8G8_USCALED | HalaFormat::R8G8_SSCALED | HalaFormat::R8G8_UINT | HalaFormat::R8G8_SINT |
+      HalaFormat::R8G8_SRGB | HalaFormat::R16_UNORM | HalaFormat::R16_SNORM | HalaFormat::R16_USCALED |
+      HalaFormat::R16_SSCALED | HalaFormat::R16_UINT | HalaFormat::R16_SINT | HalaFormat::R16_SFLOAT |
+      HalaFormat::D16_UNORM | HalaFormat::D16_UNORM_S8_UINT => Some(2),
+      HalaFormat::R8G8B8_UNORM | HalaFormat::R8G8B8_SNORM | HalaFormat::R8G8B8_USCALED |
+      HalaFormat::R8G8B8_SSCALED | HalaFormat::R8G8B8_UINT | HalaFormat::R8G8B8_SINT |
+      HalaFormat::R8G8B8_SRGB | HalaFormat::B8G8R8_UNORM | HalaFormat::B8G8R8_SNORM |
+      HalaFormat::B8G8R8_USCALED | HalaFormat::B8G8R8_SSCALED | HalaFormat::B8G8R8_UINT |
+      HalaFormat::B8G8R8_SINT | HalaFormat::B8G8R8_SRGB => Some(3),
+      HalaFormat::R8G8B8A8_UNORM | HalaFormat::R8G8B8A8_SNORM | HalaFormat::R8G8B8A8_USCALED |
+      HalaFormat::R8G8B8A8_SSCALED | HalaFormat::R8G8B8A8_UINT | HalaFormat::R8G8B8A8_SINT |
+      HalaFormat::R8G8B8A8_SRGB | HalaFormat::B8G8R8A8_UNORM | HalaFormat::B8G8R8A8_SNORM |
+      HalaFormat::B8G8R8A8_USCALED | HalaFormat::B8G8R8A8_SSCALED | HalaFormat::B8G8R8A8_UINT |
+      HalaFormat::B8G8R8A8_SINT | HalaFormat::B8G8R8A8_SRGB | HalaFormat::A8B8G8R8_UNORM_PACK32 |
+      HalaFormat::A8B8G8R8_SNORM_PACK32 | HalaFormat::A8B8G8R8_USCALED_PACK32 |
+      HalaFormat::A8B8G8R8_SSCALED_PACK32 | HalaFormat::A8B8G8R8_UINT_PACK32 |
+      HalaFormat::A8B8G8R8_SINT_PACK32 | HalaFormat::A8B8G8R8_SRGB_PACK32 |
+      HalaFormat::A2R10G10B10_UNORM_PACK32 | HalaFormat::A2R10G10B10_SNORM_PACK32 |
+      HalaFormat::A2R10G10B10_USCALED_PACK32 | HalaFormat::A2R10G10B10_SSCALED_PACK32 |
+      HalaFormat::A2R10G10B10_UINT_PACK32 | HalaFormat::A2R10G10B10_SINT_PACK32 |
+      HalaFormat::A2B10G10R10_UNORM_PACK32 | HalaFormat::A2B10G10R10_SNORM_PACK32 |
+      HalaFormat::A2B10G10R10_USCALED_PACK32 | HalaFormat::A2B10G10R10_SSCALED_PACK32 |
+      HalaFormat::A2B10G10R10_UINT_PACK32 | HalaFormat::A2B10G10R10_SINT_PACK32 | HalaFormat::R16G16_UNORM |
+      HalaFormat::R16G16_SNORM | HalaFormat::R16G16_USCALED | HalaFormat::R16G16_SSCALED |
+      HalaFormat::R16G16_UINT | HalaFormat::R16G16_SINT | HalaFormat::R16G16_SFLOAT | HalaFormat::R32_UINT |
+      HalaFormat::R32_SINT | HalaFormat::R32_SFLOAT | HalaFormat::B10G11R11_UFLOAT_PACK32 |
+      HalaFormat::E5B9G9R9_UFLOAT_PACK32 | HalaFormat::X8_D24_UNORM_PACK32 | HalaFormat::D32_SFLOAT |
+      HalaFormat::D24_UNORM_S8_UINT | HalaFormat::D32_SFLOAT_S8_UINT => Some(4),
+      HalaFormat::R16G16B16_UNORM | HalaFormat::R16G16B16_SNORM | HalaFormat::R16G16B16_USCALED |
+      HalaFormat::R16G16B16_SSCALED | HalaFormat::R16G16B16_UINT | HalaFormat::R16G16B16_SINT |
+      HalaFormat::R16G16B16_SFLOAT => Some(6),
+      HalaFormat::R16G16B16A16_UNORM | HalaFormat::R16G16B16A16_SNORM | HalaFormat::R16G16B16A16_USCALED |
+      HalaFormat::R16G16B16A16_SSCALED | HalaFormat::R16G16B16A16_UINT | HalaFormat::R16G16B16A16_SINT |
+      HalaFormat::R16G16B16A16_SFLOAT | HalaFormat::R32G32_UINT | HalaFormat::R32G32_SINT |
+      HalaFormat::R32G32_SFLOAT | HalaFormat::R64_UINT | HalaFormat::R64_SINT | HalaFormat::R64_SFLOAT => Some(8),
+      HalaFormat::R32G32B32_UINT | HalaFormat::R32G32B32_SINT | HalaFormat::R32G32B32_SFLOAT => Some(12),
+      HalaFormat::R32G32B32A32_UINT | HalaFormat::R32G32B32A32_SINT | HalaFormat::R32G32B32A32_SFLOAT |
+      HalaFormat::R64G64_UINT | HalaFormat::R64G64_SINT | HalaFormat::R64G64_SFLOAT => Some(16),
+      HalaFormat::R64G64B64_UINT | HalaFormat::R64G64B64_SINT | HalaFormat::R64G64B64_SFLOAT => Some(24),
+      HalaFormat::R64G64B64A64_UINT | HalaFormat::R64G64B64A64_SINT | HalaFormat::R64G64B64A64_SFLOAT => Some(32),
+      _ => None,
+    }
+  }
+}
+
 impl std::fmt::Display for HalaFormat {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(f, "{:?}", vk::Format::from_raw(self.0))
@@ -822,4 +938,57 @@ impl<'de> Deserialize<'de> for HalaFormat {
 
     deserializer.deserialize_str(HalaFormatVisitor)
   }
-}
\ No newline at end of file
+}
+/// The format feature flags.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HalaFormatFeatureFlags(u32);
+crate::hala_bitflags_wrapped!(HalaFormatFeatureFlags, u32);
+impl HalaFormatFeatureFlags {
+  pub const SAMPLED_IMAGE: Self = Self(vk::FormatFeatureFlags::SAMPLED_IMAGE.as_raw());
+  pub const STORAGE_IMAGE: Self = Self(vk::FormatFeatureFlags::STORAGE_IMAGE.as_raw());
+  pub const STORAGE_IMAGE_ATOMIC: Self = Self(vk::FormatFeatureFlags::STORAGE_IMAGE_ATOMIC.as_raw());
+  pub const UNIFORM_TEXEL_BUFFER: Self = Self(vk::FormatFeatureFlags::UNIFORM_TEXEL_BUFFER.as_raw());
+  pub const STORAGE_TEXEL_BUFFER: Self = Self(vk::FormatFeatureFlags::STORAGE_TEXEL_BUFFER.as_raw());
+  pub const STORAGE_TEXEL_BUFFER_ATOMIC: Self = Self(vk::FormatFeatureFlags::STORAGE_TEXEL_BUFFER_ATOMIC.as_raw());
+  pub const VERTEX_BUFFER: Self = Self(vk::FormatFeatureFlags::VERTEX_BUFFER.as_raw());
+  pub const COLOR_ATTACHMENT: Self = Self(vk::FormatFeatureFlags::COLOR_ATTACHMENT.as_raw());
+  pub const COLOR_ATTACHMENT_BLEND: Self = Self(vk::FormatFeatureFlags::COLOR_ATTACHMENT_BLEND.as_raw());
+  pub const DEPTH_STENCIL_ATTACHMENT: Self = Self(vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT.as_raw());
+  pub const BLIT_SRC: Self = Self(vk::FormatFeatureFlags::BLIT_SRC.as_raw());
+  pub const BLIT_DST: Self = Self(vk::FormatFeatureFlags::BLIT_DST.as_raw());
+  pub const SAMPLED_IMAGE_FILTER_LINEAR: Self = Self(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR.as_raw());
+  pub const TRANSFER_SRC: Self = Self(vk::FormatFeatureFlags::TRANSFER_SRC.as_raw());
+  pub const TRANSFER_DST: Self = Self(vk::FormatFeatureFlags::TRANSFER_DST.as_raw());
+  pub const SAMPLED_IMAGE_FILTER_MINMAX: Self = Self(vk::FormatFeatureFlags::SAMPLED_IMAGE_FILTER_MINMAX.as_raw());
+  pub const ACCELERATION_STRUCTURE_VERTEX_BUFFER: Self = Self(vk::FormatFeatureFlags::ACCELERATION_STRUCTURE_VERTEX_BUFFER_KHR.as_raw());
+}
+
+impl std::convert::From<vk::FormatFeatureFlags> for HalaFormatFeatureFlags {
+  fn from(v: vk::FormatFeatureFlags) -> Self {
+    Self(v.as_raw())
+  }
+}
+
+impl std::convert::From<HalaFormatFeatureFlags> for vk::FormatFeatureFlags {
+  fn from(v: HalaFormatFeatureFlags) -> Self {
+    Self::from_raw(v.0)
+  }
+}
+
+/// The properties of a format.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct HalaFormatProperties {
+  pub linear_tiling_features: HalaFormatFeatureFlags,
+  pub optimal_tiling_features: HalaFormatFeatureFlags,
+  pub buffer_features: HalaFormatFeatureFlags,
+}
+
+impl std::convert::From<vk::FormatProperties> for HalaFormatProperties {
+  fn from(v: vk::FormatProperties) -> Self {
+    Self {
+      linear_tiling_features: v.linear_tiling_features.into(),
+      optimal_tiling_features: v.optimal_tiling_features.into(),
+      buffer_features: v.buffer_features.into(),
+    }
+  }
+}
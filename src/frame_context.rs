@@ -0,0 +1,201 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use ash::vk;
+
+use crate::{
+  HalaCommandBufferSet,
+  HalaCommandPools,
+  HalaFrameRing,
+  HalaGfxError,
+  HalaLogicalDevice,
+  HalaSemaphore,
+  HalaSwapchain,
+  HalaSwapchainStatus,
+};
+
+/// The token of the frame which is returned by the begin frame.
+/// It must be passed back to the end frame.
+#[derive(Clone, Copy, Debug)]
+pub struct HalaFrameToken {
+  pub frame_index: usize,
+  pub image_index: usize,
+  pub status: HalaSwapchainStatus,
+}
+
+/// The implementation of the frame token.
+impl HalaFrameToken {
+  /// Whether the swapchain should be recreated.
+  /// return: True if the swapchain is suboptimal or out of date.
+  pub fn need_recreate_swapchain(&self) -> bool {
+    self.status != HalaSwapchainStatus::Optimal
+  }
+}
+
+/// The semaphores of a frame in flight.
+pub struct HalaFrameSemaphores {
+  pub image_available: HalaSemaphore,
+  pub render_finished: HalaSemaphore,
+}
+
+/// The frame context manages the frames in flight.
+/// Each frame owns a command buffer, and the frame ring keeps its semaphores and in-flight fence.
+/// It codifies the acquire -> record -> submit -> present pattern.
+pub struct HalaFrameContext {
+  pub ring: HalaFrameRing<HalaFrameSemaphores>,
+  pub command_buffers: HalaCommandBufferSet,
+  pub(crate) image_fences: Vec<vk::Fence>,
+  pub(crate) debug_name: String,
+}
+
+/// The Drop trait implementation of the frame context.
+/// The frame ring is dropped before the command buffers, so the frames in flight are finished before they are freed.
+impl Drop for HalaFrameContext {
+  fn drop(&mut self) {
+    log::debug!("A HalaFrameContext \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation of the frame context.
+impl HalaFrameContext {
+  /// Create a new frame context.
+  /// param logical_device: The logical device.
+  /// param command_pools: The command pools.
+  /// param frames_in_flight: The number of the frames in flight, usually 2 or 3.
+  /// param debug_name: The debug name.
+  /// return: The frame context.
+  pub fn new(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    command_pools: Rc<RefCell<HalaCommandPools>>,
+    frames_in_flight: usize,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let ring = HalaFrameRing::new(
+      Rc::clone(&logical_device),
+      frames_in_flight,
+      |frame_index| Ok(HalaFrameSemaphores {
+        image_available: HalaSemaphore::new(
+          Rc::clone(&logical_device),
+          &format!("{}.image_available_{}", debug_name, frame_index),
+        )?,
+        render_finished: HalaSemaphore::new(
+          Rc::clone(&logical_device),
+          &format!("{}.render_finished_{}", debug_name, frame_index),
+        )?,
+      }),
+      debug_name,
+    )?;
+
+    let command_buffers = HalaCommandBufferSet::new(
+      Rc::clone(&logical_device),
+      command_pools,
+      crate::HalaCommandBufferType::GRAPHICS,
+      crate::HalaCommandBufferLevel::PRIMARY,
+      frames_in_flight,
+      &format!("{}.command_buffer", debug_name),
+    )?;
+
+    log::debug!("A HalaFrameContext \"{}\" with {} frames in flight is created.", debug_name, frames_in_flight);
+    Ok(Self {
+      ring,
+      command_buffers,
+      image_fences: Vec::new(),
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Begin a frame.
+  /// It waits for the in-flight fence of the current frame and acquires the next swapchain image.
+  /// If the status of the token is out of date, the image index is invalid,
+  /// the caller should recreate the swapchain and skip this frame without calling the end frame.
+  /// If the status is suboptimal, the image is acquired and the frame must still be ended before recreating the swapchain.
+  /// param swapchain: The swapchain.
+  /// return: The frame token.
+  pub fn begin_frame(&mut self, swapchain: &HalaSwapchain) -> Result<HalaFrameToken, HalaGfxError> {
+    let frame_index = self.ring.current_frame;
+    let fence = self.ring.current_fence();
+    if self.image_fences.len() != swapchain.num_of_images {
+      self.image_fences = vec![vk::Fence::null(); swapchain.num_of_images];
+    }
+
+    self.ring.wait()?;
+
+    let result = unsafe {
+      swapchain.swapchain_loader.acquire_next_image(
+        swapchain.swapchain,
+        u64::MAX,
+        self.ring.current_resources().image_available.raw,
+        vk::Fence::null(),
+      )
+    };
+    let (image_index, status) = match result {
+      Ok((image_index, false)) => (image_index as usize, HalaSwapchainStatus::Optimal),
+      Ok((image_index, true)) => (image_index as usize, HalaSwapchainStatus::Suboptimal),
+      Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+        return Ok(HalaFrameToken { frame_index, image_index: 0, status: HalaSwapchainStatus::OutOfDate });
+      },
+      Err(err) => return Err(HalaGfxError::new("Failed to acquire next image.", Some(Box::new(err)))),
+    };
+
+    // The swapchain image may still be used by another frame in flight.
+    let image_fence = self.image_fences[image_index];
+    if image_fence != vk::Fence::null() && image_fence != fence {
+      unsafe {
+        self.ring.logical_device.borrow().raw.wait_for_fences(&[image_fence], true, u64::MAX)
+          .map_err(|err| HalaGfxError::new("Failed to wait for fence.", Some(Box::new(err))))?;
+      }
+    }
+    self.image_fences[image_index] = fence;
+
+    Ok(HalaFrameToken { frame_index, image_index, status })
+  }
+
+  /// End a frame.
+  /// It submits the command buffer of the frame and presents the swapchain image, then moves to the next frame.
+  /// param swapchain: The swapchain.
+  /// param token: The frame token returned by the begin frame.
+  /// return: The swapchain status, the caller should recreate the swapchain if it is not optimal.
+  pub fn end_frame(&mut self, swapchain: &HalaSwapchain, token: HalaFrameToken) -> Result<HalaSwapchainStatus, HalaGfxError> {
+    if token.status == HalaSwapchainStatus::OutOfDate {
+      return Err(HalaGfxError::new("The frame with an out of date swapchain can not be ended.", None));
+    }
+    if token.frame_index != self.ring.current_frame {
+      return Err(HalaGfxError::new("The frame token does not match the current frame.", None));
+    }
+
+    let semaphores = self.ring.current_resources();
+    let submit_info = vk::SubmitInfo::default()
+      .command_buffers(std::slice::from_ref(&self.command_buffers.raw[token.frame_index]))
+      .wait_semaphores(std::slice::from_ref(&semaphores.image_available.raw))
+      .wait_dst_stage_mask(std::slice::from_ref(&vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT))
+      .signal_semaphores(std::slice::from_ref(&semaphores.render_finished.raw));
+    let queue = self.ring.logical_device.borrow().get_graphics_queue(0);
+    // The fence is only reset when the frame is going to be submitted,
+    // so the frame skipped by the caller never leaves it unsignaled.
+    self.ring.submit_with_fence(queue, &submit_info)?;
+
+    let swapchains = [swapchain.swapchain];
+    let image_indices = [token.image_index as u32];
+    let present_info = vk::PresentInfoKHR::default()
+      .wait_semaphores(std::slice::from_ref(&semaphores.render_finished.raw))
+      .swapchains(&swapchains)
+      .image_indices(&image_indices);
+    let result = unsafe {
+      swapchain.swapchain_loader.queue_present(queue, &present_info)
+    };
+    self.ring.advance();
+
+    match result {
+      Ok(false) => Ok(token.status),
+      Ok(true) => Ok(HalaSwapchainStatus::Suboptimal),
+      Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(HalaSwapchainStatus::OutOfDate),
+      Err(err) => Err(HalaGfxError::new("Failed to present queue.", Some(Box::new(err)))),
+    }
+  }
+
+  /// Forget the swapchain images tracked by the frame context.
+  /// It should be called after the swapchain is recreated.
+  pub fn reset_image_tracking(&mut self) {
+    self.image_fences.clear();
+  }
+}
@@ -0,0 +1,183 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use ash::vk;
+
+use crate::{
+  HalaCommandBufferSet,
+  HalaGfxError,
+  HalaLogicalDevice,
+};
+
+/// The ring of the frames in flight.
+/// Each frame slot owns its own resources(e.g. uniform buffers and descriptor sets) and fence,
+/// so the CPU never writes the resources which are still read by the GPU.
+pub struct HalaFrameRing<T> {
+  pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  pub frames_in_flight: usize,
+  pub current_frame: usize,
+  pub resources: Vec<T>,
+  pub fences: Vec<vk::Fence>,
+  pub(crate) debug_name: String,
+}
+
+/// The Drop trait implementation of the frame ring.
+impl<T> Drop for HalaFrameRing<T> {
+  fn drop(&mut self) {
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      // The fences can only be destroyed after the GPU finishes the submitted frames.
+      if let Err(err) = logical_device.raw.wait_for_fences(&self.fences, true, u64::MAX) {
+        log::error!("Failed to wait the fences of HalaFrameRing \"{}\": {}", self.debug_name, err);
+      }
+      for fence in self.fences.iter() {
+        logical_device.raw.destroy_fence(*fence, None);
+      }
+    }
+    log::debug!("A HalaFrameRing \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation of the frame ring.
+impl<T> HalaFrameRing<T> {
+  /// Create a new frame ring.
+  /// param logical_device: The logical device.
+  /// param frames_in_flight: The number of the frames in flight, usually 2 or 3.
+  /// param factory: The factory to create the resources of each frame slot.
+  /// param debug_name: The debug name.
+  /// return: The frame ring.
+  pub fn new<F>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    frames_in_flight: usize,
+    mut factory: F,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError>
+    where F: FnMut(usize) -> Result<T, HalaGfxError>
+  {
+    if frames_in_flight == 0 {
+      return Err(HalaGfxError::new("The number of the frames in flight must be greater than 0.", None));
+    }
+
+    let mut resources = Vec::with_capacity(frames_in_flight);
+    for frame_index in 0..frames_in_flight {
+      resources.push(factory(frame_index)?);
+    }
+
+    let mut fences = Vec::with_capacity(frames_in_flight);
+    for frame_index in 0..frames_in_flight {
+      let fence_info = vk::FenceCreateInfo::default()
+        .flags(vk::FenceCreateFlags::SIGNALED);
+      let fence = unsafe {
+        let logical_device = logical_device.borrow();
+        let fence = logical_device.raw.create_fence(&fence_info, None)
+          .map_err(|err| HalaGfxError::new("Failed to create fence.", Some(Box::new(err))))?;
+        logical_device.set_debug_name(
+          fence,
+          &format!("{}.fence_{}", debug_name, frame_index),
+        ).map_err(|err| HalaGfxError::new("Failed to set debug name for fence.", Some(Box::new(err))))?;
+        fence
+      };
+      fences.push(fence);
+    }
+
+    log::debug!("A HalaFrameRing \"{}\" with {} frames in flight is created.", debug_name, frames_in_flight);
+    Ok(Self {
+      logical_device,
+      frames_in_flight,
+      current_frame: 0,
+      resources,
+      fences,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Wait for the GPU to finish the previous use of the current frame slot.
+  /// return: The result.
+  pub fn wait(&self) -> Result<(), HalaGfxError> {
+    unsafe {
+      self.logical_device.borrow().raw.wait_for_fences(
+        &[self.fences[self.current_frame]],
+        true,
+        u64::MAX,
+      ).map_err(|err| HalaGfxError::new("Failed to wait for fence.", Some(Box::new(err))))?;
+    }
+    Ok(())
+  }
+
+  /// Record the current frame.
+  /// It waits for the current frame slot and hands its resources to the record function.
+  /// param record_fn: The record function with the frame index and the resources of the frame.
+  /// return: The result of the record function.
+  pub fn record<R, F>(&mut self, record_fn: F) -> Result<R, HalaGfxError>
+    where F: FnOnce(usize, &mut T) -> Result<R, HalaGfxError>
+  {
+    self.wait()?;
+    record_fn(self.current_frame, &mut self.resources[self.current_frame])
+  }
+
+  /// Get the resources of the current frame.
+  /// return: The resources.
+  pub fn current_resources(&self) -> &T {
+    &self.resources[self.current_frame]
+  }
+
+  /// Get the fence of the current frame.
+  /// It should be signaled by the submission of the current frame if the frame is submitted by the caller.
+  /// return: The fence.
+  pub fn current_fence(&self) -> vk::Fence {
+    self.fences[self.current_frame]
+  }
+
+  /// Reset the fence of the current frame before it is submitted.
+  /// return: The result.
+  pub fn reset_fence(&self) -> Result<(), HalaGfxError> {
+    unsafe {
+      self.logical_device.borrow().raw.reset_fences(
+        &[self.fences[self.current_frame]]
+      ).map_err(|err| HalaGfxError::new("Failed to reset fence.", Some(Box::new(err))))?;
+    }
+    Ok(())
+  }
+
+  /// Submit the command buffer of the current frame to the graphics queue and move to the next frame.
+  /// The command buffer set should have one command buffer for each frame in flight.
+  /// param command_buffers: The graphics command buffer set.
+  /// param queue_index: The queue index.
+  /// return: The result.
+  pub fn submit(&mut self, command_buffers: &HalaCommandBufferSet, queue_index: u32) -> Result<(), HalaGfxError> {
+    let submit_info = vk::SubmitInfo::default()
+      .command_buffers(std::slice::from_ref(&command_buffers.raw[self.current_frame]));
+    let queue = self.logical_device.borrow().get_graphics_queue(queue_index);
+    self.submit_with_fence(queue, &submit_info)?;
+    self.advance();
+
+    Ok(())
+  }
+
+  /// Submit to the queue with the fence of the current frame.
+  /// The fence is reset right before the submission. If the submission fails,
+  /// the fence is signaled again by an empty submission so the next wait on the frame slot does not hang.
+  /// param queue: The queue.
+  /// param submit_info: The submit information.
+  /// return: The result.
+  pub(crate) fn submit_with_fence(&self, queue: vk::Queue, submit_info: &vk::SubmitInfo) -> Result<(), HalaGfxError> {
+    let fence = self.fences[self.current_frame];
+    self.reset_fence()?;
+    let logical_device = self.logical_device.borrow();
+    unsafe {
+      if let Err(err) = logical_device.raw.queue_submit(queue, std::slice::from_ref(submit_info), fence) {
+        if let Err(signal_err) = logical_device.raw.queue_submit(queue, &[], fence) {
+          log::error!("Failed to signal the fence of HalaFrameRing \"{}\" after the failed submission: {}", self.debug_name, signal_err);
+        }
+        return Err(HalaGfxError::new("Failed to submit queue.", Some(Box::new(err))));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Move to the next frame.
+  pub fn advance(&mut self) {
+    self.current_frame = (self.current_frame + 1) % self.frames_in_flight;
+  }
+}
@@ -5,7 +5,11 @@ use crate::{
   HalaBuffer,
   HalaCommandBufferSet,
   HalaFormat,
+  HalaFormatFeatureFlags,
   HalaGfxError,
+  HalaImageAspectFlags,
+  HalaImageView,
+  HalaImageViewDesc,
   HalaImageLayout,
   HalaLogicalDevice,
   HalaMemoryLocation,
@@ -26,6 +30,7 @@ impl HalaImageUsageFlags {
   pub const DEPTH_STENCIL_ATTACHMENT: Self = Self(vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT.as_raw());
   pub const TRANSIENT_ATTACHMENT: Self = Self(vk::ImageUsageFlags::TRANSIENT_ATTACHMENT.as_raw());
   pub const INPUT_ATTACHMENT: Self = Self(vk::ImageUsageFlags::INPUT_ATTACHMENT.as_raw());
+  pub const FRAGMENT_SHADING_RATE_ATTACHMENT: Self = Self(vk::ImageUsageFlags::FRAGMENT_SHADING_RATE_ATTACHMENT_KHR.as_raw());
 }
 
 impl std::convert::From<vk::ImageUsageFlags> for HalaImageUsageFlags {
@@ -40,6 +45,75 @@ impl std::convert::From<HalaImageUsageFlags> for vk::ImageUsageFlags {
   }
 }
 
+/// The image view type.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HalaImageViewType(i32);
+impl HalaImageViewType {
+  pub const TYPE_1D: Self = Self(vk::ImageViewType::TYPE_1D.as_raw());
+  pub const TYPE_2D: Self = Self(vk::ImageViewType::TYPE_2D.as_raw());
+  pub const TYPE_3D: Self = Self(vk::ImageViewType::TYPE_3D.as_raw());
+  pub const CUBE: Self = Self(vk::ImageViewType::CUBE.as_raw());
+  pub const TYPE_1D_ARRAY: Self = Self(vk::ImageViewType::TYPE_1D_ARRAY.as_raw());
+  pub const TYPE_2D_ARRAY: Self = Self(vk::ImageViewType::TYPE_2D_ARRAY.as_raw());
+  pub const CUBE_ARRAY: Self = Self(vk::ImageViewType::CUBE_ARRAY.as_raw());
+}
+
+impl std::convert::From<vk::ImageViewType> for HalaImageViewType {
+  fn from(v: vk::ImageViewType) -> Self {
+    Self(v.as_raw())
+  }
+}
+
+impl std::convert::From<HalaImageViewType> for vk::ImageViewType {
+  fn from(v: HalaImageViewType) -> Self {
+    Self::from_raw(v.0)
+  }
+}
+
+/// The options of the views created with the image.
+/// The main view covers all mips and layers, the per-mip and per-array views cover one mip or one layer each.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HalaImageViewOptions {
+  pub main: bool,
+  pub per_mip: bool,
+  pub per_array: bool,
+}
+
+/// The Default trait implementation of the image view options.
+/// Only the main view is created, which matches the plain constructors.
+impl Default for HalaImageViewOptions {
+  fn default() -> Self {
+    Self {
+      main: true,
+      per_mip: false,
+      per_array: false,
+    }
+  }
+}
+
+/// The implementation of the image view options.
+impl HalaImageViewOptions {
+  /// Create the options which create all views.
+  /// return: The image view options.
+  pub fn all() -> Self {
+    Self {
+      main: true,
+      per_mip: true,
+      per_array: true,
+    }
+  }
+
+  /// Create the options which create no views, the views can be created later with create_sub_view.
+  /// return: The image view options.
+  pub fn none() -> Self {
+    Self {
+      main: false,
+      per_mip: false,
+      per_array: false,
+    }
+  }
+}
+
 /// The image.
 pub struct HalaImage {
   pub(crate) logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
@@ -47,14 +121,20 @@ pub struct HalaImage {
   pub view: vk::ImageView,
   pub extent: vk::Extent3D,
   pub format: HalaFormat,
+  /// The usage of the image, it is empty if the image is created from a raw image.
+  pub usage: HalaImageUsageFlags,
+  /// The additional formats of the views, the image is created with the mutable format if it is not empty.
+  pub view_formats: Vec<HalaFormat>,
   pub mip_levels: u32,
   pub mip_views: Vec<vk::ImageView>,
   pub array_layers: u32,
   pub array_views: Vec<vk::ImageView>,
+  pub sub_views: Vec<vk::ImageView>,
   pub memory_requirements: vk::MemoryRequirements,
   pub allocation: gpu_allocator::vulkan::Allocation,
   pub memory_location: gpu_allocator::MemoryLocation,
   pub size: u64,
+  pub(crate) owns_image: bool,
   pub(crate) debug_name: String,
 }
 
@@ -76,10 +156,15 @@ impl Drop for HalaImage {
       for array_view in self.array_views.iter() {
         logical_device.raw.destroy_image_view(*array_view, None);
       }
+      for sub_view in self.sub_views.iter() {
+        logical_device.raw.destroy_image_view(*sub_view, None);
+      }
       logical_device.raw.destroy_image_view(self.view, None);
-      let allocation = std::mem::take(&mut self.allocation);
-      logical_device.gpu_allocator.free(allocation).unwrap();
-      logical_device.raw.destroy_image(self.raw, None);
+      if self.owns_image {
+        let allocation = std::mem::take(&mut self.allocation);
+        logical_device.gpu_allocator.free(allocation).unwrap();
+        logical_device.raw.destroy_image(self.raw, None);
+      }
     }
     log::debug!("The HalaImage \"{}\" is dropped.", self.debug_name);
   }
@@ -119,7 +204,8 @@ impl HalaImage {
       height,
       mip_levels,
       array_layers,
-      false,
+      HalaImageViewOptions::default(),
+      &[],
       HalaSampleCountFlags::TYPE_1,
       memory_location,
       false,
@@ -158,7 +244,131 @@ impl HalaImage {
       height,
       mip_levels,
       array_layers,
-      true,
+      HalaImageViewOptions::all(),
+      &[],
+      HalaSampleCountFlags::TYPE_1,
+      memory_location,
+      false,
+      debug_name,
+    )
+  }
+
+  /// Create a 2D image with dedicated memory and the specified views.
+  /// param logical_device: The logical device.
+  /// param usage: The image usage flags.
+  /// param format: The image format.
+  /// param width: The image width.
+  /// param height: The image height.
+  /// param mip_levels: The number of mip levels.
+  /// param array_layers: The number of array layers.
+  /// param view_options: The options of the views to create.
+  /// param memory_location: The memory location.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_2d_with_view_options(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    usage: HalaImageUsageFlags,
+    format: HalaFormat,
+    width: u32,
+    height: u32,
+    mip_levels: u32,
+    array_layers: u32,
+    view_options: HalaImageViewOptions,
+    memory_location: HalaMemoryLocation,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_2d_impl(
+      logical_device,
+      usage,
+      format,
+      width,
+      height,
+      mip_levels,
+      array_layers,
+      view_options,
+      &[],
+      HalaSampleCountFlags::TYPE_1,
+      memory_location,
+      false,
+      debug_name,
+    )
+  }
+
+  /// Create a 2D image with dedicated memory and without any view.
+  /// It is used for the images which are only copied or whose views are created with create_sub_view.
+  /// param logical_device: The logical device.
+  /// param usage: The image usage flags.
+  /// param format: The image format.
+  /// param width: The image width.
+  /// param height: The image height.
+  /// param mip_levels: The number of mip levels.
+  /// param array_layers: The number of array layers.
+  /// param memory_location: The memory location.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_2d_minimal(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    usage: HalaImageUsageFlags,
+    format: HalaFormat,
+    width: u32,
+    height: u32,
+    mip_levels: u32,
+    array_layers: u32,
+    memory_location: HalaMemoryLocation,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_2d_with_view_options(
+      logical_device,
+      usage,
+      format,
+      width,
+      height,
+      mip_levels,
+      array_layers,
+      HalaImageViewOptions::none(),
+      memory_location,
+      debug_name,
+    )
+  }
+
+  /// Create a 2D image with dedicated memory which can be viewed with the additional formats.
+  /// It is used to write the linear data through a UNORM storage view and sample it through a SRGB view.
+  /// param logical_device: The logical device.
+  /// param usage: The image usage flags.
+  /// param format: The image format.
+  /// param width: The image width.
+  /// param height: The image height.
+  /// param mip_levels: The number of mip levels.
+  /// param array_layers: The number of array layers.
+  /// param view_formats: The additional view formats, they must be compatible with the image format.
+  /// param memory_location: The memory location.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_2d_with_view_formats(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    usage: HalaImageUsageFlags,
+    format: HalaFormat,
+    width: u32,
+    height: u32,
+    mip_levels: u32,
+    array_layers: u32,
+    view_formats: &[HalaFormat],
+    memory_location: HalaMemoryLocation,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_2d_impl(
+      logical_device,
+      usage,
+      format,
+      width,
+      height,
+      mip_levels,
+      array_layers,
+      HalaImageViewOptions::default(),
+      view_formats,
       HalaSampleCountFlags::TYPE_1,
       memory_location,
       false,
@@ -197,7 +407,8 @@ impl HalaImage {
       height,
       mip_levels,
       array_layers,
-      false,
+      HalaImageViewOptions::default(),
+      &[],
       HalaSampleCountFlags::TYPE_1,
       memory_location,
       true,
@@ -236,7 +447,8 @@ impl HalaImage {
       height,
       mip_levels,
       array_layers,
-      true,
+      HalaImageViewOptions::all(),
+      &[],
       HalaSampleCountFlags::TYPE_1,
       memory_location,
       true,
@@ -277,7 +489,8 @@ impl HalaImage {
       height,
       mip_levels,
       array_layers,
-      false,
+      HalaImageViewOptions::default(),
+      &[],
       samples,
       memory_location,
       false,
@@ -318,7 +531,8 @@ impl HalaImage {
       height,
       mip_levels,
       array_layers,
-      true,
+      HalaImageViewOptions::all(),
+      &[],
       samples,
       memory_location,
       false,
@@ -359,7 +573,8 @@ impl HalaImage {
       height,
       mip_levels,
       array_layers,
-      false,
+      HalaImageViewOptions::default(),
+      &[],
       samples,
       memory_location,
       true,
@@ -400,7 +615,8 @@ impl HalaImage {
       height,
       mip_levels,
       array_layers,
-      true,
+      HalaImageViewOptions::all(),
+      &[],
       samples,
       memory_location,
       true,
@@ -416,7 +632,7 @@ impl HalaImage {
   /// param height: The image height.
   /// param mip_levels: The number of mip levels.
   /// param array_layers: The number of array layers.
-  /// param require_seperate_views: Whether to require seperate views.
+  /// param view_options: The options of the views to create.
   /// param samples: The number of samples.
   /// param memory_location: The memory location.
   /// param use_managed_memory: Whether to use managed memory.
@@ -431,13 +647,24 @@ impl HalaImage {
     height: u32,
     mip_levels: u32,
     array_layers: u32,
-    require_seperate_views: bool,
+    view_options: HalaImageViewOptions,
+    view_formats: &[HalaFormat],
     samples: HalaSampleCountFlags,
     memory_location: HalaMemoryLocation,
     use_managed_memory: bool,
     debug_name: &str,
   ) -> Result<Self, HalaGfxError> {
+    Self::check_format_usage(&logical_device.borrow(), format, usage)?;
+    // The format list contains the image format itself and the additional view formats.
+    let format_list = if view_formats.is_empty() {
+      Vec::new()
+    } else {
+      std::iter::once(format).chain(view_formats.iter().copied()).map(vk::Format::from).collect::<Vec<_>>()
+    };
+    let mut format_list_info = vk::ImageFormatListCreateInfo::default()
+      .view_formats(format_list.as_slice());
     let image_info = vk::ImageCreateInfo::default()
+      .flags(if view_formats.is_empty() { vk::ImageCreateFlags::empty() } else { vk::ImageCreateFlags::MUTABLE_FORMAT })
       .image_type(vk::ImageType::TYPE_2D)
       .format(format.into())
       .extent(vk::Extent3D {
@@ -452,6 +679,11 @@ impl HalaImage {
       .usage(usage.into())
       .sharing_mode(vk::SharingMode::EXCLUSIVE)
       .initial_layout(vk::ImageLayout::UNDEFINED);
+    let image_info = if view_formats.is_empty() {
+      image_info
+    } else {
+      image_info.push_next(&mut format_list_info)
+    };
 
     let (image, memory_requirements, allocation) = Self::create_and_allocate(
       &logical_device,
@@ -461,14 +693,14 @@ impl HalaImage {
       debug_name,
     )?;
 
-    let (view, mip_views, array_views) = Self::create_view(
+    let (view, mip_views, array_views) = Self::create_views(
       &logical_device,
       image,
       vk::ImageViewType::TYPE_2D,
       format.into(),
       mip_levels,
       array_layers,
-      require_seperate_views,
+      view_options,
       debug_name,
     )?;
 
@@ -487,14 +719,168 @@ impl HalaImage {
       mip_views,
       array_layers,
       array_views,
+      sub_views: Vec::new(),
+      usage,
+      view_formats: view_formats.to_vec(),
       memory_requirements,
       allocation,
       memory_location: memory_location.into(),
       size: memory_requirements.size,
+      owns_image: true,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Create a sparse 2D image.
+  /// The memory of the image is not bound at creation, the caller manages the pages and binds them with HalaLogicalDevice::bind_sparse_image.
+  /// param logical_device: The logical device.
+  /// param usage: The image usage flags.
+  /// param format: The image format.
+  /// param width: The image width.
+  /// param height: The image height.
+  /// param mip_levels: The number of mip levels.
+  /// param array_layers: The number of array layers.
+  /// param is_residency: Whether the image can be partially resident.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_2d_sparse(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    usage: HalaImageUsageFlags,
+    format: HalaFormat,
+    width: u32,
+    height: u32,
+    mip_levels: u32,
+    array_layers: u32,
+    is_residency: bool,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::check_format_usage(&logical_device.borrow(), format, usage)?;
+    let mut create_flags = vk::ImageCreateFlags::SPARSE_BINDING;
+    {
+      let logical_device = logical_device.borrow();
+      if logical_device.physical_device_features.sparse_binding != vk::TRUE {
+        return Err(HalaGfxError::new("The sparse binding feature is not supported.", None));
+      }
+      logical_device.check_sparse_binding_queue()?;
+      if is_residency {
+        if logical_device.physical_device_features.sparse_residency_image2_d != vk::TRUE {
+          return Err(HalaGfxError::new("The sparse residency image 2D feature is not supported.", None));
+        }
+        create_flags |= vk::ImageCreateFlags::SPARSE_RESIDENCY;
+      }
+    }
+
+    let extent = vk::Extent3D {
+      width,
+      height,
+      depth: 1,
+    };
+    let image_info = vk::ImageCreateInfo::default()
+      .flags(create_flags)
+      .image_type(vk::ImageType::TYPE_2D)
+      .format(format.into())
+      .extent(extent)
+      .mip_levels(mip_levels)
+      .array_layers(array_layers)
+      .samples(vk::SampleCountFlags::TYPE_1)
+      .tiling(vk::ImageTiling::OPTIMAL)
+      .usage(usage.into())
+      .sharing_mode(vk::SharingMode::EXCLUSIVE)
+      .initial_layout(vk::ImageLayout::UNDEFINED);
+    let (image, memory_requirements) = unsafe {
+      let logical_device = logical_device.borrow();
+      let image = logical_device.raw.create_image(&image_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create sparse image.", Some(Box::new(err))))?;
+      logical_device.set_debug_name(
+        image,
+        debug_name,
+      ).map_err(|err| HalaGfxError::new("Failed to set debug name for sparse image.", Some(Box::new(err))))?;
+      (image, logical_device.raw.get_image_memory_requirements(image))
+    };
+
+    let (view, mip_views, array_views) = Self::create_views(
+      &logical_device,
+      image,
+      vk::ImageViewType::TYPE_2D,
+      format.into(),
+      mip_levels,
+      array_layers,
+      HalaImageViewOptions::default(),
+      debug_name,
+    )?;
+
+    log::debug!("A sparse HalaImage \"{}\" with resolution [{} x {}], format {} and page size {} is created.", debug_name, width, height, format, memory_requirements.alignment);
+    Ok(Self {
+      logical_device,
+      raw: image,
+      view,
+      extent,
+      format,
+      mip_levels,
+      mip_views,
+      array_layers,
+      array_views,
+      sub_views: Vec::new(),
+      usage,
+      view_formats: Vec::new(),
+      memory_requirements,
+      // The null allocation is skipped when the image is dropped, the pages are freed by the caller.
+      allocation: gpu_allocator::vulkan::Allocation::default(),
+      memory_location: gpu_allocator::MemoryLocation::GpuOnly,
+      size: memory_requirements.size,
+      owns_image: true,
       debug_name: debug_name.to_string(),
     })
   }
 
+  /// Get the sparse memory requirements of the sparse image.
+  /// Each requirement describes the page granularity of an aspect and the mip tail which must be bound as opaque memory.
+  /// return: The sparse memory requirements.
+  pub fn sparse_memory_requirements(&self) -> Vec<vk::SparseImageMemoryRequirements> {
+    unsafe {
+      self.logical_device.borrow().raw.get_image_sparse_memory_requirements(self.raw)
+    }
+  }
+
+  /// Get the page size of the sparse image, which is the granularity of the memory binds in bytes.
+  /// return: The page size in bytes.
+  pub fn sparse_page_size(&self) -> u64 {
+    self.memory_requirements.alignment
+  }
+
+  /// Allocate the GPU only memory of the sparse image pages.
+  /// param page_count: The number of the pages.
+  /// param debug_name: The debug name of the allocation.
+  /// return: The allocation.
+  pub fn allocate_sparse_pages(&self, page_count: u64, debug_name: &str) -> Result<gpu_allocator::vulkan::Allocation, HalaGfxError> {
+    let requirements = vk::MemoryRequirements {
+      size: self.sparse_page_size() * page_count,
+      alignment: self.sparse_page_size(),
+      memory_type_bits: self.memory_requirements.memory_type_bits,
+    };
+    self.logical_device.borrow_mut().gpu_allocator
+      .allocate(
+        &gpu_allocator::vulkan::AllocationCreateDesc {
+          name: debug_name,
+          requirements,
+          location: gpu_allocator::MemoryLocation::GpuOnly,
+          linear: false,
+          allocation_scheme: gpu_allocator::vulkan::AllocationScheme::GpuAllocatorManaged,
+        }
+      ).map_err(|err| HalaGfxError::new("Failed to allocate sparse image pages.", Some(Box::new(err))))
+  }
+
+  /// Free the memory of the sparse image pages.
+  /// The pages must be unbound and not used by the GPU.
+  /// param allocation: The allocation.
+  /// return: The result.
+  pub fn free_sparse_pages(&self, allocation: gpu_allocator::vulkan::Allocation) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow_mut().gpu_allocator
+      .free(allocation)
+      .map_err(|err| HalaGfxError::new("Failed to free sparse image pages.", Some(Box::new(err))))
+  }
+
   /// Create a 3D image with dedicated memory.
   /// param logical_device: The logical device.
   /// param usage: The image usage flags.
@@ -586,6 +972,7 @@ impl HalaImage {
     use_managed_memory: bool,
     debug_name: &str,
   ) -> Result<Self, HalaGfxError> {
+    Self::check_format_usage(&logical_device.borrow(), format, usage)?;
     let image_info = vk::ImageCreateInfo::default()
       .image_type(vk::ImageType::TYPE_3D)
       .format(format.into())
@@ -610,14 +997,14 @@ impl HalaImage {
       debug_name,
     )?;
 
-    let (view, mip_views, array_views) = Self::create_view(
+    let (view, mip_views, array_views) = Self::create_views(
       &logical_device,
       image,
       vk::ImageViewType::TYPE_3D,
       format.into(),
       1,
       1,
-      false,
+      HalaImageViewOptions::default(),
       debug_name,
     )?;
 
@@ -636,34 +1023,293 @@ impl HalaImage {
       mip_views,
       array_layers: 1,
       array_views,
+      sub_views: Vec::new(),
+      usage,
+      view_formats: Vec::new(),
       memory_requirements,
       allocation,
       memory_location: memory_location.into(),
       size: memory_requirements.size,
+      owns_image: true,
       debug_name: debug_name.to_string(),
     })
   }
 
-  /// Create and allocate an image.
+  /// Create a cube image with dedicated memory.
   /// param logical_device: The logical device.
-  /// param image_info: The image create info.
+  /// param usage: The image usage flags.
+  /// param format: The image format.
+  /// param size: The width and height of each face.
+  /// param mip_levels: The number of mip levels.
   /// param memory_location: The memory location.
-  /// param use_managed_memory: Whether to use managed memory.
   /// param debug_name: The debug name.
-  /// return: The result(image, memory requirements, allocation).
-  fn create_and_allocate(
-    logical_device: &std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
-    image_info: vk::ImageCreateInfo<'_>,
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_cube(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    usage: HalaImageUsageFlags,
+    format: HalaFormat,
+    size: u32,
+    mip_levels: u32,
     memory_location: HalaMemoryLocation,
-    use_managed_memory: bool,
     debug_name: &str,
-  ) -> Result<(vk::Image, vk::MemoryRequirements, gpu_allocator::vulkan::Allocation), HalaGfxError> {
-    let (image,memory_requirements) = unsafe {
-      let logical_device = logical_device.borrow();
-      let image = logical_device.raw.create_image(&image_info, None)
-        .map_err(|err| HalaGfxError::new("Failed to create image.", Some(Box::new(err))))?;
-      logical_device.set_debug_name(
-        image,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_cube_impl(
+      logical_device,
+      usage,
+      format,
+      size,
+      mip_levels,
+      1,
+      false,
+      HalaImageViewOptions::default(),
+      memory_location,
+      debug_name,
+    )
+  }
+
+  /// Create a cube image with dedicated memory and seperate views.
+  /// The array views are the 2D views of each face.
+  /// param logical_device: The logical device.
+  /// param usage: The image usage flags.
+  /// param format: The image format.
+  /// param size: The width and height of each face.
+  /// param mip_levels: The number of mip levels.
+  /// param memory_location: The memory location.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_cube_with_seperate_views(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    usage: HalaImageUsageFlags,
+    format: HalaFormat,
+    size: u32,
+    mip_levels: u32,
+    memory_location: HalaMemoryLocation,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_cube_impl(
+      logical_device,
+      usage,
+      format,
+      size,
+      mip_levels,
+      1,
+      false,
+      HalaImageViewOptions::all(),
+      memory_location,
+      debug_name,
+    )
+  }
+
+  /// Create a cube array image with dedicated memory.
+  /// param logical_device: The logical device.
+  /// param usage: The image usage flags.
+  /// param format: The image format.
+  /// param size: The width and height of each face.
+  /// param mip_levels: The number of mip levels.
+  /// param cube_count: The number of cubes.
+  /// param memory_location: The memory location.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_cube_array(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    usage: HalaImageUsageFlags,
+    format: HalaFormat,
+    size: u32,
+    mip_levels: u32,
+    cube_count: u32,
+    memory_location: HalaMemoryLocation,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_cube_impl(
+      logical_device,
+      usage,
+      format,
+      size,
+      mip_levels,
+      cube_count,
+      true,
+      HalaImageViewOptions::default(),
+      memory_location,
+      debug_name,
+    )
+  }
+
+  /// Create a cube or cube array image.
+  /// param logical_device: The logical device.
+  /// param usage: The image usage flags.
+  /// param format: The image format.
+  /// param size: The width and height of each face.
+  /// param mip_levels: The number of mip levels.
+  /// param cube_count: The number of cubes.
+  /// param is_array: Whether to create a cube array view.
+  /// param view_options: The options of the views to create.
+  /// param memory_location: The memory location.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  fn new_cube_impl(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    usage: HalaImageUsageFlags,
+    format: HalaFormat,
+    size: u32,
+    mip_levels: u32,
+    cube_count: u32,
+    is_array: bool,
+    view_options: HalaImageViewOptions,
+    memory_location: HalaMemoryLocation,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::check_format_usage(&logical_device.borrow(), format, usage)?;
+    let array_layers = cube_count * 6;
+    let image_info = vk::ImageCreateInfo::default()
+      .flags(vk::ImageCreateFlags::CUBE_COMPATIBLE)
+      .image_type(vk::ImageType::TYPE_2D)
+      .format(format.into())
+      .extent(vk::Extent3D {
+        width: size,
+        height: size,
+        depth: 1,
+      })
+      .mip_levels(mip_levels)
+      .array_layers(array_layers)
+      .samples(vk::SampleCountFlags::TYPE_1)
+      .tiling(vk::ImageTiling::OPTIMAL)
+      .usage(usage.into())
+      .sharing_mode(vk::SharingMode::EXCLUSIVE)
+      .initial_layout(vk::ImageLayout::UNDEFINED);
+
+    let (image, memory_requirements, allocation) = Self::create_and_allocate(
+      &logical_device,
+      image_info,
+      memory_location,
+      false,
+      debug_name,
+    )?;
+
+    let (view, mip_views, array_views) = Self::create_views(
+      &logical_device,
+      image,
+      if is_array { vk::ImageViewType::CUBE_ARRAY } else { vk::ImageViewType::CUBE },
+      format.into(),
+      mip_levels,
+      array_layers,
+      view_options,
+      debug_name,
+    )?;
+
+    log::debug!("A HalaImage \"{}\" with {} cube(s) of resolution [{} x {}], format {} is created.", debug_name, cube_count, size, size, format);
+    Ok(Self {
+      logical_device,
+      raw: image,
+      view,
+      extent: vk::Extent3D {
+        width: size,
+        height: size,
+        depth: 1,
+      },
+      format,
+      mip_levels,
+      mip_views,
+      array_layers,
+      array_views,
+      sub_views: Vec::new(),
+      usage,
+      view_formats: Vec::new(),
+      memory_requirements,
+      allocation,
+      memory_location: memory_location.into(),
+      size: memory_requirements.size,
+      owns_image: true,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Create an image from an existing raw image, e.g. an external or interop image.
+  /// Only the views are created, the memory is managed by the creator of the raw image.
+  /// param logical_device: The logical device.
+  /// param raw: The raw image.
+  /// param extent: The image extent.
+  /// param format: The image format.
+  /// param mip_levels: The number of mip levels.
+  /// param array_layers: The number of array layers.
+  /// param owns_image: Whether to destroy the raw image when the image is dropped.
+  /// param debug_name: The debug name.
+  /// return: The image.
+  #[allow(clippy::too_many_arguments)]
+  pub fn from_raw(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    raw: vk::Image,
+    extent: vk::Extent3D,
+    format: HalaFormat,
+    mip_levels: u32,
+    array_layers: u32,
+    owns_image: bool,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let view_type = if extent.depth > 1 {
+      vk::ImageViewType::TYPE_3D
+    } else if array_layers > 1 {
+      vk::ImageViewType::TYPE_2D_ARRAY
+    } else {
+      vk::ImageViewType::TYPE_2D
+    };
+    let (view, mip_views, array_views) = Self::create_views(
+      &logical_device,
+      raw,
+      view_type,
+      format.into(),
+      mip_levels,
+      array_layers,
+      HalaImageViewOptions::default(),
+      debug_name,
+    )?;
+
+    log::debug!("A HalaImage \"{}\" from raw image of resolution [{} x {} x {}], format {} is created.", debug_name, extent.width, extent.height, extent.depth, format);
+    Ok(Self {
+      logical_device,
+      raw,
+      view,
+      extent,
+      format,
+      mip_levels,
+      mip_views,
+      array_layers,
+      array_views,
+      sub_views: Vec::new(),
+      usage: HalaImageUsageFlags::empty(),
+      view_formats: Vec::new(),
+      memory_requirements: vk::MemoryRequirements::default(),
+      allocation: gpu_allocator::vulkan::Allocation::default(),
+      memory_location: gpu_allocator::MemoryLocation::Unknown,
+      size: 0,
+      owns_image,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Create and allocate an image.
+  /// param logical_device: The logical device.
+  /// param image_info: The image create info.
+  /// param memory_location: The memory location.
+  /// param use_managed_memory: Whether to use managed memory.
+  /// param debug_name: The debug name.
+  /// return: The result(image, memory requirements, allocation).
+  fn create_and_allocate(
+    logical_device: &std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    image_info: vk::ImageCreateInfo<'_>,
+    memory_location: HalaMemoryLocation,
+    use_managed_memory: bool,
+    debug_name: &str,
+  ) -> Result<(vk::Image, vk::MemoryRequirements, gpu_allocator::vulkan::Allocation), HalaGfxError> {
+    let (image,memory_requirements) = unsafe {
+      let logical_device = logical_device.borrow();
+      let image = logical_device.raw.create_image(&image_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create image.", Some(Box::new(err))))?;
+      logical_device.set_debug_name(
+        image,
         debug_name,
       ).map_err(|err| HalaGfxError::new("Failed to set debug name for image.", Some(Box::new(err))))?;
       (image, logical_device.raw.get_image_memory_requirements(image))
@@ -688,24 +1334,78 @@ impl HalaImage {
     Ok((image, memory_requirements, allocation))
   }
 
-  /// Create an image view.
+  /// Get the single aspect of the format used by the view and the copy.
+  /// The depth aspect is chosen for the combined depth stencil format.
+  /// param format: The format.
+  /// return: The aspect flags.
+  pub(crate) fn single_aspect_for_format(format: HalaFormat) -> vk::ImageAspectFlags {
+    let aspect_mask = format.aspect_flags();
+    if aspect_mask.contains(HalaImageAspectFlags::DEPTH) {
+      vk::ImageAspectFlags::DEPTH
+    } else {
+      aspect_mask.into()
+    }
+  }
+
+  /// Check the optimal tiling features of the format support the image usage.
+  /// param logical_device: The logical device.
+  /// param format: The image format.
+  /// param usage: The image usage flags.
+  /// return: The result.
+  fn check_format_usage(
+    logical_device: &HalaLogicalDevice,
+    format: HalaFormat,
+    usage: HalaImageUsageFlags,
+  ) -> Result<(), HalaGfxError> {
+    let features = logical_device.format_properties(format).optimal_tiling_features;
+    let requirements = [
+      (HalaImageUsageFlags::TRANSFER_SRC, HalaFormatFeatureFlags::TRANSFER_SRC, "transfer source"),
+      (HalaImageUsageFlags::TRANSFER_DST, HalaFormatFeatureFlags::TRANSFER_DST, "transfer destination"),
+      (HalaImageUsageFlags::SAMPLED, HalaFormatFeatureFlags::SAMPLED_IMAGE, "sampled image"),
+      (HalaImageUsageFlags::STORAGE, HalaFormatFeatureFlags::STORAGE_IMAGE, "storage image"),
+      (HalaImageUsageFlags::COLOR_ATTACHMENT, HalaFormatFeatureFlags::COLOR_ATTACHMENT, "color attachment"),
+      (HalaImageUsageFlags::DEPTH_STENCIL_ATTACHMENT, HalaFormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT, "depth stencil attachment"),
+    ];
+    for (usage_flag, feature_flag, name) in requirements.iter() {
+      if usage.contains(*usage_flag) && !features.contains(*feature_flag) {
+        return Err(HalaGfxError::new(
+          &format!("The format {} does not support the {} usage.", format, name),
+          None,
+        ));
+      }
+    }
+    // The input attachment can be either a color or a depth stencil attachment.
+    if usage.contains(HalaImageUsageFlags::INPUT_ATTACHMENT)
+      && !features.intersects(HalaFormatFeatureFlags::COLOR_ATTACHMENT | HalaFormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+    {
+      return Err(HalaGfxError::new(
+        &format!("The format {} does not support the input attachment usage.", format),
+        None,
+      ));
+    }
+
+    Ok(())
+  }
+
+  /// Create the image views.
   /// param logical_device: The logical device.
   /// param image: The image.
   /// param view_type: The image view type.
   /// param format: The image format.
   /// param mip_levels: The number of mip levels.
   /// param array_layers: The number of array layers.
-  /// param require_seperate_views: Whether to require seperate views.
+  /// param view_options: The options of the views to create.
   /// param debug_name: The debug name.
   /// return: The image view.
-  fn create_view(
+  #[allow(clippy::too_many_arguments)]
+  fn create_views(
     logical_device: &std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
     image: vk::Image,
     view_type: vk::ImageViewType,
     format: vk::Format,
     mip_levels: u32,
     array_layers: u32,
-    require_seperate_views: bool,
+    view_options: HalaImageViewOptions,
     debug_name: &str,
   ) -> Result<
     (
@@ -715,38 +1415,44 @@ impl HalaImage {
     ),
     HalaGfxError
   > {
+    // The view which is used as a shader resource can only have one aspect, so the depth aspect is used for the depth stencil format.
+    let view_aspect_mask = Self::single_aspect_for_format(HalaFormat::from(format));
     let view_info = vk::ImageViewCreateInfo::default()
       .image(image)
       .view_type(view_type)
       .format(format)
       .subresource_range(vk::ImageSubresourceRange {
-        aspect_mask: if format == vk::Format::D16_UNORM || format == vk::Format::D32_SFLOAT || format == vk::Format::D24_UNORM_S8_UINT { vk::ImageAspectFlags::DEPTH } else { vk::ImageAspectFlags::COLOR },
+        aspect_mask: view_aspect_mask,
         base_mip_level: 0,
         level_count: mip_levels,
         base_array_layer: 0,
         layer_count: array_layers,
       });
 
-    let view = unsafe {
-      let logical_device = logical_device.borrow();
-      let view = logical_device.raw.create_image_view(&view_info, None)
-        .map_err(|err| HalaGfxError::new("Failed to create image view.", Some(Box::new(err))))?;
-      logical_device.set_debug_name(
-        view,
-        &format!("{}_view", debug_name),
-      ).map_err(|err| HalaGfxError::new("Failed to set debug name for image view.", Some(Box::new(err))))?;
-      view
+    let view = if view_options.main {
+      unsafe {
+        let logical_device = logical_device.borrow();
+        let view = logical_device.raw.create_image_view(&view_info, None)
+          .map_err(|err| HalaGfxError::new("Failed to create image view.", Some(Box::new(err))))?;
+        logical_device.set_debug_name(
+          view,
+          &format!("{}_view", debug_name),
+        ).map_err(|err| HalaGfxError::new("Failed to set debug name for image view.", Some(Box::new(err))))?;
+        view
+      }
+    } else {
+      vk::ImageView::null()
     };
 
     let mut mip_views = Vec::new();
-    if require_seperate_views {
+    if view_options.per_mip {
       for mip_level in 0..mip_levels {
         let mip_view_info = vk::ImageViewCreateInfo::default()
           .image(image)
           .view_type(view_type)
           .format(format)
           .subresource_range(vk::ImageSubresourceRange {
-            aspect_mask: if format == vk::Format::D16_UNORM || format == vk::Format::D32_SFLOAT || format == vk::Format::D24_UNORM_S8_UINT { vk::ImageAspectFlags::DEPTH } else { vk::ImageAspectFlags::COLOR },
+            aspect_mask: view_aspect_mask,
             base_mip_level: mip_level,
             level_count: 1,
             base_array_layer: 0,
@@ -768,14 +1474,15 @@ impl HalaImage {
     }
 
     let mut array_views = Vec::new();
-    if require_seperate_views {
+    if view_options.per_array {
       for array_layer in 0..array_layers {
+        // The single layer of a cube image can only be viewed as a 2D image.
         let array_view_info = vk::ImageViewCreateInfo::default()
           .image(image)
-          .view_type(view_type)
+          .view_type(if view_type == vk::ImageViewType::CUBE || view_type == vk::ImageViewType::CUBE_ARRAY { vk::ImageViewType::TYPE_2D } else { view_type })
           .format(format)
           .subresource_range(vk::ImageSubresourceRange {
-            aspect_mask: if format == vk::Format::D16_UNORM || format == vk::Format::D32_SFLOAT || format == vk::Format::D24_UNORM_S8_UINT { vk::ImageAspectFlags::DEPTH } else { vk::ImageAspectFlags::COLOR },
+            aspect_mask: view_aspect_mask,
             base_mip_level: 0,
             level_count: mip_levels,
             base_array_layer: array_layer,
@@ -799,15 +1506,207 @@ impl HalaImage {
     Ok((view, mip_views, array_views))
   }
 
+  /// Create a view of a sub-range of the image.
+  /// The view is owned by the image and destroyed when the image is dropped.
+  /// It can view a 2D array as individual 2D images, a cube as a 2D array or the whole image with another format.
+  /// param desc: The image view description.
+  /// return: The image view.
+  pub fn create_sub_view(&mut self, desc: &HalaImageViewDesc) -> Result<vk::ImageView, HalaGfxError> {
+    let (sub_view, ..) = self.create_raw_view(desc, &format!("{}_sub_view_{}", self.debug_name, self.sub_views.len()))?;
+    self.sub_views.push(sub_view);
+
+    Ok(sub_view)
+  }
+
+  /// Create a view which is independent of the views owned by the image.
+  /// The image must outlive the view.
+  /// param desc: The image view description.
+  /// param debug_name: The debug name.
+  /// return: The image view.
+  pub fn create_view(&self, desc: &HalaImageViewDesc, debug_name: &str) -> Result<HalaImageView, HalaGfxError> {
+    HalaImageView::new(self, desc, debug_name)
+  }
+
+  /// Create a raw view of the image from the description, the caller owns the view.
+  /// param desc: The image view description.
+  /// param debug_name: The debug name.
+  /// return: The raw image view, the view format, the number of the mip levels and the number of the array layers.
+  pub(crate) fn create_raw_view(
+    &self,
+    desc: &HalaImageViewDesc,
+    debug_name: &str,
+  ) -> Result<(vk::ImageView, HalaFormat, u32, u32), HalaGfxError> {
+    let format = desc.format.unwrap_or(self.format);
+    let mip_level_count = desc.mip_level_count.unwrap_or(self.mip_levels.saturating_sub(desc.base_mip_level));
+    let array_layer_count = desc.array_layer_count.unwrap_or(self.array_layers.saturating_sub(desc.base_array_layer));
+    self.check_view_range(
+      desc.base_mip_level,
+      mip_level_count,
+      desc.base_array_layer,
+      array_layer_count,
+      desc.view_type,
+      format,
+    )?;
+
+    let mut usage_info = vk::ImageViewUsageCreateInfo::default();
+    let mut view_info = vk::ImageViewCreateInfo::default()
+      .image(self.raw)
+      .view_type(desc.view_type.into())
+      .format(format.into())
+      .subresource_range(vk::ImageSubresourceRange {
+        aspect_mask: Self::single_aspect_for_format(format),
+        base_mip_level: desc.base_mip_level,
+        level_count: mip_level_count,
+        base_array_layer: desc.base_array_layer,
+        layer_count: array_layer_count,
+      });
+    if let Some(usage) = self.get_view_usage(format) {
+      usage_info = usage_info.usage(usage);
+      view_info = view_info.push_next(&mut usage_info);
+    }
+    let logical_device = self.logical_device.borrow();
+    let view = unsafe {
+      logical_device.raw.create_image_view(&view_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create image view.", Some(Box::new(err))))?
+    };
+    if let Err(err) = logical_device.set_debug_name(view, debug_name) {
+      unsafe {
+        logical_device.raw.destroy_image_view(view, None);
+      }
+      return Err(HalaGfxError::new("Failed to set debug name for image view.", Some(Box::new(err))));
+    }
+
+    Ok((view, format, mip_level_count, array_layer_count))
+  }
+
+  /// Check the sub-range and the format of a view.
+  /// param base_mip_level: The base mip level.
+  /// param mip_level_count: The number of mip levels.
+  /// param base_array_layer: The base array layer.
+  /// param array_layer_count: The number of array layers.
+  /// param view_type: The view type.
+  /// param format: The view format.
+  /// return: The result.
+  fn check_view_range(
+    &self,
+    base_mip_level: u32,
+    mip_level_count: u32,
+    base_array_layer: u32,
+    array_layer_count: u32,
+    view_type: HalaImageViewType,
+    format: HalaFormat,
+  ) -> Result<(), HalaGfxError> {
+    if mip_level_count == 0 || base_mip_level.checked_add(mip_level_count).is_none_or(|end| end > self.mip_levels) {
+      return Err(HalaGfxError::new(
+        &format!("The mip levels [{}, +{}) are out of the image \"{}\" with {} mip levels.", base_mip_level, mip_level_count, self.debug_name, self.mip_levels),
+        None,
+      ));
+    }
+    if array_layer_count == 0 || base_array_layer.checked_add(array_layer_count).is_none_or(|end| end > self.array_layers) {
+      return Err(HalaGfxError::new(
+        &format!("The array layers [{}, +{}) are out of the image \"{}\" with {} array layers.", base_array_layer, array_layer_count, self.debug_name, self.array_layers),
+        None,
+      ));
+    }
+    // The format of the external image is unknown, so it is not checked.
+    if self.owns_image && format != self.format && !self.view_formats.contains(&format) {
+      return Err(HalaGfxError::new(
+        &format!("The format {} is not in the view formats of the image \"{}\".", format, self.debug_name),
+        None,
+      ));
+    }
+    if (view_type == HalaImageViewType::CUBE && array_layer_count != 6)
+      || (view_type == HalaImageViewType::CUBE_ARRAY && !array_layer_count.is_multiple_of(6))
+    {
+      return Err(HalaGfxError::new("The cube view must have 6 array layers for each cube.", None));
+    }
+
+    Ok(())
+  }
+
+  /// Get the usage of a view with another format, the usages of the image which the view format does not support are removed.
+  /// e.g. the sRGB view of a storage image can not be used as the storage image.
+  /// param format: The view format.
+  /// return: The view usage, None if the view format is the image format or the usage of the image is unknown.
+  pub(crate) fn get_view_usage(&self, format: HalaFormat) -> Option<vk::ImageUsageFlags> {
+    if format == self.format || self.usage.is_empty() {
+      return None;
+    }
+
+    let features = self.logical_device.borrow().format_properties(format).optimal_tiling_features;
+    let mut usage = vk::ImageUsageFlags::from(self.usage);
+    for (usage_flag, feature_flag) in [
+      (vk::ImageUsageFlags::SAMPLED, crate::HalaFormatFeatureFlags::SAMPLED_IMAGE),
+      (vk::ImageUsageFlags::STORAGE, crate::HalaFormatFeatureFlags::STORAGE_IMAGE),
+      (vk::ImageUsageFlags::COLOR_ATTACHMENT, crate::HalaFormatFeatureFlags::COLOR_ATTACHMENT),
+      (vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT, crate::HalaFormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT),
+    ] {
+      if !features.contains(feature_flag) {
+        usage &= !usage_flag;
+      }
+    }
+
+    Some(usage)
+  }
+
   /// Generate mipmaps for the image.
+  /// The first mip level is expected in the shader read only layout and all mip levels end in it for the shader sampling.
   /// param command_buffers: The command buffer set.
   /// return: The result.
   pub fn gen_mipmaps(
     &self,
     command_buffers: &HalaCommandBufferSet,
   ) -> Result<(), HalaGfxError> {
-    unsafe {
-      let logical_device = self.logical_device.borrow();
+    self.gen_mipmaps_with_layouts(
+      command_buffers,
+      HalaImageLayout::SHADER_READ_ONLY_OPTIMAL,
+      HalaImageLayout::SHADER_READ_ONLY_OPTIMAL,
+      crate::barrier::SHADER_READ_STAGES,
+      HalaAccessFlags2::SHADER_SAMPLED_READ,
+    )
+  }
+
+  /// Generate mipmaps for all the array layers of the image.
+  /// Each mip level is blitted from the previous one, the other mip levels' content is discarded.
+  /// param command_buffers: The command buffer set.
+  /// param src_layout: The current layout of the first mip level.
+  /// param final_layout: The layout of all the mip levels after the generation.
+  /// param dst_stage_mask: The stages which wait for the generated mip levels.
+  /// param dst_access_mask: The accesses of the generated mip levels after the generation.
+  /// return: The result.
+  pub fn gen_mipmaps_with_layouts(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    src_layout: HalaImageLayout,
+    final_layout: HalaImageLayout,
+    dst_stage_mask: HalaPipelineStageFlags2,
+    dst_access_mask: HalaAccessFlags2,
+  ) -> Result<(), HalaGfxError> {
+    let aspect_mask = vk::ImageAspectFlags::from(self.format.aspect_flags());
+    // The depth stencil image can only be blitted with the nearest filter.
+    let filter = if self.format.has_depth() || self.format.has_stencil() {
+      vk::Filter::NEAREST
+    } else {
+      vk::Filter::LINEAR
+    };
+    let subresource_range = |base_mip_level: u32, level_count: u32| {
+      vk::ImageSubresourceRange::default()
+        .aspect_mask(aspect_mask)
+        .base_mip_level(base_mip_level)
+        .level_count(level_count)
+        .base_array_layer(0)
+        .layer_count(self.array_layers)
+    };
+    let subresource_layers = |mip_level: u32| {
+      vk::ImageSubresourceLayers::default()
+        .aspect_mask(aspect_mask)
+        .mip_level(mip_level)
+        .base_array_layer(0)
+        .layer_count(self.array_layers)
+    };
+
+    unsafe {
+      let logical_device = self.logical_device.borrow();
       let queue = match command_buffers.command_buffer_type {
         crate::HalaCommandBufferType::GRAPHICS => logical_device.get_graphics_queue(0),
         crate::HalaCommandBufferType::TRANSFER => logical_device.get_transfer_queue(0),
@@ -818,29 +1717,21 @@ impl HalaImage {
         command_buffers,
         0,
         |logical_device, command_buffers, index| {
-          for mip_level in 1..self.mip_levels {
-            let mip_width = std::cmp::max(1, self.extent.width >> (mip_level - 1));
-            let mip_height = std::cmp::max(1, self.extent.height >> (mip_level - 1));
-
-            let input_barriers = [
-              vk::ImageMemoryBarrier2::default()
-                .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
-                .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
-                .dst_stage_mask(vk::PipelineStageFlags2::TRANSFER)
-                .dst_access_mask(vk::AccessFlags2::TRANSFER_READ)
-                .old_layout(vk::ImageLayout::UNDEFINED)
-                .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
-                .image(self.raw)
-                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                .subresource_range(
-                  vk::ImageSubresourceRange::default()
-                    .aspect_mask(vk::ImageAspectFlags::COLOR)
-                    .base_mip_level(mip_level - 1)
-                    .level_count(1)
-                    .base_array_layer(0)
-                    .layer_count(1)
-                ),
+          let mut input_barriers = vec![
+            vk::ImageMemoryBarrier2::default()
+              .src_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+              .src_access_mask(vk::AccessFlags2::MEMORY_WRITE)
+              .dst_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+              .dst_access_mask(vk::AccessFlags2::TRANSFER_READ)
+              .old_layout(src_layout.into())
+              .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+              .image(self.raw)
+              .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+              .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+              .subresource_range(subresource_range(0, 1)),
+          ];
+          if self.mip_levels > 1 {
+            input_barriers.push(
               vk::ImageMemoryBarrier2::default()
                 .src_stage_mask(vk::PipelineStageFlags2::NONE)
                 .src_access_mask(vk::AccessFlags2::NONE)
@@ -851,121 +1742,245 @@ impl HalaImage {
                 .image(self.raw)
                 .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
                 .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                .subresource_range(
-                  vk::ImageSubresourceRange::default()
-                    .aspect_mask(vk::ImageAspectFlags::COLOR)
-                    .base_mip_level(mip_level)
-                    .level_count(1)
-                    .base_array_layer(0)
-                    .layer_count(1)
-                ),
-            ];
-
-            let input_dependency_info = vk::DependencyInfoKHR::default()
-              .image_memory_barriers(&input_barriers);
-            logical_device.raw.cmd_pipeline_barrier2(
-              command_buffers.raw[index],
-              &input_dependency_info,
+                .subresource_range(subresource_range(1, self.mip_levels - 1))
             );
+          }
+          let input_dependency_info = vk::DependencyInfoKHR::default()
+            .image_memory_barriers(input_barriers.as_slice());
+          logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &input_dependency_info);
 
-            logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &input_dependency_info);
+          for mip_level in 1..self.mip_levels {
+            let src_width = std::cmp::max(1, self.extent.width >> (mip_level - 1));
+            let src_height = std::cmp::max(1, self.extent.height >> (mip_level - 1));
+            let dst_width = std::cmp::max(1, self.extent.width >> mip_level);
+            let dst_height = std::cmp::max(1, self.extent.height >> mip_level);
 
             let blit = vk::ImageBlit2::default()
               .src_offsets([
                 vk::Offset3D::default(),
                 vk::Offset3D {
-                  x: mip_width as i32,
-                  y: mip_height as i32,
+                  x: src_width as i32,
+                  y: src_height as i32,
                   z: 1,
                 },
               ])
-              .src_subresource(vk::ImageSubresourceLayers::default()
-                .aspect_mask(vk::ImageAspectFlags::COLOR)
-                .mip_level(mip_level - 1)
-                .base_array_layer(0)
-                .layer_count(1)
-              )
+              .src_subresource(subresource_layers(mip_level - 1))
               .dst_offsets([
                 vk::Offset3D::default(),
                 vk::Offset3D {
-                  x: if mip_width > 1 { mip_width / 2 } else { 1 } as i32,
-                  y: if mip_height > 1 { mip_height / 2 } else { 1 } as i32,
+                  x: dst_width as i32,
+                  y: dst_height as i32,
                   z: 1,
                 },
               ])
-              .dst_subresource(vk::ImageSubresourceLayers::default()
-                .aspect_mask(vk::ImageAspectFlags::COLOR)
-                .mip_level(mip_level)
-                .base_array_layer(0)
-                .layer_count(1)
-              );
+              .dst_subresource(subresource_layers(mip_level));
 
             let blit_info = vk::BlitImageInfo2::default()
               .src_image(self.raw)
               .src_image_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
               .dst_image(self.raw)
               .dst_image_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-              .regions(std::slice::from_ref(&blit));
+              .regions(std::slice::from_ref(&blit))
+              .filter(filter);
 
             logical_device.raw.cmd_blit_image2(command_buffers.raw[index], &blit_info);
 
-            let output_barriers = [
+            // The generated mip level becomes the source of the next one.
+            let barrier = vk::ImageMemoryBarrier2::default()
+              .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+              .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+              .dst_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+              .dst_access_mask(vk::AccessFlags2::TRANSFER_READ)
+              .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+              .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+              .image(self.raw)
+              .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+              .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+              .subresource_range(subresource_range(mip_level, 1));
+            let dependency_info = vk::DependencyInfoKHR::default()
+              .image_memory_barriers(std::slice::from_ref(&barrier));
+            logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &dependency_info);
+          }
+
+          let output_barrier = vk::ImageMemoryBarrier2::default()
+            .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+            .src_access_mask(vk::AccessFlags2::TRANSFER_READ | vk::AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(dst_stage_mask.into())
+            .dst_access_mask(dst_access_mask.into())
+            .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .new_layout(final_layout.into())
+            .image(self.raw)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .subresource_range(subresource_range(0, self.mip_levels));
+          let output_dependency_info = vk::DependencyInfoKHR::default()
+            .image_memory_barriers(std::slice::from_ref(&output_barrier));
+          logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &output_dependency_info);
+        },
+        queue,
+      )?;
+    }
+
+    Ok(())
+  }
+
+  /// Get the view of the mip level.
+  /// The image must be created with seperate views.
+  /// param mip_level: The mip level.
+  /// return: The image view.
+  pub fn get_mip_view(&self, mip_level: u32) -> vk::ImageView {
+    assert!(
+      (mip_level as usize) < self.mip_views.len(),
+      "The mip view {} does not exist, the image should be created with seperate views.", mip_level
+    );
+    self.mip_views[mip_level as usize]
+  }
+
+  /// Generate mipmaps for the image with a compute shader.
+  /// The compute shader must have the local size 8x8x1, read the source mip level from the storage image at binding 0,
+  /// write the destination mip level to the storage image at binding 1 and get the destination size(uvec2) from the push constants.
+  /// The z of the dispatch is the array layer.
+  /// param command_buffers: The command buffer set.
+  /// param pipeline: The downsample compute pipeline.
+  /// param descriptor_set: The descriptor set with at least mip_levels - 1 sets, the set i is used for the mip level i + 1.
+  /// param src_layout: The current layout of the first mip level.
+  /// param final_layout: The layout of all the mip levels after the generation.
+  /// param dst_stage_mask: The stages which wait for the generated mip levels.
+  /// param dst_access_mask: The accesses of the generated mip levels after the generation.
+  /// return: The result.
+  #[allow(clippy::too_many_arguments)]
+  pub fn gen_mipmaps_compute(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    pipeline: &crate::HalaComputePipeline,
+    descriptor_set: &crate::HalaDescriptorSet,
+    src_layout: HalaImageLayout,
+    final_layout: HalaImageLayout,
+    dst_stage_mask: HalaPipelineStageFlags2,
+    dst_access_mask: HalaAccessFlags2,
+  ) -> Result<(), HalaGfxError> {
+    if self.mip_views.len() != self.mip_levels as usize {
+      return Err(HalaGfxError::new("The image should be created with seperate views to generate mipmaps with the compute shader.", None));
+    }
+    if descriptor_set.raw.len() + 1 < self.mip_levels as usize {
+      return Err(HalaGfxError::new("The descriptor set count is less than the mip levels - 1.", None));
+    }
+    for mip_level in 1..self.mip_levels {
+      descriptor_set.update_storage_image_mip((mip_level - 1) as usize, 0, self, mip_level - 1);
+      descriptor_set.update_storage_image_mip((mip_level - 1) as usize, 1, self, mip_level);
+    }
+
+    let aspect_mask = vk::ImageAspectFlags::from(self.format.aspect_flags());
+    let subresource_range = |base_mip_level: u32, level_count: u32| {
+      vk::ImageSubresourceRange::default()
+        .aspect_mask(aspect_mask)
+        .base_mip_level(base_mip_level)
+        .level_count(level_count)
+        .base_array_layer(0)
+        .layer_count(self.array_layers)
+    };
+
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      let queue = match command_buffers.command_buffer_type {
+        crate::HalaCommandBufferType::GRAPHICS => logical_device.get_graphics_queue(0),
+        crate::HalaCommandBufferType::COMPUTE => logical_device.get_compute_queue(0),
+        _ => return Err(HalaGfxError::new("Invalid command buffer type.", None)),
+      };
+      logical_device.execute_and_submit(
+        command_buffers,
+        0,
+        |logical_device, command_buffers, index| {
+          let mut input_barriers = vec![
+            vk::ImageMemoryBarrier2::default()
+              .src_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+              .src_access_mask(vk::AccessFlags2::MEMORY_WRITE)
+              .dst_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER)
+              .dst_access_mask(vk::AccessFlags2::SHADER_STORAGE_READ)
+              .old_layout(src_layout.into())
+              .new_layout(vk::ImageLayout::GENERAL)
+              .image(self.raw)
+              .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+              .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+              .subresource_range(subresource_range(0, 1)),
+          ];
+          if self.mip_levels > 1 {
+            input_barriers.push(
               vk::ImageMemoryBarrier2::default()
-                .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
-                .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
-                .dst_stage_mask(vk::PipelineStageFlags2::ALL_GRAPHICS)
-                .dst_access_mask(vk::AccessFlags2::SHADER_READ)
+                .src_stage_mask(vk::PipelineStageFlags2::NONE)
+                .src_access_mask(vk::AccessFlags2::NONE)
+                .dst_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER)
+                .dst_access_mask(vk::AccessFlags2::SHADER_STORAGE_WRITE)
                 .old_layout(vk::ImageLayout::UNDEFINED)
-                .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+                .new_layout(vk::ImageLayout::GENERAL)
                 .image(self.raw)
                 .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
                 .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                .subresource_range(
-                  vk::ImageSubresourceRange::default()
-                    .aspect_mask(vk::ImageAspectFlags::COLOR)
-                    .base_mip_level(mip_level - 1)
-                    .level_count(1)
-                    .base_array_layer(0)
-                    .layer_count(1)
-                ),
-            ];
-
-            let output_dependency_info = vk::DependencyInfoKHR::default()
-              .image_memory_barriers(&output_barriers);
-            logical_device.raw.cmd_pipeline_barrier2(
+                .subresource_range(subresource_range(1, self.mip_levels - 1))
+            );
+          }
+          let input_dependency_info = vk::DependencyInfoKHR::default()
+            .image_memory_barriers(input_barriers.as_slice());
+          logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &input_dependency_info);
+
+          logical_device.raw.cmd_bind_pipeline(command_buffers.raw[index], vk::PipelineBindPoint::COMPUTE, pipeline.raw);
+          for mip_level in 1..self.mip_levels {
+            let dst_width = std::cmp::max(1, self.extent.width >> mip_level);
+            let dst_height = std::cmp::max(1, self.extent.height >> mip_level);
+
+            logical_device.raw.cmd_bind_descriptor_sets(
               command_buffers.raw[index],
-              &output_dependency_info,
+              vk::PipelineBindPoint::COMPUTE,
+              pipeline.layout,
+              0,
+              &[descriptor_set.raw[(mip_level - 1) as usize]],
+              &[],
+            );
+            let dst_size = [dst_width, dst_height];
+            logical_device.raw.cmd_push_constants(
+              command_buffers.raw[index],
+              pipeline.layout,
+              vk::ShaderStageFlags::COMPUTE,
+              0,
+              std::slice::from_raw_parts(dst_size.as_ptr() as *const u8, std::mem::size_of_val(&dst_size)),
+            );
+            logical_device.raw.cmd_dispatch(
+              command_buffers.raw[index],
+              dst_width.div_ceil(8),
+              dst_height.div_ceil(8),
+              self.array_layers,
             );
 
-            logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &output_dependency_info);
+            // The generated mip level becomes the source of the next one.
+            let barrier = vk::ImageMemoryBarrier2::default()
+              .src_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER)
+              .src_access_mask(vk::AccessFlags2::SHADER_STORAGE_WRITE)
+              .dst_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER)
+              .dst_access_mask(vk::AccessFlags2::SHADER_STORAGE_READ)
+              .old_layout(vk::ImageLayout::GENERAL)
+              .new_layout(vk::ImageLayout::GENERAL)
+              .image(self.raw)
+              .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+              .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+              .subresource_range(subresource_range(mip_level, 1));
+            let dependency_info = vk::DependencyInfoKHR::default()
+              .image_memory_barriers(std::slice::from_ref(&barrier));
+            logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &dependency_info);
           }
 
           let output_barrier = vk::ImageMemoryBarrier2::default()
-            .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
-            .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
-            .dst_stage_mask(vk::PipelineStageFlags2::ALL_GRAPHICS)
-            .dst_access_mask(vk::AccessFlags2::SHADER_READ)
-            .old_layout(vk::ImageLayout::UNDEFINED)
-            .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_stage_mask(vk::PipelineStageFlags2::COMPUTE_SHADER)
+            .src_access_mask(vk::AccessFlags2::SHADER_STORAGE_READ | vk::AccessFlags2::SHADER_STORAGE_WRITE)
+            .dst_stage_mask(dst_stage_mask.into())
+            .dst_access_mask(dst_access_mask.into())
+            .old_layout(vk::ImageLayout::GENERAL)
+            .new_layout(final_layout.into())
             .image(self.raw)
             .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
             .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-            .subresource_range(
-              vk::ImageSubresourceRange::default()
-                .aspect_mask(vk::ImageAspectFlags::COLOR)
-                .base_mip_level(self.mip_levels - 1)
-                .level_count(1)
-                .base_array_layer(0)
-                .layer_count(1)
-            );
-
+            .subresource_range(subresource_range(0, self.mip_levels));
           let output_dependency_info = vk::DependencyInfoKHR::default()
             .image_memory_barriers(std::slice::from_ref(&output_barrier));
-          logical_device.raw.cmd_pipeline_barrier2(
-            command_buffers.raw[index],
-            &output_dependency_info,
-          );
-
           logical_device.raw.cmd_pipeline_barrier2(command_buffers.raw[index], &output_dependency_info);
         },
         queue,
@@ -975,6 +1990,122 @@ impl HalaImage {
     Ok(())
   }
 
+  /// Record the barrier which makes the rendered attachment readable by the following passes.
+  /// The color attachment is transitioned from COLOR_ATTACHMENT_OPTIMAL and the depth stencil attachment
+  /// from DEPTH_STENCIL_ATTACHMENT_OPTIMAL to SHADER_READ_ONLY_OPTIMAL, the writes are visible to the fragment shader.
+  /// param command_buffers: The command buffer set.
+  /// param index: The index of the command buffer.
+  pub fn barrier_attachment_to_sampled(&self, command_buffers: &HalaCommandBufferSet, index: usize) {
+    command_buffers.set_image_barriers(
+      index,
+      &[Self::attachment_to_sampled_barrier(self.format, self.mip_levels, self.array_layers, self.raw)],
+    );
+  }
+
+  /// Build the barrier which transitions the whole attachment to SHADER_READ_ONLY_OPTIMAL for the fragment shader.
+  /// param format: The image format.
+  /// param mip_levels: The number of mip levels.
+  /// param array_layers: The number of array layers.
+  /// param image: The image.
+  /// return: The image barrier.
+  fn attachment_to_sampled_barrier(
+    format: HalaFormat,
+    mip_levels: u32,
+    array_layers: u32,
+    image: vk::Image,
+  ) -> crate::HalaImageBarrierInfo {
+    let aspect_mask = format.aspect_flags();
+    let barrier = crate::HalaImageBarrierInfo {
+      dst_stage_mask: HalaPipelineStageFlags2::FRAGMENT_SHADER,
+      dst_access_mask: HalaAccessFlags2::SHADER_READ,
+      new_layout: HalaImageLayout::SHADER_READ_ONLY_OPTIMAL,
+      aspect_mask,
+      level_count: mip_levels,
+      layer_count: array_layers,
+      image,
+      ..Default::default()
+    };
+    if aspect_mask.contains(HalaImageAspectFlags::COLOR) {
+      crate::HalaImageBarrierInfo {
+        src_stage_mask: HalaPipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+        src_access_mask: HalaAccessFlags2::COLOR_ATTACHMENT_WRITE,
+        old_layout: HalaImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        ..barrier
+      }
+    } else {
+      crate::HalaImageBarrierInfo {
+        src_stage_mask: HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS,
+        src_access_mask: HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+        old_layout: HalaImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+        ..barrier
+      }
+    }
+  }
+
+  /// Record the barrier which makes the sampled image renderable again, e.g. in the next frame.
+  /// The image is transitioned from SHADER_READ_ONLY_OPTIMAL back to the attachment layout
+  /// after the fragment shader reads are finished.
+  /// param command_buffers: The command buffer set.
+  /// param index: The index of the command buffer.
+  pub fn barrier_sampled_to_attachment(&self, command_buffers: &HalaCommandBufferSet, index: usize) {
+    let barrier = crate::HalaImageBarrierInfo {
+      src_stage_mask: HalaPipelineStageFlags2::FRAGMENT_SHADER,
+      src_access_mask: HalaAccessFlags2::NONE,
+      old_layout: HalaImageLayout::SHADER_READ_ONLY_OPTIMAL,
+      ..crate::HalaImageBarrierInfo::for_image(self)
+    };
+    let barrier = if self.format.aspect_flags().contains(HalaImageAspectFlags::COLOR) {
+      crate::HalaImageBarrierInfo {
+        dst_stage_mask: HalaPipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+        dst_access_mask: HalaAccessFlags2::COLOR_ATTACHMENT_READ | HalaAccessFlags2::COLOR_ATTACHMENT_WRITE,
+        new_layout: HalaImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        ..barrier
+      }
+    } else {
+      crate::HalaImageBarrierInfo {
+        dst_stage_mask: HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS,
+        dst_access_mask: HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ | HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+        new_layout: HalaImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+        ..barrier
+      }
+    };
+    command_buffers.set_image_barriers(index, &[barrier]);
+  }
+
+  /// Record the barrier which transitions all mip levels and array layers of the image to the new layout.
+  /// The aspect mask is inferred from the format and the stages and accesses from the layouts by barrier::default_barrier_params.
+  /// Use HalaCommandBufferSet::set_image_barriers for the sub-ranges or the tighter synchronization.
+  /// param command_buffers: The command buffer set.
+  /// param index: The index of the command buffer.
+  /// param old_layout: The old layout.
+  /// param new_layout: The new layout.
+  pub fn transition_layout(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    index: usize,
+    old_layout: HalaImageLayout,
+    new_layout: HalaImageLayout,
+  ) {
+    let (
+      src_stage_mask,
+      src_access_mask,
+      dst_stage_mask,
+      dst_access_mask,
+    ) = crate::barrier::default_barrier_params(old_layout, new_layout);
+    command_buffers.set_image_barriers(
+      index,
+      &[crate::HalaImageBarrierInfo {
+        src_stage_mask,
+        src_access_mask,
+        dst_stage_mask,
+        dst_access_mask,
+        old_layout,
+        new_layout,
+        ..crate::HalaImageBarrierInfo::for_image(self)
+      }],
+    );
+  }
+
   /// Upload data to the gpu image with a staging buffer.
   /// This is expensive and should not be done in a hot loop.
   /// param data: The data to be uploaded.
@@ -1026,9 +2157,11 @@ impl HalaImage {
 
       let dst = staging_buffer.allocation.mapped_ptr().unwrap().as_ptr() as *mut u8;
       let dst_bytes = staging_buffer.size as usize;
-      unsafe { std::ptr::copy_nonoverlapping(src, dst, std::cmp::min(src_bytes, dst_bytes)) };
+      let copy_bytes = std::cmp::min(src_bytes, dst_bytes);
+      unsafe { std::ptr::copy_nonoverlapping(src, dst, copy_bytes) };
+      staging_buffer.flush(0, copy_bytes as u64)?;
 
-      unsafe {
+      {
         let logical_device = self.logical_device.borrow();
         let queue = match command_buffers.command_buffer_type {
           crate::HalaCommandBufferType::GRAPHICS => logical_device.get_graphics_queue(0),
@@ -1040,75 +2173,15 @@ impl HalaImage {
           command_buffers,
           0,
           |logical_device, command_buffers, index| {
-            let input_barrier = vk::ImageMemoryBarrier2::default()
-              .src_stage_mask(vk::PipelineStageFlags2::NONE)
-              .src_access_mask(vk::AccessFlags2::NONE)
-              .dst_stage_mask(vk::PipelineStageFlags2::TRANSFER)
-              .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
-              .old_layout(vk::ImageLayout::UNDEFINED)
-              .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-              .image(self.raw)
-              .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-              .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-              .subresource_range(
-                vk::ImageSubresourceRange::default()
-                  .aspect_mask(vk::ImageAspectFlags::COLOR)
-                  .base_mip_level(0)
-                  .level_count(1)
-                  .base_array_layer(0)
-                  .layer_count(1)
-              );
-
-            let dependency_info = vk::DependencyInfoKHR::default()
-              .image_memory_barriers(std::slice::from_ref(&input_barrier));
-            logical_device.raw.cmd_pipeline_barrier2(
-              command_buffers.raw[index],
-              &dependency_info,
-            );
-
-            let region = vk::BufferImageCopy2::default()
-              .image_subresource(vk::ImageSubresourceLayers::default()
-                .aspect_mask(vk::ImageAspectFlags::COLOR)
-                .mip_level(0)
-                .base_array_layer(0)
-                .layer_count(1)
-              )
-              .image_extent(self.extent);
-            let copy_buffer_to_image_info = vk::CopyBufferToImageInfo2::default()
-              .src_buffer(staging_buffer.raw)
-              .dst_image(self.raw)
-              .dst_image_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-              .regions(std::slice::from_ref(&region));
-
-            logical_device.raw.cmd_copy_buffer_to_image2(
-              command_buffers.raw[index],
-              &copy_buffer_to_image_info,
-            );
-
-            let output_barrier = vk::ImageMemoryBarrier2::default()
-              .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
-              .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
-              .dst_stage_mask(dst_stage_mask.into())
-              .dst_access_mask(dst_access_mask.into())
-              .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
-              .new_layout(dst_layout.into())
-              .image(self.raw)
-              .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-              .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-              .subresource_range(
-                vk::ImageSubresourceRange::default()
-                  .aspect_mask(vk::ImageAspectFlags::COLOR)
-                  .base_mip_level(0)
-                  .level_count(1)
-                  .base_array_layer(0)
-                  .layer_count(1)
-              );
-
-            let dependency_info = vk::DependencyInfoKHR::default()
-              .image_memory_barriers(std::slice::from_ref(&output_barrier));
-            logical_device.raw.cmd_pipeline_barrier2(
-              command_buffers.raw[index],
-              &dependency_info,
+            self.record_upload_from_buffer(
+              logical_device,
+              command_buffers,
+              index,
+              staging_buffer,
+              dst_stage_mask,
+              dst_access_mask,
+              dst_layout,
+              None,
             );
           },
           queue,
@@ -1121,4 +2194,440 @@ impl HalaImage {
     Ok(())
   }
 
+  /// Record the commands which copy the staging buffer to the first mip level and array layer of the image.
+  /// The image is transitioned from UNDEFINED to TRANSFER_DST_OPTIMAL before the copy and to the destination layout after it.
+  /// param logical_device: The logical device.
+  /// param command_buffers: The command buffer set.
+  /// param index: The index of the command buffer.
+  /// param staging_buffer: The staging buffer.
+  /// param dst_stage_mask: The destination stage mask.
+  /// param dst_access_mask: The destination access mask.
+  /// param dst_layout: The destination layout.
+  /// param queue_family_indices: The source and destination queue family indices,
+  /// if they are different, the final barrier is the release half of the ownership transfer and the destination stage and access are ignored.
+  #[allow(clippy::too_many_arguments)]
+  pub(crate) fn record_upload_from_buffer(
+    &self,
+    logical_device: &HalaLogicalDevice,
+    command_buffers: &HalaCommandBufferSet,
+    index: usize,
+    staging_buffer: &HalaBuffer,
+    dst_stage_mask: HalaPipelineStageFlags2,
+    dst_access_mask: HalaAccessFlags2,
+    dst_layout: HalaImageLayout,
+    queue_family_indices: Option<(u32, u32)>,
+  ) {
+    let (src_queue_family_index, dst_queue_family_index, dst_stage_mask, dst_access_mask) = match queue_family_indices {
+      Some((src, dst)) if src != dst => (src, dst, HalaPipelineStageFlags2::NONE, HalaAccessFlags2::NONE),
+      _ => (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED, dst_stage_mask, dst_access_mask),
+    };
+    let aspect_mask = vk::ImageAspectFlags::from(self.format.aspect_flags());
+    let copy_aspect_mask = Self::single_aspect_for_format(self.format);
+    unsafe {
+      let input_barrier = vk::ImageMemoryBarrier2::default()
+        .src_stage_mask(vk::PipelineStageFlags2::NONE)
+        .src_access_mask(vk::AccessFlags2::NONE)
+        .dst_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+        .dst_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+        .old_layout(vk::ImageLayout::UNDEFINED)
+        .new_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .image(self.raw)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .subresource_range(
+          vk::ImageSubresourceRange::default()
+            .aspect_mask(aspect_mask)
+            .base_mip_level(0)
+            .level_count(1)
+            .base_array_layer(0)
+            .layer_count(1)
+        );
+
+      let dependency_info = vk::DependencyInfoKHR::default()
+        .image_memory_barriers(std::slice::from_ref(&input_barrier));
+      logical_device.raw.cmd_pipeline_barrier2(
+        command_buffers.raw[index],
+        &dependency_info,
+      );
+
+      let region = vk::BufferImageCopy2::default()
+        .image_subresource(vk::ImageSubresourceLayers::default()
+          .aspect_mask(copy_aspect_mask)
+          .mip_level(0)
+          .base_array_layer(0)
+          .layer_count(1)
+        )
+        .image_extent(self.extent);
+      let copy_buffer_to_image_info = vk::CopyBufferToImageInfo2::default()
+        .src_buffer(staging_buffer.raw)
+        .dst_image(self.raw)
+        .dst_image_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .regions(std::slice::from_ref(&region));
+
+      logical_device.raw.cmd_copy_buffer_to_image2(
+        command_buffers.raw[index],
+        &copy_buffer_to_image_info,
+      );
+
+      let output_barrier = vk::ImageMemoryBarrier2::default()
+        .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+        .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+        .dst_stage_mask(dst_stage_mask.into())
+        .dst_access_mask(dst_access_mask.into())
+        .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+        .new_layout(dst_layout.into())
+        .image(self.raw)
+        .src_queue_family_index(src_queue_family_index)
+        .dst_queue_family_index(dst_queue_family_index)
+        .subresource_range(
+          vk::ImageSubresourceRange::default()
+            .aspect_mask(aspect_mask)
+            .base_mip_level(0)
+            .level_count(1)
+            .base_array_layer(0)
+            .layer_count(1)
+        );
+
+      let dependency_info = vk::DependencyInfoKHR::default()
+        .image_memory_barriers(std::slice::from_ref(&output_barrier));
+      logical_device.raw.cmd_pipeline_barrier2(
+        command_buffers.raw[index],
+        &dependency_info,
+      );
+    }
+  }
+
+  /// Get the size in bytes of the tightly packed first mip level and array layer.
+  /// return: The size, None for the formats without the texel size, e.g. the block compressed formats.
+  pub(crate) fn first_mip_size(&self) -> Option<u64> {
+    self.format.texel_size().map(|texel_size|
+      texel_size as u64 * self.extent.width as u64 * self.extent.height as u64 * self.extent.depth as u64
+    )
+  }
+
+  /// Read the first mip level and array layer of the image to the staging buffer.
+  /// The texels are tightly packed in the staging buffer without row padding.
+  /// For the depth stencil image, only the depth aspect is read.
+  /// This is expensive and should not be done in a hot loop.
+  /// param staging_buffer: The staging buffer.
+  /// param command_buffers: The command buffer set.
+  /// param src_layout: The current layout of the image, the image is transitioned back to it after reading, so it can not be UNDEFINED.
+  /// return: The result.
+  pub fn read_gpu_memory_to_buffer(
+    &self,
+    staging_buffer: &HalaBuffer,
+    command_buffers: &HalaCommandBufferSet,
+    src_layout: HalaImageLayout,
+  ) -> Result<(), HalaGfxError> {
+    self.read_gpu_memory_to_buffer_impl(
+      staging_buffer,
+      command_buffers,
+      src_layout,
+      0,
+      0,
+      vk::Offset3D::default(),
+      self.extent,
+    )
+  }
+
+  /// Read back a region of the image to the CPU.
+  /// The optimal tiling is resolved by the copy and the returned bytes are tightly packed.
+  /// This is expensive and should not be done in a hot loop.
+  /// param mip_level: The mip level.
+  /// param array_layer: The array layer.
+  /// param region: The region(x, y, width, height), None for the whole mip level.
+  /// param src_layout: The current layout of the image, the image is transitioned back to it after reading, so it can not be UNDEFINED.
+  /// param command_buffers: The command buffer set.
+  /// return: The result(width, height, bytes).
+  pub fn read_back(
+    &self,
+    mip_level: u32,
+    array_layer: u32,
+    region: Option<(i32, i32, u32, u32)>,
+    src_layout: HalaImageLayout,
+    command_buffers: &HalaCommandBufferSet,
+  ) -> Result<(u32, u32, Vec<u8>), HalaGfxError> {
+    if mip_level >= self.mip_levels || array_layer >= self.array_layers {
+      return Err(HalaGfxError::new("The mip level or array layer is out of range.", None));
+    }
+    let texel_size = self.format.texel_size()
+      .ok_or(HalaGfxError::new(&format!("The format {} can not be read back.", self.format), None))?;
+    let mip_width = std::cmp::max(1, self.extent.width >> mip_level);
+    let mip_height = std::cmp::max(1, self.extent.height >> mip_level);
+    let (x, y, width, height) = region.unwrap_or((0, 0, mip_width, mip_height));
+    if x < 0 || y < 0 || x as u32 + width > mip_width || y as u32 + height > mip_height {
+      return Err(HalaGfxError::new("The read back region is out of the mip level.", None));
+    }
+
+    let size = width as u64 * height as u64 * texel_size as u64;
+    let readback_buffer = HalaBuffer::new(
+      std::rc::Rc::clone(&self.logical_device),
+      size,
+      crate::HalaBufferUsageFlags::TRANSFER_DST,
+      HalaMemoryLocation::GpuToCpu,
+      &format!("{}.readback_buffer", self.debug_name),
+    )?;
+    self.read_gpu_memory_to_buffer_impl(
+      &readback_buffer,
+      command_buffers,
+      src_layout,
+      mip_level,
+      array_layer,
+      vk::Offset3D { x, y, z: 0 },
+      vk::Extent3D { width, height, depth: 1 },
+    )?;
+
+    let mut data = vec![0u8; size as usize];
+    readback_buffer.download_memory(0, data.as_mut_slice())?;
+
+    Ok((width, height, data))
+  }
+
+  /// Read a region of the image to the staging buffer.
+  /// param staging_buffer: The staging buffer.
+  /// param command_buffers: The command buffer set.
+  /// param src_layout: The current layout of the image.
+  /// param mip_level: The mip level.
+  /// param array_layer: The array layer.
+  /// param offset: The offset of the region.
+  /// param extent: The extent of the region.
+  /// return: The result.
+  #[allow(clippy::too_many_arguments)]
+  fn read_gpu_memory_to_buffer_impl(
+    &self,
+    staging_buffer: &HalaBuffer,
+    command_buffers: &HalaCommandBufferSet,
+    src_layout: HalaImageLayout,
+    mip_level: u32,
+    array_layer: u32,
+    offset: vk::Offset3D,
+    extent: vk::Extent3D,
+  ) -> Result<(), HalaGfxError> {
+    if staging_buffer.allocation.mapped_ptr().is_none() {
+      return Err(HalaGfxError::new("The staging buffer is not host visible.", None));
+    }
+    // The image is transitioned back to the source layout after reading, UNDEFINED and PREINITIALIZED can not be the new layout.
+    if src_layout == HalaImageLayout::UNDEFINED || src_layout == HalaImageLayout::PREINITIALIZED {
+      return Err(HalaGfxError::new("The source layout of the image can not be UNDEFINED or PREINITIALIZED.", None));
+    }
+    let texel_size = self.format.texel_size()
+      .ok_or(HalaGfxError::new(&format!("The format {} can not be read back.", self.format), None))?;
+    let size = texel_size as u64 * extent.width as u64 * extent.height as u64 * extent.depth as u64;
+    if size > staging_buffer.size {
+      return Err(HalaGfxError::new(
+        &format!("The staging buffer size {} is smaller than the copied data size {}.", staging_buffer.size, size),
+        None,
+      ));
+    }
+    let aspect_mask = vk::ImageAspectFlags::from(self.format.aspect_flags());
+    let copy_aspect_mask = Self::single_aspect_for_format(self.format);
+
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      let queue = match command_buffers.command_buffer_type {
+        crate::HalaCommandBufferType::GRAPHICS => logical_device.get_graphics_queue(0),
+        crate::HalaCommandBufferType::TRANSFER => logical_device.get_transfer_queue(0),
+        crate::HalaCommandBufferType::COMPUTE => logical_device.get_compute_queue(0),
+        _ => return Err(HalaGfxError::new("Invalid command buffer type.", None)),
+      };
+      logical_device.execute_and_submit(
+        command_buffers,
+        0,
+        |logical_device, command_buffers, index| {
+          let subresource_range = vk::ImageSubresourceRange::default()
+            .aspect_mask(aspect_mask)
+            .base_mip_level(mip_level)
+            .level_count(1)
+            .base_array_layer(array_layer)
+            .layer_count(1);
+          let input_barrier = vk::ImageMemoryBarrier2::default()
+            .src_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+            .src_access_mask(vk::AccessFlags2::MEMORY_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+            .dst_access_mask(vk::AccessFlags2::TRANSFER_READ)
+            .old_layout(src_layout.into())
+            .new_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .image(self.raw)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .subresource_range(subresource_range);
+
+          let dependency_info = vk::DependencyInfoKHR::default()
+            .image_memory_barriers(std::slice::from_ref(&input_barrier));
+          logical_device.raw.cmd_pipeline_barrier2(
+            command_buffers.raw[index],
+            &dependency_info,
+          );
+
+          // Zero buffer row length and image height mean the texels are tightly packed.
+          let region = vk::BufferImageCopy2::default()
+            .buffer_offset(0)
+            .buffer_row_length(0)
+            .buffer_image_height(0)
+            .image_subresource(vk::ImageSubresourceLayers::default()
+              .aspect_mask(copy_aspect_mask)
+              .mip_level(mip_level)
+              .base_array_layer(array_layer)
+              .layer_count(1)
+            )
+            .image_offset(offset)
+            .image_extent(extent);
+          let copy_image_to_buffer_info = vk::CopyImageToBufferInfo2::default()
+            .src_image(self.raw)
+            .src_image_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .dst_buffer(staging_buffer.raw)
+            .regions(std::slice::from_ref(&region));
+
+          logical_device.raw.cmd_copy_image_to_buffer2(
+            command_buffers.raw[index],
+            &copy_image_to_buffer_info,
+          );
+
+          let output_barrier = vk::ImageMemoryBarrier2::default()
+            .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+            .src_access_mask(vk::AccessFlags2::TRANSFER_READ)
+            .dst_stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+            .dst_access_mask(vk::AccessFlags2::MEMORY_READ | vk::AccessFlags2::MEMORY_WRITE)
+            .old_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+            .new_layout(src_layout.into())
+            .image(self.raw)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .subresource_range(subresource_range);
+          let host_barrier = vk::BufferMemoryBarrier2::default()
+            .src_stage_mask(vk::PipelineStageFlags2::TRANSFER)
+            .src_access_mask(vk::AccessFlags2::TRANSFER_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags2::HOST)
+            .dst_access_mask(vk::AccessFlags2::HOST_READ)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .buffer(staging_buffer.raw)
+            .offset(0)
+            .size(vk::WHOLE_SIZE);
+
+          let dependency_info = vk::DependencyInfoKHR::default()
+            .image_memory_barriers(std::slice::from_ref(&output_barrier))
+            .buffer_memory_barriers(std::slice::from_ref(&host_barrier));
+          logical_device.raw.cmd_pipeline_barrier2(
+            command_buffers.raw[index],
+            &dependency_info,
+          );
+        },
+        queue,
+      )?;
+    }
+
+    Ok(())
+  }
+
+  /// Read the texels from the staging buffer filled by read_gpu_memory_to_buffer.
+  /// param staging_buffer: The staging buffer.
+  /// return: The texels, the size of T must match the texel size of the format.
+  #[cfg(feature = "bytemuck")]
+  pub fn read_to_vec<T: bytemuck::Pod>(&self, staging_buffer: &HalaBuffer) -> Result<Vec<T>, HalaGfxError> {
+    // Any bit pattern is a valid Pod value, so the downloaded bytes can be read as T safely.
+    unsafe { self.read_to_vec_unchecked(staging_buffer) }
+  }
+
+  /// Read the texels from the staging buffer filled by read_gpu_memory_to_buffer without checking the texel type.
+  /// # Safety
+  /// Every bit pattern of the texel must be a valid value of T,
+  /// e.g. bool, char and the references are not allowed.
+  /// param staging_buffer: The staging buffer.
+  /// return: The texels, the size of T must match the texel size of the format.
+  pub unsafe fn read_to_vec_unchecked<T: Copy>(&self, staging_buffer: &HalaBuffer) -> Result<Vec<T>, HalaGfxError> {
+    let texel_size = self.format.texel_size()
+      .ok_or(HalaGfxError::new(&format!("The format {} can not be read back.", self.format), None))?;
+    if std::mem::size_of::<T>() != texel_size as usize {
+      return Err(HalaGfxError::new(
+        &format!("The size {} of the texel type does not match the texel size {} of the format {}.", std::mem::size_of::<T>(), texel_size, self.format),
+        None,
+      ));
+    }
+    let count = self.extent.width as usize * self.extent.height as usize * self.extent.depth as usize;
+    let size = count * std::mem::size_of::<T>();
+    if size as u64 > staging_buffer.size {
+      return Err(HalaGfxError::new(
+        &format!("The staging buffer size {} is smaller than the image data size {}.", staging_buffer.size, size),
+        None,
+      ));
+    }
+    let src = staging_buffer.allocation.mapped_ptr()
+      .ok_or(HalaGfxError::new("The staging buffer is not host visible.", None))?
+      .as_ptr() as *const T;
+    staging_buffer.invalidate(0, size as u64)?;
+
+    let mut data = Vec::with_capacity(count);
+    std::ptr::copy_nonoverlapping(src, data.as_mut_ptr(), count);
+    data.set_len(count);
+
+    Ok(data)
+  }
+
+  /// Set the debug name of the image and all its views.
+  /// The memory is also renamed if it is dedicated to the image, the shared memory block of the allocator is not renamed.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    logical_device.set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for image.", Some(Box::new(err))))?;
+    // The sparse image and the image from a raw image have the null allocation.
+    if self.allocation.is_dedicated() && unsafe { self.allocation.memory() } != vk::DeviceMemory::null() {
+      unsafe {
+        logical_device.set_debug_name(self.allocation.memory(), debug_name)
+          .map_err(|err| HalaGfxError::new("Failed to set debug name for image memory.", Some(Box::new(err))))?;
+      }
+    }
+    if self.view != vk::ImageView::null() {
+      logical_device.set_debug_name(self.view, &format!("{}_view", debug_name))
+        .map_err(|err| HalaGfxError::new("Failed to set debug name for image view.", Some(Box::new(err))))?;
+    }
+    for (mip_level, &mip_view) in self.mip_views.iter().enumerate() {
+      logical_device.set_debug_name(mip_view, &format!("{}_mip_view_{}", debug_name, mip_level))
+        .map_err(|err| HalaGfxError::new("Failed to set debug name for mip view.", Some(Box::new(err))))?;
+    }
+    for (array_layer, &array_view) in self.array_views.iter().enumerate() {
+      logical_device.set_debug_name(array_view, &format!("{}_array_view_{}", debug_name, array_layer))
+        .map_err(|err| HalaGfxError::new("Failed to set debug name for array view.", Some(Box::new(err))))?;
+    }
+    for (i, &sub_view) in self.sub_views.iter().enumerate() {
+      logical_device.set_debug_name(sub_view, &format!("{}_sub_view_{}", debug_name, i))
+        .map_err(|err| HalaGfxError::new("Failed to set debug name for sub view.", Some(Box::new(err))))?;
+    }
+    drop(logical_device);
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn attachment_to_sampled_barrier_of_color_attachment() {
+    let barrier = HalaImage::attachment_to_sampled_barrier(HalaFormat::R8G8B8A8_UNORM, 3, 2, vk::Image::null());
+    assert!(barrier.old_layout == HalaImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+    assert!(barrier.new_layout == HalaImageLayout::SHADER_READ_ONLY_OPTIMAL);
+    assert!(barrier.src_stage_mask == HalaPipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT);
+    assert!(barrier.src_access_mask == HalaAccessFlags2::COLOR_ATTACHMENT_WRITE);
+    assert!(barrier.dst_stage_mask == HalaPipelineStageFlags2::FRAGMENT_SHADER);
+    assert!(barrier.dst_access_mask == HalaAccessFlags2::SHADER_READ);
+    assert!(barrier.aspect_mask == HalaImageAspectFlags::COLOR);
+    assert_eq!((barrier.base_mip_level, barrier.level_count), (0, 3));
+    assert_eq!((barrier.base_array_layer, barrier.layer_count), (0, 2));
+  }
+
+  #[test]
+  fn attachment_to_sampled_barrier_of_depth_stencil_attachment() {
+    let barrier = HalaImage::attachment_to_sampled_barrier(HalaFormat::D24_UNORM_S8_UINT, 1, 1, vk::Image::null());
+    assert!(barrier.old_layout == HalaImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+    assert!(barrier.new_layout == HalaImageLayout::SHADER_READ_ONLY_OPTIMAL);
+    assert!(barrier.src_stage_mask == HalaPipelineStageFlags2::EARLY_FRAGMENT_TESTS | HalaPipelineStageFlags2::LATE_FRAGMENT_TESTS);
+    assert!(barrier.src_access_mask == HalaAccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE);
+    assert!(barrier.dst_stage_mask == HalaPipelineStageFlags2::FRAGMENT_SHADER);
+    assert!(barrier.dst_access_mask == HalaAccessFlags2::SHADER_READ);
+    assert!(barrier.aspect_mask == HalaImageAspectFlags::DEPTH | HalaImageAspectFlags::STENCIL);
+  }
 }
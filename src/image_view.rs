@@ -0,0 +1,135 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use ash::vk;
+
+use crate::{
+  HalaFormat,
+  HalaGfxError,
+  HalaImage,
+  HalaImageViewType,
+  HalaLogicalDevice,
+};
+
+/// The description of an image view.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HalaImageViewDesc {
+  pub view_type: HalaImageViewType,
+  /// The view format, the image format is used if it is None.
+  pub format: Option<HalaFormat>,
+  pub base_mip_level: u32,
+  /// The number of the mip levels, the remaining mip levels are used if it is None.
+  pub mip_level_count: Option<u32>,
+  pub base_array_layer: u32,
+  /// The number of the array layers, the remaining array layers are used if it is None.
+  pub array_layer_count: Option<u32>,
+}
+
+/// The implementation of the image view description.
+impl HalaImageViewDesc {
+  /// Create a description of the view which covers the whole image.
+  /// param view_type: The view type.
+  /// return: The image view description.
+  pub fn new(view_type: HalaImageViewType) -> Self {
+    Self {
+      view_type,
+      ..Default::default()
+    }
+  }
+
+  /// Set the view format.
+  /// param format: The view format, it must be the image format or one of the view formats of the image.
+  /// return: The image view description.
+  pub fn with_format(mut self, format: HalaFormat) -> Self {
+    self.format = Some(format);
+    self
+  }
+
+  /// Set the mip levels.
+  /// param base_mip_level: The base mip level.
+  /// param mip_level_count: The number of the mip levels.
+  /// return: The image view description.
+  pub fn with_mip_levels(mut self, base_mip_level: u32, mip_level_count: u32) -> Self {
+    self.base_mip_level = base_mip_level;
+    self.mip_level_count = Some(mip_level_count);
+    self
+  }
+
+  /// Set the array layers.
+  /// param base_array_layer: The base array layer.
+  /// param array_layer_count: The number of the array layers.
+  /// return: The image view description.
+  pub fn with_array_layers(mut self, base_array_layer: u32, array_layer_count: u32) -> Self {
+    self.base_array_layer = base_array_layer;
+    self.array_layer_count = Some(array_layer_count);
+    self
+  }
+}
+
+/// The image view which is independent of the views owned by the image.
+/// The image must outlive the view.
+pub struct HalaImageView {
+  pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  pub raw: vk::ImageView,
+  pub image: vk::Image,
+  pub view_type: HalaImageViewType,
+  pub format: HalaFormat,
+  pub base_mip_level: u32,
+  pub mip_level_count: u32,
+  pub base_array_layer: u32,
+  pub array_layer_count: u32,
+  pub(crate) debug_name: String,
+}
+
+/// The AsRef trait implementation of the image view.
+impl AsRef<HalaImageView> for HalaImageView {
+  fn as_ref(&self) -> &HalaImageView {
+    self
+  }
+}
+
+/// The Drop trait implementation of the image view.
+impl Drop for HalaImageView {
+  fn drop(&mut self) {
+    unsafe {
+      self.logical_device.borrow().raw.destroy_image_view(self.raw, None);
+    }
+    log::debug!("The HalaImageView \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation of the image view.
+impl HalaImageView {
+  /// Create a view of the image.
+  /// param image: The image.
+  /// param desc: The image view description.
+  /// param debug_name: The debug name.
+  /// return: The image view.
+  pub fn new(image: &HalaImage, desc: &HalaImageViewDesc, debug_name: &str) -> Result<Self, HalaGfxError> {
+    let (raw, format, mip_level_count, array_layer_count) = image.create_raw_view(desc, debug_name)?;
+
+    log::debug!("A HalaImageView \"{}\" of the image \"{}\" is created.", debug_name, image.debug_name);
+    Ok(Self {
+      logical_device: Rc::clone(&image.logical_device),
+      raw,
+      image: image.raw,
+      view_type: desc.view_type,
+      format,
+      base_mip_level: desc.base_mip_level,
+      mip_level_count,
+      base_array_layer: desc.base_array_layer,
+      array_layer_count,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for image view.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
@@ -4,21 +4,115 @@ use ash::vk;
 
 use crate::error::HalaGfxError;
 
+/// The validation features.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HalaValidationFeatureFlags(u32);
+crate::hala_bitflags_wrapped!(HalaValidationFeatureFlags, u32);
+impl HalaValidationFeatureFlags {
+  pub const GPU_ASSISTED: Self = Self(0b1);
+  pub const BEST_PRACTICES: Self = Self(0b10);
+  pub const SYNCHRONIZATION_VALIDATION: Self = Self(0b100);
+  pub const DEBUG_PRINTF: Self = Self(0b1000);
+}
+
+/// The implementation of the validation features.
+impl HalaValidationFeatureFlags {
+  /// Convert to the raw validation feature enables.
+  /// return: The raw validation feature enables.
+  pub(crate) fn to_raw(self) -> Vec<vk::ValidationFeatureEnableEXT> {
+    [
+      (Self::GPU_ASSISTED, vk::ValidationFeatureEnableEXT::GPU_ASSISTED),
+      (Self::BEST_PRACTICES, vk::ValidationFeatureEnableEXT::BEST_PRACTICES),
+      (Self::SYNCHRONIZATION_VALIDATION, vk::ValidationFeatureEnableEXT::SYNCHRONIZATION_VALIDATION),
+      (Self::DEBUG_PRINTF, vk::ValidationFeatureEnableEXT::DEBUG_PRINTF),
+    ]
+      .into_iter()
+      .filter(|(flag, _)| self.contains(*flag))
+      .map(|(_, raw)| raw)
+      .collect()
+  }
+}
+
+/// The debug messenger callback.
+/// It receives the severity, the type and the message of the validation messages instead of the log.
+/// It may be called from any thread which calls into Vulkan.
+pub type HalaDebugMessengerCallback = Box<dyn Fn(vk::DebugUtilsMessageSeverityFlagsEXT, vk::DebugUtilsMessageTypeFlagsEXT, &str) + Send + Sync>;
+
+/// The validation configuration of the instance.
+pub struct HalaValidationConfig {
+  /// Whether to enable the validation layer and the debug messenger.
+  pub enabled: bool,
+  pub features: HalaValidationFeatureFlags,
+  /// The custom callback, the messages are written to the log if it is None.
+  /// The process is aborted if the callback panics, because the panic can not unwind through the Vulkan call.
+  pub callback: Option<HalaDebugMessengerCallback>,
+  /// Whether to fail on the error messages.
+  /// The first error message is recorded and returned by HalaInstance::take_validation_error.
+  pub fail_on_error: bool,
+}
+
+/// The Default trait implementation of the validation configuration.
+impl Default for HalaValidationConfig {
+  fn default() -> Self {
+    Self {
+      enabled: cfg!(debug_assertions),
+      features: HalaValidationFeatureFlags::empty(),
+      callback: None,
+      fail_on_error: false,
+    }
+  }
+}
+
+/// The user data of the debug messenger.
+struct HalaDebugMessengerData {
+  callback: Option<HalaDebugMessengerCallback>,
+  fail_on_error: bool,
+  /// The first error message which is not taken yet.
+  error: std::sync::Mutex<Option<String>>,
+}
+
 unsafe extern "system" fn vulkan_debug_utils_callback(
   message_severity: vk::DebugUtilsMessageSeverityFlagsEXT,
   message_type: vk::DebugUtilsMessageTypeFlagsEXT,
   p_callback_data: *const vk::DebugUtilsMessengerCallbackDataEXT,
-  _p_user_data: *mut std::ffi::c_void,
+  p_user_data: *mut std::ffi::c_void,
 ) -> vk::Bool32 {
   let message = std::ffi::CStr::from_ptr((*p_callback_data).p_message);
-  let severity = format!("{:?}", message_severity).to_lowercase();
-  let ty = format!("{:?}", message_type).to_lowercase();
-  match severity {
-    s if s.contains("error") => log::error!("[{}] {:?}", ty, message),
-    s if s.contains("warning") => log::warn!("[{}] {:?}", ty, message),
-    s if s.contains("info") => log::info!("[{}] {:?}", ty, message),
-    s if s.contains("verbose") => log::debug!("[{}] {:?}", ty, message),
-    _ => (),
+  let message_id_name = if (*p_callback_data).p_message_id_name.is_null() {
+    std::borrow::Cow::Borrowed("")
+  } else {
+    std::ffi::CStr::from_ptr((*p_callback_data).p_message_id_name).to_string_lossy()
+  };
+  let data = (p_user_data as *const HalaDebugMessengerData).as_ref();
+  if let Some(callback) = data.and_then(|data| data.callback.as_ref()) {
+    // The panic can not unwind through the Vulkan call, so abort explicitly.
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+      callback(message_severity, message_type, &message.to_string_lossy());
+    }));
+    if result.is_err() {
+      log::error!("The debug messenger callback panicked, abort the process.");
+      std::process::abort();
+    }
+  } else if message_id_name.contains("DEBUG-PRINTF") {
+    // The printf messages in shader are info level messages with the DEBUG-PRINTF message ID.
+    log::info!("[printf] {}", message.to_string_lossy());
+  } else {
+    let severity = format!("{:?}", message_severity).to_lowercase();
+    let ty = format!("{:?}", message_type).to_lowercase();
+    match severity {
+      s if s.contains("error") => log::error!("[{}] {:?}", ty, message),
+      s if s.contains("warning") => log::warn!("[{}] {:?}", ty, message),
+      s if s.contains("info") => log::info!("[{}] {:?}", ty, message),
+      s if s.contains("verbose") => log::debug!("[{}] {:?}", ty, message),
+      _ => (),
+    }
+  }
+  if let Some(data) = data.filter(|data| data.fail_on_error) {
+    if message_severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR) {
+      if let Ok(mut error) = data.error.lock() {
+        error.get_or_insert_with(|| message.to_string_lossy().into_owned());
+      }
+    }
   }
   vk::FALSE
 }
@@ -31,6 +125,10 @@ pub struct HalaInstance {
 
   pub(crate) debug_utils_loader: Option<ash::ext::debug_utils::Instance>,
   pub(crate) debug_call_back: Option<vk::DebugUtilsMessengerEXT>,
+  /// Whether the non-sRGB color spaces of the swapchain are enabled.
+  pub is_swapchain_colorspace_enabled: bool,
+  // The user data of the debug messenger must outlive it.
+  debug_messenger_data: Box<HalaDebugMessengerData>,
 }
 
 /// The Drop trait implementation of the instance.
@@ -53,18 +151,47 @@ impl Drop for HalaInstance {
 /// The implementation of the instance.
 impl HalaInstance {
   /// Create a new instance.
+  /// The DEBUG_PRINTF validation feature is also enabled if the printf in shader is required.
   /// param name: The name of the instance.
   /// param gpu_req: The GPU requirements.
+  /// param validation_config: The validation configuration.
   /// return: The instance.
-  pub fn new(name: &str, gpu_req: &crate::HalaGPURequirements) -> Result<Self, HalaGfxError> {
+  pub fn new(
+    name: &str,
+    gpu_req: &crate::HalaGPURequirements,
+    mut validation_config: HalaValidationConfig,
+  ) -> Result<Self, HalaGfxError> {
     // Load Vulkan entry.
     let entry = unsafe {
       ash::Entry::load()
         .map_err(|err| HalaGfxError::new("Failed to load Vulkan entry.", Some(Box::new(err))))?
     };
 
+    // The preferred color space falls back to SRGB_NONLINEAR if the swapchain color space extension is not available.
+    let is_swapchain_colorspace_enabled = if gpu_req.preferred_color_space != crate::HalaColorSpace::SRGB_NONLINEAR {
+      let is_supported = Self::is_extension_supported(&entry, ash::ext::swapchain_colorspace::NAME)?;
+      if !is_supported {
+        log::warn!("The swapchain color space extension is not supported, use SRGB_NONLINEAR color space instead.");
+      }
+      is_supported
+    } else {
+      false
+    };
+
     // Create Vulkan instance.
-    let (debug_utils_loader, debug_call_back, instance) = Self::create_instance(name, gpu_req, &entry)?;
+    let debug_messenger_data = Box::new(HalaDebugMessengerData {
+      callback: validation_config.callback.take(),
+      fail_on_error: validation_config.fail_on_error,
+      error: std::sync::Mutex::new(None),
+    });
+    let (debug_utils_loader, debug_call_back, instance) = Self::create_instance(
+      name,
+      gpu_req,
+      &validation_config,
+      &debug_messenger_data,
+      is_swapchain_colorspace_enabled,
+      &entry,
+    )?;
 
     log::debug!("A HalaInstance is created.");
     Ok(
@@ -73,18 +200,38 @@ impl HalaInstance {
         raw: instance,
         debug_utils_loader,
         debug_call_back,
+        is_swapchain_colorspace_enabled,
+        debug_messenger_data,
       }
     )
   }
 
+  /// Take the first validation error recorded since the last call.
+  /// The error is only recorded if the instance is created with HalaValidationConfig::fail_on_error.
+  /// return: The result, the error contains the validation message.
+  pub fn take_validation_error(&self) -> Result<(), HalaGfxError> {
+    let error = self.debug_messenger_data.error.lock()
+      .map_or(None, |mut error| error.take());
+    match error {
+      Some(message) => Err(HalaGfxError::new(&format!("Vulkan validation error: {}", message), None)),
+      None => Ok(()),
+    }
+  }
+
   /// Create a Vulkan instance.
   /// param name: The name of the instance.
   /// param gpu_req: The GPU requirements.
+  /// param validation_config: The validation configuration.
+  /// param debug_messenger_data: The user data of the debug messenger.
+  /// param is_swapchain_colorspace_enabled: Whether to enable the swapchain color space extension.
   /// param entry: The Vulkan entry.
   /// return: The debug utils loader, the debug call back, and the instance.
   fn create_instance(
     name: &str,
     gpu_req: &crate::HalaGPURequirements,
+    validation_config: &HalaValidationConfig,
+    debug_messenger_data: &HalaDebugMessengerData,
+    is_swapchain_colorspace_enabled: bool,
     entry: &ash::Entry
   ) -> Result<
     (
@@ -106,7 +253,7 @@ impl HalaInstance {
         .engine_version(vk::make_api_version(0, 0, 1, 0))
         .api_version(vk::make_api_version(0, gpu_req.version.0, gpu_req.version.1, gpu_req.version.2));
 
-      let debug_create_info = if cfg!(debug_assertions) {
+      let debug_create_info = if cfg!(debug_assertions) || gpu_req.require_printf_in_shader {
         vk::DebugUtilsMessengerCreateInfoEXT::default()
           .message_severity(
             vk::DebugUtilsMessageSeverityFlagsEXT::WARNING
@@ -122,13 +269,21 @@ impl HalaInstance {
           )
       }
       .message_type(
+        if gpu_req.require_printf_in_shader {
+          // Some versions of the validation layer report the printf messages as the general type.
           vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
-          | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
-          // | vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
+            | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
+            | vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
+        } else {
+          vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
+            | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE
+            // | vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
+        }
       )
-      .pfn_user_callback(Some(vulkan_debug_utils_callback));
+      .pfn_user_callback(Some(vulkan_debug_utils_callback))
+      .user_data(debug_messenger_data as *const HalaDebugMessengerData as *mut std::ffi::c_void);
 
-      let layer_names = if cfg!(debug_assertions) {
+      let layer_names = if validation_config.enabled {
         vec![
           CString::new("VK_LAYER_KHRONOS_validation")
             .map_err(|err| HalaGfxError::new("Failed to create CString VK_LAYER_KHRONOS_validation.", Some(Box::new(err))))?,
@@ -153,15 +308,31 @@ impl HalaInstance {
         #[cfg(target_os = "macos")]
         ash::mvk::macos_surface::NAME.as_ptr(),
       ];
-      if cfg!(debug_assertions) {
+      // The debug utils is also used by the debug names and labels in the debug build.
+      let is_debug_utils_enabled = cfg!(debug_assertions) || validation_config.enabled;
+      if is_debug_utils_enabled {
         extension_name_ptrs.push(ash::ext::debug_utils::NAME.as_ptr());
       }
+      if is_swapchain_colorspace_enabled {
+        extension_name_ptrs.push(ash::ext::swapchain_colorspace::NAME.as_ptr());
+      }
 
-      let validation_feature_enables = vec![vk::ValidationFeatureEnableEXT::DEBUG_PRINTF];
+      let mut validation_feature_flags = validation_config.features;
+      if gpu_req.require_printf_in_shader {
+        validation_feature_flags |= HalaValidationFeatureFlags::DEBUG_PRINTF;
+      }
+      let validation_feature_enables = validation_feature_flags.to_raw();
       let mut validation_features = vk::ValidationFeaturesEXT::default()
         .enabled_validation_features(&validation_feature_enables);
+      let is_validation_features_enabled = validation_config.enabled && !validation_feature_enables.is_empty();
+      if is_validation_features_enabled {
+        // The extension is provided by the validation layer.
+        extension_name_ptrs.push(ash::ext::validation_features::NAME.as_ptr());
+      } else if gpu_req.require_printf_in_shader {
+        log::warn!("The printf in shader is required, but it is ignored because the validation is disabled.");
+      }
 
-      let instance_create_info = if cfg!(debug_assertions) && gpu_req.require_printf_in_shader {
+      let instance_create_info = if is_validation_features_enabled {
         vk::InstanceCreateInfo::default()
           .push_next(&mut validation_features)
           .application_info(&app_info)
@@ -176,13 +347,19 @@ impl HalaInstance {
       let instance = entry.create_instance(&instance_create_info, None)
         .map_err(|err| HalaGfxError::new("Failed to create Vulkan instance.", Some(Box::new(err))))?;
 
-      let debug_obj = if cfg!(debug_assertions) {
+      let debug_obj = if is_debug_utils_enabled {
         let debug_utils_loader = ash::ext::debug_utils::Instance::new(entry, &instance);
-        let debug_call_back = debug_utils_loader
-          .create_debug_utils_messenger(&debug_create_info, None)
-          .map_err(|err| HalaGfxError::new("Failed to create Vulkan debug utils messenger.", Some(Box::new(err))))?;
+        let debug_call_back = if validation_config.enabled {
+          Some(
+            debug_utils_loader
+              .create_debug_utils_messenger(&debug_create_info, None)
+              .map_err(|err| HalaGfxError::new("Failed to create Vulkan debug utils messenger.", Some(Box::new(err))))?
+          )
+        } else {
+          None
+        };
 
-        (Some(debug_utils_loader), Some(debug_call_back))
+        (Some(debug_utils_loader), debug_call_back)
       } else {
         (None, None)
       };
@@ -191,4 +368,16 @@ impl HalaInstance {
     };
     Ok((debug_utils_loader, debug_call_back, instance))
   }
-}
\ No newline at end of file
+
+  /// Check if the instance extension is supported.
+  /// param entry: The Vulkan entry.
+  /// param extension_name: The extension name.
+  /// return: True if the extension is supported.
+  fn is_extension_supported(entry: &ash::Entry, extension_name: &std::ffi::CStr) -> Result<bool, HalaGfxError> {
+    let extension_properties = unsafe {
+      entry.enumerate_instance_extension_properties(None)
+        .map_err(|err| HalaGfxError::new("Failed to enumerate instance extension properties.", Some(Box::new(err))))?
+    };
+    Ok(extension_properties.iter().any(|properties| properties.extension_name_as_c_str() == Ok(extension_name)))
+  }
+}
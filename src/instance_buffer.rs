@@ -0,0 +1,188 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::{
+  HalaBuffer,
+  HalaBufferUsageFlags,
+  HalaCommandBufferSet,
+  HalaDescriptorSet,
+  HalaGfxError,
+  HalaLogicalDevice,
+  HalaMemoryLocation,
+};
+
+/// The per-instance data buffer.
+/// The instances are stored in a GPU only storage buffer and indexed by gl_InstanceIndex in the shader.
+/// The buffers replaced by the growth are kept until the frames in flight which may read them are retired.
+pub struct HalaInstanceBuffer<T: Copy> {
+  pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  pub buffer: HalaBuffer,
+  pub(crate) staging_buffer: HalaBuffer,
+  pub instances: Vec<T>,
+  pub capacity: usize,
+  pub frames_in_flight: usize,
+  pub(crate) frame_count: u64,
+  pub(crate) retired_buffers: Vec<(u64, HalaBuffer, HalaBuffer)>,
+  pub(crate) is_dirty: bool,
+  pub(crate) debug_name: String,
+}
+
+/// The Drop trait implementation of the instance buffer.
+impl<T: Copy> Drop for HalaInstanceBuffer<T> {
+  fn drop(&mut self) {
+    log::debug!("A HalaInstanceBuffer \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation of the instance buffer.
+impl<T: Copy> HalaInstanceBuffer<T> {
+  /// Create a new instance buffer.
+  /// param logical_device: The logical device.
+  /// param capacity: The initial capacity of the instances.
+  /// param frames_in_flight: The number of the frames in flight which may read the buffer.
+  /// param debug_name: The debug name.
+  /// return: The instance buffer.
+  pub fn new(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    capacity: usize,
+    frames_in_flight: usize,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    if std::mem::size_of::<T>() == 0 {
+      return Err(HalaGfxError::new("The size of the instance type must be greater than 0.", None));
+    }
+    if frames_in_flight == 0 {
+      return Err(HalaGfxError::new("The number of the frames in flight must be greater than 0.", None));
+    }
+    let capacity = capacity.max(1);
+    let (buffer, staging_buffer) = Self::create_buffers(&logical_device, capacity, debug_name)?;
+
+    log::debug!("A HalaInstanceBuffer \"{}\" is created.", debug_name);
+    Ok(Self {
+      logical_device,
+      buffer,
+      staging_buffer,
+      instances: Vec::with_capacity(capacity),
+      capacity,
+      frames_in_flight,
+      frame_count: 0,
+      retired_buffers: Vec::new(),
+      is_dirty: false,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Get the number of the instances.
+  /// return: The number of the instances.
+  pub fn len(&self) -> usize {
+    self.instances.len()
+  }
+
+  /// Check if there is no instance.
+  /// return: True if there is no instance.
+  pub fn is_empty(&self) -> bool {
+    self.instances.is_empty()
+  }
+
+  /// Add an instance.
+  /// param instance: The instance data.
+  /// return: The index of the instance.
+  pub fn push(&mut self, instance: T) -> u32 {
+    self.instances.push(instance);
+    self.is_dirty = true;
+    (self.instances.len() - 1) as u32
+  }
+
+  /// Set the instance data.
+  /// param index: The index of the instance.
+  /// param instance: The instance data.
+  pub fn set(&mut self, index: usize, instance: T) {
+    self.instances[index] = instance;
+    self.is_dirty = true;
+  }
+
+  /// Remove all the instances.
+  pub fn clear(&mut self) {
+    self.instances.clear();
+    self.is_dirty = true;
+  }
+
+  /// Upload the instances to the GPU buffer.
+  /// If the instances exceed the capacity, the buffer will be recreated with a larger size.
+  /// It should be called once per frame after the frame ring waits the current frame slot,
+  /// the replaced buffers are destroyed after the frames in flight which may read them are retired.
+  /// param command_buffers: The transfer command buffer set.
+  /// return: True if the buffer is recreated and the descriptor set should be updated.
+  pub fn flush(&mut self, command_buffers: &HalaCommandBufferSet) -> Result<bool, HalaGfxError> {
+    self.frame_count += 1;
+    let retired_frame_count = self.frame_count.saturating_sub(self.frames_in_flight as u64);
+    self.retired_buffers.retain(|(frame_count, _, _)| *frame_count > retired_frame_count);
+
+    if !self.is_dirty {
+      return Ok(false);
+    }
+
+    let is_recreated = if self.instances.len() > self.capacity {
+      let capacity = self.instances.len().next_power_of_two();
+      let (buffer, staging_buffer) = Self::create_buffers(&self.logical_device, capacity, &self.debug_name)?;
+      let buffer = std::mem::replace(&mut self.buffer, buffer);
+      let staging_buffer = std::mem::replace(&mut self.staging_buffer, staging_buffer);
+      self.retired_buffers.push((self.frame_count, buffer, staging_buffer));
+      self.capacity = capacity;
+      true
+    } else {
+      false
+    };
+
+    if !self.instances.is_empty() {
+      self.buffer.update_gpu_memory_with_buffer(self.instances.as_slice(), &self.staging_buffer, command_buffers)?;
+    }
+    self.is_dirty = false;
+
+    Ok(is_recreated)
+  }
+
+  /// Get the device address of the instance buffer.
+  /// return: The device address.
+  pub fn get_device_address(&self) -> u64 {
+    self.buffer.get_device_address()
+  }
+
+  /// Bind the instance buffer to the storage buffer binding of the descriptor set.
+  /// param descriptor_set: The descriptor set.
+  /// param index: The index of the descriptor set.
+  /// param binding: The binding.
+  pub fn update_descriptor_set(&self, descriptor_set: &HalaDescriptorSet, index: usize, binding: u32) {
+    descriptor_set.update_storage_buffers(index, binding, &[&self.buffer]);
+  }
+
+  /// Create the GPU buffer and the staging buffer.
+  /// param logical_device: The logical device.
+  /// param capacity: The capacity of the instances.
+  /// param debug_name: The debug name.
+  /// return: The GPU buffer and the staging buffer.
+  fn create_buffers(
+    logical_device: &Rc<RefCell<HalaLogicalDevice>>,
+    capacity: usize,
+    debug_name: &str,
+  ) -> Result<(HalaBuffer, HalaBuffer), HalaGfxError> {
+    let size = capacity.checked_mul(std::mem::size_of::<T>())
+      .ok_or(HalaGfxError::new(&format!("The size of {} instances overflows.", capacity), None))? as u64;
+    let buffer = HalaBuffer::new(
+      Rc::clone(logical_device),
+      size,
+      HalaBufferUsageFlags::STORAGE_BUFFER | HalaBufferUsageFlags::TRANSFER_DST | HalaBufferUsageFlags::SHADER_DEVICE_ADDRESS,
+      HalaMemoryLocation::GpuOnly,
+      debug_name,
+    )?;
+    let staging_buffer = HalaBuffer::new(
+      Rc::clone(logical_device),
+      size,
+      HalaBufferUsageFlags::TRANSFER_SRC,
+      HalaMemoryLocation::CpuToGpu,
+      &format!("{}.staging", debug_name),
+    )?;
+
+    Ok((buffer, staging_buffer))
+  }
+}
@@ -16,15 +16,28 @@ pub mod pipeline_cache;
 pub mod command_pools;
 pub mod command_buffer;
 pub mod buffer;
+pub mod instance_buffer;
+pub mod ring_uniform_buffer;
+pub mod staging_buffer_pool;
+pub mod async_uploader;
+pub mod sparse_buffer;
 pub mod descriptor_pool;
 pub mod descriptor_set;
 pub mod format;
 pub mod acceleration_structure;
 pub mod image;
+pub mod image_view;
 pub mod sampler;
 pub mod shader_binding_table;
 pub mod query;
 pub mod barrier;
+pub mod event;
+pub mod semaphore;
+pub mod frame_ring;
+pub mod frame_context;
+pub mod ui_overlay;
+#[cfg(feature = "spirv-reflect")]
+pub mod reflection;
 pub mod aabb;
 
 pub use prelude::*;
@@ -48,12 +61,17 @@ pub struct HalaGPURequirements {
   pub require_mesh_shader: bool,
   pub require_ray_tracing: bool,
   pub require_10bits_output: bool,
+  pub preferred_color_space: HalaColorSpace,
   pub is_immediate: bool,
   pub is_low_latency: bool,
   pub require_depth: bool,
   pub require_stencil: bool,
   pub require_printf_in_shader: bool,
   pub require_depth_stencil_resolve: bool,
+  pub require_sparse_residency: bool,
+  pub require_multiview: bool,
+  pub require_fragment_shading_rate: bool,
+  pub require_ray_query: bool,
 }
 
 impl Default for HalaGPURequirements {
@@ -69,12 +87,17 @@ impl Default for HalaGPURequirements {
       require_mesh_shader: false,
       require_ray_tracing: false,
       require_10bits_output: false,
+      preferred_color_space: HalaColorSpace::SRGB_NONLINEAR,
       is_immediate: false,
       is_low_latency: false,
       require_depth: true,
       require_stencil: false,
       require_printf_in_shader: false,
       require_depth_stencil_resolve: false,
+      require_sparse_residency: false,
+      require_multiview: false,
+      require_fragment_shading_rate: false,
+      require_ray_query: false,
     }
   }
 }
\ No newline at end of file
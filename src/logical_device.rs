@@ -4,6 +4,8 @@ use crate::{
   HalaCommandBufferSet,
   HalaCommandBufferUsageFlags,
   HalaGfxError,
+  HalaPipelineStageFlags2,
+  HalaSemaphore,
 };
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -36,6 +38,230 @@ impl std::convert::From<HalaMemoryLocation> for gpu_allocator::MemoryLocation {
   }
 }
 
+/// The subgroup feature flags.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HalaSubgroupFeatureFlags(u32);
+crate::hala_bitflags_wrapped!(HalaSubgroupFeatureFlags, u32);
+impl HalaSubgroupFeatureFlags {
+  pub const BASIC: Self = Self(vk::SubgroupFeatureFlags::BASIC.as_raw());
+  pub const VOTE: Self = Self(vk::SubgroupFeatureFlags::VOTE.as_raw());
+  pub const ARITHMETIC: Self = Self(vk::SubgroupFeatureFlags::ARITHMETIC.as_raw());
+  pub const BALLOT: Self = Self(vk::SubgroupFeatureFlags::BALLOT.as_raw());
+  pub const SHUFFLE: Self = Self(vk::SubgroupFeatureFlags::SHUFFLE.as_raw());
+  pub const SHUFFLE_RELATIVE: Self = Self(vk::SubgroupFeatureFlags::SHUFFLE_RELATIVE.as_raw());
+  pub const CLUSTERED: Self = Self(vk::SubgroupFeatureFlags::CLUSTERED.as_raw());
+  pub const QUAD: Self = Self(vk::SubgroupFeatureFlags::QUAD.as_raw());
+}
+
+impl std::convert::From<vk::SubgroupFeatureFlags> for HalaSubgroupFeatureFlags {
+  fn from(v: vk::SubgroupFeatureFlags) -> Self {
+    Self(v.as_raw())
+  }
+}
+
+impl std::convert::From<HalaSubgroupFeatureFlags> for vk::SubgroupFeatureFlags {
+  fn from(v: HalaSubgroupFeatureFlags) -> Self {
+    Self::from_raw(v.0)
+  }
+}
+
+/// The subgroup information of the device.
+#[derive(Clone, Copy, Default)]
+pub struct HalaSubgroupInfo {
+  /// The default number of the invocations in a subgroup.
+  pub size: u32,
+  pub supported_stages: crate::HalaShaderStageFlags,
+  pub supported_operations: HalaSubgroupFeatureFlags,
+  pub quad_operations_in_all_stages: bool,
+}
+
+/// The subgroup properties of the physical device.
+#[derive(Clone, Copy, Default)]
+pub struct HalaSubgroupProperties {
+  pub info: HalaSubgroupInfo,
+  /// The minimum subgroup size which can be required by a pipeline shader stage.
+  pub min_size: u32,
+  /// The maximum subgroup size which can be required by a pipeline shader stage.
+  pub max_size: u32,
+  /// The stages which support the required subgroup size.
+  pub required_size_stages: crate::HalaShaderStageFlags,
+  /// Whether the subgroup size control is supported(VK_EXT_subgroup_size_control, core in Vulkan 1.3).
+  pub size_control_supported: bool,
+  pub compute_full_subgroups_supported: bool,
+}
+
+/// The budget and usage of a memory heap.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HalaMemoryHeapBudget {
+  pub heap_index: u32,
+  /// The size of the heap in bytes.
+  pub size: u64,
+  /// The estimated bytes the process can use from the heap, it is the heap size without the memory budget extension.
+  pub budget: u64,
+  /// The estimated bytes the process uses from the heap, it is 0 without the memory budget extension.
+  pub usage: u64,
+  pub is_device_local: bool,
+}
+
+/// The implementation of the budget of a memory heap.
+impl HalaMemoryHeapBudget {
+  /// Get the bytes which can still be allocated from the heap before exceeding the budget.
+  /// A streaming system should back off when it approaches 0.
+  /// return: The available bytes.
+  pub fn available(&self) -> u64 {
+    self.budget.saturating_sub(self.usage)
+  }
+}
+
+/// The commonly used limits of the device.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HalaDeviceLimits {
+  pub max_image_dimension_1d: u32,
+  pub max_image_dimension_2d: u32,
+  pub max_image_dimension_3d: u32,
+  pub max_image_dimension_cube: u32,
+  pub max_image_array_layers: u32,
+  pub max_uniform_buffer_range: u32,
+  pub max_storage_buffer_range: u32,
+  pub max_push_constants_size: u32,
+  pub max_memory_allocation_count: u32,
+  pub max_sampler_allocation_count: u32,
+  pub buffer_image_granularity: u64,
+  pub max_bound_descriptor_sets: u32,
+  pub max_per_stage_descriptor_samplers: u32,
+  pub max_per_stage_descriptor_uniform_buffers: u32,
+  pub max_per_stage_descriptor_storage_buffers: u32,
+  pub max_per_stage_descriptor_sampled_images: u32,
+  pub max_per_stage_descriptor_storage_images: u32,
+  pub max_per_stage_resources: u32,
+  pub max_descriptor_set_samplers: u32,
+  pub max_descriptor_set_uniform_buffers: u32,
+  pub max_descriptor_set_uniform_buffers_dynamic: u32,
+  pub max_descriptor_set_storage_buffers: u32,
+  pub max_descriptor_set_storage_buffers_dynamic: u32,
+  pub max_descriptor_set_sampled_images: u32,
+  pub max_descriptor_set_storage_images: u32,
+  pub max_descriptor_set_input_attachments: u32,
+  pub max_vertex_input_attributes: u32,
+  pub max_vertex_input_bindings: u32,
+  pub max_vertex_input_attribute_offset: u32,
+  pub max_vertex_input_binding_stride: u32,
+  pub max_fragment_output_attachments: u32,
+  pub max_compute_shared_memory_size: u32,
+  pub max_compute_work_group_count: [u32; 3],
+  pub max_compute_work_group_invocations: u32,
+  pub max_compute_work_group_size: [u32; 3],
+  pub max_draw_indexed_index_value: u32,
+  pub max_draw_indirect_count: u32,
+  pub max_sampler_lod_bias: f32,
+  pub max_sampler_anisotropy: f32,
+  pub max_viewports: u32,
+  pub max_viewport_dimensions: [u32; 2],
+  pub min_texel_buffer_offset_alignment: u64,
+  pub min_uniform_buffer_offset_alignment: u64,
+  pub min_storage_buffer_offset_alignment: u64,
+  pub max_framebuffer_width: u32,
+  pub max_framebuffer_height: u32,
+  pub max_framebuffer_layers: u32,
+  pub max_color_attachments: u32,
+  pub timestamp_compute_and_graphics: bool,
+  pub timestamp_period: f32,
+  pub optimal_buffer_copy_offset_alignment: u64,
+  pub optimal_buffer_copy_row_pitch_alignment: u64,
+  pub non_coherent_atom_size: u64,
+  /// Whether the lines are rasterized according to the preferred method of the spec.
+  pub strict_lines: bool,
+}
+
+/// The From trait implementation of the device limits.
+impl From<&vk::PhysicalDeviceLimits> for HalaDeviceLimits {
+  fn from(limits: &vk::PhysicalDeviceLimits) -> Self {
+    Self {
+      max_image_dimension_1d: limits.max_image_dimension1_d,
+      max_image_dimension_2d: limits.max_image_dimension2_d,
+      max_image_dimension_3d: limits.max_image_dimension3_d,
+      max_image_dimension_cube: limits.max_image_dimension_cube,
+      max_image_array_layers: limits.max_image_array_layers,
+      max_uniform_buffer_range: limits.max_uniform_buffer_range,
+      max_storage_buffer_range: limits.max_storage_buffer_range,
+      max_push_constants_size: limits.max_push_constants_size,
+      max_memory_allocation_count: limits.max_memory_allocation_count,
+      max_sampler_allocation_count: limits.max_sampler_allocation_count,
+      buffer_image_granularity: limits.buffer_image_granularity,
+      max_bound_descriptor_sets: limits.max_bound_descriptor_sets,
+      max_per_stage_descriptor_samplers: limits.max_per_stage_descriptor_samplers,
+      max_per_stage_descriptor_uniform_buffers: limits.max_per_stage_descriptor_uniform_buffers,
+      max_per_stage_descriptor_storage_buffers: limits.max_per_stage_descriptor_storage_buffers,
+      max_per_stage_descriptor_sampled_images: limits.max_per_stage_descriptor_sampled_images,
+      max_per_stage_descriptor_storage_images: limits.max_per_stage_descriptor_storage_images,
+      max_per_stage_resources: limits.max_per_stage_resources,
+      max_descriptor_set_samplers: limits.max_descriptor_set_samplers,
+      max_descriptor_set_uniform_buffers: limits.max_descriptor_set_uniform_buffers,
+      max_descriptor_set_uniform_buffers_dynamic: limits.max_descriptor_set_uniform_buffers_dynamic,
+      max_descriptor_set_storage_buffers: limits.max_descriptor_set_storage_buffers,
+      max_descriptor_set_storage_buffers_dynamic: limits.max_descriptor_set_storage_buffers_dynamic,
+      max_descriptor_set_sampled_images: limits.max_descriptor_set_sampled_images,
+      max_descriptor_set_storage_images: limits.max_descriptor_set_storage_images,
+      max_descriptor_set_input_attachments: limits.max_descriptor_set_input_attachments,
+      max_vertex_input_attributes: limits.max_vertex_input_attributes,
+      max_vertex_input_bindings: limits.max_vertex_input_bindings,
+      max_vertex_input_attribute_offset: limits.max_vertex_input_attribute_offset,
+      max_vertex_input_binding_stride: limits.max_vertex_input_binding_stride,
+      max_fragment_output_attachments: limits.max_fragment_output_attachments,
+      max_compute_shared_memory_size: limits.max_compute_shared_memory_size,
+      max_compute_work_group_count: limits.max_compute_work_group_count,
+      max_compute_work_group_invocations: limits.max_compute_work_group_invocations,
+      max_compute_work_group_size: limits.max_compute_work_group_size,
+      max_draw_indexed_index_value: limits.max_draw_indexed_index_value,
+      max_draw_indirect_count: limits.max_draw_indirect_count,
+      max_sampler_lod_bias: limits.max_sampler_lod_bias,
+      max_sampler_anisotropy: limits.max_sampler_anisotropy,
+      max_viewports: limits.max_viewports,
+      max_viewport_dimensions: limits.max_viewport_dimensions,
+      min_texel_buffer_offset_alignment: limits.min_texel_buffer_offset_alignment,
+      min_uniform_buffer_offset_alignment: limits.min_uniform_buffer_offset_alignment,
+      min_storage_buffer_offset_alignment: limits.min_storage_buffer_offset_alignment,
+      max_framebuffer_width: limits.max_framebuffer_width,
+      max_framebuffer_height: limits.max_framebuffer_height,
+      max_framebuffer_layers: limits.max_framebuffer_layers,
+      max_color_attachments: limits.max_color_attachments,
+      timestamp_compute_and_graphics: limits.timestamp_compute_and_graphics == vk::TRUE,
+      timestamp_period: limits.timestamp_period,
+      optimal_buffer_copy_offset_alignment: limits.optimal_buffer_copy_offset_alignment,
+      optimal_buffer_copy_row_pitch_alignment: limits.optimal_buffer_copy_row_pitch_alignment,
+      non_coherent_atom_size: limits.non_coherent_atom_size,
+      strict_lines: limits.strict_lines == vk::TRUE,
+    }
+  }
+}
+
+/// The report of the GPU allocator with the live allocations and the memory blocks.
+pub type HalaAllocatorReport = gpu_allocator::AllocatorReport;
+
+/// The statistics of the GPU allocator.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HalaAllocatorStatistics {
+  /// The bytes used by the allocations.
+  pub total_allocated_bytes: u64,
+  /// The bytes reserved by the memory blocks including the unallocated regions.
+  pub total_reserved_bytes: u64,
+  pub allocation_count: usize,
+  pub block_count: usize,
+}
+
+/// The implementation of the statistics of the GPU allocator.
+impl HalaAllocatorStatistics {
+  /// Get the ratio of the reserved bytes which are not used by the allocations.
+  /// return: The fragmentation in [0, 1].
+  pub fn fragmentation(&self) -> f32 {
+    if self.total_reserved_bytes == 0 {
+      0.0
+    } else {
+      1.0 - self.total_allocated_bytes as f32 / self.total_reserved_bytes as f32
+    }
+  }
+}
+
 /// The logical device.
 pub struct HalaLogicalDevice {
   pub raw: ash::Device,
@@ -49,6 +275,18 @@ pub struct HalaLogicalDevice {
   pub acceleration_structure_loader: ash::khr::acceleration_structure::Device,
   pub deferred_host_operations_loader: ash::khr::deferred_host_operations::Device,
   pub ray_tracing_pipeline_loader: ash::khr::ray_tracing_pipeline::Device,
+  pub hdr_metadata_loader: Option<ash::ext::hdr_metadata::Device>,
+  pub push_descriptor_loader: Option<ash::khr::push_descriptor::Device>,
+  pub conditional_rendering_loader: Option<ash::ext::conditional_rendering::Device>,
+  pub extended_dynamic_state_loader: Option<ash::ext::extended_dynamic_state::Device>,
+  pub pipeline_executable_properties_loader: Option<ash::khr::pipeline_executable_properties::Device>,
+  pub line_rasterization_loader: Option<ash::ext::line_rasterization::Device>,
+  pub fragment_shading_rate_loader: Option<ash::khr::fragment_shading_rate::Device>,
+  pub vertex_input_dynamic_state_loader: Option<ash::ext::vertex_input_dynamic_state::Device>,
+  pub transform_feedback_loader: Option<ash::ext::transform_feedback::Device>,
+  pub multi_draw_loader: Option<ash::ext::multi_draw::Device>,
+
+  pub memory_budget_supported: bool,
 
   pub min_acceleration_structure_scratch_offset_alignment: u32,
   pub max_ray_recursion_depth: u32,
@@ -56,6 +294,9 @@ pub struct HalaLogicalDevice {
   pub shader_group_handle_size: u32,
   pub shader_group_handle_alignment: u32,
   pub shader_group_base_alignment: u32,
+  pub max_shader_group_stride: u32,
+  pub shader_group_handle_capture_replay_size: u32,
+  pub shader_group_handle_capture_replay_supported: bool,
 
   pub framebuffer_color_sample_counts: vk::SampleCountFlags,
   pub framebuffer_depth_sample_counts: vk::SampleCountFlags,
@@ -63,8 +304,38 @@ pub struct HalaLogicalDevice {
   pub framebuffer_no_attachments_sample_counts: vk::SampleCountFlags,
 
   pub supported_depth_resolve_modes: vk::ResolveModeFlags,
+  pub supported_stencil_resolve_modes: vk::ResolveModeFlags,
+  /// Whether the depth and stencil resolve modes can be different.
+  pub independent_resolve: bool,
+  /// Whether one of the depth and stencil resolve modes can be NONE while the other is not.
+  pub independent_resolve_none: bool,
+
+  pub max_task_work_group_count: [u32; 3],
+  pub max_task_work_group_total_count: u32,
+
+  pub subgroup_properties: HalaSubgroupProperties,
+
+  pub multiview_enabled: bool,
+  pub ray_query_enabled: bool,
+  pub max_multi_draw_count: u32,
+  pub max_multiview_view_count: u32,
+
+  pub attachment_fragment_shading_rate_supported: bool,
+  pub min_fragment_shading_rate_attachment_texel_size: vk::Extent2D,
+  pub max_fragment_shading_rate_attachment_texel_size: vk::Extent2D,
+
+  pub physical_device_features: vk::PhysicalDeviceFeatures,
+  /// The line rasterization modes and stippled line features, they are all false without the line rasterization extension.
+  pub line_rasterization_features: vk::PhysicalDeviceLineRasterizationFeaturesEXT<'static>,
+
+  pub descriptor_indexing_properties: vk::PhysicalDeviceDescriptorIndexingProperties<'static>,
+
+  pub limits: HalaDeviceLimits,
 
   pub gpu_allocator: gpu_allocator::vulkan::Allocator,
+
+  pub(crate) instance: ash::Instance,
+  pub(crate) physical_device: vk::PhysicalDevice,
 }
 
 /// The Drop trait implementation of the logical device.
@@ -129,8 +400,49 @@ impl HalaLogicalDevice {
 
     let physical_device_properties = Self::get_physical_device_properties(instance, physical_device);
 
+    let physical_device_features = Self::get_physical_device_features(instance, physical_device);
+
     let depth_stencil_resolve_features = Self::get_depth_stencil_resolve_features(instance, physical_device);
 
+    let descriptor_indexing_properties = Self::get_descriptor_indexing_properties(instance, physical_device);
+
+    let mesh_shader_properties = Self::get_mesh_shader_properties(instance, physical_device);
+
+    let subgroup_properties = physical_device.subgroup_properties(instance);
+
+    let (
+      multiview_properties,
+      multiview_features,
+    ) = Self::get_multiview_info(instance, physical_device);
+
+    let (
+      fragment_shading_rate_properties,
+      fragment_shading_rate_features,
+    ) = Self::get_fragment_shading_rate_info(instance, physical_device);
+    let is_fragment_shading_rate_enabled = gpu_req.require_mesh_shader || gpu_req.require_fragment_shading_rate;
+
+    let is_vertex_input_dynamic_state_enabled =
+      Self::is_extension_supported(instance, physical_device, ash::ext::vertex_input_dynamic_state::NAME)
+      && Self::get_vertex_input_dynamic_state_features(instance, physical_device).vertex_input_dynamic_state == vk::TRUE;
+
+    let line_rasterization_features = if Self::is_extension_supported(instance, physical_device, ash::ext::line_rasterization::NAME) {
+      Self::get_line_rasterization_features(instance, physical_device)
+    } else {
+      vk::PhysicalDeviceLineRasterizationFeaturesEXT::default()
+    };
+
+    let is_transform_feedback_enabled =
+      Self::is_extension_supported(instance, physical_device, ash::ext::transform_feedback::NAME)
+      && Self::get_transform_feedback_features(instance, physical_device).transform_feedback == vk::TRUE;
+
+    let (
+      multi_draw_properties,
+      multi_draw_features,
+    ) = Self::get_multi_draw_info(instance, physical_device);
+    let is_multi_draw_enabled =
+      Self::is_extension_supported(instance, physical_device, ash::ext::multi_draw::NAME)
+      && multi_draw_features.multi_draw == vk::TRUE;
+
     // Create ray tracing objects.
     let (
       acceleration_structure,
@@ -142,7 +454,7 @@ impl HalaLogicalDevice {
       acceleration_structure_properties,
       _acceleration_structure_features,
       ray_tracing_pipeline_properties,
-      _ray_tracing_pipeline_features,
+      ray_tracing_pipeline_features,
     ) = Self::get_ray_tracing_features(instance, physical_device);
 
     let gpu_allocator = Self::create_gpu_allocator(
@@ -156,7 +468,7 @@ impl HalaLogicalDevice {
     Ok(
       Self {
         raw: device.clone(),
-        debug_utils_loader: if cfg!(debug_assertions) {
+        debug_utils_loader: if instance.debug_utils_loader.is_some() {
           Some(ash::ext::debug_utils::Device::new(&instance.raw, &device))
         } else {
           None
@@ -169,21 +481,206 @@ impl HalaLogicalDevice {
         acceleration_structure_loader: acceleration_structure,
         deferred_host_operations_loader: deferred_host_operations,
         ray_tracing_pipeline_loader: ray_tracing_pipeline,
+        hdr_metadata_loader: if Self::is_hdr_metadata_enabled(gpu_req, instance, physical_device) {
+          Some(ash::ext::hdr_metadata::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        push_descriptor_loader: if Self::is_extension_supported(instance, physical_device, ash::khr::push_descriptor::NAME) {
+          Some(ash::khr::push_descriptor::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        conditional_rendering_loader: if Self::is_extension_supported(instance, physical_device, ash::ext::conditional_rendering::NAME) {
+          Some(ash::ext::conditional_rendering::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        extended_dynamic_state_loader: if Self::is_extension_supported(instance, physical_device, ash::ext::extended_dynamic_state::NAME) {
+          Some(ash::ext::extended_dynamic_state::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        pipeline_executable_properties_loader: if Self::is_pipeline_executable_info_supported(instance, physical_device) {
+          Some(ash::khr::pipeline_executable_properties::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        line_rasterization_loader: if Self::is_extension_supported(instance, physical_device, ash::ext::line_rasterization::NAME) {
+          Some(ash::ext::line_rasterization::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        fragment_shading_rate_loader: if is_fragment_shading_rate_enabled {
+          Some(ash::khr::fragment_shading_rate::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        vertex_input_dynamic_state_loader: if is_vertex_input_dynamic_state_enabled {
+          Some(ash::ext::vertex_input_dynamic_state::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        transform_feedback_loader: if is_transform_feedback_enabled {
+          Some(ash::ext::transform_feedback::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        multi_draw_loader: if is_multi_draw_enabled {
+          Some(ash::ext::multi_draw::Device::new(&instance.raw, &device))
+        } else {
+          None
+        },
+        memory_budget_supported: Self::is_extension_supported(instance, physical_device, ash::ext::memory_budget::NAME),
 
         min_acceleration_structure_scratch_offset_alignment: acceleration_structure_properties.min_acceleration_structure_scratch_offset_alignment,
         max_ray_recursion_depth: ray_tracing_pipeline_properties.max_ray_recursion_depth,
         shader_group_handle_size: ray_tracing_pipeline_properties.shader_group_handle_size,
         shader_group_handle_alignment: ray_tracing_pipeline_properties.shader_group_handle_alignment,
         shader_group_base_alignment: ray_tracing_pipeline_properties.shader_group_base_alignment,
+        max_shader_group_stride: ray_tracing_pipeline_properties.max_shader_group_stride,
+        shader_group_handle_capture_replay_size: ray_tracing_pipeline_properties.shader_group_handle_capture_replay_size,
+        shader_group_handle_capture_replay_supported: gpu_req.require_ray_tracing
+          && ray_tracing_pipeline_features.ray_tracing_pipeline_shader_group_handle_capture_replay == vk::TRUE,
+        limits: HalaDeviceLimits::from(&physical_device_properties.limits),
         framebuffer_color_sample_counts: physical_device_properties.limits.framebuffer_color_sample_counts,
         framebuffer_depth_sample_counts: physical_device_properties.limits.framebuffer_depth_sample_counts,
         framebuffer_stencil_sample_counts: physical_device_properties.limits.framebuffer_stencil_sample_counts,
         framebuffer_no_attachments_sample_counts: physical_device_properties.limits.framebuffer_no_attachments_sample_counts,
         supported_depth_resolve_modes: depth_stencil_resolve_features.supported_depth_resolve_modes,
+        supported_stencil_resolve_modes: depth_stencil_resolve_features.supported_stencil_resolve_modes,
+        independent_resolve: depth_stencil_resolve_features.independent_resolve == vk::TRUE,
+        independent_resolve_none: depth_stencil_resolve_features.independent_resolve_none == vk::TRUE,
+        max_task_work_group_count: mesh_shader_properties.max_task_work_group_count,
+        max_task_work_group_total_count: mesh_shader_properties.max_task_work_group_total_count,
+        subgroup_properties,
+        multiview_enabled: (gpu_req.require_multiview || gpu_req.require_mesh_shader)
+          && multiview_features.multiview == vk::TRUE,
+        max_multiview_view_count: multiview_properties.max_multiview_view_count,
+        ray_query_enabled: gpu_req.require_ray_query,
+        max_multi_draw_count: multi_draw_properties.max_multi_draw_count,
+        attachment_fragment_shading_rate_supported: is_fragment_shading_rate_enabled
+          && fragment_shading_rate_features.attachment_fragment_shading_rate == vk::TRUE,
+        min_fragment_shading_rate_attachment_texel_size: fragment_shading_rate_properties.min_fragment_shading_rate_attachment_texel_size,
+        max_fragment_shading_rate_attachment_texel_size: fragment_shading_rate_properties.max_fragment_shading_rate_attachment_texel_size,
+        physical_device_features,
+        line_rasterization_features,
+        descriptor_indexing_properties,
+        instance: instance.raw.clone(),
+        physical_device: physical_device.raw,
       }
     )
   }
 
+  /// Check the group count of the compute dispatch against the device limits.
+  /// param group_count: The group count(x, y, z).
+  /// return: The result.
+  pub fn check_compute_group_count(&self, group_count: [u32; 3]) -> Result<(), HalaGfxError> {
+    for (axis, (count, max_count)) in group_count.iter().zip(self.limits.max_compute_work_group_count.iter()).enumerate() {
+      if count > max_count {
+        return Err(HalaGfxError::new(
+          &format!("The compute group count {} on axis {} exceeds the max count {}.", count, axis, max_count),
+          None,
+        ));
+      }
+    }
+    Ok(())
+  }
+
+  /// Check the group count of the mesh tasks against the device limits.
+  /// param group_count: The group count(x, y, z).
+  /// return: The result.
+  pub fn check_task_group_count(&self, group_count: [u32; 3]) -> Result<(), HalaGfxError> {
+    for (axis, (count, max_count)) in group_count.iter().zip(self.max_task_work_group_count.iter()).enumerate() {
+      if count > max_count {
+        return Err(HalaGfxError::new(
+          &format!("The task group count {} on axis {} exceeds the max count {}.", count, axis, max_count),
+          None,
+        ));
+      }
+    }
+    let total_count = group_count.iter().map(|&count| count as u64).product::<u64>();
+    if total_count > self.max_task_work_group_total_count as u64 {
+      return Err(HalaGfxError::new(
+        &format!("The total task group count {} exceeds the max count {}.", total_count, self.max_task_work_group_total_count),
+        None,
+      ));
+    }
+    Ok(())
+  }
+
+  /// Get the properties of the format.
+  /// param format: The format.
+  /// return: The format properties.
+  pub fn format_properties(&self, format: crate::HalaFormat) -> crate::HalaFormatProperties {
+    unsafe {
+      self.instance.get_physical_device_format_properties(self.physical_device, format.into())
+    }.into()
+  }
+
+  /// Check the graphics queue family supports the sparse binding, the sparse memory is bound on it.
+  /// return: The result.
+  pub(crate) fn check_sparse_binding_queue(&self) -> Result<(), HalaGfxError> {
+    let queue_family_properties = unsafe { self.instance.get_physical_device_queue_family_properties(self.physical_device) };
+    let is_sparse_binding_supported = queue_family_properties.get(self.graphics_queue_family_index as usize)
+      .is_some_and(|properties| properties.queue_flags.contains(vk::QueueFlags::SPARSE_BINDING));
+    if !is_sparse_binding_supported {
+      return Err(HalaGfxError::new("The graphics queue family does not support the sparse binding.", None));
+    }
+    Ok(())
+  }
+
+  /// Get the budget and usage of the memory heaps.
+  /// Without the memory budget extension, the budget is the heap size and the usage is unknown and reported as 0,
+  /// use allocator_statistics to get the bytes allocated by this process instead.
+  /// return: The budgets of the memory heaps.
+  pub fn memory_budget(&self) -> Vec<HalaMemoryHeapBudget> {
+    let mut memory_budget_properties = vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+    let mut memory_properties2 = vk::PhysicalDeviceMemoryProperties2::default();
+    if self.memory_budget_supported {
+      memory_properties2 = memory_properties2.push_next(&mut memory_budget_properties);
+    }
+    unsafe {
+      self.instance.get_physical_device_memory_properties2(self.physical_device, &mut memory_properties2);
+    }
+    let memory_properties = memory_properties2.memory_properties;
+
+    memory_properties.memory_heaps_as_slice().iter().enumerate().map(|(heap_index, heap)| {
+      let (budget, usage) = if self.memory_budget_supported {
+        (memory_budget_properties.heap_budget[heap_index], memory_budget_properties.heap_usage[heap_index])
+      } else {
+        (heap.size, 0)
+      };
+      HalaMemoryHeapBudget {
+        heap_index: heap_index as u32,
+        size: heap.size,
+        budget,
+        usage,
+        is_device_local: heap.flags.contains(vk::MemoryHeapFlags::DEVICE_LOCAL),
+      }
+    }).collect()
+  }
+
+  /// Get the statistics of the GPU allocator.
+  /// return: The allocator statistics.
+  pub fn allocator_statistics(&self) -> HalaAllocatorStatistics {
+    let report = self.allocator_report();
+    HalaAllocatorStatistics {
+      total_allocated_bytes: report.total_allocated_bytes,
+      total_reserved_bytes: report.total_reserved_bytes,
+      allocation_count: report.allocations.len(),
+      block_count: report.blocks.len(),
+    }
+  }
+
+  /// Get the report of the GPU allocator.
+  /// Each memory block refers to the range of its allocations in the allocation list,
+  /// which helps to find the leaks and the sizing problems.
+  /// return: The allocator report.
+  pub fn allocator_report(&self) -> HalaAllocatorReport {
+    self.gpu_allocator.generate_report()
+  }
+
   /// Get a queue.
   /// param queue_family_index: The queue family index.
   /// param queue_index: The queue index.
@@ -229,7 +726,7 @@ impl HalaLogicalDevice {
   /// param name: The name.
   /// return: The result.
   pub fn set_debug_name<T: vk::Handle>(&self, handle: T, name: &str) -> Result<(), HalaGfxError> {
-    let name = std::ffi::CString::new(name).unwrap();
+    let name = debug_cstring(name);
     let info = vk::DebugUtilsObjectNameInfoEXT::default()
       .object_handle(handle)
       .object_name(&name);
@@ -242,6 +739,48 @@ impl HalaLogicalDevice {
     Ok(())
   }
 
+  /// Begin a debug label on the queue.
+  /// param queue: The queue.
+  /// param name: The name of the label.
+  /// param color: The color of the label.
+  pub fn queue_begin_debug_label(&self, queue: vk::Queue, name: &str, color: [f32; 4]) {
+    let name = debug_cstring(name);
+    let label = vk::DebugUtilsLabelEXT::default()
+      .label_name(&name)
+      .color(color);
+    unsafe {
+      if let Some(debug_utils_loader) = &self.debug_utils_loader {
+        debug_utils_loader.queue_begin_debug_utils_label(queue, &label);
+      }
+    }
+  }
+
+  /// End the debug label on the queue.
+  /// param queue: The queue.
+  pub fn queue_end_debug_label(&self, queue: vk::Queue) {
+    unsafe {
+      if let Some(debug_utils_loader) = &self.debug_utils_loader {
+        debug_utils_loader.queue_end_debug_utils_label(queue);
+      }
+    }
+  }
+
+  /// Insert a debug label into the queue.
+  /// param queue: The queue.
+  /// param name: The name of the label.
+  /// param color: The color of the label.
+  pub fn queue_insert_debug_label(&self, queue: vk::Queue, name: &str, color: [f32; 4]) {
+    let name = debug_cstring(name);
+    let label = vk::DebugUtilsLabelEXT::default()
+      .label_name(&name)
+      .color(color);
+    unsafe {
+      if let Some(debug_utils_loader) = &self.debug_utils_loader {
+        debug_utils_loader.queue_insert_debug_utils_label(queue, &label);
+      }
+    }
+  }
+
   /// Execute and submit a transfer command buffer.
   /// param command_buffers: The transfer command buffer set.
   /// param buffer_index: The buffer index.
@@ -377,6 +916,243 @@ impl HalaLogicalDevice {
     Ok(())
   }
 
+  /// Submit a graphics command buffer with semaphores.
+  /// param command_buffers: The graphics command buffer set.
+  /// param index: The buffer index.
+  /// param queue_index: The queue index.
+  /// param wait_semaphores: The semaphores to wait and the stages which wait for them.
+  /// param signal_semaphores: The semaphores to signal when the command buffer is finished.
+  /// return: The result.
+  pub fn graphics_submit_with_semaphores(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    index: usize,
+    queue_index: u32,
+    wait_semaphores: &[(&HalaSemaphore, HalaPipelineStageFlags2)],
+    signal_semaphores: &[&HalaSemaphore],
+  ) -> Result<(), HalaGfxError> {
+    self.submit_with_semaphores(command_buffers, index, self.get_graphics_queue(queue_index), wait_semaphores, signal_semaphores)
+  }
+
+  /// Submit a transfer command buffer with semaphores.
+  /// It is used for the async transfer, e.g. the transfer queue releases the uploaded images
+  /// and signals a semaphore which is waited by the graphics queue acquiring them.
+  /// param command_buffers: The transfer command buffer set.
+  /// param index: The buffer index.
+  /// param queue_index: The queue index.
+  /// param wait_semaphores: The semaphores to wait and the stages which wait for them.
+  /// param signal_semaphores: The semaphores to signal when the command buffer is finished.
+  /// return: The result.
+  pub fn transfer_submit_with_semaphores(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    index: usize,
+    queue_index: u32,
+    wait_semaphores: &[(&HalaSemaphore, HalaPipelineStageFlags2)],
+    signal_semaphores: &[&HalaSemaphore],
+  ) -> Result<(), HalaGfxError> {
+    self.submit_with_semaphores(command_buffers, index, self.get_transfer_queue(queue_index), wait_semaphores, signal_semaphores)
+  }
+
+  /// Submit a compute command buffer with semaphores.
+  /// param command_buffers: The compute command buffer set.
+  /// param index: The buffer index.
+  /// param queue_index: The queue index.
+  /// param wait_semaphores: The semaphores to wait and the stages which wait for them.
+  /// param signal_semaphores: The semaphores to signal when the command buffer is finished.
+  /// return: The result.
+  pub fn compute_submit_with_semaphores(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    index: usize,
+    queue_index: u32,
+    wait_semaphores: &[(&HalaSemaphore, HalaPipelineStageFlags2)],
+    signal_semaphores: &[&HalaSemaphore],
+  ) -> Result<(), HalaGfxError> {
+    self.submit_with_semaphores(command_buffers, index, self.get_compute_queue(queue_index), wait_semaphores, signal_semaphores)
+  }
+
+  /// Submit a command buffer with semaphores.
+  /// param command_buffers: The command buffer set.
+  /// param index: The buffer index.
+  /// param queue: The queue.
+  /// param wait_semaphores: The semaphores to wait and the stages which wait for them.
+  /// param signal_semaphores: The semaphores to signal.
+  /// return: The result.
+  fn submit_with_semaphores(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    index: usize,
+    queue: vk::Queue,
+    wait_semaphores: &[(&HalaSemaphore, HalaPipelineStageFlags2)],
+    signal_semaphores: &[&HalaSemaphore],
+  ) -> Result<(), HalaGfxError> {
+    let wait_semaphore_infos = wait_semaphores.iter().map(|(semaphore, stage_mask)| {
+      vk::SemaphoreSubmitInfo::default()
+        .semaphore(semaphore.raw)
+        .stage_mask((*stage_mask).into())
+    }).collect::<Vec<_>>();
+    let signal_semaphore_infos = signal_semaphores.iter().map(|semaphore| {
+      vk::SemaphoreSubmitInfo::default()
+        .semaphore(semaphore.raw)
+        .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+    }).collect::<Vec<_>>();
+    self.submit_with_semaphore_infos(command_buffers, index, queue, &wait_semaphore_infos, &signal_semaphore_infos)
+  }
+
+  /// Submit a transfer command buffer which signals the timeline semaphore.
+  /// It is used for the async uploads, the host polls or waits the value instead of waiting the queue idle.
+  /// param command_buffers: The transfer command buffer set.
+  /// param index: The buffer index.
+  /// param queue_index: The queue index.
+  /// param timeline_semaphore: The timeline semaphore.
+  /// param signal_value: The value to signal when the command buffer is finished.
+  /// return: The result.
+  pub fn transfer_submit_with_timeline_semaphore(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    index: usize,
+    queue_index: u32,
+    timeline_semaphore: &crate::HalaTimelineSemaphore,
+    signal_value: u64,
+  ) -> Result<(), HalaGfxError> {
+    let signal_semaphore_infos = [
+      vk::SemaphoreSubmitInfo::default()
+        .semaphore(timeline_semaphore.raw)
+        .value(signal_value)
+        .stage_mask(vk::PipelineStageFlags2::ALL_COMMANDS)
+    ];
+    self.submit_with_semaphore_infos(command_buffers, index, self.get_transfer_queue(queue_index), &[], &signal_semaphore_infos)
+  }
+
+  /// Submit a command buffer with the raw semaphore submit infos.
+  /// param command_buffers: The command buffer set.
+  /// param index: The buffer index.
+  /// param queue: The queue.
+  /// param wait_semaphore_infos: The semaphore infos to wait.
+  /// param signal_semaphore_infos: The semaphore infos to signal.
+  /// return: The result.
+  fn submit_with_semaphore_infos(
+    &self,
+    command_buffers: &HalaCommandBufferSet,
+    index: usize,
+    queue: vk::Queue,
+    wait_semaphore_infos: &[vk::SemaphoreSubmitInfo],
+    signal_semaphore_infos: &[vk::SemaphoreSubmitInfo],
+  ) -> Result<(), HalaGfxError> {
+    let command_buffer_infos = [
+      vk::CommandBufferSubmitInfo::default()
+        .command_buffer(command_buffers.raw[index])
+    ];
+    let submit_info = vk::SubmitInfo2::default()
+      .wait_semaphore_infos(wait_semaphore_infos)
+      .command_buffer_infos(&command_buffer_infos)
+      .signal_semaphore_infos(signal_semaphore_infos);
+
+    unsafe {
+      self.raw.queue_submit2(queue, std::slice::from_ref(&submit_info), vk::Fence::null())
+        .map_err(|err| HalaGfxError::new("Failed to submit queue.", Some(Box::new(err))))?;
+    }
+
+    Ok(())
+  }
+
+  /// Bind or unbind the memory pages of the sparse buffers on the graphics queue.
+  /// The graphics queue family must support the sparse binding.
+  /// param queue_index: The queue index.
+  /// param buffer_binds: The sparse buffers and their memory binds.
+  /// param wait_semaphores: The semaphores to wait before binding.
+  /// param signal_semaphores: The semaphores to signal after binding.
+  /// param fence: The fence to signal after binding, it can be null.
+  /// return: The result.
+  pub fn bind_sparse<B, M>(
+    &self,
+    queue_index: u32,
+    buffer_binds: &[(B, &[M])],
+    wait_semaphores: &[&HalaSemaphore],
+    signal_semaphores: &[&HalaSemaphore],
+    fence: vk::Fence,
+  ) -> Result<(), HalaGfxError>
+    where B: AsRef<crate::HalaSparseBuffer>,
+          M: AsRef<crate::HalaSparseMemoryBind>
+  {
+    let memory_binds = buffer_binds.iter().map(|(_, binds)| {
+      binds.iter().map(|bind| bind.as_ref().to_raw()).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    let buffer_bind_infos = buffer_binds.iter().zip(memory_binds.iter()).map(|((buffer, _), binds)| {
+      vk::SparseBufferMemoryBindInfo::default()
+        .buffer(buffer.as_ref().raw)
+        .binds(binds.as_slice())
+    }).collect::<Vec<_>>();
+    let wait_semaphores = wait_semaphores.iter().map(|semaphore| semaphore.raw).collect::<Vec<_>>();
+    let signal_semaphores = signal_semaphores.iter().map(|semaphore| semaphore.raw).collect::<Vec<_>>();
+    let bind_info = vk::BindSparseInfo::default()
+      .wait_semaphores(wait_semaphores.as_slice())
+      .buffer_binds(buffer_bind_infos.as_slice())
+      .signal_semaphores(signal_semaphores.as_slice());
+
+    unsafe {
+      self.raw.queue_bind_sparse(self.get_graphics_queue(queue_index), std::slice::from_ref(&bind_info), fence)
+        .map_err(|err| HalaGfxError::new("Failed to bind sparse memory.", Some(Box::new(err))))?;
+    }
+
+    Ok(())
+  }
+
+  /// Bind or unbind the memory pages of the sparse images on the graphics queue.
+  /// The graphics queue family must support the sparse binding.
+  /// param queue_index: The queue index.
+  /// param image_binds: The sparse images and their memory binds of the regions.
+  /// param image_opaque_binds: The sparse images and their opaque memory binds, e.g. the mip tails and the non-resident images.
+  /// param wait_semaphores: The semaphores to wait before binding.
+  /// param signal_semaphores: The semaphores to signal after binding.
+  /// param fence: The fence to signal after binding, it can be null.
+  /// return: The result.
+  pub fn bind_sparse_image<I, M, O>(
+    &self,
+    queue_index: u32,
+    image_binds: &[(I, &[M])],
+    image_opaque_binds: &[(I, &[O])],
+    wait_semaphores: &[&HalaSemaphore],
+    signal_semaphores: &[&HalaSemaphore],
+    fence: vk::Fence,
+  ) -> Result<(), HalaGfxError>
+    where I: AsRef<crate::HalaImage>,
+          M: AsRef<crate::HalaSparseImageMemoryBind>,
+          O: AsRef<crate::HalaSparseMemoryBind>
+  {
+    let memory_binds = image_binds.iter().map(|(_, binds)| {
+      binds.iter().map(|bind| bind.as_ref().to_raw()).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    let image_bind_infos = image_binds.iter().zip(memory_binds.iter()).map(|((image, _), binds)| {
+      vk::SparseImageMemoryBindInfo::default()
+        .image(image.as_ref().raw)
+        .binds(binds.as_slice())
+    }).collect::<Vec<_>>();
+    let opaque_memory_binds = image_opaque_binds.iter().map(|(_, binds)| {
+      binds.iter().map(|bind| bind.as_ref().to_raw()).collect::<Vec<_>>()
+    }).collect::<Vec<_>>();
+    let image_opaque_bind_infos = image_opaque_binds.iter().zip(opaque_memory_binds.iter()).map(|((image, _), binds)| {
+      vk::SparseImageOpaqueMemoryBindInfo::default()
+        .image(image.as_ref().raw)
+        .binds(binds.as_slice())
+    }).collect::<Vec<_>>();
+    let wait_semaphores = wait_semaphores.iter().map(|semaphore| semaphore.raw).collect::<Vec<_>>();
+    let signal_semaphores = signal_semaphores.iter().map(|semaphore| semaphore.raw).collect::<Vec<_>>();
+    let bind_info = vk::BindSparseInfo::default()
+      .wait_semaphores(wait_semaphores.as_slice())
+      .image_binds(image_bind_infos.as_slice())
+      .image_opaque_binds(image_opaque_bind_infos.as_slice())
+      .signal_semaphores(signal_semaphores.as_slice());
+
+    unsafe {
+      self.raw.queue_bind_sparse(self.get_graphics_queue(queue_index), std::slice::from_ref(&bind_info), fence)
+        .map_err(|err| HalaGfxError::new("Failed to bind sparse image memory.", Some(Box::new(err))))?;
+    }
+
+    Ok(())
+  }
+
   /// Wait a graphics queue.
   /// param queue_index: The queue index.
   /// return: The result.
@@ -581,11 +1357,18 @@ impl HalaLogicalDevice {
     };
     if gpu_req.require_mesh_shader {
       extension_name_ptrs.push(ash::ext::mesh_shader::NAME.as_ptr());
+    }
+    if gpu_req.require_mesh_shader || gpu_req.require_fragment_shading_rate {
       extension_name_ptrs.push(ash::khr::fragment_shading_rate::NAME.as_ptr());
     }
-    if gpu_req.require_ray_tracing {
+    if gpu_req.require_ray_tracing || gpu_req.require_ray_query {
       extension_name_ptrs.push(ash::khr::acceleration_structure::NAME.as_ptr());
       extension_name_ptrs.push(ash::khr::deferred_host_operations::NAME.as_ptr());
+    }
+    if gpu_req.require_ray_query {
+      extension_name_ptrs.push(ash::khr::ray_query::NAME.as_ptr());
+    }
+    if gpu_req.require_ray_tracing {
       extension_name_ptrs.push(ash::khr::ray_tracing_pipeline::NAME.as_ptr());
       // extension_name_ptrs.push(ash::khr::ray_tracing_maintenance1::NAME.as_ptr());
       extension_name_ptrs.push(ash::ext::scalar_block_layout::NAME.as_ptr());
@@ -593,6 +1376,48 @@ impl HalaLogicalDevice {
     if gpu_req.require_depth_stencil_resolve {
       extension_name_ptrs.push(ash::khr::depth_stencil_resolve::NAME.as_ptr());
     };
+    // The debugPrintfEXT in shader is compiled to the non-semantic instructions, which is core since Vulkan 1.3.
+    if gpu_req.require_printf_in_shader
+      && Self::is_extension_supported(instance, physical_device, ash::khr::shader_non_semantic_info::NAME) {
+      extension_name_ptrs.push(ash::khr::shader_non_semantic_info::NAME.as_ptr());
+    }
+    if Self::is_hdr_metadata_enabled(gpu_req, instance, physical_device) {
+      extension_name_ptrs.push(ash::ext::hdr_metadata::NAME.as_ptr());
+    }
+    if Self::is_extension_supported(instance, physical_device, ash::khr::push_descriptor::NAME) {
+      extension_name_ptrs.push(ash::khr::push_descriptor::NAME.as_ptr());
+    }
+    let is_conditional_rendering_supported = Self::is_extension_supported(instance, physical_device, ash::ext::conditional_rendering::NAME);
+    if is_conditional_rendering_supported {
+      extension_name_ptrs.push(ash::ext::conditional_rendering::NAME.as_ptr());
+    }
+    let is_extended_dynamic_state_supported = Self::is_extension_supported(instance, physical_device, ash::ext::extended_dynamic_state::NAME);
+    if is_extended_dynamic_state_supported {
+      extension_name_ptrs.push(ash::ext::extended_dynamic_state::NAME.as_ptr());
+    }
+    let is_pipeline_executable_info_supported = Self::is_pipeline_executable_info_supported(instance, physical_device);
+    if is_pipeline_executable_info_supported {
+      extension_name_ptrs.push(ash::khr::pipeline_executable_properties::NAME.as_ptr());
+    }
+    if Self::is_extension_supported(instance, physical_device, ash::ext::memory_budget::NAME) {
+      extension_name_ptrs.push(ash::ext::memory_budget::NAME.as_ptr());
+    }
+    let is_line_rasterization_supported = Self::is_extension_supported(instance, physical_device, ash::ext::line_rasterization::NAME);
+    if is_line_rasterization_supported {
+      extension_name_ptrs.push(ash::ext::line_rasterization::NAME.as_ptr());
+    }
+    let is_vertex_input_dynamic_state_supported = Self::is_extension_supported(instance, physical_device, ash::ext::vertex_input_dynamic_state::NAME);
+    if is_vertex_input_dynamic_state_supported {
+      extension_name_ptrs.push(ash::ext::vertex_input_dynamic_state::NAME.as_ptr());
+    }
+    let is_transform_feedback_supported = Self::is_extension_supported(instance, physical_device, ash::ext::transform_feedback::NAME);
+    if is_transform_feedback_supported {
+      extension_name_ptrs.push(ash::ext::transform_feedback::NAME.as_ptr());
+    }
+    let is_multi_draw_supported = Self::is_extension_supported(instance, physical_device, ash::ext::multi_draw::NAME);
+    if is_multi_draw_supported {
+      extension_name_ptrs.push(ash::ext::multi_draw::NAME.as_ptr());
+    }
     log::debug!("Extension names: {:?}", extension_name_ptrs.iter().map(|&ptr| unsafe { std::ffi::CStr::from_ptr(ptr) }).collect::<Vec<_>>() );
 
     let mut maintenance4_features = vk::PhysicalDeviceMaintenance4Features::default();
@@ -616,6 +1441,24 @@ impl HalaLogicalDevice {
       vk::PhysicalDeviceRayTracingPipelineFeaturesKHR::default();
     let mut acceleration_structure_features =
       vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default();
+    let mut ray_query_features =
+      vk::PhysicalDeviceRayQueryFeaturesKHR::default();
+    let mut conditional_rendering_features =
+      vk::PhysicalDeviceConditionalRenderingFeaturesEXT::default();
+    let mut extended_dynamic_state_features =
+      vk::PhysicalDeviceExtendedDynamicStateFeaturesEXT::default();
+    let mut pipeline_executable_properties_features =
+      vk::PhysicalDevicePipelineExecutablePropertiesFeaturesKHR::default();
+    let mut subgroup_size_control_features =
+      vk::PhysicalDeviceSubgroupSizeControlFeatures::default();
+    let mut line_rasterization_features =
+      vk::PhysicalDeviceLineRasterizationFeaturesEXT::default();
+    let mut vertex_input_dynamic_state_features =
+      vk::PhysicalDeviceVertexInputDynamicStateFeaturesEXT::default();
+    let mut transform_feedback_features =
+      vk::PhysicalDeviceTransformFeedbackFeaturesEXT::default();
+    let mut multi_draw_features =
+      vk::PhysicalDeviceMultiDrawFeaturesEXT::default();
     #[cfg(not(feature = "nsight"))]
     let mut maintenance5_features = vk::PhysicalDeviceMaintenance5FeaturesKHR::default();
     #[cfg(not(feature = "nsight"))]
@@ -634,6 +1477,7 @@ impl HalaLogicalDevice {
       .push_next(&mut synchronization2_features)
       .push_next(&mut shader_demote_to_helper_invocation_features)
       .push_next(&mut timeline_semaphore_features)
+      .push_next(&mut subgroup_size_control_features)
       .push_next(&mut dynamic_rendering_features);
     #[cfg(not(feature = "nsight"))]
     {
@@ -647,22 +1491,93 @@ impl HalaLogicalDevice {
     }
     if gpu_req.require_mesh_shader {
       features2 = features2
-        .push_next(&mut mesh_shader_features)
-        .push_next(&mut multiview_features)
+        .push_next(&mut mesh_shader_features);
+    }
+    if gpu_req.require_mesh_shader || gpu_req.require_fragment_shading_rate {
+      features2 = features2
         .push_next(&mut primitive_fragment_shading_rate_features);
     }
+    if gpu_req.require_mesh_shader || gpu_req.require_multiview {
+      features2 = features2
+        .push_next(&mut multiview_features);
+    }
     if gpu_req.require_ray_tracing {
       features2 = features2
-        .push_next(&mut ray_tracing_pipeline_features)
+        .push_next(&mut ray_tracing_pipeline_features);
+    }
+    if gpu_req.require_ray_tracing || gpu_req.require_ray_query {
+      features2 = features2
         .push_next(&mut acceleration_structure_features);
     }
+    if gpu_req.require_ray_query {
+      features2 = features2
+        .push_next(&mut ray_query_features);
+    }
+    if is_conditional_rendering_supported {
+      features2 = features2
+        .push_next(&mut conditional_rendering_features);
+    }
+    if is_extended_dynamic_state_supported {
+      features2 = features2
+        .push_next(&mut extended_dynamic_state_features);
+    }
+    if is_pipeline_executable_info_supported {
+      features2 = features2
+        .push_next(&mut pipeline_executable_properties_features);
+    }
+    if is_line_rasterization_supported {
+      features2 = features2
+        .push_next(&mut line_rasterization_features);
+    }
+    if is_vertex_input_dynamic_state_supported {
+      features2 = features2
+        .push_next(&mut vertex_input_dynamic_state_features);
+    }
+    if is_transform_feedback_supported {
+      features2 = features2
+        .push_next(&mut transform_feedback_features);
+    }
+    if is_multi_draw_supported {
+      features2 = features2
+        .push_next(&mut multi_draw_features);
+    }
     unsafe {
       instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
     };
+    // All the supported features are enabled, the sparse features must be supported if they are required.
+    if gpu_req.require_sparse_residency
+      && (features2.features.sparse_binding != vk::TRUE || features2.features.sparse_residency_image2_d != vk::TRUE)
+    {
+      return Err(HalaGfxError::new("The sparse binding and sparse residency image 2D features are not supported.", None));
+    }
+    // The sparse memory is bound on the graphics queue, so its family must support the sparse binding.
+    if gpu_req.require_sparse_residency {
+      let queue_family_properties = unsafe { instance.raw.get_physical_device_queue_family_properties(physical_device.raw) };
+      let is_sparse_binding_supported = queue_family_properties.get(graphics_queue_family_index as usize)
+        .is_some_and(|properties| properties.queue_flags.contains(vk::QueueFlags::SPARSE_BINDING));
+      if !is_sparse_binding_supported {
+        return Err(HalaGfxError::new("The graphics queue family does not support the sparse binding.", None));
+      }
+    }
+    if gpu_req.require_multiview && Self::get_multiview_info(instance, physical_device).1.multiview != vk::TRUE {
+      return Err(HalaGfxError::new("The multiview feature is not supported.", None));
+    }
+    if gpu_req.require_fragment_shading_rate
+      && Self::get_fragment_shading_rate_info(instance, physical_device).1.pipeline_fragment_shading_rate != vk::TRUE
+    {
+      return Err(HalaGfxError::new("The pipeline fragment shading rate feature is not supported.", None));
+    }
+    if gpu_req.require_ray_query {
+      // The ray query is useless without the acceleration structure to trace against.
+      if Self::get_ray_tracing_features(instance, physical_device).1.acceleration_structure != vk::TRUE {
+        return Err(HalaGfxError::new("The acceleration structure feature is not supported.", None));
+      }
+      if Self::get_ray_query_features(instance, physical_device).ray_query != vk::TRUE {
+        return Err(HalaGfxError::new("The ray query feature is not supported.", None));
+      }
+    }
 
-    let mut subgroup_properties = vk::PhysicalDeviceSubgroupProperties::default();
-    let mut properties2 = vk::PhysicalDeviceProperties2::default()
-      .push_next(&mut subgroup_properties);
+    let mut properties2 = vk::PhysicalDeviceProperties2::default();
     unsafe {
       instance.raw.get_physical_device_properties2(physical_device.raw, &mut properties2);
     }
@@ -685,6 +1600,7 @@ impl HalaLogicalDevice {
     log::debug!("Synchronization2 features: {:?}", synchronization2_features);
     log::debug!("Shader demote to helper invocation features: {:?}", shader_demote_to_helper_invocation_features);
     log::debug!("Timeline semaphore features: {:?}", timeline_semaphore_features);
+    log::debug!("Subgroup size control features: {:?}", subgroup_size_control_features);
     log::debug!("Dynamic rendering features: {:?}", dynamic_rendering_features);
     #[cfg(not(feature = "nsight"))]
     {
@@ -695,16 +1611,45 @@ impl HalaLogicalDevice {
     }
     if gpu_req.require_mesh_shader {
       log::debug!("Mesh shader features: {:?}", mesh_shader_features);
+    }
+    if gpu_req.require_mesh_shader || gpu_req.require_fragment_shading_rate {
+      log::debug!("Fragment shading rate features: {:?}", primitive_fragment_shading_rate_features);
+    }
+    if gpu_req.require_mesh_shader || gpu_req.require_multiview {
       log::debug!("Multiview features: {:?}", multiview_features);
-      log::debug!("Primitive fragment shading rate features: {:?}", primitive_fragment_shading_rate_features);
     }
     if gpu_req.require_ray_tracing {
       log::debug!("Ray tracing pipeline features: {:?}", ray_tracing_pipeline_features);
+    }
+    if gpu_req.require_ray_tracing || gpu_req.require_ray_query {
       log::debug!("Acceleration structure features: {:?}", acceleration_structure_features);
     }
+    if gpu_req.require_ray_query {
+      log::debug!("Ray query features: {:?}", ray_query_features);
+    }
+    if is_conditional_rendering_supported {
+      log::debug!("Conditional rendering features: {:?}", conditional_rendering_features);
+    }
+    if is_extended_dynamic_state_supported {
+      log::debug!("Extended dynamic state features: {:?}", extended_dynamic_state_features);
+    }
+    if is_pipeline_executable_info_supported {
+      log::debug!("Pipeline executable properties features: {:?}", pipeline_executable_properties_features);
+    }
+    if is_line_rasterization_supported {
+      log::debug!("Line rasterization features: {:?}", line_rasterization_features);
+    }
+    if is_vertex_input_dynamic_state_supported {
+      log::debug!("Vertex input dynamic state features: {:?}", vertex_input_dynamic_state_features);
+    }
+    if is_transform_feedback_supported {
+      log::debug!("Transform feedback features: {:?}", transform_feedback_features);
+    }
+    if is_multi_draw_supported {
+      log::debug!("Multi draw features: {:?}", multi_draw_features);
+    }
 
     log::debug!("Properties2: {:?}", properties2);
-    log::debug!("Subgroup properties: {:?}", subgroup_properties);
 
     // TODO: Check if the features are supported.
 
@@ -713,6 +1658,59 @@ impl HalaLogicalDevice {
     Ok(logical_device)
   }
 
+  /// Check if the device extension is supported.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// param extension_name: The extension name.
+  /// return: Whether the extension is supported.
+  fn is_extension_supported(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+    extension_name: &std::ffi::CStr,
+  ) -> bool {
+    let extension_properties = unsafe {
+      instance.raw.enumerate_device_extension_properties(physical_device.raw)
+        .unwrap_or_default()
+    };
+    extension_properties.iter().any(|properties| properties.extension_name_as_c_str() == Ok(extension_name))
+  }
+
+  /// Check if the pipeline executable properties extension and its pipelineExecutableInfo feature are supported.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: Whether the pipeline executable info is supported.
+  fn is_pipeline_executable_info_supported(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> bool {
+    if !Self::is_extension_supported(instance, physical_device, ash::khr::pipeline_executable_properties::NAME) {
+      return false;
+    }
+    let mut pipeline_executable_properties_features = vk::PhysicalDevicePipelineExecutablePropertiesFeaturesKHR::default();
+    let mut features2 = vk::PhysicalDeviceFeatures2::default()
+      .push_next(&mut pipeline_executable_properties_features);
+    unsafe {
+      instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
+    }
+    pipeline_executable_properties_features.pipeline_executable_info == vk::TRUE
+  }
+
+  /// Check if the HDR metadata extension should be enabled.
+  /// It is only enabled when a non-sRGB color space is preferred and the extension is available.
+  /// param gpu_req: The GPU requirements.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: Whether the HDR metadata extension should be enabled.
+  fn is_hdr_metadata_enabled(
+    gpu_req: &crate::HalaGPURequirements,
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> bool {
+    gpu_req.preferred_color_space != crate::HalaColorSpace::SRGB_NONLINEAR
+      && instance.is_swapchain_colorspace_enabled
+      && Self::is_extension_supported(instance, physical_device, ash::ext::hdr_metadata::NAME)
+  }
+
   /// Get ray tracing information.
   /// param instance: The instance.
   /// param logical_device: The ash logical device.
@@ -758,6 +1756,19 @@ impl HalaLogicalDevice {
     }
   }
 
+  /// Get physical device features.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The physical device features.
+  fn get_physical_device_features(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> vk::PhysicalDeviceFeatures {
+    unsafe {
+      instance.raw.get_physical_device_features(physical_device.raw)
+    }
+  }
+
   /// Get depth stencil resolve features.
   /// param instance: The instance.
   /// param physical_device: The physical device.
@@ -775,6 +1786,192 @@ impl HalaLogicalDevice {
     depth_stencil_resolve_features
   }
 
+  /// Get descriptor indexing properties.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The descriptor indexing properties.
+  fn get_descriptor_indexing_properties<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> vk::PhysicalDeviceDescriptorIndexingProperties<'a> {
+    let mut descriptor_indexing_properties = vk::PhysicalDeviceDescriptorIndexingProperties::default();
+    let mut properties2 = vk::PhysicalDeviceProperties2::default()
+      .push_next(&mut descriptor_indexing_properties);
+    unsafe {
+      instance.raw.get_physical_device_properties2(physical_device.raw, &mut properties2);
+    }
+    descriptor_indexing_properties
+  }
+
+  /// Get the mesh shader properties.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The mesh shader properties.
+  fn get_mesh_shader_properties<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> vk::PhysicalDeviceMeshShaderPropertiesEXT<'a> {
+    let mut mesh_shader_properties = vk::PhysicalDeviceMeshShaderPropertiesEXT::default();
+    let mut properties2 = vk::PhysicalDeviceProperties2::default()
+      .push_next(&mut mesh_shader_properties);
+    unsafe {
+      instance.raw.get_physical_device_properties2(physical_device.raw, &mut properties2);
+    }
+    mesh_shader_properties
+  }
+
+  /// Get the multiview properties and features.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The multiview properties and features.
+  fn get_multiview_info<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> (
+    vk::PhysicalDeviceMultiviewProperties<'a>,
+    vk::PhysicalDeviceMultiviewFeatures<'a>,
+  ) {
+    let multiview_properties = unsafe {
+      let mut multiview_properties = vk::PhysicalDeviceMultiviewProperties::default();
+      let mut properties2 = vk::PhysicalDeviceProperties2::default()
+        .push_next(&mut multiview_properties);
+      instance.raw.get_physical_device_properties2(physical_device.raw, &mut properties2);
+      multiview_properties
+    };
+    let multiview_features = unsafe {
+      let mut multiview_features = vk::PhysicalDeviceMultiviewFeatures::default();
+      let mut features2 = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut multiview_features);
+      instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
+      multiview_features
+    };
+    (multiview_properties, multiview_features)
+  }
+
+  /// Get the fragment shading rate properties and features.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The fragment shading rate properties and features.
+  fn get_fragment_shading_rate_info<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> (
+    vk::PhysicalDeviceFragmentShadingRatePropertiesKHR<'a>,
+    vk::PhysicalDeviceFragmentShadingRateFeaturesKHR<'a>,
+  ) {
+    let fragment_shading_rate_properties = unsafe {
+      let mut fragment_shading_rate_properties = vk::PhysicalDeviceFragmentShadingRatePropertiesKHR::default();
+      let mut properties2 = vk::PhysicalDeviceProperties2::default()
+        .push_next(&mut fragment_shading_rate_properties);
+      instance.raw.get_physical_device_properties2(physical_device.raw, &mut properties2);
+      fragment_shading_rate_properties
+    };
+    let fragment_shading_rate_features = unsafe {
+      let mut fragment_shading_rate_features = vk::PhysicalDeviceFragmentShadingRateFeaturesKHR::default();
+      let mut features2 = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut fragment_shading_rate_features);
+      instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
+      fragment_shading_rate_features
+    };
+    (fragment_shading_rate_properties, fragment_shading_rate_features)
+  }
+
+  /// Get the ray query features.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The ray query features.
+  fn get_ray_query_features<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> vk::PhysicalDeviceRayQueryFeaturesKHR<'a> {
+    unsafe {
+      let mut ray_query_features = vk::PhysicalDeviceRayQueryFeaturesKHR::default();
+      let mut features2 = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut ray_query_features);
+      instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
+      ray_query_features
+    }
+  }
+
+  /// Get the multi draw properties and features.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The multi draw properties and features.
+  fn get_multi_draw_info<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> (
+    vk::PhysicalDeviceMultiDrawPropertiesEXT<'a>,
+    vk::PhysicalDeviceMultiDrawFeaturesEXT<'a>,
+  ) {
+    let multi_draw_properties = unsafe {
+      let mut multi_draw_properties = vk::PhysicalDeviceMultiDrawPropertiesEXT::default();
+      let mut properties2 = vk::PhysicalDeviceProperties2::default()
+        .push_next(&mut multi_draw_properties);
+      instance.raw.get_physical_device_properties2(physical_device.raw, &mut properties2);
+      multi_draw_properties
+    };
+    let multi_draw_features = unsafe {
+      let mut multi_draw_features = vk::PhysicalDeviceMultiDrawFeaturesEXT::default();
+      let mut features2 = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut multi_draw_features);
+      instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
+      multi_draw_features
+    };
+    (multi_draw_properties, multi_draw_features)
+  }
+
+  /// Get the transform feedback features.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The transform feedback features.
+  fn get_transform_feedback_features<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> vk::PhysicalDeviceTransformFeedbackFeaturesEXT<'a> {
+    unsafe {
+      let mut transform_feedback_features = vk::PhysicalDeviceTransformFeedbackFeaturesEXT::default();
+      let mut features2 = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut transform_feedback_features);
+      instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
+      transform_feedback_features
+    }
+  }
+
+  /// Get the vertex input dynamic state features.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The vertex input dynamic state features.
+  fn get_vertex_input_dynamic_state_features<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> vk::PhysicalDeviceVertexInputDynamicStateFeaturesEXT<'a> {
+    unsafe {
+      let mut vertex_input_dynamic_state_features = vk::PhysicalDeviceVertexInputDynamicStateFeaturesEXT::default();
+      let mut features2 = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut vertex_input_dynamic_state_features);
+      instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
+      vertex_input_dynamic_state_features
+    }
+  }
+
+  /// Get the line rasterization features.
+  /// param instance: The instance.
+  /// param physical_device: The physical device.
+  /// return: The line rasterization features.
+  fn get_line_rasterization_features<'a>(
+    instance: &crate::HalaInstance,
+    physical_device: &crate::HalaPhysicalDevice,
+  ) -> vk::PhysicalDeviceLineRasterizationFeaturesEXT<'a> {
+    unsafe {
+      let mut line_rasterization_features = vk::PhysicalDeviceLineRasterizationFeaturesEXT::default();
+      let mut features2 = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut line_rasterization_features);
+      instance.raw.get_physical_device_features2(physical_device.raw, &mut features2);
+      line_rasterization_features
+    }
+  }
+
   /// Get ray tracing features.
   /// param instance: The instance.
   /// param physical_device: The physical device.
@@ -866,3 +2063,11 @@ impl HalaLogicalDevice {
     Ok(gpu_allocator)
   }
 }
+
+/// Convert the debug name or label to the C string.
+/// The interior NUL characters are stripped so the name never fails to convert.
+/// param name: The name.
+/// return: The C string.
+pub(crate) fn debug_cstring(name: &str) -> std::ffi::CString {
+  std::ffi::CString::new(name.replace('\0', "")).unwrap_or_default()
+}
@@ -61,6 +61,12 @@ impl HalaPhysicalDevice {
       if !is_gpu_name_match {
         continue;
       }
+      if gpu_req.require_sparse_residency {
+        let features = unsafe { instance.raw.get_physical_device_features(p) };
+        if features.sparse_binding != vk::TRUE || features.sparse_residency_image2_d != vk::TRUE {
+          continue;
+        }
+      }
       chosen = Some((p, properties));
       // If we find a discrete GPU, we use it directly.
       if properties.device_type == vk::PhysicalDeviceType::DISCRETE_GPU {
@@ -96,6 +102,149 @@ impl HalaPhysicalDevice {
     )
   }
 
+  /// Get the properties of the format.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// return: The format properties.
+  pub fn format_properties(&self, instance: &crate::HalaInstance, format: crate::HalaFormat) -> crate::HalaFormatProperties {
+    unsafe {
+      instance.raw.get_physical_device_format_properties(self.raw, format.into())
+    }.into()
+  }
+
+  /// Check if the format supports the features with optimal tiling.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// param features: The format features.
+  /// return: True if all the features are supported.
+  pub fn supports_format_features(
+    &self,
+    instance: &crate::HalaInstance,
+    format: crate::HalaFormat,
+    features: crate::HalaFormatFeatureFlags,
+  ) -> bool {
+    self.format_properties(instance, format).optimal_tiling_features.contains(features)
+  }
+
+  /// Check if the format can be used as a color attachment with optimal tiling.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// return: True if supported.
+  pub fn supports_color_attachment(&self, instance: &crate::HalaInstance, format: crate::HalaFormat) -> bool {
+    self.supports_format_features(instance, format, crate::HalaFormatFeatureFlags::COLOR_ATTACHMENT)
+  }
+
+  /// Check if the format can be used as a blended color attachment with optimal tiling.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// return: True if supported.
+  pub fn supports_color_attachment_blend(&self, instance: &crate::HalaInstance, format: crate::HalaFormat) -> bool {
+    self.supports_format_features(instance, format, crate::HalaFormatFeatureFlags::COLOR_ATTACHMENT_BLEND)
+  }
+
+  /// Check if the format can be used as a depth stencil attachment with optimal tiling.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// return: True if supported.
+  pub fn supports_depth_stencil_attachment(&self, instance: &crate::HalaInstance, format: crate::HalaFormat) -> bool {
+    self.supports_format_features(instance, format, crate::HalaFormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+  }
+
+  /// Check if the format can be used as a storage image with optimal tiling.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// return: True if supported.
+  pub fn supports_storage_image(&self, instance: &crate::HalaInstance, format: crate::HalaFormat) -> bool {
+    self.supports_format_features(instance, format, crate::HalaFormatFeatureFlags::STORAGE_IMAGE)
+  }
+
+  /// Check if the format can be sampled with the linear filter with optimal tiling.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// return: True if supported.
+  pub fn supports_linear_filter(&self, instance: &crate::HalaInstance, format: crate::HalaFormat) -> bool {
+    self.supports_format_features(instance, format, crate::HalaFormatFeatureFlags::SAMPLED_IMAGE_FILTER_LINEAR)
+  }
+
+  /// Check if the format can be the source of a blit with optimal tiling.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// return: True if supported.
+  pub fn supports_blit_src(&self, instance: &crate::HalaInstance, format: crate::HalaFormat) -> bool {
+    self.supports_format_features(instance, format, crate::HalaFormatFeatureFlags::BLIT_SRC)
+  }
+
+  /// Check if the format can be the destination of a blit with optimal tiling.
+  /// param instance: The instance.
+  /// param format: The format.
+  /// return: True if supported.
+  pub fn supports_blit_dst(&self, instance: &crate::HalaInstance, format: crate::HalaFormat) -> bool {
+    self.supports_format_features(instance, format, crate::HalaFormatFeatureFlags::BLIT_DST)
+  }
+
+  /// Get the subgroup properties, including the subgroup size control limits.
+  /// param instance: The instance.
+  /// return: The subgroup properties.
+  pub fn subgroup_properties(&self, instance: &crate::HalaInstance) -> crate::HalaSubgroupProperties {
+    let mut subgroup_properties = vk::PhysicalDeviceSubgroupProperties::default();
+    let mut subgroup_size_control_properties = vk::PhysicalDeviceSubgroupSizeControlProperties::default();
+    let mut properties2 = vk::PhysicalDeviceProperties2::default()
+      .push_next(&mut subgroup_properties)
+      .push_next(&mut subgroup_size_control_properties);
+    unsafe {
+      instance.raw.get_physical_device_properties2(self.raw, &mut properties2);
+    }
+
+    let mut subgroup_size_control_features = vk::PhysicalDeviceSubgroupSizeControlFeatures::default();
+    let mut features2 = vk::PhysicalDeviceFeatures2::default()
+      .push_next(&mut subgroup_size_control_features);
+    unsafe {
+      instance.raw.get_physical_device_features2(self.raw, &mut features2);
+    }
+
+    crate::HalaSubgroupProperties {
+      info: crate::HalaSubgroupInfo {
+        size: subgroup_properties.subgroup_size,
+        supported_stages: subgroup_properties.supported_stages.into(),
+        supported_operations: subgroup_properties.supported_operations.into(),
+        quad_operations_in_all_stages: subgroup_properties.quad_operations_in_all_stages == vk::TRUE,
+      },
+      min_size: subgroup_size_control_properties.min_subgroup_size,
+      max_size: subgroup_size_control_properties.max_subgroup_size,
+      required_size_stages: subgroup_size_control_properties.required_subgroup_size_stages.into(),
+      size_control_supported: subgroup_size_control_features.subgroup_size_control == vk::TRUE,
+      compute_full_subgroups_supported: subgroup_size_control_features.compute_full_subgroups == vk::TRUE,
+    }
+  }
+
+  /// Find the first depth format which supports the depth stencil attachment with optimal tiling.
+  /// The candidates are D32_SFLOAT_S8_UINT and D24_UNORM_S8_UINT in order if the stencil is required,
+  /// otherwise D32_SFLOAT and D16_UNORM are preferred and the combined formats are the fallback.
+  /// param instance: The instance.
+  /// param require_stencil: Whether the format must have the stencil aspect.
+  /// return: The depth format.
+  pub fn find_supported_depth_format(&self, instance: &crate::HalaInstance, require_stencil: bool) -> Option<crate::HalaFormat> {
+    let stencil_formats = [
+      crate::HalaFormat::D32_SFLOAT_S8_UINT,
+      crate::HalaFormat::D24_UNORM_S8_UINT,
+    ];
+    let depth_only_formats = [
+      crate::HalaFormat::D32_SFLOAT,
+      crate::HalaFormat::D16_UNORM,
+    ];
+    let candidates = if require_stencil {
+      stencil_formats.to_vec()
+    } else {
+      depth_only_formats.into_iter().chain(stencil_formats).collect()
+    };
+    candidates.into_iter()
+      .find(|&format| {
+        self.format_properties(instance, format)
+          .optimal_tiling_features
+          .contains(crate::HalaFormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT)
+      })
+  }
+
   pub(crate) fn find_memory_type_index(
     &self,
     memory_requset: &vk::MemoryRequirements,
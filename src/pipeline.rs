@@ -740,6 +740,83 @@ impl std::convert::From<HalaPolygonMode> for vk::PolygonMode {
   }
 }
 
+/// The line rasterization mode.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HalaLineRasterizationMode(i32);
+impl HalaLineRasterizationMode {
+  pub const DEFAULT: Self = Self(vk::LineRasterizationModeEXT::DEFAULT.as_raw());
+  pub const RECTANGULAR: Self = Self(vk::LineRasterizationModeEXT::RECTANGULAR.as_raw());
+  pub const BRESENHAM: Self = Self(vk::LineRasterizationModeEXT::BRESENHAM.as_raw());
+  pub const RECTANGULAR_SMOOTH: Self = Self(vk::LineRasterizationModeEXT::RECTANGULAR_SMOOTH.as_raw());
+}
+
+impl Serialize for HalaLineRasterizationMode {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    let s = match *self {
+      HalaLineRasterizationMode::DEFAULT => "default",
+      HalaLineRasterizationMode::RECTANGULAR => "rectangular",
+      HalaLineRasterizationMode::BRESENHAM => "bresenham",
+      HalaLineRasterizationMode::RECTANGULAR_SMOOTH => "rectangular_smooth",
+      _ => "default",
+    };
+
+    serializer.serialize_str(s)
+  }
+}
+
+impl<'de> Deserialize<'de> for HalaLineRasterizationMode {
+  fn deserialize<D>(deserializer: D) -> Result<HalaLineRasterizationMode, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    struct HalaLineRasterizationModeVisitor;
+
+    impl<'de> Visitor<'de> for HalaLineRasterizationModeVisitor {
+      type Value = HalaLineRasterizationMode;
+
+      fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a string of line rasterization mode")
+      }
+
+      fn visit_str<E>(self, value: &str) -> Result<HalaLineRasterizationMode, E>
+      where
+        E: de::Error,
+      {
+        let val = match value {
+          "DEFAULT" => HalaLineRasterizationMode::DEFAULT,
+          "default" => HalaLineRasterizationMode::DEFAULT,
+          "RECTANGULAR" => HalaLineRasterizationMode::RECTANGULAR,
+          "rectangular" => HalaLineRasterizationMode::RECTANGULAR,
+          "BRESENHAM" => HalaLineRasterizationMode::BRESENHAM,
+          "bresenham" => HalaLineRasterizationMode::BRESENHAM,
+          "RECTANGULAR_SMOOTH" => HalaLineRasterizationMode::RECTANGULAR_SMOOTH,
+          "rectangular_smooth" => HalaLineRasterizationMode::RECTANGULAR_SMOOTH,
+          _ => return Err(de::Error::invalid_value(Unexpected::Str(value), &"a line rasterization mode")),
+        };
+
+        Ok(val)
+      }
+    }
+
+    deserializer.deserialize_str(HalaLineRasterizationModeVisitor)
+  }
+}
+
+impl std::convert::From<vk::LineRasterizationModeEXT> for HalaLineRasterizationMode {
+  fn from(val: vk::LineRasterizationModeEXT) -> Self {
+    Self(val.as_raw())
+  }
+}
+
+impl std::convert::From<HalaLineRasterizationMode> for vk::LineRasterizationModeEXT {
+  fn from(val: HalaLineRasterizationMode) -> Self {
+    vk::LineRasterizationModeEXT::from_raw(val.0)
+  }
+}
+
 /// The compare operation.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct HalaCompareOp(i32);
@@ -1045,6 +1122,90 @@ impl std::convert::From<&HalaVertexInputBindingDescription> for vk::VertexInputB
   }
 }
 
+/// The vertex input binding description which is set dynamically.
+#[derive(Copy, Clone)]
+pub struct HalaVertexInputBindingDescription2 {
+  pub binding: u32,
+  pub stride: u32,
+  pub input_rate: HalaVertexInputRate,
+  /// The number of the instances which use the same vertex attribute, it must be 1 if the input rate is VERTEX.
+  pub divisor: u32,
+}
+
+impl AsRef<HalaVertexInputBindingDescription2> for HalaVertexInputBindingDescription2 {
+  fn as_ref(&self) -> &Self {
+    self
+  }
+}
+
+impl Default for HalaVertexInputBindingDescription2 {
+  fn default() -> Self {
+    Self {
+      binding: 0,
+      stride: 0,
+      input_rate: HalaVertexInputRate::default(),
+      divisor: 1,
+    }
+  }
+}
+
+impl std::convert::From<&HalaVertexInputBindingDescription> for HalaVertexInputBindingDescription2 {
+  fn from(val: &HalaVertexInputBindingDescription) -> Self {
+    Self {
+      binding: val.binding,
+      stride: val.stride,
+      input_rate: val.input_rate,
+      divisor: 1,
+    }
+  }
+}
+
+impl std::convert::From<&HalaVertexInputBindingDescription2> for vk::VertexInputBindingDescription2EXT<'_> {
+  fn from(val: &HalaVertexInputBindingDescription2) -> Self {
+    vk::VertexInputBindingDescription2EXT::default()
+      .binding(val.binding)
+      .stride(val.stride)
+      .input_rate(val.input_rate.into())
+      .divisor(val.divisor)
+  }
+}
+
+/// The vertex input attribute description which is set dynamically.
+#[derive(Copy, Clone, Default)]
+pub struct HalaVertexInputAttributeDescription2 {
+  pub location: u32,
+  pub binding: u32,
+  pub format: HalaFormat,
+  pub offset: u32,
+}
+
+impl AsRef<HalaVertexInputAttributeDescription2> for HalaVertexInputAttributeDescription2 {
+  fn as_ref(&self) -> &Self {
+    self
+  }
+}
+
+impl std::convert::From<&HalaVertexInputAttributeDescription> for HalaVertexInputAttributeDescription2 {
+  fn from(val: &HalaVertexInputAttributeDescription) -> Self {
+    Self {
+      location: val.location,
+      binding: val.binding,
+      format: val.format,
+      offset: val.offset,
+    }
+  }
+}
+
+impl std::convert::From<&HalaVertexInputAttributeDescription2> for vk::VertexInputAttributeDescription2EXT<'_> {
+  fn from(val: &HalaVertexInputAttributeDescription2) -> Self {
+    vk::VertexInputAttributeDescription2EXT::default()
+      .location(val.location)
+      .binding(val.binding)
+      .format(val.format.into())
+      .offset(val.offset)
+  }
+}
+
 /// The push constant range.
 #[derive(Copy, Clone, Default)]
 pub struct HalaPushConstantRange {
@@ -1125,6 +1286,8 @@ impl HalaDynamicState {
   pub const DEPTH_BOUNDS_TEST_ENABLE_EXT: Self = Self(vk::DynamicState::DEPTH_BOUNDS_TEST_ENABLE_EXT.as_raw());
   pub const STENCIL_TEST_ENABLE_EXT: Self = Self(vk::DynamicState::STENCIL_TEST_ENABLE_EXT.as_raw());
   pub const STENCIL_OP_EXT: Self = Self(vk::DynamicState::STENCIL_OP_EXT.as_raw());
+  pub const DEPTH_BIAS_ENABLE_EXT: Self = Self(vk::DynamicState::DEPTH_BIAS_ENABLE_EXT.as_raw());
+  pub const VERTEX_INPUT_EXT: Self = Self(vk::DynamicState::VERTEX_INPUT_EXT.as_raw());
 }
 
 impl std::convert::From<vk::DynamicState> for HalaDynamicState {
@@ -1188,6 +1351,110 @@ impl HalaBlendState {
 
 }
 
+/// The line rasterization state.
+/// It requires the line rasterization extension of the device.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct HalaLineRasterizationState {
+  pub mode: HalaLineRasterizationMode,
+  #[serde(default)]
+  pub stippled_enable: bool,
+  #[serde(default = "HalaLineRasterizationState::default_stipple_factor")]
+  pub stipple_factor: u32,
+  #[serde(default = "HalaLineRasterizationState::default_stipple_pattern")]
+  pub stipple_pattern: u16,
+}
+
+/// The line rasterization state implementation.
+impl AsRef<HalaLineRasterizationState> for HalaLineRasterizationState {
+  fn as_ref(&self) -> &Self {
+    self
+  }
+}
+
+/// The default implementation for the line rasterization state.
+impl Default for HalaLineRasterizationState {
+  fn default() -> Self {
+    Self {
+      mode: HalaLineRasterizationMode::DEFAULT,
+      stippled_enable: false,
+      stipple_factor: 1,
+      stipple_pattern: 0xFFFF,
+    }
+  }
+}
+
+/// The line rasterization state implementation.
+impl HalaLineRasterizationState {
+
+  pub(crate) fn default_stipple_factor() -> u32 { 1 }
+  pub(crate) fn default_stipple_pattern() -> u16 { 0xFFFF }
+
+  pub fn new(mode: HalaLineRasterizationMode) -> Self {
+    Self {
+      mode,
+      ..Default::default()
+    }
+  }
+
+  /// Enable the line stipple.
+  /// The stipple is set dynamically if the pipeline uses the LINE_STIPPLE_EXT dynamic state.
+  /// The factor and the stippled line feature of the mode are validated when the pipeline is created.
+  /// param stipple_factor: The repeat factor of each bit in the pattern, it is in [1, 256].
+  /// param stipple_pattern: The bit pattern, the lowest bit is used first.
+  /// return: The line rasterization state.
+  pub fn with_stipple(mut self, stipple_factor: u32, stipple_pattern: u16) -> Self {
+    self.stippled_enable = true;
+    self.stipple_factor = stipple_factor;
+    self.stipple_pattern = stipple_pattern;
+    self
+  }
+
+  /// Validate the line rasterization state with the features of the device.
+  /// param logical_device: The logical device.
+  /// return: The result.
+  pub(crate) fn validate(&self, logical_device: &HalaLogicalDevice) -> Result<(), HalaGfxError> {
+    if logical_device.line_rasterization_loader.is_none() {
+      return Err(HalaGfxError::new("The line rasterization extension is not supported.", None));
+    }
+    let features = &logical_device.line_rasterization_features;
+    // The default mode is stippled as the rectangular lines only if the lines are strict.
+    let (is_mode_supported, is_stipple_supported) = match self.mode {
+      HalaLineRasterizationMode::DEFAULT => (
+        true,
+        features.stippled_rectangular_lines == vk::TRUE && logical_device.limits.strict_lines,
+      ),
+      HalaLineRasterizationMode::RECTANGULAR => (
+        features.rectangular_lines == vk::TRUE,
+        features.stippled_rectangular_lines == vk::TRUE,
+      ),
+      HalaLineRasterizationMode::BRESENHAM => (
+        features.bresenham_lines == vk::TRUE,
+        features.stippled_bresenham_lines == vk::TRUE,
+      ),
+      HalaLineRasterizationMode::RECTANGULAR_SMOOTH => (
+        features.smooth_lines == vk::TRUE,
+        features.stippled_smooth_lines == vk::TRUE,
+      ),
+      _ => (false, false),
+    };
+    let mode = vk::LineRasterizationModeEXT::from(self.mode);
+    if !is_mode_supported {
+      return Err(HalaGfxError::new(&format!("The line rasterization mode {:?} is not supported.", mode), None));
+    }
+    if self.stippled_enable {
+      if !is_stipple_supported {
+        return Err(HalaGfxError::new(&format!("The stippled line of the line rasterization mode {:?} is not supported.", mode), None));
+      }
+      if !(1..=256).contains(&self.stipple_factor) {
+        return Err(HalaGfxError::new(&format!("The line stipple factor {} is not in [1, 256].", self.stipple_factor), None));
+      }
+    }
+
+    Ok(())
+  }
+
+}
+
 /// The rasterizer state.
 #[derive(Serialize, Deserialize)]
 pub struct HalaRasterizerState {
@@ -1195,6 +1462,12 @@ pub struct HalaRasterizerState {
   pub cull_mode: HalaCullModeFlags,
   pub polygon_mode: HalaPolygonMode,
   pub line_width: f32,
+  #[serde(default)]
+  pub line_rasterization: Option<HalaLineRasterizationState>,
+  #[serde(default)]
+  pub depth_clamp_enable: bool,
+  #[serde(default)]
+  pub rasterizer_discard_enable: bool,
 }
 
 /// The rasterizer state implementation.
@@ -1212,6 +1485,9 @@ impl Default for HalaRasterizerState {
       cull_mode: HalaCullModeFlags::NONE,
       polygon_mode: HalaPolygonMode::FILL,
       line_width: 1.0,
+      line_rasterization: None,
+      depth_clamp_enable: false,
+      rasterizer_discard_enable: false,
     }
   }
 }
@@ -1230,9 +1506,35 @@ impl HalaRasterizerState {
       cull_mode,
       polygon_mode,
       line_width,
+      ..Default::default()
     }
   }
 
+  /// Set the line rasterization state.
+  /// param line_rasterization: The line rasterization state.
+  /// return: The rasterizer state.
+  pub fn with_line_rasterization(mut self, line_rasterization: HalaLineRasterizationState) -> Self {
+    self.line_rasterization = Some(line_rasterization);
+    self
+  }
+
+  /// Enable the depth clamp.
+  /// The fragment depth is clamped to the viewport depth range instead of clipping the primitives, e.g. for the shadow maps.
+  /// It requires the depth clamp feature of the device.
+  /// return: The rasterizer state.
+  pub fn with_depth_clamp(mut self) -> Self {
+    self.depth_clamp_enable = true;
+    self
+  }
+
+  /// Enable the rasterizer discard.
+  /// The primitives are discarded before the rasterization, e.g. for the transform feedback only passes.
+  /// return: The rasterizer state.
+  pub fn with_rasterizer_discard(mut self) -> Self {
+    self.rasterizer_discard_enable = true;
+    self
+  }
+
 }
 
 /// The multisample state.
@@ -1287,6 +1589,47 @@ impl HalaMultisampleState {
     }
   }
 
+  /// Create a multisample state whose sample mask covers all the samples.
+  /// param rasterization_samples: The rasterization samples.
+  /// return: The multisample state.
+  pub fn with_full_mask(rasterization_samples: HalaSampleCountFlags) -> Self {
+    let sample_count = rasterization_samples.as_raw();
+    let sample_masks = (0..Self::sample_mask_len(rasterization_samples)).map(|i| {
+      let remaining = sample_count - i as u32 * 32;
+      if remaining >= 32 { u32::MAX } else { (1u32 << remaining) - 1 }
+    }).collect();
+    Self {
+      rasterization_samples,
+      sample_masks,
+      ..Default::default()
+    }
+  }
+
+  /// Get the number of the 32-bit words of the sample mask.
+  /// param rasterization_samples: The rasterization samples.
+  /// return: The length of the sample mask.
+  pub fn sample_mask_len(rasterization_samples: HalaSampleCountFlags) -> usize {
+    rasterization_samples.as_raw().div_ceil(32) as usize
+  }
+
+  /// Validate the sample mask and the sample shading.
+  /// The sample mask must be empty or have one bit for each sample.
+  /// param logical_device: The logical device.
+  /// return: The result.
+  pub(crate) fn validate(&self, logical_device: &HalaLogicalDevice) -> Result<(), HalaGfxError> {
+    let sample_mask_len = Self::sample_mask_len(self.rasterization_samples);
+    if !self.sample_masks.is_empty() && self.sample_masks.len() != sample_mask_len {
+      return Err(HalaGfxError::new(
+        &format!("The sample mask length {} does not match the {} words required by the rasterization samples.", self.sample_masks.len(), sample_mask_len),
+        None,
+      ));
+    }
+    if self.sample_shading_enable && logical_device.physical_device_features.sample_rate_shading != vk::TRUE {
+      return Err(HalaGfxError::new("The sample rate shading feature is not supported.", None));
+    }
+    Ok(())
+  }
+
 }
 
 /// The depth state.
@@ -1295,6 +1638,12 @@ pub struct HalaDepthState {
   pub test_enable: bool,
   pub write_enable: bool,
   pub compare_op: HalaCompareOp,
+  #[serde(default)]
+  pub bounds_test_enable: bool,
+  #[serde(default)]
+  pub min_bounds: f32,
+  #[serde(default = "HalaDepthState::default_max_bounds")]
+  pub max_bounds: f32,
 }
 
 /// The depth state implementation.
@@ -1311,6 +1660,9 @@ impl Default for HalaDepthState {
       test_enable: true,
       write_enable: true,
       compare_op: HalaCompareOp::LESS,
+      bounds_test_enable: false,
+      min_bounds: 0.0,
+      max_bounds: 1.0,
     }
   }
 }
@@ -1318,6 +1670,8 @@ impl Default for HalaDepthState {
 /// The depth state implementation.
 impl HalaDepthState {
 
+  pub(crate) fn default_max_bounds() -> f32 { 1.0 }
+
   pub fn new(
     test_enable: bool,
     write_enable: bool,
@@ -1327,9 +1681,23 @@ impl HalaDepthState {
       test_enable,
       write_enable,
       compare_op,
+      ..Default::default()
     }
   }
 
+  /// Enable the depth bounds test.
+  /// The fragments whose depth in the attachment is outside the bounds are discarded.
+  /// It requires the depth bounds feature of the device.
+  /// param min_bounds: The min depth bounds.
+  /// param max_bounds: The max depth bounds.
+  /// return: The depth state.
+  pub fn with_bounds(mut self, min_bounds: f32, max_bounds: f32) -> Self {
+    self.bounds_test_enable = true;
+    self.min_bounds = min_bounds;
+    self.max_bounds = max_bounds;
+    self
+  }
+
 }
 
 /// The stencil operation state.
@@ -1438,6 +1806,28 @@ impl HalaStencilState {
 
 }
 
+/// The value of a pipeline executable statistic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HalaPipelineExecutableStatisticValue {
+  Bool(bool),
+  Int(i64),
+  Uint(u64),
+  Float(f64),
+}
+
+/// The statistic of a pipeline executable, e.g. the register usage or the spill count of a shader stage.
+/// The names and the meanings of the statistics are defined by the driver.
+#[derive(Clone)]
+pub struct HalaPipelineExecutableStatistic {
+  pub executable_name: String,
+  pub executable_description: String,
+  pub stage_flags: HalaShaderStageFlags,
+  pub subgroup_size: u32,
+  pub name: String,
+  pub description: String,
+  pub value: HalaPipelineExecutableStatisticValue,
+}
+
 /// The pipeline base.
 pub(crate) struct HalaPipelineBase;
 impl HalaPipelineBase {
@@ -1477,10 +1867,137 @@ impl HalaPipelineBase {
     logical_device.borrow().set_debug_name(
       pipeline_layout,
       &format!("{}_layout", debug_name),
-    ).map_err(|err| HalaGfxError::new("Failed to set debug name for pipeline layout.", Some(Box::new(err))))?;
+    ).map_err(|err| {
+      unsafe {
+        logical_device.borrow().raw.destroy_pipeline_layout(pipeline_layout, None);
+      }
+      HalaGfxError::new("Failed to set debug name for pipeline layout.", Some(Box::new(err)))
+    })?;
 
     Ok(pipeline_layout)
   }
+
+  /// Get the statistics of all executables of the pipeline.
+  /// The pipeline should be created with HalaPipelineCreateFlags::CAPTURE_STATISTICS.
+  /// param logical_device: The logical device.
+  /// param pipeline: The pipeline.
+  /// return: The statistics.
+  pub(crate) fn get_statistics(
+    logical_device: &Rc<RefCell<HalaLogicalDevice>>,
+    pipeline: vk::Pipeline,
+  ) -> Result<Vec<HalaPipelineExecutableStatistic>, HalaGfxError> {
+    let logical_device = logical_device.borrow();
+    let loader = logical_device.pipeline_executable_properties_loader.as_ref()
+      .ok_or(HalaGfxError::new("The pipeline executable properties extension is not supported.", None))?;
+    let to_string = |name: Result<&std::ffi::CStr, std::ffi::FromBytesUntilNulError>| {
+      name.map(|name| name.to_string_lossy().into_owned()).unwrap_or_default()
+    };
+
+    let pipeline_info = vk::PipelineInfoKHR::default()
+      .pipeline(pipeline);
+    let executables = unsafe {
+      loader.get_pipeline_executable_properties(&pipeline_info)
+        .map_err(|err| HalaGfxError::new("Failed to get pipeline executable properties.", Some(Box::new(err))))?
+    };
+
+    let mut statistics = Vec::new();
+    for (executable_index, executable) in executables.iter().enumerate() {
+      let executable_info = vk::PipelineExecutableInfoKHR::default()
+        .pipeline(pipeline)
+        .executable_index(executable_index as u32);
+      let executable_statistics = unsafe {
+        loader.get_pipeline_executable_statistics(&executable_info)
+          .map_err(|err| HalaGfxError::new("Failed to get pipeline executable statistics.", Some(Box::new(err))))?
+      };
+      for statistic in executable_statistics.iter() {
+        let value = unsafe {
+          match statistic.format {
+            vk::PipelineExecutableStatisticFormatKHR::BOOL32 => HalaPipelineExecutableStatisticValue::Bool(statistic.value.b32 == vk::TRUE),
+            vk::PipelineExecutableStatisticFormatKHR::INT64 => HalaPipelineExecutableStatisticValue::Int(statistic.value.i64),
+            vk::PipelineExecutableStatisticFormatKHR::UINT64 => HalaPipelineExecutableStatisticValue::Uint(statistic.value.u64),
+            vk::PipelineExecutableStatisticFormatKHR::FLOAT64 => HalaPipelineExecutableStatisticValue::Float(statistic.value.f64),
+            _ => return Err(HalaGfxError::new("Unknown pipeline executable statistic format.", None)),
+          }
+        };
+        statistics.push(HalaPipelineExecutableStatistic {
+          executable_name: to_string(executable.name_as_c_str()),
+          executable_description: to_string(executable.description_as_c_str()),
+          stage_flags: executable.stages.into(),
+          subgroup_size: executable.subgroup_size,
+          name: to_string(statistic.name_as_c_str()),
+          description: to_string(statistic.description_as_c_str()),
+          value,
+        });
+      }
+    }
+
+    Ok(statistics)
+  }
+
+  /// Set the debug name of the pipeline and its layout.
+  /// param logical_device: The logical device.
+  /// param pipeline: The pipeline.
+  /// param pipeline_layout: The pipeline layout.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub(crate) fn set_debug_name(
+    logical_device: &Rc<RefCell<HalaLogicalDevice>>,
+    pipeline: vk::Pipeline,
+    pipeline_layout: vk::PipelineLayout,
+    debug_name: &str,
+  ) -> Result<(), HalaGfxError> {
+    let logical_device = logical_device.borrow();
+    logical_device.set_debug_name(pipeline, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for pipeline.", Some(Box::new(err))))?;
+    logical_device.set_debug_name(pipeline_layout, &format!("{}_layout", debug_name))
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for pipeline layout.", Some(Box::new(err))))?;
+    Ok(())
+  }
+}
+
+/// The description of a graphics pipeline which is created in a batch.
+/// The fields are the same as the parameters of HalaGraphicsPipeline::with_renderpass_format_and_size.
+pub struct HalaGraphicsPipelineDesc<'a> {
+  pub color_formats: &'a [HalaFormat],
+  pub depth_format: Option<HalaFormat>,
+  pub width: u32,
+  pub height: u32,
+  pub descriptor_set_layouts: &'a [&'a HalaDescriptorSetLayout],
+  pub flags: HalaPipelineCreateFlags,
+  pub vertex_attribute_descriptions: &'a [HalaVertexInputAttributeDescription],
+  pub vertex_binding_descriptions: &'a [HalaVertexInputBindingDescription],
+  pub push_constant_ranges: &'a [HalaPushConstantRange],
+  pub primitive_topology: HalaPrimitiveTopology,
+  pub color_blends: &'a [HalaBlendState],
+  pub alpha_blends: &'a [HalaBlendState],
+  pub rasterizer_info: &'a HalaRasterizerState,
+  pub multisample_info: &'a HalaMultisampleState,
+  pub depth_info: &'a HalaDepthState,
+  pub stencil_info: Option<&'a HalaStencilState>,
+  pub shaders: &'a [&'a HalaShader],
+  pub dynamic_states: &'a [HalaDynamicState],
+  /// The view mask for multiview rendering, it must match the view mask of the rendering.
+  pub view_mask: Option<u32>,
+  pub render_pass: Option<&'a HalaRenderPass>,
+  pub subpass_index: u32,
+  /// The base pipeline to derive from, it must be created with HalaPipelineCreateFlags::ALLOW_DERIVATIVES.
+  pub base_pipeline: Option<&'a HalaGraphicsPipeline>,
+  pub debug_name: &'a str,
+}
+
+/// The pipeline layout shared by the graphics pipelines created in a batch.
+pub(crate) struct HalaSharedPipelineLayout {
+  logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  raw: vk::PipelineLayout,
+}
+
+/// The Drop trait implementation for shared pipeline layout.
+impl Drop for HalaSharedPipelineLayout {
+  fn drop(&mut self) {
+    unsafe {
+      self.logical_device.borrow().raw.destroy_pipeline_layout(self.raw, None);
+    }
+  }
 }
 
 /// The graphics pipeline.
@@ -1488,6 +2005,9 @@ pub struct HalaGraphicsPipeline {
   pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
   pub raw: vk::Pipeline,
   pub layout: vk::PipelineLayout,
+  pub flags: HalaPipelineCreateFlags,
+  /// The owner of the layout if it is shared with other pipelines, None if the layout is owned by this pipeline.
+  pub(crate) shared_layout: Option<Rc<HalaSharedPipelineLayout>>,
 
   pub(crate) debug_name: String,
 }
@@ -1497,7 +2017,9 @@ impl Drop for HalaGraphicsPipeline {
   fn drop(&mut self) {
     unsafe {
       self.logical_device.borrow().raw.destroy_pipeline(self.raw, None);
-      self.logical_device.borrow().raw.destroy_pipeline_layout(self.layout, None);
+      if self.shared_layout.is_none() {
+        self.logical_device.borrow().raw.destroy_pipeline_layout(self.layout, None);
+      }
     }
     log::debug!("A HalaGraphicsPipeline \"{}\" is dropped.", self.debug_name);
   }
@@ -1507,6 +2029,165 @@ impl Drop for HalaGraphicsPipeline {
 #[allow(clippy::too_many_arguments)]
 impl HalaGraphicsPipeline {
 
+  /// Get the statistics of the pipeline executables, e.g. the register usage and the spill count.
+  /// The pipeline must be created with HalaPipelineCreateFlags::CAPTURE_STATISTICS
+  /// and the device must support the pipeline executable properties extension.
+  /// return: The statistics.
+  pub fn get_statistics(&self) -> Result<Vec<HalaPipelineExecutableStatistic>, HalaGfxError> {
+    HalaPipelineBase::get_statistics(&self.logical_device, self.raw)
+  }
+
+  /// Create the graphics pipelines in one call, so the driver can compile them in parallel.
+  /// The descriptions with the same descriptor set layouts and push constant ranges share one pipeline layout.
+  /// param logical_device: The logical device.
+  /// param descs: The descriptions of the graphics pipelines.
+  /// param pipeline_cache: The pipeline cache.
+  /// return: The graphics pipelines in the same order as the descriptions.
+  pub fn new_batch(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    descs: &[HalaGraphicsPipelineDesc],
+    pipeline_cache: Option<&HalaPipelineCache>,
+  ) -> Result<Vec<Self>, HalaGfxError> {
+    if descs.is_empty() {
+      return Ok(vec![]);
+    }
+
+    let flags = descs
+      .iter()
+      .map(|desc| Self::derivative_flags(desc.flags, desc.base_pipeline, desc.debug_name))
+      .collect::<Vec<_>>();
+
+    // The unique pipeline layouts with the index of the first description which uses them.
+    let mut pipeline_layouts: Vec<(usize, vk::PipelineLayout)> = Vec::new();
+    let mut layout_indices = Vec::with_capacity(descs.len());
+    let result = (|| {
+      let mut create_datas = Vec::with_capacity(descs.len());
+      for (desc_index, (desc, &flags)) in descs.iter().zip(flags.iter()).enumerate() {
+        let layout_index = match pipeline_layouts
+          .iter()
+          .position(|&(first_index, _)| Self::is_same_layout(&descs[first_index], desc))
+        {
+          Some(layout_index) => layout_index,
+          None => {
+            let pipeline_layout = HalaPipelineBase::create_pipeline_layout(
+              &logical_device,
+              desc.push_constant_ranges,
+              desc.descriptor_set_layouts,
+              desc.debug_name,
+            )?;
+            pipeline_layouts.push((desc_index, pipeline_layout));
+            pipeline_layouts.len() - 1
+          }
+        };
+        layout_indices.push(layout_index);
+        let pipeline_layout = pipeline_layouts[layout_index].1;
+
+        create_datas.push(HalaGraphicsPipelineCreateData::new(
+          &logical_device.borrow(),
+          desc.color_formats,
+          desc.depth_format,
+          desc.width,
+          desc.height,
+          flags,
+          desc.vertex_attribute_descriptions,
+          desc.vertex_binding_descriptions,
+          desc.primitive_topology,
+          desc.color_blends,
+          desc.alpha_blends,
+          desc.rasterizer_info,
+          desc.multisample_info,
+          desc.depth_info,
+          desc.stencil_info,
+          desc.shaders,
+          desc.dynamic_states,
+          desc.view_mask,
+          pipeline_layout,
+          desc.render_pass,
+          desc.subpass_index,
+          desc.base_pipeline,
+        )?);
+      }
+
+      let debug_names = descs.iter().map(|desc| desc.debug_name).collect::<Vec<_>>();
+      Self::create_pipelines(&logical_device, create_datas.as_slice(), pipeline_cache, debug_names.as_slice())
+    })();
+
+    let pipelines = match result {
+      Ok(pipelines) => pipelines,
+      Err(err) => {
+        for (_, pipeline_layout) in pipeline_layouts {
+          unsafe {
+            logical_device.borrow().raw.destroy_pipeline_layout(pipeline_layout, None);
+          }
+        }
+        return Err(err);
+      }
+    };
+
+    let shared_layouts = pipeline_layouts
+      .into_iter()
+      .map(|(_, raw)| Rc::new(HalaSharedPipelineLayout {
+        logical_device: Rc::clone(&logical_device),
+        raw,
+      }))
+      .collect::<Vec<_>>();
+    Ok(
+      pipelines.into_iter()
+        .zip(layout_indices)
+        .zip(descs.iter().zip(flags))
+        .map(|((raw, layout_index), (desc, flags))| {
+          log::debug!("A HalaGraphicsPipeline \"{}\" is created.", desc.debug_name);
+          let shared_layout = &shared_layouts[layout_index];
+          Self {
+            logical_device: Rc::clone(&logical_device),
+            raw,
+            layout: shared_layout.raw,
+            flags,
+            shared_layout: Some(Rc::clone(shared_layout)),
+            debug_name: desc.debug_name.to_string(),
+          }
+        })
+        .collect()
+    )
+  }
+
+  /// Check whether two descriptions can share one pipeline layout.
+  /// param a: The first description.
+  /// param b: The second description.
+  /// return: True if the descriptor set layouts and the push constant ranges are identical.
+  fn is_same_layout(a: &HalaGraphicsPipelineDesc, b: &HalaGraphicsPipelineDesc) -> bool {
+    a.descriptor_set_layouts.len() == b.descriptor_set_layouts.len()
+      && a.descriptor_set_layouts.iter().zip(b.descriptor_set_layouts.iter()).all(|(a, b)| a.raw == b.raw)
+      && a.push_constant_ranges.len() == b.push_constant_ranges.len()
+      && a.push_constant_ranges.iter().zip(b.push_constant_ranges.iter()).all(|(a, b)| {
+        a.stage_flags == b.stage_flags && a.offset == b.offset && a.size == b.size
+      })
+  }
+
+  /// Get the create flags of a pipeline which derives from the base pipeline.
+  /// param flags: The pipeline create flags.
+  /// param base_pipeline: The base pipeline to derive from.
+  /// param debug_name: The debug name.
+  /// return: The pipeline create flags.
+  fn derivative_flags(
+    flags: HalaPipelineCreateFlags,
+    base_pipeline: Option<&HalaGraphicsPipeline>,
+    debug_name: &str,
+  ) -> HalaPipelineCreateFlags {
+    if let Some(base_pipeline) = base_pipeline {
+      if !base_pipeline.flags.contains(HalaPipelineCreateFlags::ALLOW_DERIVATIVES) {
+        log::warn!(
+          "The base pipeline \"{}\" of the graphics pipeline \"{}\" is not created with ALLOW_DERIVATIVES.",
+          base_pipeline.debug_name,
+          debug_name,
+        );
+      }
+      flags | HalaPipelineCreateFlags::DERIVATIVE
+    } else {
+      flags
+    }
+  }
+
   /// Create a graphics pipeline.
   /// param logical_device: The logical device.
   /// param swapchain: The swapchain.
@@ -1579,6 +2260,7 @@ impl HalaGraphicsPipeline {
       pipeline_layout,
       None,
       0,
+      None,
       debug_name
     )?;
 
@@ -1588,6 +2270,8 @@ impl HalaGraphicsPipeline {
         logical_device,
         raw: graphics_pipeline,
         layout: pipeline_layout,
+        flags,
+        shared_layout: None,
         debug_name: debug_name.to_string(),
       }
     )
@@ -1670,6 +2354,7 @@ impl HalaGraphicsPipeline {
       pipeline_layout,
       None,
       0,
+      None,
       debug_name
     )?;
 
@@ -1679,6 +2364,8 @@ impl HalaGraphicsPipeline {
         logical_device,
         raw: graphics_pipeline,
         layout: pipeline_layout,
+        flags,
+        shared_layout: None,
         debug_name: debug_name.to_string(),
       }
     )
@@ -1758,37 +2445,128 @@ impl HalaGraphicsPipeline {
       shaders,
       dynamic_states,
       None,
+      None,
       0,
+      None,
       pipeline_cache,
       debug_name
     )
   }
 
-  /// Create a graphics pipeline with specified render pass, formats and size.
+  /// Create a mesh shading graphics pipeline with specified formats and size.
+  /// The pipeline has no vertex input and input assembly states, the primitives are emitted by the mesh shader.
   /// param logical_device: The logical device.
-  /// color_formats: The color formats.
-  /// depth_format: The depth format.
-  /// width: The width.
-  /// height: The height.
-  /// descriptor_set_layouts: The descriptor set layouts.
-  /// flags: The pipeline create flags.
-  /// vertex_attribute_descriptions: The vertex attribute descriptions.
-  /// vertex_binding_descriptions: The vertex binding descriptions.
-  /// push_constant_ranges: The push constant ranges.
-  /// primitive_topology: The primitive topology.
-  /// color_blends: The color blend(source, destination, operation).
-  /// alpha_blends: The alpha blend(source, destination, operation).
-  /// rasterizer_info: The rasterizer info(line width, front face, cull mode, polygon mode)
-  /// multisample_info: The multisample info(rasterization samples, sample shading enable, min sample shading, sample masks, alpha to coverage enable, alpha to one enable).
-  /// depth_info: The depth info(test enable, write enable, compare operation).
-  /// stencil_info: The stencil info(test enable, front, back).
-  /// shaders: The shaders.
-  /// dynamic_states: The dynamic states.
-  /// render_pass: The render pass.
-  /// subpass_index: The subpass index.
-  /// pipeline_cache: The pipeline cache.
-  /// debug_name: The debug name.
-  /// return: The graphics pipeline.
+  /// param color_formats: The color formats.
+  /// param depth_format: The depth format.
+  /// param width: The width.
+  /// param height: The height.
+  /// param descriptor_set_layouts: The descriptor set layouts.
+  /// param flags: The pipeline create flags.
+  /// param push_constant_ranges: The push constant ranges.
+  /// param color_blends: The color blend(source, destination, operation).
+  /// param alpha_blends: The alpha blend(source, destination, operation).
+  /// param rasterizer_info: The rasterizer info(line width, front face, cull mode, polygon mode)
+  /// param multisample_info: The multisample info(rasterization samples, sample shading enable, min sample shading, sample masks, alpha to coverage enable, alpha to one enable).
+  /// param depth_info: The depth info(test enable, write enable, compare operation).
+  /// param stencil_info: The stencil info(test enable, front, back).
+  /// param task_shader: The optional task shader.
+  /// param mesh_shader: The mesh shader.
+  /// param fragment_shader: The fragment shader.
+  /// param dynamic_states: The dynamic states.
+  /// param pipeline_cache: The pipeline cache.
+  /// param debug_name: The debug name.
+  /// return: The graphics pipeline.
+  pub fn new_mesh<DSL, PCR, BS>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    color_formats: &[HalaFormat],
+    depth_format: Option<HalaFormat>,
+    width: u32,
+    height: u32,
+    descriptor_set_layouts: &[DSL],
+    flags: HalaPipelineCreateFlags,
+    push_constant_ranges: &[PCR],
+    color_blends: &[BS],
+    alpha_blends: &[BS],
+    rasterizer_info: &HalaRasterizerState,
+    multisample_info: &HalaMultisampleState,
+    depth_info: &HalaDepthState,
+    stencil_info: Option<&HalaStencilState>,
+    task_shader: Option<&HalaShader>,
+    mesh_shader: &HalaShader,
+    fragment_shader: &HalaShader,
+    dynamic_states: &[HalaDynamicState],
+    pipeline_cache: Option<&HalaPipelineCache>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError>
+    where DSL: AsRef<HalaDescriptorSetLayout>,
+          PCR: AsRef<HalaPushConstantRange>,
+          BS: AsRef<HalaBlendState>,
+  {
+    if task_shader.is_some_and(|shader| shader.stage_flags != HalaShaderStageFlags::TASK) {
+      return Err(HalaGfxError::new("The task shader must be in the task stage.", None));
+    }
+    if mesh_shader.stage_flags != HalaShaderStageFlags::MESH {
+      return Err(HalaGfxError::new("The mesh shader must be in the mesh stage.", None));
+    }
+    if fragment_shader.stage_flags != HalaShaderStageFlags::FRAGMENT {
+      return Err(HalaGfxError::new("The fragment shader must be in the fragment stage.", None));
+    }
+    let shaders = task_shader.into_iter()
+      .chain([mesh_shader, fragment_shader])
+      .collect::<Vec<_>>();
+
+    Self::with_format_and_size(
+      logical_device,
+      color_formats,
+      depth_format,
+      width,
+      height,
+      descriptor_set_layouts,
+      flags,
+      &[] as &[HalaVertexInputAttributeDescription],
+      &[] as &[HalaVertexInputBindingDescription],
+      push_constant_ranges,
+      HalaPrimitiveTopology::TRIANGLE_LIST,
+      color_blends,
+      alpha_blends,
+      rasterizer_info,
+      multisample_info,
+      depth_info,
+      stencil_info,
+      shaders.as_slice(),
+      dynamic_states,
+      pipeline_cache,
+      debug_name,
+    )
+  }
+
+  /// Create a graphics pipeline with specified render pass, formats and size.
+  /// param logical_device: The logical device.
+  /// color_formats: The color formats.
+  /// depth_format: The depth format.
+  /// width: The width.
+  /// height: The height.
+  /// descriptor_set_layouts: The descriptor set layouts.
+  /// flags: The pipeline create flags.
+  /// vertex_attribute_descriptions: The vertex attribute descriptions.
+  /// vertex_binding_descriptions: The vertex binding descriptions.
+  /// push_constant_ranges: The push constant ranges.
+  /// primitive_topology: The primitive topology.
+  /// color_blends: The color blend(source, destination, operation).
+  /// alpha_blends: The alpha blend(source, destination, operation).
+  /// rasterizer_info: The rasterizer info(line width, front face, cull mode, polygon mode)
+  /// multisample_info: The multisample info(rasterization samples, sample shading enable, min sample shading, sample masks, alpha to coverage enable, alpha to one enable).
+  /// depth_info: The depth info(test enable, write enable, compare operation).
+  /// stencil_info: The stencil info(test enable, front, back).
+  /// shaders: The shaders.
+  /// dynamic_states: The dynamic states.
+  /// view_mask: The view mask for multiview rendering, it must match the view mask of the rendering.
+  /// render_pass: The render pass.
+  /// subpass_index: The subpass index.
+  /// base_pipeline: The base pipeline to derive from, it must be created with HalaPipelineCreateFlags::ALLOW_DERIVATIVES.
+  /// pipeline_cache: The pipeline cache.
+  /// debug_name: The debug name.
+  /// return: The graphics pipeline.
   pub fn with_renderpass_format_and_size<DSL, VIAD, VIBD, PCR, BS, S>(
     logical_device: Rc<RefCell<HalaLogicalDevice>>,
     color_formats: &[HalaFormat],
@@ -1809,8 +2587,10 @@ impl HalaGraphicsPipeline {
     stencil_info: Option<&HalaStencilState>,
     shaders: &[S],
     dynamic_states: &[HalaDynamicState],
+    view_mask: Option<u32>,
     render_pass: Option<&HalaRenderPass>,
     subpass_index: u32,
+    base_pipeline: Option<&HalaGraphicsPipeline>,
     pipeline_cache: Option<&HalaPipelineCache>,
     debug_name: &str,
   ) -> Result<Self, HalaGfxError>
@@ -1821,6 +2601,8 @@ impl HalaGraphicsPipeline {
           BS: AsRef<HalaBlendState>,
           S: AsRef<HalaShader>,
   {
+    let flags = Self::derivative_flags(flags, base_pipeline, debug_name);
+
     let pipeline_layout = HalaPipelineBase::create_pipeline_layout(
       &logical_device,
       push_constant_ranges,
@@ -1846,10 +2628,12 @@ impl HalaGraphicsPipeline {
       stencil_info,
       shaders,
       dynamic_states,
+      view_mask,
       pipeline_cache,
       pipeline_layout,
       render_pass,
       subpass_index,
+      base_pipeline,
       debug_name
     )?;
 
@@ -1859,6 +2643,8 @@ impl HalaGraphicsPipeline {
         logical_device,
         raw: graphics_pipeline,
         layout: pipeline_layout,
+        flags,
+        shared_layout: None,
         debug_name: debug_name.to_string(),
       }
     )
@@ -1883,6 +2669,7 @@ impl HalaGraphicsPipeline {
   /// param pipeline_layout: The pipeline layout.
   /// param render_pass: The render pass.
   /// param subpass_index: The subpass index.
+  /// param base_pipeline: The base pipeline to derive from.
   /// param debug_name: The debug name.
   /// return: The graphics pipeline.
   fn create_pipeline<VIAD, VIBD, S>(
@@ -1904,6 +2691,7 @@ impl HalaGraphicsPipeline {
     pipeline_layout: vk::PipelineLayout,
     render_pass: Option<&HalaRenderPass>,
     subpass_index: u32,
+    base_pipeline: Option<&HalaGraphicsPipeline>,
     debug_name: &str,
   ) -> Result<vk::Pipeline, HalaGfxError>
     where VIAD: AsRef<HalaVertexInputAttributeDescription>,
@@ -1928,10 +2716,12 @@ impl HalaGraphicsPipeline {
       stencil_info,
       shaders,
       dynamic_states,
+      None,
       pipeline_cache,
       pipeline_layout,
       render_pass,
       subpass_index,
+      base_pipeline,
       debug_name
     )
   }
@@ -1956,6 +2746,7 @@ impl HalaGraphicsPipeline {
   /// param pipeline_layout: The pipeline layout.
   /// param render_pass: The render pass.
   /// param subpass_index: The subpass index.
+  /// param base_pipeline: The base pipeline to derive from.
   /// param debug_name: The debug name.
   /// return: The graphics pipeline.
   fn create_pipeline_with_rt<T, VIAD, VIBD, BS, S>(
@@ -1978,6 +2769,7 @@ impl HalaGraphicsPipeline {
     pipeline_layout: vk::PipelineLayout,
     render_pass: Option<&HalaRenderPass>,
     subpass_index: u32,
+    base_pipeline: Option<&HalaGraphicsPipeline>,
     debug_name: &str,
   ) -> Result<vk::Pipeline, HalaGfxError>
     where T: AsRef<HalaImage>,
@@ -2004,10 +2796,12 @@ impl HalaGraphicsPipeline {
       stencil_info,
       shaders,
       dynamic_states,
+      None,
       pipeline_cache,
       pipeline_layout,
       render_pass,
       subpass_index,
+      base_pipeline,
       debug_name
     )
   }
@@ -2030,10 +2824,12 @@ impl HalaGraphicsPipeline {
   /// param stencil_info: The stencil info(test enable, front, back).
   /// param shaders: The shaders.
   /// param dynamic_states: The dynamic states.
+  /// param view_mask: The view mask for multiview rendering.
   /// param pipeline_cache: The pipeline cache.
   /// param pipeline_layout: The pipeline layout.
   /// param render_pass: The render pass.
   /// param subpass_index: The subpass index.
+  /// param base_pipeline: The base pipeline to derive from.
   /// param debug_name: The debug name.
   /// return: The graphics pipeline.
   fn create_pipeline_with_format_and_size<VIAD, VIBD, BS, S>(
@@ -2054,10 +2850,12 @@ impl HalaGraphicsPipeline {
     stencil_info: Option<&HalaStencilState>,
     shaders: &[S],
     dynamic_states: &[HalaDynamicState],
+    view_mask: Option<u32>,
     pipeline_cache: Option<&HalaPipelineCache>,
     pipeline_layout: vk::PipelineLayout,
     render_pass: Option<&HalaRenderPass>,
     subpass_index: u32,
+    base_pipeline: Option<&HalaGraphicsPipeline>,
     debug_name: &str,
   ) -> Result<vk::Pipeline, HalaGfxError>
     where VIAD: AsRef<HalaVertexInputAttributeDescription>,
@@ -2065,50 +2863,294 @@ impl HalaGraphicsPipeline {
           BS: AsRef<HalaBlendState>,
           S: AsRef<HalaShader>
   {
-    let has_depth = depth_format.is_some();
-    let has_stencil = depth_format.map_or(false, |fmt| fmt == HalaFormat::D16_UNORM_S8_UINT || fmt == HalaFormat::D24_UNORM_S8_UINT || fmt == HalaFormat::D32_SFLOAT_S8_UINT);
+    let create_data = HalaGraphicsPipelineCreateData::new(
+      &logical_device.borrow(),
+      color_formats,
+      depth_format,
+      width,
+      height,
+      flags,
+      vertex_attribute_descriptions,
+      vertex_binding_descriptions,
+      primitive_topology,
+      color_blends,
+      alpha_blends,
+      rasterizer_info,
+      multisample_info,
+      depth_info,
+      stencil_info,
+      shaders,
+      dynamic_states,
+      view_mask,
+      pipeline_layout,
+      render_pass,
+      subpass_index,
+      base_pipeline,
+    )?;
+
+    let pipelines = Self::create_pipelines(
+      logical_device,
+      std::slice::from_ref(&create_data),
+      pipeline_cache,
+      &[debug_name],
+    )?;
+    pipelines.into_iter().next().ok_or(HalaGfxError::new("Failed to create graphics pipeline", None))
+  }
+
+  /// Create the graphics pipelines in one call.
+  /// param logical_device: The logical device.
+  /// param create_datas: The create data of the pipelines.
+  /// param pipeline_cache: The pipeline cache.
+  /// param debug_names: The debug names.
+  /// return: The graphics pipelines.
+  fn create_pipelines(
+    logical_device: &Rc<RefCell<HalaLogicalDevice>>,
+    create_datas: &[HalaGraphicsPipelineCreateData],
+    pipeline_cache: Option<&HalaPipelineCache>,
+    debug_names: &[&str],
+  ) -> Result<Vec<vk::Pipeline>, HalaGfxError> {
+    let main_func_name = std::ffi::CString::new("main")
+      .map_err(|err| HalaGfxError::new("Failed to create \"main\" CString.", Some(Box::new(err))))?;
 
-    let vertex_attribute_descriptions: Vec<vk::VertexInputAttributeDescription> = vertex_attribute_descriptions
+    // The create infos are chained by pointers, so they are built in stages which outlive each other.
+    let mut line_rasterization_states = create_datas
       .iter()
-      .map(|v| v.as_ref().into())
-      .collect();
-    let vertex_binding_descriptions: Vec<vk::VertexInputBindingDescription> = vertex_binding_descriptions
+      .map(|create_data| create_data.line_rasterization_state)
+      .collect::<Vec<_>>();
+    let mut create_states = create_datas
       .iter()
-      .map(|v| v.as_ref().into())
-      .collect();
-    let vertex_input_info = vk::PipelineVertexInputStateCreateInfo::default()
-      .vertex_attribute_descriptions(&vertex_attribute_descriptions)
-      .vertex_binding_descriptions(&vertex_binding_descriptions);
-    let input_assembly_info = vk::PipelineInputAssemblyStateCreateInfo::default()
-      .topology(primitive_topology.into());
-
-    let viewports = [vk::Viewport {
-      x: 0.,
-      y: height as f32,
-      width: width as f32,
-      height: -(height as f32),
-      min_depth: 0.,
-      max_depth: 1.,
-    }];
-    let scissors = [vk::Rect2D {
-      offset: vk::Offset2D { x: 0, y: 0 },
-      extent: vk::Extent2D { width, height },
-    }];
-    let viewport_info = vk::PipelineViewportStateCreateInfo::default()
-      .viewports(&viewports)
-      .scissors(&scissors);
-
-    let rasterizer_info = vk::PipelineRasterizationStateCreateInfo::default()
+      .zip(line_rasterization_states.iter_mut())
+      .map(|(create_data, line_rasterization_state)| create_data.to_states(&main_func_name, line_rasterization_state.as_mut()))
+      .collect::<Vec<_>>();
+    let pipeline_infos = create_states
+      .iter_mut()
+      .zip(create_datas.iter())
+      .map(|(create_states, create_data)| create_data.to_pipeline_info(create_states))
+      .collect::<Vec<_>>();
+
+    let pipelines = unsafe {
+      logical_device.borrow().raw
+        .create_graphics_pipelines(
+          pipeline_cache.map_or(vk::PipelineCache::null(), |pc| pc.raw),
+          pipeline_infos.as_slice(),
+          None,
+        )
+        .map_err(|(pipelines, err)| {
+          // Some pipelines may be created even if the call fails.
+          for pipeline in pipelines.into_iter().filter(|pipeline| *pipeline != vk::Pipeline::null()) {
+            logical_device.borrow().raw.destroy_pipeline(pipeline, None);
+          }
+          HalaGfxError::new("Failed to create graphics pipeline", Some(Box::new(err)))
+        })?
+    };
+
+    for (&pipeline, &debug_name) in pipelines.iter().zip(debug_names.iter()) {
+      logical_device.borrow().set_debug_name(
+        pipeline,
+        debug_name,
+      ).map_err(|err| {
+        for &pipeline in pipelines.iter() {
+          unsafe {
+            logical_device.borrow().raw.destroy_pipeline(pipeline, None);
+          }
+        }
+        HalaGfxError::new("Failed to set debug name for graphics pipeline.", Some(Box::new(err)))
+      })?;
+    }
+
+    Ok(pipelines)
+  }
+
+  /// Set the debug name of the pipeline and its layout.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    HalaPipelineBase::set_debug_name(&self.logical_device, self.raw, self.layout, debug_name)?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
+
+/// The owned data to create a graphics pipeline.
+/// The pointers in the create infos refer to it, so it must outlive the creation.
+struct HalaGraphicsPipelineCreateData {
+  flags: vk::PipelineCreateFlags,
+  vertex_attribute_descriptions: Vec<vk::VertexInputAttributeDescription>,
+  vertex_binding_descriptions: Vec<vk::VertexInputBindingDescription>,
+  primitive_topology: vk::PrimitiveTopology,
+  /// Whether the primitives are emitted by the mesh shader without the vertex input and input assembly states.
+  is_mesh: bool,
+  viewports: Vec<vk::Viewport>,
+  scissors: Vec<vk::Rect2D>,
+  rasterization_state: vk::PipelineRasterizationStateCreateInfo<'static>,
+  line_rasterization_state: Option<vk::PipelineRasterizationLineStateCreateInfoEXT<'static>>,
+  multisample_state: vk::PipelineMultisampleStateCreateInfo<'static>,
+  sample_masks: Vec<u32>,
+  color_blend_attachments: Vec<vk::PipelineColorBlendAttachmentState>,
+  shader_stages: Vec<(vk::ShaderStageFlags, vk::ShaderModule)>,
+  color_formats: Vec<vk::Format>,
+  depth_format: vk::Format,
+  stencil_format: vk::Format,
+  depth_stencil_state: Option<vk::PipelineDepthStencilStateCreateInfo<'static>>,
+  dynamic_states: Vec<vk::DynamicState>,
+  view_mask: u32,
+  layout: vk::PipelineLayout,
+  render_pass: vk::RenderPass,
+  subpass_index: u32,
+  base_pipeline: vk::Pipeline,
+}
+
+/// The state create infos of a graphics pipeline which refer to the create data.
+struct HalaGraphicsPipelineCreateStates<'a> {
+  shader_stages: Vec<vk::PipelineShaderStageCreateInfo<'a>>,
+  vertex_input_state: vk::PipelineVertexInputStateCreateInfo<'a>,
+  input_assembly_state: vk::PipelineInputAssemblyStateCreateInfo<'a>,
+  viewport_state: vk::PipelineViewportStateCreateInfo<'a>,
+  rasterization_state: vk::PipelineRasterizationStateCreateInfo<'a>,
+  multisample_state: vk::PipelineMultisampleStateCreateInfo<'a>,
+  depth_stencil_state: Option<vk::PipelineDepthStencilStateCreateInfo<'a>>,
+  color_blend_state: vk::PipelineColorBlendStateCreateInfo<'a>,
+  dynamic_state: vk::PipelineDynamicStateCreateInfo<'a>,
+  rendering_info: vk::PipelineRenderingCreateInfo<'a>,
+}
+
+/// The implementation of the graphics pipeline create data.
+impl HalaGraphicsPipelineCreateData {
+  /// Validate the states and convert them to the create data.
+  /// param logical_device: The logical device.
+  /// param color_formats: The color formats.
+  /// param depth_format: The depth format.
+  /// param width: The width.
+  /// param height: The height.
+  /// param flags: The pipeline create flags.
+  /// param vertex_attribute_descriptions: The vertex attribute descriptions.
+  /// param vertex_binding_descriptions: The vertex binding descriptions.
+  /// param primitive_topology: The primitive topology.
+  /// param color_blends: The color blend(source, destination, operation).
+  /// param alpha_blends: The alpha blend(source, destination, operation).
+  /// param rasterizer_info: The rasterizer info.
+  /// param multisample_info: The multisample info.
+  /// param depth_info: The depth info.
+  /// param stencil_info: The stencil info.
+  /// param shaders: The shaders.
+  /// param dynamic_states: The dynamic states.
+  /// param view_mask: The view mask for multiview rendering.
+  /// param pipeline_layout: The pipeline layout.
+  /// param render_pass: The render pass.
+  /// param subpass_index: The subpass index.
+  /// param base_pipeline: The base pipeline to derive from.
+  /// return: The create data.
+  #[allow(clippy::too_many_arguments)]
+  fn new<VIAD, VIBD, BS, S>(
+    logical_device: &HalaLogicalDevice,
+    color_formats: &[HalaFormat],
+    depth_format: Option<HalaFormat>,
+    width: u32,
+    height: u32,
+    flags: HalaPipelineCreateFlags,
+    vertex_attribute_descriptions: &[VIAD],
+    vertex_binding_descriptions: &[VIBD],
+    primitive_topology: HalaPrimitiveTopology,
+    color_blends: &[BS],
+    alpha_blends: &[BS],
+    rasterizer_info: &HalaRasterizerState,
+    multisample_info: &HalaMultisampleState,
+    depth_info: &HalaDepthState,
+    stencil_info: Option<&HalaStencilState>,
+    shaders: &[S],
+    dynamic_states: &[HalaDynamicState],
+    view_mask: Option<u32>,
+    pipeline_layout: vk::PipelineLayout,
+    render_pass: Option<&HalaRenderPass>,
+    subpass_index: u32,
+    base_pipeline: Option<&HalaGraphicsPipeline>,
+  ) -> Result<Self, HalaGfxError>
+    where VIAD: AsRef<HalaVertexInputAttributeDescription>,
+          VIBD: AsRef<HalaVertexInputBindingDescription>,
+          BS: AsRef<HalaBlendState>,
+          S: AsRef<HalaShader>
+  {
+    let has_depth = depth_format.is_some();
+    let has_stencil = depth_format.is_some_and(|fmt| fmt.has_stencil());
+
+    let is_mesh = shaders.iter().any(|shader| shader.as_ref().stage_flags == HalaShaderStageFlags::MESH);
+    if is_mesh && shaders.iter().any(|shader| shader.as_ref().stage_flags == HalaShaderStageFlags::VERTEX) {
+      return Err(HalaGfxError::new("The mesh shader can not be used with the vertex shader.", None));
+    }
+    if is_mesh && (!vertex_attribute_descriptions.is_empty() || !vertex_binding_descriptions.is_empty()) {
+      return Err(HalaGfxError::new("The mesh shading pipeline can not have the vertex input.", None));
+    }
+
+    // The viewport and scissor counts must be 0 if they are set with count dynamically.
+    let viewports = if dynamic_states.contains(&HalaDynamicState::VIEWPORT_WITH_COUNT_EXT) {
+      vec![]
+    } else {
+      vec![vk::Viewport {
+        x: 0.,
+        y: height as f32,
+        width: width as f32,
+        height: -(height as f32),
+        min_depth: 0.,
+        max_depth: 1.,
+      }]
+    };
+    let scissors = if dynamic_states.contains(&HalaDynamicState::SCISSOR_WITH_COUNT_EXT) {
+      vec![]
+    } else {
+      vec![vk::Rect2D {
+        offset: vk::Offset2D { x: 0, y: 0 },
+        extent: vk::Extent2D { width, height },
+      }]
+    };
+
+    if rasterizer_info.depth_clamp_enable && logical_device.physical_device_features.depth_clamp != vk::TRUE {
+      return Err(HalaGfxError::new("The depth clamp feature is not supported.", None));
+    }
+    let rasterization_state = vk::PipelineRasterizationStateCreateInfo::default()
+      .depth_clamp_enable(rasterizer_info.depth_clamp_enable)
+      .rasterizer_discard_enable(rasterizer_info.rasterizer_discard_enable)
       .line_width(rasterizer_info.line_width)
       .front_face(rasterizer_info.front_face.into())
       .cull_mode(rasterizer_info.cull_mode.into())
       .polygon_mode(rasterizer_info.polygon_mode.into());
+    let line_rasterization_state = if let Some(line_rasterization) = rasterizer_info.line_rasterization {
+      line_rasterization.validate(logical_device)?;
+      Some(
+        vk::PipelineRasterizationLineStateCreateInfoEXT::default()
+          .line_rasterization_mode(line_rasterization.mode.into())
+          .stippled_line_enable(line_rasterization.stippled_enable)
+          .line_stipple_factor(line_rasterization.stipple_factor)
+          .line_stipple_pattern(line_rasterization.stipple_pattern)
+      )
+    } else {
+      None
+    };
 
-    let multisampler_info = vk::PipelineMultisampleStateCreateInfo::default()
+    multisample_info.validate(logical_device)?;
+
+    if dynamic_states.contains(&HalaDynamicState::VERTEX_INPUT_EXT) && logical_device.vertex_input_dynamic_state_loader.is_none() {
+      return Err(HalaGfxError::new("The vertex input dynamic state feature is not supported.", None));
+    }
+
+    if let Some(view_mask) = view_mask {
+      if !logical_device.multiview_enabled {
+        return Err(HalaGfxError::new("The multiview feature is not enabled.", None));
+      }
+      if 32 - view_mask.leading_zeros() > logical_device.max_multiview_view_count {
+        return Err(HalaGfxError::new(
+          &format!(
+            "The view mask {:#x} exceeds the max multiview view count {}.",
+            view_mask,
+            logical_device.max_multiview_view_count,
+          ),
+          None,
+        ));
+      }
+    }
+    let multisample_state = vk::PipelineMultisampleStateCreateInfo::default()
       .rasterization_samples(multisample_info.rasterization_samples.into())
       .sample_shading_enable(multisample_info.sample_shading_enable)
       .min_sample_shading(multisample_info.min_sample_shading)
-      .sample_mask(multisample_info.sample_masks.as_ref())
       .alpha_to_coverage_enable(multisample_info.alpha_to_coverage_enable)
       .alpha_to_one_enable(multisample_info.alpha_to_one_enable);
 
@@ -2125,119 +3167,165 @@ impl HalaGraphicsPipeline {
           vk::ColorComponentFlags::R | vk::ColorComponentFlags::G | vk::ColorComponentFlags::B | vk::ColorComponentFlags::A,
         )
     }).collect::<Vec<_>>();
-    let color_blend_info =
-      vk::PipelineColorBlendStateCreateInfo::default().attachments(color_blend_attachments.as_slice());
 
-    let main_func_name = std::ffi::CString::new("main")
-      .map_err(|err| HalaGfxError::new("Failed to create \"main\" CString.", Some(Box::new(err))))?;
-    let shader_stage_infos = shaders
-      .iter()
-      .map(|shader| {
-        let shader = shader.as_ref();
-        vk::PipelineShaderStageCreateInfo::default()
-          .stage(shader.stage_flags.into())
-          .module(shader.module)
-          .name(&main_func_name)
-      })
-      .collect::<Vec<_>>();
-
-    let formats = color_formats
-      .iter()
-      .map(|fmt| fmt.into())
-      .collect::<Vec<vk::Format>>();
-    let rendering_info = vk::PipelineRenderingCreateInfo::default()
-      .color_attachment_formats(formats.as_slice());
-    let rendering_info = if has_depth {
-      rendering_info.depth_attachment_format(depth_format.unwrap().into())
-    } else {
-      rendering_info
-    };
-    let mut rendering_info = if has_stencil {
-      rendering_info.stencil_attachment_format(depth_format.unwrap().into())
-    } else {
-      rendering_info
-    };
-
-    let dynamic_states = dynamic_states
-      .iter()
-      .map(|ds| vk::DynamicState::from(*ds))
-      .collect::<Vec<_>>();
-    let dynamic_state_info = vk::PipelineDynamicStateCreateInfo::default()
-      .dynamic_states(dynamic_states.as_slice());
-
-    let pipeline_info = vk::GraphicsPipelineCreateInfo::default()
-      .flags(flags.into())
-      .stages(shader_stage_infos.as_slice())
-      .vertex_input_state(&vertex_input_info)
-      .input_assembly_state(&input_assembly_info)
-      .viewport_state(&viewport_info)
-      .rasterization_state(&rasterizer_info)
-      .multisample_state(&multisampler_info)
-      .color_blend_state(&color_blend_info)
-      .dynamic_state(&dynamic_state_info)
-      .layout(pipeline_layout)
-      .push_next(&mut rendering_info);
-    let pipeline_info = if let Some(rp) = render_pass {
-      pipeline_info
-        .render_pass(rp.raw)
-        .subpass(subpass_index)
-    } else {
-      pipeline_info
-        .subpass(0)
-    };
-
-    let graphics_pipeline = if has_depth {
-      let depth_stencil_info = if !has_stencil {
+    if has_depth && depth_info.bounds_test_enable && logical_device.physical_device_features.depth_bounds != vk::TRUE {
+      return Err(HalaGfxError::new("The depth bounds feature is not supported.", None));
+    }
+    let depth_stencil_state = if !has_depth {
+      None
+    } else if !has_stencil {
+      Some(
         vk::PipelineDepthStencilStateCreateInfo::default()
           .depth_test_enable(depth_info.test_enable)
           .depth_write_enable(depth_info.write_enable)
           .depth_compare_op(depth_info.compare_op.into())
-          .depth_bounds_test_enable(false)
+          .depth_bounds_test_enable(depth_info.bounds_test_enable)
+          .min_depth_bounds(depth_info.min_bounds)
+          .max_depth_bounds(depth_info.max_bounds)
           .stencil_test_enable(false)
           .front(Default::default())
           .back(Default::default())
-      } else {
-        let stencil_info = stencil_info.ok_or(HalaGfxError::new("Stencil info is required.", None))?;
+      )
+    } else {
+      let stencil_info = stencil_info.ok_or(HalaGfxError::new("Stencil info is required.", None))?;
+      Some(
         vk::PipelineDepthStencilStateCreateInfo::default()
           .depth_test_enable(depth_info.test_enable)
           .depth_write_enable(depth_info.write_enable)
           .depth_compare_op(depth_info.compare_op.into())
-          .depth_bounds_test_enable(false)
+          .depth_bounds_test_enable(depth_info.bounds_test_enable)
+          .min_depth_bounds(depth_info.min_bounds)
+          .max_depth_bounds(depth_info.max_bounds)
           .stencil_test_enable(stencil_info.test_enable)
           .front(stencil_info.front.into())
           .back(stencil_info.back.into())
-      };
-      let pipelines = unsafe {
-        logical_device.borrow().raw
-          .create_graphics_pipelines(
-            pipeline_cache.map_or(vk::PipelineCache::null(), |pc| pc.raw),
-            &[pipeline_info.depth_stencil_state(&depth_stencil_info)],
-            None,
-          )
-          .map_err(|err| HalaGfxError::new("Failed to create graphics pipeline", Some(Box::new(err.1))))?
-      };
-      pipelines.into_iter().next().ok_or(HalaGfxError::new("Failed to create graphics pipeline", None))?
-    } else {
-      let pipelines = unsafe {
-        logical_device.borrow().raw
-          .create_graphics_pipelines(
-            pipeline_cache.map_or(vk::PipelineCache::null(), |pc| pc.raw),
-            &[pipeline_info],
-            None,
-          )
-          .map_err(|err| HalaGfxError::new("Failed to create graphics pipeline", Some(Box::new(err.1))))?
-      };
-      pipelines.into_iter().next().ok_or(HalaGfxError::new("Failed to create graphics pipeline", None))?
+      )
     };
 
-    logical_device.borrow().set_debug_name(
-      graphics_pipeline,
-      debug_name,
-    ).map_err(|err| HalaGfxError::new("Failed to set debug name for graphics pipeline.", Some(Box::new(err))))?;
+    Ok(Self {
+      flags: flags.into(),
+      vertex_attribute_descriptions: vertex_attribute_descriptions.iter().map(|v| v.as_ref().into()).collect(),
+      vertex_binding_descriptions: vertex_binding_descriptions.iter().map(|v| v.as_ref().into()).collect(),
+      primitive_topology: primitive_topology.into(),
+      is_mesh,
+      viewports,
+      scissors,
+      rasterization_state,
+      line_rasterization_state,
+      multisample_state,
+      sample_masks: multisample_info.sample_masks.clone(),
+      color_blend_attachments,
+      shader_stages: shaders.iter().map(|shader| (shader.as_ref().stage_flags.into(), shader.as_ref().module)).collect(),
+      color_formats: color_formats.iter().map(|fmt| fmt.into()).collect(),
+      depth_format: depth_format.filter(|_| has_depth).map_or(vk::Format::UNDEFINED, |fmt| fmt.into()),
+      stencil_format: depth_format.filter(|_| has_stencil).map_or(vk::Format::UNDEFINED, |fmt| fmt.into()),
+      depth_stencil_state,
+      dynamic_states: dynamic_states.iter().map(|ds| vk::DynamicState::from(*ds)).collect(),
+      view_mask: view_mask.unwrap_or(0),
+      layout: pipeline_layout,
+      render_pass: render_pass.map_or(vk::RenderPass::null(), |rp| rp.raw),
+      subpass_index: if render_pass.is_some() { subpass_index } else { 0 },
+      base_pipeline: base_pipeline.map_or(vk::Pipeline::null(), |bp| bp.raw),
+    })
+  }
 
-    Ok(graphics_pipeline)
+  /// Build the state create infos.
+  /// param main_func_name: The entry point name of the shaders.
+  /// param line_rasterization_state: The line rasterization state chained to the rasterization state.
+  /// return: The state create infos.
+  fn to_states<'a>(
+    &'a self,
+    main_func_name: &'a std::ffi::CStr,
+    line_rasterization_state: Option<&'a mut vk::PipelineRasterizationLineStateCreateInfoEXT<'static>>,
+  ) -> HalaGraphicsPipelineCreateStates<'a> {
+    let rasterization_state = if let Some(line_rasterization_state) = line_rasterization_state {
+      self.rasterization_state.push_next(line_rasterization_state)
+    } else {
+      self.rasterization_state
+    };
+    let rendering_info = vk::PipelineRenderingCreateInfo::default()
+      .color_attachment_formats(self.color_formats.as_slice())
+      .depth_attachment_format(self.depth_format)
+      .stencil_attachment_format(self.stencil_format)
+      .view_mask(self.view_mask);
+
+    HalaGraphicsPipelineCreateStates {
+      shader_stages: self.shader_stages.iter().map(|(stage, module)| {
+        vk::PipelineShaderStageCreateInfo::default()
+          .stage(*stage)
+          .module(*module)
+          .name(main_func_name)
+      }).collect(),
+      vertex_input_state: vk::PipelineVertexInputStateCreateInfo::default()
+        .vertex_attribute_descriptions(self.vertex_attribute_descriptions.as_slice())
+        .vertex_binding_descriptions(self.vertex_binding_descriptions.as_slice()),
+      input_assembly_state: vk::PipelineInputAssemblyStateCreateInfo::default()
+        .topology(self.primitive_topology),
+      viewport_state: vk::PipelineViewportStateCreateInfo::default()
+        .viewports(self.viewports.as_slice())
+        .scissors(self.scissors.as_slice()),
+      rasterization_state,
+      multisample_state: self.multisample_state
+        .sample_mask(self.sample_masks.as_slice()),
+      depth_stencil_state: self.depth_stencil_state,
+      color_blend_state: vk::PipelineColorBlendStateCreateInfo::default()
+        .attachments(self.color_blend_attachments.as_slice()),
+      dynamic_state: vk::PipelineDynamicStateCreateInfo::default()
+        .dynamic_states(self.dynamic_states.as_slice()),
+      rendering_info,
+    }
   }
 
+  /// Build the pipeline create info.
+  /// param create_states: The state create infos.
+  /// return: The pipeline create info.
+  fn to_pipeline_info<'a>(&self, create_states: &'a mut HalaGraphicsPipelineCreateStates<'_>) -> vk::GraphicsPipelineCreateInfo<'a> {
+    let HalaGraphicsPipelineCreateStates {
+      shader_stages,
+      vertex_input_state,
+      input_assembly_state,
+      viewport_state,
+      rasterization_state,
+      multisample_state,
+      depth_stencil_state,
+      color_blend_state,
+      dynamic_state,
+      rendering_info,
+    } = create_states;
+
+    let pipeline_info = vk::GraphicsPipelineCreateInfo::default()
+      .flags(self.flags)
+      .stages(shader_stages.as_slice())
+      .viewport_state(viewport_state)
+      .rasterization_state(rasterization_state)
+      .multisample_state(multisample_state)
+      .color_blend_state(color_blend_state)
+      .dynamic_state(dynamic_state)
+      .layout(self.layout)
+      .render_pass(self.render_pass)
+      .subpass(self.subpass_index)
+      .push_next(rendering_info);
+    // The vertex input and input assembly states are ignored by the mesh shading pipeline.
+    let pipeline_info = if self.is_mesh {
+      pipeline_info
+    } else {
+      pipeline_info
+        .vertex_input_state(vertex_input_state)
+        .input_assembly_state(input_assembly_state)
+    };
+    let pipeline_info = if let Some(depth_stencil_state) = depth_stencil_state.as_ref() {
+      pipeline_info.depth_stencil_state(depth_stencil_state)
+    } else {
+      pipeline_info
+    };
+    if self.base_pipeline != vk::Pipeline::null() {
+      pipeline_info
+        .base_pipeline_handle(self.base_pipeline)
+        .base_pipeline_index(-1)
+    } else {
+      pipeline_info
+    }
+  }
 }
 
 
@@ -2246,6 +3334,8 @@ pub struct HalaRayTracingPipeline {
   pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
   pub raw: vk::Pipeline,
   pub layout: vk::PipelineLayout,
+  pub group_counts: crate::HalaShaderGroupCounts,
+  pub is_capture_replay: bool,
 
   pub(crate) debug_name: String,
 }
@@ -2294,6 +3384,140 @@ impl HalaRayTracingPipeline {
     where DSL: AsRef<HalaDescriptorSetLayout>,
           PCR: AsRef<HalaPushConstantRange>,
           S: AsRef<HalaShader>
+  {
+    Self::new_impl(
+      logical_device,
+      descriptor_set_layouts,
+      push_constant_ranges,
+      raygen_shaders,
+      miss_shaders,
+      hit_shaders,
+      callable_shaders,
+      max_pipeline_ray_recursion_depth,
+      pipeline_cache,
+      is_dynamic_stack,
+      false,
+      None,
+      debug_name,
+    )
+  }
+
+  /// Create a ray tracing pipeline with the shader group handle capture replay.
+  /// When capturing, the pipeline is created with the capture replay flag,
+  /// and the opaque handles can be got by get_capture_replay_shader_group_handles.
+  /// When replaying, the captured handles are passed back, so the pipeline gets the same shader group handles as the captured one.
+  /// It requires the rayTracingPipelineShaderGroupHandleCaptureReplay feature.
+  /// param logical_device: The logical device.
+  /// param descriptor_set_layouts: The descriptor set layouts.
+  /// param push_constant_ranges: The push constant ranges.
+  /// param raygen_shaders: The ray generation shaders.
+  /// param miss_shaders: The miss shaders.
+  /// param hit_shaders: The hit shaders.
+  /// param callable_shaders: The callable shaders.
+  /// param max_pipeline_ray_recursion_depth: The max pipeline ray recursion depth.
+  /// param pipeline_cache: The pipeline cache.
+  /// param is_dynamic_stack: The flag to indicate whether the stack is dynamic.
+  /// param replay_handles: The captured handles to replay, None to capture.
+  /// param debug_name: The debug name.
+  /// return: The ray tracing pipeline.
+  #[allow(clippy::too_many_arguments)]
+  pub fn with_capture_replay<DSL, PCR, S>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    descriptor_set_layouts: &[DSL],
+    push_constant_ranges: &[PCR],
+    raygen_shaders: &[S],
+    miss_shaders: &[S],
+    hit_shaders: &[(Option<S>, Option<S>, Option<S>)],
+    callable_shaders: &[S],
+    max_pipeline_ray_recursion_depth: u32,
+    pipeline_cache: Option<&HalaPipelineCache>,
+    is_dynamic_stack: bool,
+    replay_handles: Option<&[u8]>,
+    debug_name: &str,
+  ) -> Result<HalaRayTracingPipeline, HalaGfxError>
+    where DSL: AsRef<HalaDescriptorSetLayout>,
+          PCR: AsRef<HalaPushConstantRange>,
+          S: AsRef<HalaShader>
+  {
+    if !logical_device.borrow().shader_group_handle_capture_replay_supported {
+      return Err(HalaGfxError::new("The ray tracing shader group handle capture replay feature is not supported.", None));
+    }
+
+    Self::new_impl(
+      logical_device,
+      descriptor_set_layouts,
+      push_constant_ranges,
+      raygen_shaders,
+      miss_shaders,
+      hit_shaders,
+      callable_shaders,
+      max_pipeline_ray_recursion_depth,
+      pipeline_cache,
+      is_dynamic_stack,
+      true,
+      replay_handles,
+      debug_name,
+    )
+  }
+
+  /// Get the opaque capture replay handles of all shader groups.
+  /// The pipeline must be created by with_capture_replay.
+  /// return: The capture replay handles, each is shader_group_handle_capture_replay_size bytes.
+  pub fn get_capture_replay_shader_group_handles(&self) -> Result<Vec<u8>, HalaGfxError> {
+    if !self.is_capture_replay {
+      return Err(HalaGfxError::new(
+        &format!("The pipeline \"{}\" is not created with the shader group handle capture replay.", self.debug_name),
+        None,
+      ));
+    }
+
+    let logical_device = self.logical_device.borrow();
+    let group_count = self.group_counts.total();
+    let data_size = (logical_device.shader_group_handle_capture_replay_size * group_count) as usize;
+    unsafe {
+      logical_device.ray_tracing_pipeline_loader.get_ray_tracing_capture_replay_shader_group_handles(
+        self.raw,
+        0,
+        group_count,
+        data_size,
+      ).map_err(|err| HalaGfxError::new("Failed to get ray tracing capture replay shader group handles.", Some(Box::new(err))))
+    }
+  }
+
+  /// Create a ray tracing pipeline.
+  /// param logical_device: The logical device.
+  /// param descriptor_set_layouts: The descriptor set layouts.
+  /// param push_constant_ranges: The push constant ranges.
+  /// param raygen_shaders: The ray generation shaders.
+  /// param miss_shaders: The miss shaders.
+  /// param hit_shaders: The hit shaders.
+  /// param callable_shaders: The callable shaders.
+  /// param max_pipeline_ray_recursion_depth: The max pipeline ray recursion depth.
+  /// param pipeline_cache: The pipeline cache.
+  /// param is_dynamic_stack: The flag to indicate whether the stack is dynamic.
+  /// param is_capture_replay: Whether to create with the shader group handle capture replay.
+  /// param replay_handles: The captured handles to replay.
+  /// param debug_name: The debug name.
+  /// return: The ray tracing pipeline.
+  #[allow(clippy::too_many_arguments)]
+  fn new_impl<DSL, PCR, S>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    descriptor_set_layouts: &[DSL],
+    push_constant_ranges: &[PCR],
+    raygen_shaders: &[S],
+    miss_shaders: &[S],
+    hit_shaders: &[(Option<S>, Option<S>, Option<S>)],
+    callable_shaders: &[S],
+    max_pipeline_ray_recursion_depth: u32,
+    pipeline_cache: Option<&HalaPipelineCache>,
+    is_dynamic_stack: bool,
+    is_capture_replay: bool,
+    replay_handles: Option<&[u8]>,
+    debug_name: &str,
+  ) -> Result<HalaRayTracingPipeline, HalaGfxError>
+    where DSL: AsRef<HalaDescriptorSetLayout>,
+          PCR: AsRef<HalaPushConstantRange>,
+          S: AsRef<HalaShader>
   {
     // Create the pipeline layout.
     let pipeline_layout = HalaPipelineBase::create_pipeline_layout(
@@ -2313,6 +3537,8 @@ impl HalaRayTracingPipeline {
       pipeline_cache,
       pipeline_layout,
       is_dynamic_stack,
+      is_capture_replay,
+      replay_handles,
       debug_name)?;
 
     log::debug!("A HalaRayTracingPipeline \"{}\" is created.", debug_name);
@@ -2321,6 +3547,13 @@ impl HalaRayTracingPipeline {
         logical_device,
         raw: pipeline,
         layout: pipeline_layout,
+        group_counts: crate::HalaShaderGroupCounts {
+          raygen: raygen_shaders.len() as u32,
+          miss: miss_shaders.len() as u32,
+          hit: hit_shaders.len() as u32,
+          callable: callable_shaders.len() as u32,
+        },
+        is_capture_replay,
         debug_name: debug_name.to_string(),
       }
     )
@@ -2336,6 +3569,8 @@ impl HalaRayTracingPipeline {
   /// param pipeline_cache: The pipeline cache.
   /// param pipeline_layout: The pipeline layout.
   /// param is_dynamic_stack: The flag to indicate whether the stack is dynamic.
+  /// param is_capture_replay: Whether to create with the shader group handle capture replay.
+  /// param replay_handles: The captured handles to replay.
   /// param debug_name: The debug name.
   /// return: The ray tracing pipeline.
   #[allow(clippy::too_many_arguments)]
@@ -2349,6 +3584,8 @@ impl HalaRayTracingPipeline {
     pipeline_cache: Option<&HalaPipelineCache>,
     pipeline_layout: vk::PipelineLayout,
     is_dynamic_stack: bool,
+    is_capture_replay: bool,
+    replay_handles: Option<&[u8]>,
     debug_name: &str
   ) -> Result<vk::Pipeline, HalaGfxError>
     where S: AsRef<HalaShader>
@@ -2476,6 +3713,20 @@ impl HalaRayTracingPipeline {
       groups.push(group);
     }
 
+    // Set the captured handles to replay.
+    if let Some(replay_handles) = replay_handles {
+      let handle_size = logical_device.borrow().shader_group_handle_capture_replay_size as usize;
+      if replay_handles.len() != handle_size * groups.len() {
+        return Err(HalaGfxError::new(
+          &format!("The size of the replay handles should be {}, but it is {}.", handle_size * groups.len(), replay_handles.len()),
+          None,
+        ));
+      }
+      for (index, group) in groups.iter_mut().enumerate() {
+        group.p_shader_group_capture_replay_handle = replay_handles[index * handle_size..].as_ptr() as *const std::ffi::c_void;
+      }
+    }
+
     let max_ray_recursion_depth = logical_device.borrow().max_ray_recursion_depth;
     let max_pipeline_ray_recursion_depth = if max_pipeline_ray_recursion_depth > max_ray_recursion_depth {
       log::warn!(
@@ -2494,6 +3745,11 @@ impl HalaRayTracingPipeline {
       .groups(groups.as_slice())
       .max_pipeline_ray_recursion_depth(max_pipeline_ray_recursion_depth)
       .layout(pipeline_layout);
+    let pipeline_info = if is_capture_replay {
+      pipeline_info.flags(vk::PipelineCreateFlags::RAY_TRACING_SHADER_GROUP_HANDLE_CAPTURE_REPLAY_KHR)
+    } else {
+      pipeline_info
+    };
     let pipeline_info = if is_dynamic_stack {
       pipeline_info.dynamic_state(&dynamic_state_info)
     } else {
@@ -2516,6 +3772,15 @@ impl HalaRayTracingPipeline {
 
     Ok(pipeline)
   }
+
+  /// Set the debug name of the pipeline and its layout.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    HalaPipelineBase::set_debug_name(&self.logical_device, self.raw, self.layout, debug_name)?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
 }
 
 /// The compute pipeline.
@@ -2545,6 +3810,7 @@ impl HalaComputePipeline {
   /// param descriptor_set_layouts: The descriptor set layouts.
   /// param push_constant_ranges: The push constant ranges.
   /// param shader: The shader.
+  /// param required_subgroup_size: The required subgroup size, None to let the driver choose it.
   /// param pipeline_cache: The pipeline cache.
   /// param debug_name: The debug name.
   /// return: The compute pipeline.
@@ -2553,12 +3819,53 @@ impl HalaComputePipeline {
     descriptor_set_layouts: &[DSL],
     push_constant_ranges: &[PCR],
     shader: &HalaShader,
+    required_subgroup_size: Option<u32>,
+    pipeline_cache: Option<&HalaPipelineCache>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError>
+    where DSL: AsRef<HalaDescriptorSetLayout>,
+          PCR: AsRef<HalaPushConstantRange>
+  {
+    Self::with_flags(
+      logical_device,
+      descriptor_set_layouts,
+      push_constant_ranges,
+      HalaPipelineCreateFlags::default(),
+      shader,
+      required_subgroup_size,
+      pipeline_cache,
+      debug_name,
+    )
+  }
+
+  /// Create a compute pipeline with the create flags.
+  /// param logical_device: The logical device.
+  /// param descriptor_set_layouts: The descriptor set layouts.
+  /// param push_constant_ranges: The push constant ranges.
+  /// param flags: The pipeline create flags.
+  /// param shader: The shader.
+  /// param required_subgroup_size: The required subgroup size, None to let the driver choose it.
+  /// param pipeline_cache: The pipeline cache.
+  /// param debug_name: The debug name.
+  /// return: The compute pipeline.
+  #[allow(clippy::too_many_arguments)]
+  pub fn with_flags<DSL, PCR>(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    descriptor_set_layouts: &[DSL],
+    push_constant_ranges: &[PCR],
+    flags: HalaPipelineCreateFlags,
+    shader: &HalaShader,
+    required_subgroup_size: Option<u32>,
     pipeline_cache: Option<&HalaPipelineCache>,
     debug_name: &str,
   ) -> Result<Self, HalaGfxError>
     where DSL: AsRef<HalaDescriptorSetLayout>,
           PCR: AsRef<HalaPushConstantRange>
   {
+    if let Some(required_subgroup_size) = required_subgroup_size {
+      Self::check_required_subgroup_size(&logical_device.borrow(), shader, required_subgroup_size)?;
+    }
+
     // Create the pipeline layout.
     let pipeline_layout = HalaPipelineBase::create_pipeline_layout(
       &logical_device,
@@ -2569,7 +3876,9 @@ impl HalaComputePipeline {
     // Create the pipeline.
     let pipeline = Self::create_pipeline(
       &logical_device,
+      flags,
       shader,
+      required_subgroup_size,
       pipeline_cache,
       pipeline_layout,
       debug_name)?;
@@ -2585,27 +3894,87 @@ impl HalaComputePipeline {
     )
   }
 
+  /// Get the statistics of the pipeline executables, e.g. the register usage and the spill count.
+  /// The pipeline must be created with HalaPipelineCreateFlags::CAPTURE_STATISTICS
+  /// and the device must support the pipeline executable properties extension.
+  /// return: The statistics.
+  pub fn get_statistics(&self) -> Result<Vec<HalaPipelineExecutableStatistic>, HalaGfxError> {
+    HalaPipelineBase::get_statistics(&self.logical_device, self.raw)
+  }
+
+  /// Check the required subgroup size against the device properties.
+  /// param logical_device: The logical device.
+  /// param shader: The shader.
+  /// param required_subgroup_size: The required subgroup size.
+  /// return: The result.
+  fn check_required_subgroup_size(
+    logical_device: &HalaLogicalDevice,
+    shader: &HalaShader,
+    required_subgroup_size: u32,
+  ) -> Result<(), HalaGfxError> {
+    let subgroup_properties = &logical_device.subgroup_properties;
+    if !subgroup_properties.size_control_supported {
+      return Err(HalaGfxError::new("The subgroup size control feature is not supported.", None));
+    }
+    if !subgroup_properties.required_size_stages.contains(shader.stage_flags) {
+      return Err(HalaGfxError::new("The required subgroup size is not supported by the shader stage.", None));
+    }
+    if !required_subgroup_size.is_power_of_two()
+      || required_subgroup_size < subgroup_properties.min_size
+      || required_subgroup_size > subgroup_properties.max_size
+    {
+      return Err(HalaGfxError::new(
+        &format!(
+          "The required subgroup size {} must be a power of two in [{}, {}].",
+          required_subgroup_size,
+          subgroup_properties.min_size,
+          subgroup_properties.max_size,
+        ),
+        None,
+      ));
+    }
+    Ok(())
+  }
+
   /// Create a compute pipeline.
+  /// If the subgroup size is required, the device supports the full subgroups
+  /// and the local size x of the shader is a multiple of the subgroup size, the shader is created with the full subgroups.
   /// param logical_device: The logical device.
+  /// param flags: The pipeline create flags.
   /// param shader: The shader.
+  /// param required_subgroup_size: The required subgroup size.
   /// param pipeline_cache: The pipeline cache.
   /// param pipeline_layout: The pipeline layout.
   /// param debug_name: The debug name.
   /// return: The compute pipeline.
   fn create_pipeline(
     logical_device: &Rc<RefCell<HalaLogicalDevice>>,
+    flags: HalaPipelineCreateFlags,
     shader: &HalaShader,
+    required_subgroup_size: Option<u32>,
     pipeline_cache: Option<&HalaPipelineCache>,
     pipeline_layout: vk::PipelineLayout,
     debug_name: &str
   ) -> Result<vk::Pipeline, HalaGfxError> {
     let main_func_name = std::ffi::CString::new("main")
       .map_err(|err| HalaGfxError::new("Failed to create \"main\" CString.", Some(Box::new(err))))?;
-    let shader_stage_info = vk::PipelineShaderStageCreateInfo::default()
+    let mut required_subgroup_size_info = vk::PipelineShaderStageRequiredSubgroupSizeCreateInfo::default()
+      .required_subgroup_size(required_subgroup_size.unwrap_or(0));
+    let mut shader_stage_info = vk::PipelineShaderStageCreateInfo::default()
       .stage(shader.stage_flags.into())
       .module(shader.module)
       .name(&main_func_name);
+    if required_subgroup_size.is_some() {
+      let is_full_subgroups = shader.local_size
+        .zip(required_subgroup_size)
+        .is_some_and(|(local_size, subgroup_size)| local_size[0] % subgroup_size == 0);
+      if is_full_subgroups && logical_device.borrow().subgroup_properties.compute_full_subgroups_supported {
+        shader_stage_info = shader_stage_info.flags(vk::PipelineShaderStageCreateFlags::REQUIRE_FULL_SUBGROUPS);
+      }
+      shader_stage_info = shader_stage_info.push_next(&mut required_subgroup_size_info);
+    }
     let pipeline_info = vk::ComputePipelineCreateInfo::default()
+      .flags(flags.into())
       .stage(shader_stage_info)
       .layout(pipeline_layout);
 
@@ -2626,4 +3995,156 @@ impl HalaComputePipeline {
 
     Ok(pipeline)
   }
-}
\ No newline at end of file
+
+  /// Set the debug name of the pipeline and its layout.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    HalaPipelineBase::set_debug_name(&self.logical_device, self.raw, self.layout, debug_name)?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
+#[cfg(all(test, feature = "shaderc"))]
+mod tests {
+  use super::*;
+
+  const MESH_SHADER_SOURCE: &str = r#"
+    #version 460
+    #extension GL_EXT_mesh_shader : require
+    layout(local_size_x = 1) in;
+    layout(triangles, max_vertices = 3, max_primitives = 1) out;
+    void main() {
+      SetMeshOutputsEXT(3, 1);
+      gl_MeshVerticesEXT[0].gl_Position = vec4(-1.0, -1.0, 0.0, 1.0);
+      gl_MeshVerticesEXT[1].gl_Position = vec4(3.0, -1.0, 0.0, 1.0);
+      gl_MeshVerticesEXT[2].gl_Position = vec4(-1.0, 3.0, 0.0, 1.0);
+      gl_PrimitiveTriangleIndicesEXT[0] = uvec3(0, 1, 2);
+    }
+  "#;
+
+  const FRAGMENT_SHADER_SOURCE: &str = r#"
+    #version 460
+    layout(location = 0) out vec4 out_color;
+    void main() {
+      out_color = vec4(1.0, 0.0, 0.0, 1.0);
+    }
+  "#;
+
+  /// Create a context on a hidden window with the mesh shader required.
+  /// return: The context, the window and the event loop, None if there is no window system or no device supports the mesh shader.
+  fn create_mesh_shader_context() -> Option<(crate::HalaContext, winit::window::Window, winit::event_loop::EventLoop<()>)> {
+    let mut builder = winit::event_loop::EventLoop::builder();
+    #[cfg(target_os = "linux")]
+    winit::platform::x11::EventLoopBuilderExtX11::with_any_thread(&mut builder, true);
+    #[cfg(target_os = "windows")]
+    winit::platform::windows::EventLoopBuilderExtWindows::with_any_thread(&mut builder, true);
+    let event_loop = builder.build().ok()?;
+    #[allow(deprecated)]
+    let window = event_loop.create_window(
+      winit::window::Window::default_attributes().with_visible(false)
+    ).ok()?;
+    let gpu_req = crate::HalaGPURequirements {
+      version: (1, 3, 0),
+      require_mesh_shader: true,
+      ..Default::default()
+    };
+    let context = crate::HalaContext::new("mesh_shader_test", &gpu_req, &window).ok()?;
+    Some((context, window, event_loop))
+  }
+
+  #[test]
+  fn new_mesh_and_draw_mesh_tasks() {
+    let Some((context, _window, _event_loop)) = create_mesh_shader_context() else {
+      log::warn!("Skip the test, there is no window system or no device supports the mesh shader.");
+      return;
+    };
+    let logical_device = &context.logical_device;
+
+    let mesh_shader = HalaShader::with_glsl_source(
+      Rc::clone(logical_device),
+      MESH_SHADER_SOURCE,
+      HalaShaderStageFlags::MESH,
+      crate::HalaRayTracingShaderGroupType::GENERAL,
+      "main",
+      &[],
+      None,
+      "test.mesh",
+    ).unwrap();
+    let fragment_shader = HalaShader::with_glsl_source(
+      Rc::clone(logical_device),
+      FRAGMENT_SHADER_SOURCE,
+      HalaShaderStageFlags::FRAGMENT,
+      crate::HalaRayTracingShaderGroupType::GENERAL,
+      "main",
+      &[],
+      None,
+      "test.frag",
+    ).unwrap();
+    let pipeline = HalaGraphicsPipeline::new_mesh(
+      Rc::clone(logical_device),
+      &[HalaFormat::R8G8B8A8_UNORM],
+      None,
+      16,
+      16,
+      &[] as &[HalaDescriptorSetLayout],
+      HalaPipelineCreateFlags::empty(),
+      &[] as &[HalaPushConstantRange],
+      &[HalaBlendState::default()],
+      &[HalaBlendState::default()],
+      &HalaRasterizerState::default(),
+      &HalaMultisampleState::default(),
+      &HalaDepthState::default(),
+      None,
+      None,
+      &mesh_shader,
+      &fragment_shader,
+      &[],
+      None,
+      "test.mesh_pipeline",
+    ).unwrap();
+
+    let image = HalaImage::new_2d(
+      Rc::clone(logical_device),
+      crate::HalaImageUsageFlags::COLOR_ATTACHMENT | crate::HalaImageUsageFlags::TRANSFER_SRC,
+      HalaFormat::R8G8B8A8_UNORM,
+      16,
+      16,
+      1,
+      1,
+      crate::HalaMemoryLocation::GpuOnly,
+      "test.color",
+    ).unwrap();
+    let command_buffers = crate::HalaCommandBufferSet::new(
+      Rc::clone(logical_device),
+      Rc::clone(&context.short_time_command_pools),
+      crate::HalaCommandBufferType::GRAPHICS,
+      crate::HalaCommandBufferLevel::PRIMARY,
+      1,
+      "test.command_buffers",
+    ).unwrap();
+    logical_device.borrow().graphics_execute_and_submit(&command_buffers, 0, |_, command_buffers, index| {
+      image.transition_layout(command_buffers, index, crate::HalaImageLayout::UNDEFINED, crate::HalaImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+      command_buffers.begin_rendering_with(
+        index,
+        &[&image],
+        None,
+        (0, 0, 16, 16),
+        &[Some([0.0, 0.0, 0.0, 0.0])],
+        None,
+        None,
+        crate::HalaAttachmentStoreOp::STORE,
+        crate::HalaAttachmentStoreOp::DONT_CARE,
+        crate::HalaAttachmentStoreOp::DONT_CARE,
+      );
+      command_buffers.bind_graphics_pipeline(index, &pipeline);
+      command_buffers.draw_mesh_tasks(index, 1, 1, 1);
+      command_buffers.end_rendering(index);
+    }, 0).unwrap();
+
+    // The triangle emitted by the mesh shader covers the whole image.
+    let (width, height, data) = image.read_back(0, 0, None, crate::HalaImageLayout::COLOR_ATTACHMENT_OPTIMAL, &command_buffers).unwrap();
+    assert_eq!((width, height), (16, 16));
+    assert!(data.chunks_exact(4).all(|texel| texel == [255, 0, 0, 255]));
+  }
+}
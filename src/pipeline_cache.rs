@@ -50,16 +50,81 @@ impl HalaPipelineCache {
     )
   }
 
+  /// Create a pipeline cache with the data in the file.
+  /// The file is not validated, use from_file to ignore the stale cache safely.
+  /// param logical_device: The logical device.
+  /// param path: The file path.
+  /// return: The pipeline cache.
   pub fn with_cache_file(logical_device: Rc<RefCell<HalaLogicalDevice>>, path: &str) -> Result<Self, HalaGfxError> {
     let data = std::fs::read(path)
       .map_err(|err| HalaGfxError::new("Failed to read pipeline cache file.", Some(Box::new(err))))?;
 
+    Self::with_data(logical_device, &data)
+  }
+
+  /// Create a pipeline cache from the file saved by save_to_file.
+  /// The cache data is specific to the GPU and the driver, so it is discarded and an empty cache is created
+  /// if the file does not exist or its header does not match the current device.
+  /// param logical_device: The logical device.
+  /// param path: The file path.
+  /// return: The pipeline cache.
+  pub fn from_file(logical_device: Rc<RefCell<HalaLogicalDevice>>, path: &str) -> Result<Self, HalaGfxError> {
+    let data = match std::fs::read(path) {
+      Ok(data) => data,
+      Err(err) => {
+        log::debug!("Failed to read pipeline cache file \"{}\": {}, an empty pipeline cache is created.", path, err);
+        return Self::new(logical_device);
+      }
+    };
+    if !Self::is_data_compatible(&logical_device.borrow(), &data) {
+      log::warn!("The pipeline cache file \"{}\" does not match the current device, an empty pipeline cache is created.", path);
+      return Self::new(logical_device);
+    }
+
+    Self::with_data(logical_device, &data)
+  }
+
+  /// Save the pipeline cache to a file.
+  /// The file is specific to the GPU and the driver, load it with from_file to discard it safely on the other devices.
+  /// param path: The file path.
+  /// return: The result.
+  pub fn save_to_file(&self, path: &str) -> Result<(), HalaGfxError> {
+    let data = unsafe {
+      self.logical_device.borrow().raw.get_pipeline_cache_data(self.raw)
+        .map_err(|err| HalaGfxError::new("Failed to get pipeline cache data.", Some(Box::new(err))))?
+    };
+    std::fs::write(path, data)
+      .map_err(|err| HalaGfxError::new("Failed to save pipeline cache.", Some(Box::new(err))))?;
+    Ok(())
+  }
+
+  /// Save the pipeline cache to a file.
+  /// param path: The file path.
+  /// return: The result.
+  pub fn save(&self, path: &str) -> Result<(), HalaGfxError> {
+    self.save_to_file(path)
+  }
+
+  /// Create a pipeline cache with the initial data.
+  /// param logical_device: The logical device.
+  /// param data: The initial data.
+  /// return: The pipeline cache.
+  fn with_data(logical_device: Rc<RefCell<HalaLogicalDevice>>, data: &[u8]) -> Result<Self, HalaGfxError> {
     let create_info = vk::PipelineCacheCreateInfo::default()
-      .initial_data(&data);
+      .initial_data(data);
     let raw = unsafe {
       logical_device.borrow().raw.create_pipeline_cache(&create_info, None)
         .map_err(|err| HalaGfxError::new("Failed to create pipeline cache.", Some(Box::new(err))))?
     };
+
+    if let Err(err) = logical_device.borrow().set_debug_name(raw, "pipeline_cache") {
+      unsafe {
+        logical_device.borrow().raw.destroy_pipeline_cache(raw, None);
+      }
+      return Err(HalaGfxError::new("Failed to set debug name for pipeline cache.", Some(Box::new(err))));
+    }
+
+    log::debug!("A HalaPipelineCache is created with {} bytes data.", data.len());
     Ok(
       Self {
         logical_device,
@@ -68,16 +133,30 @@ impl HalaPipelineCache {
     )
   }
 
-  /// Load a pipeline cache from a file.
-  /// param path: The file path.
-  /// return: The result.
-  pub fn save(&self, path: &str) -> Result<(), HalaGfxError> {
-    let data = unsafe {
-      self.logical_device.borrow().raw.get_pipeline_cache_data(self.raw)
-        .map_err(|err| HalaGfxError::new("Failed to get pipeline cache data.", Some(Box::new(err))))?
+  /// Check the header(vk::PipelineCacheHeaderVersionOne) of the cache data matches the current device.
+  /// param logical_device: The logical device.
+  /// param data: The cache data.
+  /// return: True if the vendor, device and UUID match.
+  fn is_data_compatible(logical_device: &HalaLogicalDevice, data: &[u8]) -> bool {
+    const HEADER_SIZE: usize = std::mem::size_of::<vk::PipelineCacheHeaderVersionOne>();
+    if data.len() < HEADER_SIZE {
+      return false;
+    }
+    let read_u32 = |offset: usize| u32::from_ne_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+    let header_size = read_u32(0) as usize;
+    let header_version = read_u32(4);
+    let vendor_id = read_u32(8);
+    let device_id = read_u32(12);
+    let pipeline_cache_uuid = &data[16..32];
+
+    let properties = unsafe {
+      logical_device.instance.get_physical_device_properties(logical_device.physical_device)
     };
-    std::fs::write(path, data)
-      .map_err(|err| HalaGfxError::new("Failed to save pipeline cache.", Some(Box::new(err))))?;
-    Ok(())
+    header_size >= HEADER_SIZE
+      && header_size <= data.len()
+      && header_version == vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32
+      && vendor_id == properties.vendor_id
+      && device_id == properties.device_id
+      && pipeline_cache_uuid == properties.pipeline_cache_uuid
   }
-}
\ No newline at end of file
+}
@@ -1,16 +1,35 @@
 pub use crate::constants::*;
 pub use crate::error::HalaGfxError;
-pub use crate::instance::HalaInstance;
+pub use crate::instance::{
+  HalaInstance,
+  HalaValidationFeatureFlags,
+  HalaDebugMessengerCallback,
+  HalaValidationConfig,
+};
 pub use crate::physical_device::{
   HalaQueueFamily,
   HalaPhysicalDevice,
 };
-pub use crate::surface::HalaSurface;
+pub use crate::surface::{
+  HalaColorSpace,
+  HalaSurface,
+};
 pub use crate::logical_device::{
   HalaMemoryLocation,
+  HalaMemoryHeapBudget,
+  HalaDeviceLimits,
+  HalaAllocatorStatistics,
+  HalaAllocatorReport,
+  HalaSubgroupFeatureFlags,
+  HalaSubgroupInfo,
+  HalaSubgroupProperties,
   HalaLogicalDevice,
 };
-pub use crate::swapchain::HalaSwapchain;
+pub use crate::swapchain::{
+  HalaSwapchainStatus,
+  HalaHdrMetadata,
+  HalaSwapchain,
+};
 pub use crate::context::HalaContext;
 pub use crate::renderpass::{
   HalaAttachmentLoadOp,
@@ -31,7 +50,13 @@ pub use crate::shader::{
   HalaRayTracingShaderGroupType,
   HalaShader,
 };
-pub use crate::format::HalaFormat;
+#[cfg(feature = "shaderc")]
+pub use crate::shader::HalaShaderLanguage;
+pub use crate::format::{
+  HalaFormat,
+  HalaFormatFeatureFlags,
+  HalaFormatProperties,
+};
 pub use crate::pipeline::{
   HalaPipelineCreateFlags,
   HalaPipelineStageFlags,
@@ -43,20 +68,27 @@ pub use crate::pipeline::{
   HalaFrontFace,
   HalaCullModeFlags,
   HalaPolygonMode,
+  HalaLineRasterizationMode,
   HalaCompareOp,
   HalaStencilFaceFlags,
   HalaStencilOp,
   HalaStencilOpState,
   HalaBlendState,
+  HalaLineRasterizationState,
   HalaRasterizerState,
   HalaMultisampleState,
   HalaDepthState,
   HalaStencilState,
   HalaVertexInputAttributeDescription,
   HalaVertexInputBindingDescription,
+  HalaVertexInputAttributeDescription2,
+  HalaVertexInputBindingDescription2,
   HalaPushConstantRange,
   HalaDynamicState,
+  HalaPipelineExecutableStatisticValue,
+  HalaPipelineExecutableStatistic,
   HalaGraphicsPipeline,
+  HalaGraphicsPipelineDesc,
   HalaRayTracingPipeline,
   HalaComputePipeline,
 };
@@ -76,18 +108,33 @@ pub use crate::command_buffer::{
   HalaClearDepthStencilValue,
   HalaClearValue,
   HalaSubpassContents,
+  HalaFragmentShadingRateCombinerOp,
+  HalaDebugLabelScope,
   HalaCommandBufferSet,
 };
 pub use crate::buffer::{
   HalaBufferUsageFlags,
   HalaBuffer,
 };
+pub use crate::instance_buffer::HalaInstanceBuffer;
+pub use crate::ring_uniform_buffer::HalaRingUniformBuffer;
+pub use crate::staging_buffer_pool::HalaStagingBufferPool;
+pub use crate::async_uploader::{
+  HalaUploadTicket,
+  HalaAsyncUploader,
+};
+pub use crate::sparse_buffer::{
+  HalaSparseMemoryBind,
+  HalaSparseImageMemoryBind,
+  HalaSparseBuffer,
+};
 pub use crate::descriptor_pool::HalaDescriptorPool;
 pub use crate::descriptor_set::{
   HalaDescriptorType,
   HalaDescriptorBindingFlags,
   HalaDescriptorSetLayoutBinding,
   HalaDescriptorSetLayout,
+  HalaWriteDescriptor,
   HalaDescriptorSet,
 };
 pub use crate::acceleration_structure::{
@@ -102,21 +149,36 @@ pub use crate::acceleration_structure::{
   HalaGeometryInstanceFlags,
   HalaAccelerationStructureGeometry,
   HalaAccelerationStructureBuildRangeInfo,
+  HalaTriangleGeometry,
   HalaAccelerationStructure,
 };
 pub use crate::image::{
   HalaImageUsageFlags,
+  HalaImageViewType,
+  HalaImageViewOptions,
   HalaImage,
 };
+pub use crate::image_view::{
+  HalaImageViewDesc,
+  HalaImageView,
+};
 pub use crate::sampler::{
   HalaFilter,
   HalaSamplerMipmapMode,
   HalaSamplerAddressMode,
+  HalaBorderColor,
+  HalaSamplerCreateInfo,
   HalaSampler,
 };
-pub use crate::shader_binding_table::HalaShaderBindingTable;
+pub use crate::shader_binding_table::{
+  HalaShaderGroupCounts,
+  HalaShaderBindingTable,
+};
 pub use crate::query::{
   HalaQueryPipelineStatisticFlags,
+  HalaQueryType,
+  HalaQueryResultFlags,
+  HalaPipelineStatistics,
   HalaQueryPool,
 };
 pub use crate::barrier::{
@@ -126,5 +188,27 @@ pub use crate::barrier::{
   HalaImageBarrierInfo,
   HalaBufferBarrierInfo,
   HalaMemoryBarrierInfo,
+  default_barrier_params,
+};
+pub use crate::event::HalaEvent;
+pub use crate::semaphore::{
+  HalaSemaphore,
+  HalaTimelineSemaphore,
+};
+pub use crate::frame_ring::HalaFrameRing;
+pub use crate::frame_context::{
+  HalaFrameToken,
+  HalaFrameSemaphores,
+  HalaFrameContext,
+};
+pub use crate::ui_overlay::{
+  HalaUiVertex,
+  HalaUiPushConstants,
+  HalaUiBufferRing,
+};
+#[cfg(feature = "spirv-reflect")]
+pub use crate::reflection::{
+  HalaReflectedDescriptorBinding,
+  HalaShaderReflection,
 };
 pub use crate::aabb::HalaAABB;
\ No newline at end of file
@@ -36,11 +36,106 @@ impl std::convert::From<HalaQueryPipelineStatisticFlags> for vk::QueryPipelineSt
   }
 }
 
+/// The query type.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HalaQueryType(i32);
+impl HalaQueryType {
+  pub const OCCLUSION: Self = Self(vk::QueryType::OCCLUSION.as_raw());
+  pub const PIPELINE_STATISTICS: Self = Self(vk::QueryType::PIPELINE_STATISTICS.as_raw());
+  pub const TIMESTAMP: Self = Self(vk::QueryType::TIMESTAMP.as_raw());
+}
+
+impl std::convert::From<vk::QueryType> for HalaQueryType {
+  fn from(val: vk::QueryType) -> Self {
+    Self(val.as_raw())
+  }
+}
+
+impl std::convert::From<HalaQueryType> for vk::QueryType {
+  fn from(val: HalaQueryType) -> Self {
+    vk::QueryType::from_raw(val.0)
+  }
+}
+
+/// The query result flags.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HalaQueryResultFlags(u32);
+crate::hala_bitflags_wrapped!(HalaQueryResultFlags, u32);
+impl HalaQueryResultFlags {
+  pub const TYPE_64: Self = Self(vk::QueryResultFlags::TYPE_64.as_raw());
+  pub const WAIT: Self = Self(vk::QueryResultFlags::WAIT.as_raw());
+  pub const WITH_AVAILABILITY: Self = Self(vk::QueryResultFlags::WITH_AVAILABILITY.as_raw());
+  pub const PARTIAL: Self = Self(vk::QueryResultFlags::PARTIAL.as_raw());
+}
+
+impl std::convert::From<vk::QueryResultFlags> for HalaQueryResultFlags {
+  fn from(flags: vk::QueryResultFlags) -> Self {
+    Self(flags.as_raw())
+  }
+}
+
+impl std::convert::From<HalaQueryResultFlags> for vk::QueryResultFlags {
+  fn from(flags: HalaQueryResultFlags) -> Self {
+    Self::from_raw(flags.0)
+  }
+}
+
+/// The pipeline statistics result of one query.
+/// The statistics which are not enabled in the query pool are 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HalaPipelineStatistics {
+  pub input_assembly_vertices: u64,
+  pub input_assembly_primitives: u64,
+  pub vertex_shader_invocations: u64,
+  pub geometry_shader_invocations: u64,
+  pub geometry_shader_primitives: u64,
+  pub clipping_invocations: u64,
+  pub clipping_primitives: u64,
+  pub fragment_shader_invocations: u64,
+  pub tessellation_control_shader_patches: u64,
+  pub tessellation_evaluation_shader_invocations: u64,
+  pub compute_shader_invocations: u64,
+}
+
+/// The implementation of the pipeline statistics.
+impl HalaPipelineStatistics {
+  /// Create the pipeline statistics from the raw values of one query.
+  /// The values are written in the order of the flag bits.
+  /// param pipeline_statistics: The enabled pipeline statistics.
+  /// param values: The raw values.
+  /// return: The pipeline statistics.
+  pub fn from_values(pipeline_statistics: HalaQueryPipelineStatisticFlags, values: &[u64]) -> Self {
+    let mut result = Self::default();
+    let mut values = values.iter();
+    let fields = [
+      (HalaQueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES, &mut result.input_assembly_vertices),
+      (HalaQueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES, &mut result.input_assembly_primitives),
+      (HalaQueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS, &mut result.vertex_shader_invocations),
+      (HalaQueryPipelineStatisticFlags::GEOMETRY_SHADER_INVOCATIONS, &mut result.geometry_shader_invocations),
+      (HalaQueryPipelineStatisticFlags::GEOMETRY_SHADER_PRIMITIVES, &mut result.geometry_shader_primitives),
+      (HalaQueryPipelineStatisticFlags::CLIPPING_INVOCATIONS, &mut result.clipping_invocations),
+      (HalaQueryPipelineStatisticFlags::CLIPPING_PRIMITIVES, &mut result.clipping_primitives),
+      (HalaQueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS, &mut result.fragment_shader_invocations),
+      (HalaQueryPipelineStatisticFlags::TESSELLATION_CONTROL_SHADER_PATCHES, &mut result.tessellation_control_shader_patches),
+      (HalaQueryPipelineStatisticFlags::TESSELLATION_EVALUATION_SHADER_INVOCATIONS, &mut result.tessellation_evaluation_shader_invocations),
+      (HalaQueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS, &mut result.compute_shader_invocations),
+    ];
+    for (flag, field) in fields {
+      if pipeline_statistics.contains(flag) {
+        *field = values.next().copied().unwrap_or(0);
+      }
+    }
+    result
+  }
+}
+
 /// The query pool.
 pub struct HalaQueryPool {
   pub(crate) logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
   pub raw: vk::QueryPool,
   pub size: u32,
+  pub query_type: HalaQueryType,
+  pub pipeline_statistics: HalaQueryPipelineStatisticFlags,
   pub(crate) timestamp_period: f64,
   pub(crate) debug_name: String,
 }
@@ -89,6 +184,8 @@ impl HalaQueryPool {
       logical_device,
       raw,
       size: count,
+      query_type: HalaQueryType::TIMESTAMP,
+      pipeline_statistics: HalaQueryPipelineStatisticFlags::empty(),
       timestamp_period,
       debug_name: debug_name.to_string(),
     })
@@ -124,6 +221,42 @@ impl HalaQueryPool {
       logical_device,
       raw,
       size: count,
+      query_type: HalaQueryType::PIPELINE_STATISTICS,
+      pipeline_statistics,
+      timestamp_period: 0.0,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Create a new occlusion query pool.
+  /// param logical_device: The logical device.
+  /// param count: The query count.
+  /// param debug_name: The debug name.
+  /// return: The query pool.
+  pub fn new_occlusion(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    count: u32,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let query_pool_info = vk::QueryPoolCreateInfo::default()
+      .query_type(vk::QueryType::OCCLUSION)
+      .query_count(count);
+    let raw = unsafe {
+      logical_device.borrow().raw.create_query_pool(&query_pool_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create query pool.", Some(Box::new(err))))?
+    };
+    logical_device.borrow().set_debug_name(
+      raw,
+      debug_name,
+    ).map_err(|err| HalaGfxError::new("Failed to set debug name.", Some(Box::new(err))))?;
+
+    log::debug!("A HalaQueryPool \"{}\" is created.", debug_name);
+    Ok(Self {
+      logical_device,
+      raw,
+      size: count,
+      query_type: HalaQueryType::OCCLUSION,
+      pipeline_statistics: HalaQueryPipelineStatisticFlags::empty(),
       timestamp_period: 0.0,
       debug_name: debug_name.to_string(),
     })
@@ -148,7 +281,7 @@ impl HalaQueryPool {
   /// param count: The query count.
   /// return: The data.
   pub fn wait(&self, first: u32, count: u32) -> Result<Vec<u64>, HalaGfxError> {
-    assert!(first + count <= self.size, "The query range is out of range.");
+    assert!(first.checked_add(count).is_some_and(|end| end <= self.size), "The query range is out of range.");
     let mut data: Vec<u64> = vec![0; count as usize];
 
     unsafe {
@@ -162,4 +295,104 @@ impl HalaQueryPool {
 
     Ok(data)
   }
-}
\ No newline at end of file
+
+  /// Get the number of the values written for each query.
+  /// param with_availability: Whether the availability value is appended.
+  /// return: The number of the values.
+  pub fn values_per_query(&self, with_availability: bool) -> usize {
+    let count = if self.query_type == HalaQueryType::PIPELINE_STATISTICS {
+      self.pipeline_statistics.as_raw().count_ones() as usize
+    } else {
+      1
+    };
+    if with_availability { count + 1 } else { count }
+  }
+
+  /// Get the results.
+  /// The pipeline statistics query writes one value for each enabled statistic,
+  /// and the availability value is appended to each query if WITH_AVAILABILITY is set.
+  /// param first: The first query.
+  /// param count: The query count.
+  /// param flags: The query result flags.
+  /// return: The results.
+  pub fn get_results(&self, first: u32, count: u32, flags: HalaQueryResultFlags) -> Result<Vec<u64>, HalaGfxError> {
+    assert!(first.checked_add(count).is_some_and(|end| end <= self.size), "The query range is out of range.");
+    let values_per_query = self.values_per_query(flags.contains(HalaQueryResultFlags::WITH_AVAILABILITY));
+    let mut data: Vec<u64> = vec![0; count as usize * values_per_query];
+
+    let result = unsafe {
+      let logical_device = self.logical_device.borrow();
+      (logical_device.raw.fp_v1_0().get_query_pool_results)(
+        logical_device.raw.handle(),
+        self.raw,
+        first,
+        count,
+        std::mem::size_of_val(data.as_slice()),
+        data.as_mut_ptr() as *mut std::ffi::c_void,
+        (values_per_query * std::mem::size_of::<u64>()) as u64,
+        vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::from(flags),
+      )
+    };
+    match result {
+      vk::Result::SUCCESS => Ok(data),
+      // Without WAIT, the unavailable queries are reported by NOT_READY, the available ones are still written.
+      vk::Result::NOT_READY if !flags.contains(HalaQueryResultFlags::WAIT) => Ok(data),
+      err => Err(HalaGfxError::new("Failed to get query pool results.", Some(Box::new(err)))),
+    }
+  }
+
+  /// Get the 64-bit results.
+  /// param first: The first query.
+  /// param count: The query count.
+  /// param wait: Whether to wait for the results to be available.
+  /// return: The results.
+  pub fn get_results_u64(&self, first: u32, count: u32, wait: bool) -> Result<Vec<u64>, HalaGfxError> {
+    self.get_results(
+      first,
+      count,
+      if wait { HalaQueryResultFlags::WAIT } else { HalaQueryResultFlags::empty() },
+    )
+  }
+
+  /// Get the timestamp period.
+  /// return: The number of nanoseconds per timestamp tick, 0 if the pool is not a timestamp pool.
+  pub fn get_timestamp_period(&self) -> f64 {
+    self.timestamp_period
+  }
+
+  /// Convert the timestamp ticks to nanoseconds.
+  /// param ticks: The timestamp ticks.
+  /// return: The nanoseconds.
+  pub fn ticks_to_nanoseconds(&self, ticks: u64) -> u64 {
+    (ticks as f64 * self.timestamp_period) as u64
+  }
+
+  /// Get the pipeline statistics results.
+  /// param first: The first query.
+  /// param count: The query count.
+  /// param wait: Whether to wait for the results to be available.
+  /// return: The pipeline statistics of each query.
+  pub fn get_pipeline_statistics(&self, first: u32, count: u32, wait: bool) -> Result<Vec<HalaPipelineStatistics>, HalaGfxError> {
+    if self.query_type != HalaQueryType::PIPELINE_STATISTICS {
+      return Err(HalaGfxError::new("The query pool is not a pipeline statistics query pool.", None));
+    }
+    let values_per_query = self.values_per_query(false);
+    let data = self.get_results_u64(first, count, wait)?;
+
+    Ok(
+      data.chunks(values_per_query)
+        .map(|values| HalaPipelineStatistics::from_values(self.pipeline_statistics, values))
+        .collect()
+    )
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for query pool.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
@@ -0,0 +1,323 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use spirv_reflect::types::ReflectDescriptorType;
+
+use crate::{
+  HalaGfxError,
+  HalaLogicalDevice,
+  HalaShader,
+  HalaShaderStageFlags,
+  HalaDescriptorType,
+  HalaDescriptorSetLayout,
+  HalaDescriptorSetLayoutBinding,
+  HalaDescriptorBindingFlags,
+  HalaPushConstantRange,
+};
+
+/// The descriptor binding discovered by the reflection.
+#[derive(Clone)]
+pub struct HalaReflectedDescriptorBinding {
+  pub name: String,
+  pub set: u32,
+  pub binding: u32,
+  pub descriptor_type: HalaDescriptorType,
+  /// The descriptor count, 0 means an unbounded runtime array.
+  pub count: u32,
+  pub stage_flags: HalaShaderStageFlags,
+}
+
+/// The shader reflection.
+#[derive(Clone, Default)]
+pub struct HalaShaderReflection {
+  pub descriptor_bindings: Vec<HalaReflectedDescriptorBinding>,
+  pub push_constant_ranges: Vec<HalaPushConstantRange>,
+}
+
+/// The AsRef trait implementation of the shader reflection.
+impl AsRef<HalaShaderReflection> for HalaShaderReflection {
+  fn as_ref(&self) -> &HalaShaderReflection {
+    self
+  }
+}
+
+/// The implementation of the shader reflection.
+impl HalaShaderReflection {
+  /// Reflect the SPIR-V code.
+  /// param code: The SPIR-V words.
+  /// param stage_flags: The shader stage.
+  /// return: The shader reflection.
+  pub fn new(code: &[u32], stage_flags: HalaShaderStageFlags) -> Result<Self, HalaGfxError> {
+    let module = spirv_reflect::ShaderModule::load_u32_data(code)
+      .map_err(|err| HalaGfxError::new(&format!("Failed to load the SPIR-V code for reflection: {}", err), None))?;
+
+    let mut descriptor_bindings = Vec::new();
+    for binding in module.enumerate_descriptor_bindings(None)
+      .map_err(|err| HalaGfxError::new(&format!("Failed to enumerate descriptor bindings: {}", err), None))?
+    {
+      let descriptor_type = match binding.descriptor_type {
+        ReflectDescriptorType::Sampler => HalaDescriptorType::SAMPLER,
+        ReflectDescriptorType::CombinedImageSampler => HalaDescriptorType::COMBINED_IMAGE_SAMPLER,
+        ReflectDescriptorType::SampledImage => HalaDescriptorType::SAMPLED_IMAGE,
+        ReflectDescriptorType::StorageImage => HalaDescriptorType::STORAGE_IMAGE,
+        ReflectDescriptorType::UniformTexelBuffer => HalaDescriptorType::UNIFORM_TEXEL_BUFFER,
+        ReflectDescriptorType::StorageTexelBuffer => HalaDescriptorType::STORAGE_TEXEL_BUFFER,
+        ReflectDescriptorType::UniformBuffer => HalaDescriptorType::UNIFORM_BUFFER,
+        ReflectDescriptorType::StorageBuffer => HalaDescriptorType::STORAGE_BUFFER,
+        ReflectDescriptorType::UniformBufferDynamic => HalaDescriptorType::UNIFORM_BUFFER_DYNAMIC,
+        ReflectDescriptorType::StorageBufferDynamic => HalaDescriptorType::STORAGE_BUFFER_DYNAMIC,
+        ReflectDescriptorType::InputAttachment => HalaDescriptorType::INPUT_ATTACHMENT,
+        ReflectDescriptorType::AccelerationStructureNV => HalaDescriptorType::ACCELERATION_STRUCTURE,
+        ReflectDescriptorType::Undefined => return Err(HalaGfxError::new(
+          &format!("The descriptor type of the binding \"{}\"(set {}, binding {}) is undefined.", binding.name, binding.set, binding.binding),
+          None,
+        )),
+      };
+      descriptor_bindings.push(HalaReflectedDescriptorBinding {
+        name: binding.name.clone(),
+        set: binding.set,
+        binding: binding.binding,
+        descriptor_type,
+        count: binding.count,
+        stage_flags,
+      });
+    }
+
+    let mut push_constant_ranges = Vec::new();
+    for block in module.enumerate_push_constant_blocks(None)
+      .map_err(|err| HalaGfxError::new(&format!("Failed to enumerate push constant blocks: {}", err), None))?
+    {
+      // The range starts at the first member, the block offset is not reliable.
+      let (offset, end) = if block.members.is_empty() {
+        (block.offset, block.offset + block.size)
+      } else {
+        block.members.iter().fold((u32::MAX, 0), |(offset, end), member| {
+          (offset.min(member.offset), end.max(member.offset + member.size))
+        })
+      };
+      push_constant_ranges.push(HalaPushConstantRange {
+        stage_flags,
+        offset,
+        size: end - offset,
+      });
+    }
+
+    Ok(Self {
+      descriptor_bindings,
+      push_constant_ranges,
+    })
+  }
+
+  /// Reflect and merge the shaders.
+  /// param shaders: The shaders.
+  /// return: The merged shader reflection.
+  pub fn from_shaders<S>(shaders: &[S]) -> Result<Self, HalaGfxError>
+    where S: AsRef<HalaShader>
+  {
+    let reflections = shaders.iter()
+      .map(|shader| shader.as_ref().reflect())
+      .collect::<Result<Vec<_>, _>>()?;
+    Self::merge(&reflections)
+  }
+
+  /// Merge the reflections of the shaders in the same pipeline.
+  /// The stage flags of the same binding are merged, and the push constant ranges are merged per stage.
+  /// param reflections: The shader reflections.
+  /// return: The merged shader reflection.
+  pub fn merge<R>(reflections: &[R]) -> Result<Self, HalaGfxError>
+    where R: AsRef<HalaShaderReflection>
+  {
+    let mut bindings = BTreeMap::<(u32, u32), HalaReflectedDescriptorBinding>::new();
+    for reflection in reflections.iter() {
+      for binding in reflection.as_ref().descriptor_bindings.iter() {
+        match bindings.get_mut(&(binding.set, binding.binding)) {
+          Some(merged) => {
+            if merged.descriptor_type != binding.descriptor_type || merged.count != binding.count {
+              return Err(HalaGfxError::new(
+                &format!(
+                  "The binding \"{}\"(set {}, binding {}) has different types or counts in the shaders.",
+                  binding.name, binding.set, binding.binding,
+                ),
+                None,
+              ));
+            }
+            merged.stage_flags |= binding.stage_flags;
+          },
+          None => {
+            bindings.insert((binding.set, binding.binding), binding.clone());
+          },
+        }
+      }
+    }
+
+    // Each stage can only appear in one push constant range.
+    let mut stage_ranges = Vec::<(HalaShaderStageFlags, u32, u32)>::new();
+    for reflection in reflections.iter() {
+      for range in reflection.as_ref().push_constant_ranges.iter() {
+        let end = range.offset + range.size;
+        match stage_ranges.iter_mut().find(|(stage_flags, _, _)| *stage_flags == range.stage_flags) {
+          Some((_, offset, merged_end)) => {
+            *offset = (*offset).min(range.offset);
+            *merged_end = (*merged_end).max(end);
+          },
+          None => stage_ranges.push((range.stage_flags, range.offset, end)),
+        }
+      }
+    }
+    let mut push_constant_ranges = Vec::<HalaPushConstantRange>::new();
+    for (stage_flags, offset, end) in stage_ranges.into_iter() {
+      match push_constant_ranges.iter_mut().find(|range| range.offset == offset && range.size == end - offset) {
+        Some(range) => range.stage_flags |= stage_flags,
+        None => push_constant_ranges.push(HalaPushConstantRange {
+          stage_flags,
+          offset,
+          size: end - offset,
+        }),
+      }
+    }
+
+    Ok(Self {
+      descriptor_bindings: bindings.into_values().collect(),
+      push_constant_ranges,
+    })
+  }
+
+  /// Create the descriptor set layouts from the reflected bindings.
+  /// The layouts are indexed by the set number, the missing sets get empty layouts.
+  /// param logical_device: The logical device.
+  /// param unbounded_descriptor_count: The max descriptor count of the unbounded runtime arrays, which are created as bindless bindings.
+  /// param debug_name: The debug name.
+  /// return: The descriptor set layouts.
+  pub fn create_descriptor_set_layouts(
+    &self,
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    unbounded_descriptor_count: u32,
+    debug_name: &str,
+  ) -> Result<Vec<HalaDescriptorSetLayout>, HalaGfxError> {
+    let set_count = self.descriptor_bindings.iter()
+      .map(|binding| binding.set + 1)
+      .max()
+      .unwrap_or(0);
+
+    let mut layouts = Vec::with_capacity(set_count as usize);
+    for set in 0..set_count {
+      let bindings = self.descriptor_bindings.iter()
+        .filter(|binding| binding.set == set)
+        .map(|binding| if binding.count == 0 {
+          HalaDescriptorSetLayoutBinding::new_bindless(
+            binding.binding,
+            binding.descriptor_type,
+            unbounded_descriptor_count,
+            binding.stage_flags,
+          )
+        } else {
+          HalaDescriptorSetLayoutBinding::new(
+            binding.binding,
+            binding.descriptor_type,
+            binding.count,
+            binding.stage_flags,
+            HalaDescriptorBindingFlags::empty(),
+          )
+        })
+        .collect::<Vec<_>>();
+      layouts.push(HalaDescriptorSetLayout::new(
+        Rc::clone(&logical_device),
+        &bindings,
+        &format!("{}.set_{}", debug_name, set),
+      )?);
+    }
+
+    Ok(layouts)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn binding(set: u32, binding: u32, descriptor_type: HalaDescriptorType, count: u32, stage_flags: HalaShaderStageFlags) -> HalaReflectedDescriptorBinding {
+    HalaReflectedDescriptorBinding {
+      name: format!("binding_{}_{}", set, binding),
+      set,
+      binding,
+      descriptor_type,
+      count,
+      stage_flags,
+    }
+  }
+
+  fn reflection(descriptor_bindings: Vec<HalaReflectedDescriptorBinding>, push_constant_ranges: Vec<HalaPushConstantRange>) -> HalaShaderReflection {
+    HalaShaderReflection {
+      descriptor_bindings,
+      push_constant_ranges,
+    }
+  }
+
+  #[test]
+  fn merge_unions_the_stage_flags_of_the_same_binding() {
+    let vertex = reflection(vec![
+      binding(0, 0, HalaDescriptorType::UNIFORM_BUFFER, 1, HalaShaderStageFlags::VERTEX),
+      binding(1, 0, HalaDescriptorType::STORAGE_BUFFER, 1, HalaShaderStageFlags::VERTEX),
+    ], vec![]);
+    let fragment = reflection(vec![
+      binding(0, 1, HalaDescriptorType::COMBINED_IMAGE_SAMPLER, 0, HalaShaderStageFlags::FRAGMENT),
+      binding(0, 0, HalaDescriptorType::UNIFORM_BUFFER, 1, HalaShaderStageFlags::FRAGMENT),
+    ], vec![]);
+    let merged = HalaShaderReflection::merge(&[vertex, fragment]).unwrap();
+
+    // The bindings are ordered by the set and the binding number.
+    let keys = merged.descriptor_bindings.iter().map(|b| (b.set, b.binding)).collect::<Vec<_>>();
+    assert_eq!(keys, vec![(0, 0), (0, 1), (1, 0)]);
+    assert!(merged.descriptor_bindings[0].stage_flags == HalaShaderStageFlags::VERTEX | HalaShaderStageFlags::FRAGMENT);
+    assert!(merged.descriptor_bindings[1].stage_flags == HalaShaderStageFlags::FRAGMENT);
+    assert_eq!(merged.descriptor_bindings[1].count, 0);
+    assert!(merged.descriptor_bindings[2].stage_flags == HalaShaderStageFlags::VERTEX);
+  }
+
+  #[test]
+  fn merge_rejects_the_conflicting_bindings() {
+    let vertex = reflection(vec![
+      binding(0, 0, HalaDescriptorType::UNIFORM_BUFFER, 1, HalaShaderStageFlags::VERTEX),
+    ], vec![]);
+    let different_type = reflection(vec![
+      binding(0, 0, HalaDescriptorType::STORAGE_BUFFER, 1, HalaShaderStageFlags::FRAGMENT),
+    ], vec![]);
+    let different_count = reflection(vec![
+      binding(0, 0, HalaDescriptorType::UNIFORM_BUFFER, 2, HalaShaderStageFlags::FRAGMENT),
+    ], vec![]);
+    assert!(HalaShaderReflection::merge(&[vertex.clone(), different_type]).is_err());
+    assert!(HalaShaderReflection::merge(&[vertex, different_count]).is_err());
+  }
+
+  #[test]
+  fn merge_push_constant_ranges() {
+    let range = |stage_flags, offset, size| HalaPushConstantRange { stage_flags, offset, size };
+    // The same range in two stages becomes one range for both stages.
+    let merged = HalaShaderReflection::merge(&[
+      reflection(vec![], vec![range(HalaShaderStageFlags::VERTEX, 0, 64)]),
+      reflection(vec![], vec![range(HalaShaderStageFlags::FRAGMENT, 0, 64)]),
+    ]).unwrap();
+    assert_eq!(merged.push_constant_ranges.len(), 1);
+    assert!(merged.push_constant_ranges[0].stage_flags == HalaShaderStageFlags::VERTEX | HalaShaderStageFlags::FRAGMENT);
+    assert_eq!((merged.push_constant_ranges[0].offset, merged.push_constant_ranges[0].size), (0, 64));
+
+    // The different ranges are kept for each stage.
+    let merged = HalaShaderReflection::merge(&[
+      reflection(vec![], vec![range(HalaShaderStageFlags::VERTEX, 0, 64)]),
+      reflection(vec![], vec![range(HalaShaderStageFlags::FRAGMENT, 64, 16)]),
+    ]).unwrap();
+    assert_eq!(merged.push_constant_ranges.len(), 2);
+    assert!(merged.push_constant_ranges[0].stage_flags == HalaShaderStageFlags::VERTEX);
+    assert!(merged.push_constant_ranges[1].stage_flags == HalaShaderStageFlags::FRAGMENT);
+    assert_eq!((merged.push_constant_ranges[1].offset, merged.push_constant_ranges[1].size), (64, 16));
+
+    // The ranges of the same stage are combined into one covering range.
+    let merged = HalaShaderReflection::merge(&[
+      reflection(vec![], vec![range(HalaShaderStageFlags::COMPUTE, 16, 16)]),
+      reflection(vec![], vec![range(HalaShaderStageFlags::COMPUTE, 0, 8)]),
+    ]).unwrap();
+    assert_eq!(merged.push_constant_ranges.len(), 1);
+    assert_eq!((merged.push_constant_ranges[0].offset, merged.push_constant_ranges[0].size), (0, 32));
+  }
+}
@@ -0,0 +1,160 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use ash::vk;
+
+use crate::{
+  HalaBuffer,
+  HalaBufferUsageFlags,
+  HalaDescriptorSet,
+  HalaGfxError,
+  HalaLogicalDevice,
+  HalaMemoryLocation,
+};
+
+/// The ring of the uniform(or storage) data which is bound with the dynamic offsets.
+/// Each frame in flight owns a region of the slots, and each slot is aligned to the min offset alignment of the device,
+/// so the data of each draw can be written to a new slot and selected by the dynamic offset when binding the descriptor sets.
+pub struct HalaRingUniformBuffer<T: Copy> {
+  pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  pub buffer: HalaBuffer,
+  pub is_storage: bool,
+  /// The size of a slot, which is the size of T aligned to the min offset alignment.
+  pub slot_size: u64,
+  pub slots_per_frame: usize,
+  pub frames_in_flight: usize,
+  pub current_frame: usize,
+  /// The number of the slots used in the current frame.
+  pub used_slots: usize,
+  pub(crate) debug_name: String,
+  _marker: std::marker::PhantomData<T>,
+}
+
+/// The implementation of the ring uniform buffer.
+impl<T: Copy> HalaRingUniformBuffer<T> {
+  /// Create a new ring uniform buffer.
+  /// param logical_device: The logical device.
+  /// param slots_per_frame: The number of the slots of each frame.
+  /// param frames_in_flight: The number of the frames in flight.
+  /// param is_storage: Whether the buffer is bound as the dynamic storage buffer instead of the dynamic uniform buffer.
+  /// param debug_name: The debug name.
+  /// return: The ring uniform buffer.
+  pub fn new(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    slots_per_frame: usize,
+    frames_in_flight: usize,
+    is_storage: bool,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    if slots_per_frame == 0 || frames_in_flight == 0 {
+      return Err(HalaGfxError::new("The number of the slots and the frames in flight must be greater than 0.", None));
+    }
+    let data_size = std::mem::size_of::<T>() as u64;
+    if data_size == 0 {
+      return Err(HalaGfxError::new("The size of the data type must be greater than 0.", None));
+    }
+
+    let (alignment, max_range) = {
+      let logical_device = logical_device.borrow();
+      if is_storage {
+        (logical_device.limits.min_storage_buffer_offset_alignment, logical_device.limits.max_storage_buffer_range)
+      } else {
+        (logical_device.limits.min_uniform_buffer_offset_alignment, logical_device.limits.max_uniform_buffer_range)
+      }
+    };
+    if data_size > max_range as u64 {
+      return Err(HalaGfxError::new(
+        &format!("The size {} of the data type exceeds the max buffer range {}.", data_size, max_range),
+        None,
+      ));
+    }
+    let alignment = alignment.max(1);
+    let slot_size = data_size.div_ceil(alignment) * alignment;
+    let num_of_slots = (slots_per_frame as u64).checked_mul(frames_in_flight as u64)
+      .ok_or(HalaGfxError::new("The number of the slots overflows.", None))?;
+    // The dynamic offset is u32, so the offset of the last slot must fit in it.
+    let buffer_size = slot_size.checked_mul(num_of_slots)
+      .filter(|&size| size - slot_size <= u32::MAX as u64)
+      .ok_or(HalaGfxError::new(
+        &format!("The offset of the last slot of {} slots with size {} exceeds the max dynamic offset.", num_of_slots, slot_size),
+        None,
+      ))?;
+    let buffer = HalaBuffer::new(
+      Rc::clone(&logical_device),
+      buffer_size,
+      if is_storage { HalaBufferUsageFlags::STORAGE_BUFFER } else { HalaBufferUsageFlags::UNIFORM_BUFFER },
+      HalaMemoryLocation::CpuToGpu,
+      debug_name,
+    )?;
+
+    log::debug!("A HalaRingUniformBuffer \"{}\" with slot size {} is created.", debug_name, slot_size);
+    Ok(Self {
+      logical_device,
+      buffer,
+      is_storage,
+      slot_size,
+      slots_per_frame,
+      frames_in_flight,
+      current_frame: 0,
+      used_slots: 0,
+      debug_name: debug_name.to_string(),
+      _marker: std::marker::PhantomData,
+    })
+  }
+
+  /// Begin to write the slots of the frame, the slots written in the last use of the frame are reused.
+  /// The GPU must have finished the last use of the frame, e.g. the fence of HalaFrameRing is waited.
+  /// param frame_index: The index of the frame in flight.
+  /// return: The result.
+  pub fn begin_frame(&mut self, frame_index: usize) -> Result<(), HalaGfxError> {
+    if frame_index >= self.frames_in_flight {
+      return Err(HalaGfxError::new(
+        &format!("The frame index {} exceeds the number of the frames in flight {}.", frame_index, self.frames_in_flight),
+        None,
+      ));
+    }
+    self.current_frame = frame_index;
+    self.used_slots = 0;
+
+    Ok(())
+  }
+
+  /// Write the data to a new slot of the current frame.
+  /// param data: The data.
+  /// return: The dynamic offset of the slot.
+  pub fn push(&mut self, data: &T) -> Result<u32, HalaGfxError> {
+    if self.used_slots >= self.slots_per_frame {
+      return Err(HalaGfxError::new(
+        &format!("The slots of the HalaRingUniformBuffer \"{}\" are used up in the frame.", self.debug_name),
+        None,
+      ));
+    }
+
+    let offset = u32::try_from(self.slot_size * (self.current_frame * self.slots_per_frame + self.used_slots) as u64)
+      .map_err(|err| HalaGfxError::new("The offset of the slot exceeds the max dynamic offset.", Some(Box::new(err))))?;
+    self.buffer.update_memory(offset as usize, std::slice::from_ref(data))?;
+    self.used_slots += 1;
+
+    Ok(offset)
+  }
+
+  /// Bind the buffer to the dynamic buffer binding of the descriptor set.
+  /// The range of the descriptor is the size of T.
+  /// param descriptor_set: The descriptor set.
+  /// param index: The index of the descriptor set.
+  /// param binding: The binding.
+  pub fn update_descriptor_set(&self, descriptor_set: &HalaDescriptorSet, index: usize, binding: u32) {
+    let buffer_infos = [vk::DescriptorBufferInfo::default()
+      .buffer(self.buffer.raw)
+      .range(std::mem::size_of::<T>() as u64)];
+    let descriptor_write = vk::WriteDescriptorSet::default()
+      .dst_set(descriptor_set.raw[index])
+      .dst_binding(binding)
+      .descriptor_type(if self.is_storage { vk::DescriptorType::STORAGE_BUFFER_DYNAMIC } else { vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC })
+      .buffer_info(&buffer_infos);
+
+    unsafe {
+      self.logical_device.borrow().raw.update_descriptor_sets(&[descriptor_write], &[]);
+    }
+  }
+}
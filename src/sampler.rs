@@ -3,6 +3,7 @@ use ash::vk;
 use crate::{
   HalaGfxError,
   HalaLogicalDevice,
+  HalaCompareOp,
 };
 
 /// The filter.
@@ -68,6 +69,123 @@ impl std::convert::From<HalaSamplerAddressMode> for vk::SamplerAddressMode {
   }
 }
 
+/// The border color.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HalaBorderColor(i32);
+impl HalaBorderColor {
+  pub const FLOAT_TRANSPARENT_BLACK: Self = Self(vk::BorderColor::FLOAT_TRANSPARENT_BLACK.as_raw());
+  pub const INT_TRANSPARENT_BLACK: Self = Self(vk::BorderColor::INT_TRANSPARENT_BLACK.as_raw());
+  pub const FLOAT_OPAQUE_BLACK: Self = Self(vk::BorderColor::FLOAT_OPAQUE_BLACK.as_raw());
+  pub const INT_OPAQUE_BLACK: Self = Self(vk::BorderColor::INT_OPAQUE_BLACK.as_raw());
+  pub const FLOAT_OPAQUE_WHITE: Self = Self(vk::BorderColor::FLOAT_OPAQUE_WHITE.as_raw());
+  pub const INT_OPAQUE_WHITE: Self = Self(vk::BorderColor::INT_OPAQUE_WHITE.as_raw());
+}
+
+impl std::convert::From<vk::BorderColor> for HalaBorderColor {
+  fn from(v: vk::BorderColor) -> Self {
+    Self(v.as_raw())
+  }
+}
+
+impl std::convert::From<HalaBorderColor> for vk::BorderColor {
+  fn from(v: HalaBorderColor) -> Self {
+    Self::from_raw(v.0)
+  }
+}
+
+/// The sampler create information.
+#[derive(Clone, Copy)]
+pub struct HalaSamplerCreateInfo {
+  pub mag_filter: HalaFilter,
+  pub min_filter: HalaFilter,
+  pub mipmap_mode: HalaSamplerMipmapMode,
+  pub address_mode_u: HalaSamplerAddressMode,
+  pub address_mode_v: HalaSamplerAddressMode,
+  pub address_mode_w: HalaSamplerAddressMode,
+  pub mip_lod_bias: f32,
+  /// The anisotropy is disabled if it is None. It is clamped to the device limit.
+  pub max_anisotropy: Option<f32>,
+  pub min_lod: f32,
+  pub max_lod: f32,
+  pub border_color: HalaBorderColor,
+  /// The compare operation for the shadow sampler. The compare is disabled if it is None.
+  pub compare_op: Option<HalaCompareOp>,
+}
+
+/// The Default trait implementation for sampler create information.
+impl Default for HalaSamplerCreateInfo {
+  fn default() -> Self {
+    Self {
+      mag_filter: HalaFilter::LINEAR,
+      min_filter: HalaFilter::LINEAR,
+      mipmap_mode: HalaSamplerMipmapMode::LINEAR,
+      address_mode_u: HalaSamplerAddressMode::REPEAT,
+      address_mode_v: HalaSamplerAddressMode::REPEAT,
+      address_mode_w: HalaSamplerAddressMode::REPEAT,
+      mip_lod_bias: 0.0,
+      max_anisotropy: None,
+      min_lod: 0.0,
+      max_lod: vk::LOD_CLAMP_NONE,
+      border_color: HalaBorderColor::FLOAT_TRANSPARENT_BLACK,
+      compare_op: None,
+    }
+  }
+}
+
+/// The implementation of the sampler create information.
+impl HalaSamplerCreateInfo {
+  /// The trilinear filtering with the repeat address mode.
+  /// return: The sampler create information.
+  pub fn trilinear_repeat() -> Self {
+    Self::default()
+  }
+
+  /// The bilinear filtering with the clamp to edge address mode.
+  /// The nearest mip level is used.
+  /// return: The sampler create information.
+  pub fn bilinear_clamp() -> Self {
+    Self {
+      mipmap_mode: HalaSamplerMipmapMode::NEAREST,
+      address_mode_u: HalaSamplerAddressMode::CLAMP_TO_EDGE,
+      address_mode_v: HalaSamplerAddressMode::CLAMP_TO_EDGE,
+      address_mode_w: HalaSamplerAddressMode::CLAMP_TO_EDGE,
+      ..Self::default()
+    }
+  }
+
+  /// The nearest filtering with the clamp to edge address mode.
+  /// return: The sampler create information.
+  pub fn nearest_clamp() -> Self {
+    Self {
+      mag_filter: HalaFilter::NEAREST,
+      min_filter: HalaFilter::NEAREST,
+      mipmap_mode: HalaSamplerMipmapMode::NEAREST,
+      address_mode_u: HalaSamplerAddressMode::CLAMP_TO_EDGE,
+      address_mode_v: HalaSamplerAddressMode::CLAMP_TO_EDGE,
+      address_mode_w: HalaSamplerAddressMode::CLAMP_TO_EDGE,
+      ..Self::default()
+    }
+  }
+
+  /// Set the mipmap mode.
+  /// param mipmap_mode: The mipmap mode, NEAREST for bilinear and LINEAR for trilinear filtering.
+  /// return: The sampler create information.
+  pub fn mipmap_mode(mut self, mipmap_mode: HalaSamplerMipmapMode) -> Self {
+    self.mipmap_mode = mipmap_mode;
+    self
+  }
+
+  /// Set the LOD clamp range.
+  /// param min_lod: The minimum LOD, e.g. the first resident mip level of a streaming texture.
+  /// param max_lod: The maximum LOD, vk::LOD_CLAMP_NONE for no clamp.
+  /// return: The sampler create information.
+  pub fn lod_clamp(mut self, min_lod: f32, max_lod: f32) -> Self {
+    self.min_lod = min_lod;
+    self.max_lod = max_lod;
+    self
+  }
+}
+
 /// The sampler.
 pub struct HalaSampler {
   pub(crate) logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
@@ -148,4 +266,104 @@ impl HalaSampler {
       debug_name: debug_name.to_string(),
     })
   }
-}
\ No newline at end of file
+
+  /// Create a new sampler with the create information.
+  /// param logical_device: The logical device.
+  /// param create_info: The sampler create information.
+  /// param debug_name: The debug name.
+  /// return: The sampler.
+  pub fn with_create_info(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    create_info: &HalaSamplerCreateInfo,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    if create_info.min_lod > create_info.max_lod {
+      return Err(HalaGfxError::new(
+        &format!("The min lod {} is greater than the max lod {}.", create_info.min_lod, create_info.max_lod),
+        None,
+      ));
+    }
+    let max_anisotropy = create_info.max_anisotropy.map(|max_anisotropy| {
+      let limit = logical_device.borrow().limits.max_sampler_anisotropy;
+      if max_anisotropy > limit {
+        log::warn!("The max anisotropy {} exceeds the device limit {}, clamp it.", max_anisotropy, limit);
+      }
+      max_anisotropy.clamp(1.0, limit)
+    });
+    let sampler_create_info = vk::SamplerCreateInfo::default()
+      .mag_filter(create_info.mag_filter.into())
+      .min_filter(create_info.min_filter.into())
+      .mipmap_mode(create_info.mipmap_mode.into())
+      .address_mode_u(create_info.address_mode_u.into())
+      .address_mode_v(create_info.address_mode_v.into())
+      .address_mode_w(create_info.address_mode_w.into())
+      .mip_lod_bias(create_info.mip_lod_bias)
+      .anisotropy_enable(max_anisotropy.is_some())
+      .max_anisotropy(max_anisotropy.unwrap_or(1.0))
+      .compare_enable(create_info.compare_op.is_some())
+      .compare_op(create_info.compare_op.unwrap_or(HalaCompareOp::NEVER).into())
+      .min_lod(create_info.min_lod)
+      .max_lod(create_info.max_lod)
+      .border_color(create_info.border_color.into())
+      .unnormalized_coordinates(false);
+    let raw = unsafe {
+      let sampler = logical_device.borrow().raw.create_sampler(&sampler_create_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create sampler.", Some(Box::new(err))))?;
+      logical_device.borrow().set_debug_name(
+        sampler,
+        debug_name,
+      ).map_err(|err| HalaGfxError::new("Failed to set debug name for sampler.", Some(Box::new(err))))?;
+      sampler
+    };
+
+    log::debug!("The HalaSampler \"{}\" is created.", debug_name);
+    Ok(Self {
+      logical_device,
+      raw,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Create a trilinear sampler with the repeat address mode.
+  /// param logical_device: The logical device.
+  /// param debug_name: The debug name.
+  /// return: The sampler.
+  pub fn trilinear_repeat(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::with_create_info(logical_device, &HalaSamplerCreateInfo::trilinear_repeat(), debug_name)
+  }
+
+  /// Create a bilinear sampler with the clamp to edge address mode.
+  /// param logical_device: The logical device.
+  /// param debug_name: The debug name.
+  /// return: The sampler.
+  pub fn bilinear_clamp(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::with_create_info(logical_device, &HalaSamplerCreateInfo::bilinear_clamp(), debug_name)
+  }
+
+  /// Create a nearest sampler with the clamp to edge address mode.
+  /// param logical_device: The logical device.
+  /// param debug_name: The debug name.
+  /// return: The sampler.
+  pub fn nearest_clamp(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::with_create_info(logical_device, &HalaSamplerCreateInfo::nearest_clamp(), debug_name)
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for sampler.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
@@ -0,0 +1,186 @@
+use ash::vk;
+
+use crate::{
+  HalaGfxError,
+  HalaLogicalDevice,
+};
+
+/// The binary semaphore.
+pub struct HalaSemaphore {
+  pub(crate) logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+  pub raw: vk::Semaphore,
+  pub(crate) debug_name: String,
+}
+
+/// The AsRef implementation for semaphore.
+impl AsRef<HalaSemaphore> for HalaSemaphore {
+  fn as_ref(&self) -> &HalaSemaphore {
+    self
+  }
+}
+
+/// The Drop implementation for semaphore.
+impl Drop for HalaSemaphore {
+  fn drop(&mut self) {
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.destroy_semaphore(self.raw, None);
+    }
+    log::debug!("A HalaSemaphore \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation for semaphore.
+impl HalaSemaphore {
+  /// Create a new binary semaphore.
+  /// param logical_device: The logical device.
+  /// param debug_name: The debug name.
+  /// return: The semaphore.
+  pub fn new(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let semaphore_info = vk::SemaphoreCreateInfo::default();
+    let raw = unsafe {
+      let logical_device = logical_device.borrow();
+      let semaphore = logical_device.raw.create_semaphore(&semaphore_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create semaphore.", Some(Box::new(err))))?;
+      logical_device.set_debug_name(
+        semaphore,
+        debug_name,
+      ).map_err(|err| HalaGfxError::new("Failed to set debug name for semaphore.", Some(Box::new(err))))?;
+      semaphore
+    };
+
+    log::debug!("A HalaSemaphore \"{}\" is created.", debug_name);
+    Ok(Self {
+      logical_device,
+      raw,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for semaphore.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
+
+/// The timeline semaphore.
+/// Its payload is a monotonically increasing 64-bit value which can be waited and polled on the host.
+pub struct HalaTimelineSemaphore {
+  pub(crate) logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+  pub raw: vk::Semaphore,
+  pub(crate) debug_name: String,
+}
+
+/// The AsRef implementation for timeline semaphore.
+impl AsRef<HalaTimelineSemaphore> for HalaTimelineSemaphore {
+  fn as_ref(&self) -> &HalaTimelineSemaphore {
+    self
+  }
+}
+
+/// The Drop implementation for timeline semaphore.
+impl Drop for HalaTimelineSemaphore {
+  fn drop(&mut self) {
+    unsafe {
+      let logical_device = self.logical_device.borrow();
+      logical_device.raw.destroy_semaphore(self.raw, None);
+    }
+    log::debug!("A HalaTimelineSemaphore \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation for timeline semaphore.
+impl HalaTimelineSemaphore {
+  /// Create a new timeline semaphore.
+  /// param logical_device: The logical device.
+  /// param initial_value: The initial value.
+  /// param debug_name: The debug name.
+  /// return: The timeline semaphore.
+  pub fn new(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    initial_value: u64,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let mut type_info = vk::SemaphoreTypeCreateInfo::default()
+      .semaphore_type(vk::SemaphoreType::TIMELINE)
+      .initial_value(initial_value);
+    let semaphore_info = vk::SemaphoreCreateInfo::default()
+      .push_next(&mut type_info);
+    let raw = unsafe {
+      let logical_device = logical_device.borrow();
+      let semaphore = logical_device.raw.create_semaphore(&semaphore_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create timeline semaphore.", Some(Box::new(err))))?;
+      logical_device.set_debug_name(
+        semaphore,
+        debug_name,
+      ).map_err(|err| HalaGfxError::new("Failed to set debug name for timeline semaphore.", Some(Box::new(err))))?;
+      semaphore
+    };
+
+    log::debug!("A HalaTimelineSemaphore \"{}\" is created.", debug_name);
+    Ok(Self {
+      logical_device,
+      raw,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Get the current value.
+  /// return: The current value.
+  pub fn get_value(&self) -> Result<u64, HalaGfxError> {
+    unsafe {
+      self.logical_device.borrow().raw.get_semaphore_counter_value(self.raw)
+        .map_err(|err| HalaGfxError::new("Failed to get the value of timeline semaphore.", Some(Box::new(err))))
+    }
+  }
+
+  /// Wait on the host until the value is reached.
+  /// param value: The value to wait.
+  /// param timeout: The timeout in nanoseconds.
+  /// return: True if the value is reached, false if timeout.
+  pub fn wait(&self, value: u64, timeout: u64) -> Result<bool, HalaGfxError> {
+    let semaphores = [self.raw];
+    let values = [value];
+    let wait_info = vk::SemaphoreWaitInfo::default()
+      .semaphores(&semaphores)
+      .values(&values);
+    unsafe {
+      match self.logical_device.borrow().raw.wait_semaphores(&wait_info, timeout) {
+        Ok(()) => Ok(true),
+        Err(vk::Result::TIMEOUT) => Ok(false),
+        Err(err) => Err(HalaGfxError::new("Failed to wait timeline semaphore.", Some(Box::new(err)))),
+      }
+    }
+  }
+
+  /// Signal the value on the host.
+  /// param value: The value to signal, it must be greater than the current value.
+  /// return: The result.
+  pub fn signal(&self, value: u64) -> Result<(), HalaGfxError> {
+    let signal_info = vk::SemaphoreSignalInfo::default()
+      .semaphore(self.raw)
+      .value(value);
+    unsafe {
+      self.logical_device.borrow().raw.signal_semaphore(&signal_info)
+        .map_err(|err| HalaGfxError::new("Failed to signal timeline semaphore.", Some(Box::new(err))))
+    }
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for timeline semaphore.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
@@ -64,12 +64,24 @@ impl std::convert::From<HalaRayTracingShaderGroupType> for vk::RayTracingShaderG
   }
 }
 
+/// The source language of the shader.
+#[cfg(feature = "shaderc")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HalaShaderLanguage {
+  Glsl,
+  Hlsl,
+}
+
 /// The shader.
 pub struct HalaShader {
   pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
   pub module: vk::ShaderModule,
   pub stage_flags: HalaShaderStageFlags,
   pub ray_tracing_group_type: HalaRayTracingShaderGroupType,
+  /// The local workgroup size declared by the LocalSize execution mode.
+  pub local_size: Option<[u32; 3]>,
+  #[cfg(feature = "spirv-reflect")]
+  pub(crate) spirv: Vec<u32>,
 
   pub(crate) debug_name: String,
 }
@@ -109,25 +121,7 @@ impl HalaShader {
   ) -> Result<Self, HalaGfxError> {
     let code = ash::util::read_spv(&mut std::io::Cursor::new(code))
       .map_err(|err| HalaGfxError::new("Failed to read shader code.", Some(Box::new(err))))?;
-    let module_create_info = vk::ShaderModuleCreateInfo::default()
-      .code(&code);
-    let module = unsafe {
-      logical_device.borrow().raw.create_shader_module(&module_create_info, None)
-        .map_err(|err| HalaGfxError::new("Failed to create shader module.", Some(Box::new(err))))?
-    };
-    logical_device.borrow().set_debug_name(module, debug_name)
-      .map_err(|err| HalaGfxError::new("Failed to set debug name of shader module.", Some(Box::new(err))))?;
-
-    log::debug!("A HalaShader \"{}\" is created.", debug_name);
-    Ok(
-      Self {
-        logical_device,
-        module,
-        stage_flags: stage,
-        ray_tracing_group_type: rt_group_type,
-        debug_name: debug_name.to_string(),
-      }
-    )
+    Self::with_spirv(logical_device, code, stage, rt_group_type, debug_name)
   }
 
   /// Create a new shader with file.
@@ -147,6 +141,149 @@ impl HalaShader {
     let code = ash::util::read_spv(&mut std::fs::File::open(file_path)
       .map_err(|err| HalaGfxError::new(&format!("Failed to open shader file {}.", file_path), Some(Box::new(err))))?)
       .map_err(|err| HalaGfxError::new("Failed to read shader code.", Some(Box::new(err))))?;
+    Self::with_spirv(logical_device, code, stage, rt_group_type, debug_name)
+  }
+
+  /// Create a new shader with GLSL source code.
+  /// The source is compiled to SPIR-V by the shaderc.
+  /// param logical_device: The logical device.
+  /// param source: The GLSL source code.
+  /// param stage: The shader stage.
+  /// param rt_group_type: The ray tracing shader group type.
+  /// param entry: The entry point name.
+  /// param defines: The macro definitions(name, value).
+  /// param include_fn: The include callback(requested name, requesting source name) -> (resolved name, content).
+  ///   If it is None, the included files are loaded from the file system relative to the requesting source.
+  /// param debug_name: The debug name.
+  /// return: The shader.
+  #[cfg(feature = "shaderc")]
+  #[allow(clippy::too_many_arguments)]
+  pub fn with_glsl_source(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    source: &str,
+    stage: HalaShaderStageFlags,
+    rt_group_type: HalaRayTracingShaderGroupType,
+    entry: &str,
+    defines: &[(&str, &str)],
+    include_fn: Option<&dyn Fn(&str, &str) -> Result<(String, String), String>>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let code = Self::compile_source(shaderc::SourceLanguage::GLSL, source, stage, entry, defines, include_fn, debug_name)?;
+    Self::with_spirv(logical_device, code, stage, rt_group_type, debug_name)
+  }
+
+  /// Create a new shader with HLSL source code.
+  /// The source is compiled to SPIR-V by the shaderc.
+  /// param logical_device: The logical device.
+  /// param source: The HLSL source code.
+  /// param stage: The shader stage.
+  /// param rt_group_type: The ray tracing shader group type.
+  /// param entry: The entry point name.
+  /// param defines: The macro definitions(name, value).
+  /// param include_fn: The include callback(requested name, requesting source name) -> (resolved name, content).
+  ///   If it is None, the included files are loaded from the file system relative to the requesting source.
+  /// param debug_name: The debug name.
+  /// return: The shader.
+  #[cfg(feature = "shaderc")]
+  #[allow(clippy::too_many_arguments)]
+  pub fn with_hlsl_source(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    source: &str,
+    stage: HalaShaderStageFlags,
+    rt_group_type: HalaRayTracingShaderGroupType,
+    entry: &str,
+    defines: &[(&str, &str)],
+    include_fn: Option<&dyn Fn(&str, &str) -> Result<(String, String), String>>,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let code = Self::compile_source(shaderc::SourceLanguage::HLSL, source, stage, entry, defines, include_fn, debug_name)?;
+    Self::with_spirv(logical_device, code, stage, rt_group_type, debug_name)
+  }
+
+  /// Create a new shader with GLSL or HLSL source code.
+  /// The included files are searched relative to the requesting source first, then in the include directory.
+  /// param logical_device: The logical device.
+  /// param source: The source code.
+  /// param language: The source language.
+  /// param stage: The shader stage.
+  /// param rt_group_type: The ray tracing shader group type.
+  /// param entry: The entry point name.
+  /// param include_dir: The include directory.
+  /// param defines: The macro definitions(name, value), which are used to generate the shader variants.
+  /// param debug_name: The debug name.
+  /// return: The shader.
+  #[cfg(feature = "shaderc")]
+  #[allow(clippy::too_many_arguments)]
+  pub fn from_source(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    source: &str,
+    language: HalaShaderLanguage,
+    stage: HalaShaderStageFlags,
+    rt_group_type: HalaRayTracingShaderGroupType,
+    entry: &str,
+    include_dir: Option<&std::path::Path>,
+    defines: &[(&str, &str)],
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let code = Self::compile_source_with_include_dir(language, source, stage, entry, include_dir, defines, debug_name)?;
+    Self::with_spirv(logical_device, code, stage, rt_group_type, debug_name)
+  }
+
+  /// Create a new shader with a GLSL or HLSL source file.
+  /// The language is chosen by the file extension, ".hlsl" for HLSL and others for GLSL.
+  /// param logical_device: The logical device.
+  /// param file_path: The source file path.
+  /// param stage: The shader stage.
+  /// param rt_group_type: The ray tracing shader group type.
+  /// param entry: The entry point name.
+  /// param include_dir: The include directory.
+  /// param defines: The macro definitions(name, value).
+  /// param debug_name: The debug name.
+  /// return: The shader.
+  #[cfg(feature = "shaderc")]
+  #[allow(clippy::too_many_arguments)]
+  pub fn from_source_file(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    file_path: &str,
+    stage: HalaShaderStageFlags,
+    rt_group_type: HalaRayTracingShaderGroupType,
+    entry: &str,
+    include_dir: Option<&std::path::Path>,
+    defines: &[(&str, &str)],
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let source = std::fs::read_to_string(file_path)
+      .map_err(|err| HalaGfxError::new(&format!("Failed to read shader source file {}.", file_path), Some(Box::new(err))))?;
+    let language = match std::path::Path::new(file_path).extension().and_then(|ext| ext.to_str()) {
+      Some(ext) if ext.eq_ignore_ascii_case("hlsl") => HalaShaderLanguage::Hlsl,
+      _ => HalaShaderLanguage::Glsl,
+    };
+    // The file path is the name of the source, so the relative includes are resolved from the file.
+    let code = Self::compile_source_with_include_dir(language, &source, stage, entry, include_dir, defines, file_path)?;
+    Self::with_spirv(logical_device, code, stage, rt_group_type, debug_name)
+  }
+
+  /// Reflect the descriptor bindings and the push constant ranges from the SPIR-V code.
+  /// return: The shader reflection.
+  #[cfg(feature = "spirv-reflect")]
+  pub fn reflect(&self) -> Result<crate::HalaShaderReflection, HalaGfxError> {
+    crate::HalaShaderReflection::new(&self.spirv, self.stage_flags)
+  }
+
+  /// Create a new shader with SPIR-V words.
+  /// param logical_device: The logical device.
+  /// param code: The SPIR-V words.
+  /// param stage: The shader stage.
+  /// param rt_group_type: The ray tracing shader group type.
+  /// param debug_name: The debug name.
+  /// return: The shader.
+  fn with_spirv(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    code: Vec<u32>,
+    stage: HalaShaderStageFlags,
+    rt_group_type: HalaRayTracingShaderGroupType,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
     let module_create_info = vk::ShaderModuleCreateInfo::default()
       .code(&code);
     let module = unsafe {
@@ -163,8 +300,231 @@ impl HalaShader {
         module,
         stage_flags: stage,
         ray_tracing_group_type: rt_group_type,
+        local_size: Self::parse_local_size(&code),
+        #[cfg(feature = "spirv-reflect")]
+        spirv: code,
         debug_name: debug_name.to_string(),
       }
     )
   }
-}
\ No newline at end of file
+
+  /// Parse the local workgroup size from the SPIR-V words.
+  /// The size specified by the LocalSizeId execution mode is not parsed.
+  /// param code: The SPIR-V words.
+  /// return: The local workgroup size.
+  fn parse_local_size(code: &[u32]) -> Option<[u32; 3]> {
+    const MAGIC_NUMBER: u32 = 0x0723_0203;
+    const HEADER_WORD_COUNT: usize = 5;
+    const OP_EXECUTION_MODE: u32 = 16;
+    const OP_FUNCTION: u32 = 54;
+    const EXECUTION_MODE_LOCAL_SIZE: u32 = 17;
+
+    if code.len() < HEADER_WORD_COUNT || code[0] != MAGIC_NUMBER {
+      return None;
+    }
+    let mut offset = HEADER_WORD_COUNT;
+    while offset < code.len() {
+      let word_count = (code[offset] >> 16) as usize;
+      let opcode = code[offset] & 0xffff;
+      if word_count == 0 || offset + word_count > code.len() || opcode == OP_FUNCTION {
+        break;
+      }
+      if opcode == OP_EXECUTION_MODE && word_count == 6 && code[offset + 2] == EXECUTION_MODE_LOCAL_SIZE {
+        return Some([code[offset + 3], code[offset + 4], code[offset + 5]]);
+      }
+      offset += word_count;
+    }
+    None
+  }
+
+  /// Compile the source code to SPIR-V words with the include directory.
+  /// param language: The source language.
+  /// param source: The source code.
+  /// param stage: The shader stage.
+  /// param entry: The entry point name.
+  /// param include_dir: The include directory.
+  /// param defines: The macro definitions(name, value).
+  /// param file_name: The file name of the source.
+  /// return: The SPIR-V words.
+  #[cfg(feature = "shaderc")]
+  fn compile_source_with_include_dir(
+    language: HalaShaderLanguage,
+    source: &str,
+    stage: HalaShaderStageFlags,
+    entry: &str,
+    include_dir: Option<&std::path::Path>,
+    defines: &[(&str, &str)],
+    file_name: &str,
+  ) -> Result<Vec<u32>, HalaGfxError> {
+    let include_fn = |requested_name: &str, requesting_name: &str| -> Result<(String, String), String> {
+      let relative_path = std::path::Path::new(requesting_name)
+        .parent()
+        .unwrap_or(std::path::Path::new(""))
+        .join(requested_name);
+      let path = match include_dir {
+        Some(include_dir) if !relative_path.is_file() => include_dir.join(requested_name),
+        _ => relative_path,
+      };
+      let content = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read include file {}: {}", path.display(), err))?;
+      Ok((path.to_string_lossy().to_string(), content))
+    };
+    let language = match language {
+      HalaShaderLanguage::Glsl => shaderc::SourceLanguage::GLSL,
+      HalaShaderLanguage::Hlsl => shaderc::SourceLanguage::HLSL,
+    };
+    Self::compile_source(language, source, stage, entry, defines, Some(&include_fn), file_name)
+  }
+
+  /// Compile the source code to SPIR-V words.
+  /// param language: The source language.
+  /// param source: The source code.
+  /// param stage: The shader stage.
+  /// param entry: The entry point name.
+  /// param defines: The macro definitions(name, value).
+  /// param include_fn: The include callback.
+  /// param file_name: The file name used in the compiler log.
+  /// return: The SPIR-V words.
+  #[cfg(feature = "shaderc")]
+  fn compile_source(
+    language: shaderc::SourceLanguage,
+    source: &str,
+    stage: HalaShaderStageFlags,
+    entry: &str,
+    defines: &[(&str, &str)],
+    include_fn: Option<&dyn Fn(&str, &str) -> Result<(String, String), String>>,
+    file_name: &str,
+  ) -> Result<Vec<u32>, HalaGfxError> {
+    let shader_kind = match stage {
+      HalaShaderStageFlags::VERTEX => shaderc::ShaderKind::Vertex,
+      HalaShaderStageFlags::TESSELLATION_CONTROL => shaderc::ShaderKind::TessControl,
+      HalaShaderStageFlags::TESSELLATION_EVALUATION => shaderc::ShaderKind::TessEvaluation,
+      HalaShaderStageFlags::GEOMETRY => shaderc::ShaderKind::Geometry,
+      HalaShaderStageFlags::FRAGMENT => shaderc::ShaderKind::Fragment,
+      HalaShaderStageFlags::COMPUTE => shaderc::ShaderKind::Compute,
+      HalaShaderStageFlags::RAYGEN => shaderc::ShaderKind::RayGeneration,
+      HalaShaderStageFlags::ANY_HIT => shaderc::ShaderKind::AnyHit,
+      HalaShaderStageFlags::CLOSEST_HIT => shaderc::ShaderKind::ClosestHit,
+      HalaShaderStageFlags::MISS => shaderc::ShaderKind::Miss,
+      HalaShaderStageFlags::INTERSECTION => shaderc::ShaderKind::Intersection,
+      HalaShaderStageFlags::CALLABLE => shaderc::ShaderKind::Callable,
+      HalaShaderStageFlags::TASK => shaderc::ShaderKind::Task,
+      HalaShaderStageFlags::MESH => shaderc::ShaderKind::Mesh,
+      _ => return Err(HalaGfxError::new("Only a single shader stage can be compiled.", None)),
+    };
+
+    let mut compiler = shaderc::Compiler::new()
+      .ok_or_else(|| HalaGfxError::new("Failed to create shader compiler.", None))?;
+    let mut options = shaderc::CompileOptions::new()
+      .ok_or_else(|| HalaGfxError::new("Failed to create shader compile options.", None))?;
+    options.set_source_language(language);
+    options.set_target_env(shaderc::TargetEnv::Vulkan, shaderc::EnvVersion::Vulkan1_2 as u32);
+    for (name, value) in defines.iter() {
+      options.add_macro_definition(name, if value.is_empty() { None } else { Some(*value) });
+    }
+    options.set_include_callback(|requested_name, _include_type, requesting_name, _depth| {
+      let (resolved_name, content) = match include_fn {
+        Some(include_fn) => include_fn(requested_name, requesting_name)?,
+        None => {
+          let path = std::path::Path::new(requesting_name)
+            .parent()
+            .unwrap_or(std::path::Path::new(""))
+            .join(requested_name);
+          let content = std::fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read include file {}: {}", path.display(), err))?;
+          (path.to_string_lossy().to_string(), content)
+        },
+      };
+      Ok(shaderc::ResolvedInclude { resolved_name, content })
+    });
+
+    let artifact = compiler.compile_into_spirv(source, shader_kind, file_name, entry, Some(&options))
+      .map_err(|err| HalaGfxError::new(
+        &format!("Failed to compile shader \"{}\":\n{}", file_name, err),
+        Some(Box::new(err)),
+      ))?;
+    if artifact.get_num_warnings() > 0 {
+      log::warn!("Compile shader \"{}\" with warnings:\n{}", file_name, artifact.get_warning_messages());
+    }
+
+    Ok(artifact.as_binary().to_vec())
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.module, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name for shader module.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const HEADER: [u32; 5] = [0x0723_0203, 0x0001_0000, 0, 16, 0];
+  // OpCapability Shader.
+  const OP_CAPABILITY_SHADER: [u32; 2] = [(2 << 16) | 17, 1];
+  // OpEntryPoint GLCompute %4 "main".
+  const OP_ENTRY_POINT: [u32; 5] = [(5 << 16) | 15, 5, 4, 0x6e69_616d, 0];
+  // OpExecutionMode %4 LocalSize 8 4 2.
+  const OP_EXECUTION_MODE_LOCAL_SIZE: [u32; 6] = [(6 << 16) | 16, 4, 17, 8, 4, 2];
+  // OpFunction %void %4 None %3.
+  const OP_FUNCTION: [u32; 5] = [(5 << 16) | 54, 2, 4, 0, 3];
+
+  fn module(instructions: &[&[u32]]) -> Vec<u32> {
+    let mut code = HEADER.to_vec();
+    for instruction in instructions {
+      code.extend_from_slice(instruction);
+    }
+    code
+  }
+
+  #[test]
+  fn parse_local_size_of_compute_shader() {
+    let code = module(&[&OP_CAPABILITY_SHADER, &OP_ENTRY_POINT, &OP_EXECUTION_MODE_LOCAL_SIZE, &OP_FUNCTION]);
+    assert_eq!(HalaShader::parse_local_size(&code), Some([8, 4, 2]));
+  }
+
+  #[test]
+  fn parse_local_size_without_execution_mode() {
+    let code = module(&[&OP_CAPABILITY_SHADER, &OP_ENTRY_POINT, &OP_FUNCTION]);
+    assert_eq!(HalaShader::parse_local_size(&code), None);
+  }
+
+  #[test]
+  fn parse_local_size_stops_at_first_function() {
+    let code = module(&[&OP_CAPABILITY_SHADER, &OP_ENTRY_POINT, &OP_FUNCTION, &OP_EXECUTION_MODE_LOCAL_SIZE]);
+    assert_eq!(HalaShader::parse_local_size(&code), None);
+  }
+
+  #[test]
+  fn parse_local_size_of_truncated_code() {
+    assert_eq!(HalaShader::parse_local_size(&[]), None);
+    assert_eq!(HalaShader::parse_local_size(&HEADER[..3]), None);
+    assert_eq!(HalaShader::parse_local_size(&HEADER), None);
+    // The execution mode instruction is cut off in the middle.
+    let code = module(&[&OP_ENTRY_POINT, &OP_EXECUTION_MODE_LOCAL_SIZE[..4]]);
+    assert_eq!(HalaShader::parse_local_size(&code), None);
+  }
+
+  #[test]
+  fn parse_local_size_of_malformed_code() {
+    // The wrong magic number.
+    let mut code = module(&[&OP_EXECUTION_MODE_LOCAL_SIZE]);
+    code[0] = 0x0302_2307;
+    assert_eq!(HalaShader::parse_local_size(&code), None);
+    // The zero word count would never advance.
+    let code = module(&[&[16], &OP_EXECUTION_MODE_LOCAL_SIZE]);
+    assert_eq!(HalaShader::parse_local_size(&code), None);
+    // The word count exceeds the code.
+    let code = module(&[&[(100 << 16) | 15, 5], &OP_EXECUTION_MODE_LOCAL_SIZE]);
+    assert_eq!(HalaShader::parse_local_size(&code), None);
+    // The local size execution mode with the wrong word count.
+    let code = module(&[&[(5 << 16) | 16, 4, 17, 8, 4]]);
+    assert_eq!(HalaShader::parse_local_size(&code), None);
+  }
+}
@@ -11,13 +11,50 @@ use crate::{
   HalaRayTracingPipeline,
 };
 
+/// The shader group counts of the ray tracing pipeline.
+/// The groups are ordered as raygen, miss, hit and callable in the pipeline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct HalaShaderGroupCounts {
+  pub raygen: u32,
+  pub miss: u32,
+  pub hit: u32,
+  pub callable: u32,
+}
+
+/// The implementation of the shader group counts.
+impl HalaShaderGroupCounts {
+  /// Get the total group count.
+  /// return: The total group count.
+  pub fn total(&self) -> u32 {
+    self.raygen + self.miss + self.hit + self.callable
+  }
+}
+
+/// The data of the shader binding table before it is uploaded.
+struct HalaShaderBindingTableData {
+  data: Vec<u8>,
+  /// The aligned sizes of the raygen, miss, hit and callable regions.
+  region_sizes: [u32; 4],
+  handle_size: u32,
+  record_size: u32,
+  handle_stride: u32,
+}
+
 /// The shader binding table.
 pub struct HalaShaderBindingTable {
   pub raygen_region: vk::StridedDeviceAddressRegionKHR,
   pub miss_region: vk::StridedDeviceAddressRegionKHR,
   pub hit_region: vk::StridedDeviceAddressRegionKHR,
   pub callable_region: vk::StridedDeviceAddressRegionKHR,
+  pub group_counts: HalaShaderGroupCounts,
+  /// The size of a shader group handle.
+  pub handle_size: u32,
+  /// The size of the largest record data which follows the handle in a record.
+  pub record_size: u32,
+  /// The stride between the records in a region, which is the handle size plus the record size aligned up to the handle alignment.
+  pub handle_stride: u32,
   pub buffer: HalaBuffer,
+  pub(crate) debug_name: String,
 }
 
 /// The AsRef implementation for shader binding table.
@@ -30,7 +67,7 @@ impl AsRef<HalaShaderBindingTable> for HalaShaderBindingTable {
 /// The Drop implementation for shader binding table.
 impl Drop for HalaShaderBindingTable {
   fn drop(&mut self) {
-    log::debug!("The HalaShaderBindingTable is dropped.");
+    log::debug!("The HalaShaderBindingTable \"{}\" is dropped.", self.debug_name);
   }
 }
 
@@ -61,42 +98,231 @@ impl HalaShaderBindingTable {
   ) -> Result<Self, HalaGfxError>
     where S: AsRef<HalaShader>
   {
-    let (
-      raygen_shader_count,
-      miss_shader_count,
-      hit_shader_count,
-      callable_shader_count
-    ) = (
-      raygen_shaders.len() as u32,
-      miss_shaders.len() as u32,
-      hit_shaders.len() as u32,
-      callable_shaders.len() as u32,
-    );
-    let group_count =
-      raygen_shader_count +
-      miss_shader_count +
-      hit_shader_count +
-      callable_shader_count;
+    Self::new_from_pipeline(
+      logical_device,
+      pipeline,
+      HalaShaderGroupCounts {
+        raygen: raygen_shaders.len() as u32,
+        miss: miss_shaders.len() as u32,
+        hit: hit_shaders.len() as u32,
+        callable: callable_shaders.len() as u32,
+      },
+      staging_buffer,
+      transfer_command_buffers,
+      debug_name,
+    )
+  }
 
-    // Get the shader group handles.
-    let (
+  /// Create a new shader binding table from the shader group handles of the pipeline.
+  /// Each handle is placed at a stride of the handle size aligned up to shader_group_handle_alignment,
+  /// and each region starts at an address aligned to shader_group_base_alignment.
+  /// The raygen region must have the same size and stride, so it holds only one raygen group for each trace.
+  /// param logical_device: The logical device.
+  /// param pipeline: The ray tracing pipeline.
+  /// param group_counts: The shader group counts, which must match the pipeline.
+  /// param staging_buffer: The staging buffer.
+  /// param transfer_command_buffers: The transfer command buffers.
+  /// param debug_name: The debug name.
+  /// return: The shader binding table.
+  pub fn new_from_pipeline(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    pipeline: &HalaRayTracingPipeline,
+    group_counts: HalaShaderGroupCounts,
+    staging_buffer: &HalaBuffer,
+    transfer_command_buffers: &HalaCommandBufferSet,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::new_with_record_data(
+      logical_device,
+      pipeline,
+      group_counts,
+      &[],
+      staging_buffer,
+      transfer_command_buffers,
+      debug_name,
+    )
+  }
+
+  /// Create a new shader binding table with the record data of the shader groups.
+  /// Each record holds the shader group handle followed by its data, e.g. the material index or the texture handles,
+  /// which the shaders read with the shaderRecordEXT buffer block.
+  /// param logical_device: The logical device.
+  /// param pipeline: The ray tracing pipeline.
+  /// param group_counts: The shader group counts, which must match the pipeline.
+  /// param record_datas: The record data of each shader group in the pipeline order, empty if the groups have no data.
+  /// param staging_buffer: The staging buffer.
+  /// param transfer_command_buffers: The transfer command buffers.
+  /// param debug_name: The debug name.
+  /// return: The shader binding table.
+  pub fn new_with_record_data(
+    logical_device: std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    pipeline: &HalaRayTracingPipeline,
+    group_counts: HalaShaderGroupCounts,
+    record_datas: &[&[u8]],
+    staging_buffer: &HalaBuffer,
+    transfer_command_buffers: &HalaCommandBufferSet,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    Self::check_group_counts(pipeline, group_counts)?;
+    let HalaShaderBindingTableData {
+      data: stb_data,
+      region_sizes,
+      handle_size,
+      record_size,
+      handle_stride,
+    } = Self::create_table_data(
+      &logical_device.borrow(),
+      pipeline,
+      group_counts,
+      record_datas,
+    )?;
+
+    let buffer = Self::create_buffer(&logical_device, stb_data.len() as _, debug_name)?;
+    buffer.update_gpu_memory_with_buffer(&stb_data, staging_buffer, transfer_command_buffers)?;
+    let [
+      raygen_region,
+      miss_region,
+      hit_region,
+      callable_region,
+    ] = Self::create_regions(buffer.get_device_address(), region_sizes, handle_stride, group_counts);
+
+    log::debug!("The HalaShaderBindingTable \"{}\" is created.", debug_name);
+    Ok(Self {
+      buffer,
+      raygen_region,
+      miss_region,
+      hit_region,
+      callable_region,
+      group_counts,
       handle_size,
-      handle_alignment,
-      group_alignment,
-    ) = {
-      let logical_device = logical_device.borrow();
-      (
-        logical_device.shader_group_handle_size,
-        logical_device.shader_group_handle_alignment,
-        logical_device.shader_group_base_alignment,
-      )
+      record_size,
+      handle_stride,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Get the device address of the shader binding table.
+  /// return: The device address.
+  pub fn get_device_address(&self) -> u64 {
+    self.buffer.get_device_address()
+  }
+
+  /// Rebuild the shader binding table after the shader groups of the pipeline are changed.
+  /// The buffer is reused if it is large enough, so the GPU must have finished the use of the table.
+  /// param pipeline: The ray tracing pipeline.
+  /// param raygen_count: The number of the ray generation groups.
+  /// param miss_count: The number of the miss groups.
+  /// param hit_count: The number of the hit groups.
+  /// param callable_count: The number of the callable groups.
+  /// param record_datas: The record data of each shader group in the pipeline order, empty if the groups have no data.
+  /// param staging_buffer: The staging buffer.
+  /// param transfer_command_buffers: The transfer command buffers.
+  /// return: The result.
+  #[allow(clippy::too_many_arguments)]
+  pub fn rebuild(
+    &mut self,
+    pipeline: &HalaRayTracingPipeline,
+    raygen_count: u32,
+    miss_count: u32,
+    hit_count: u32,
+    callable_count: u32,
+    record_datas: &[&[u8]],
+    staging_buffer: &HalaBuffer,
+    transfer_command_buffers: &HalaCommandBufferSet,
+  ) -> Result<(), HalaGfxError> {
+    let group_counts = HalaShaderGroupCounts {
+      raygen: raygen_count,
+      miss: miss_count,
+      hit: hit_count,
+      callable: callable_count,
     };
-    let aligned_handle_size = (handle_size + handle_alignment - 1) & !(handle_alignment - 1);
-    let handle_pad = aligned_handle_size - handle_size;
+    Self::check_group_counts(pipeline, group_counts)?;
+    let logical_device = std::rc::Rc::clone(&self.buffer.logical_device);
+    let HalaShaderBindingTableData {
+      data: stb_data,
+      region_sizes,
+      handle_size,
+      record_size,
+      handle_stride,
+    } = Self::create_table_data(
+      &logical_device.borrow(),
+      pipeline,
+      group_counts,
+      record_datas,
+    )?;
+
+    if stb_data.len() as u64 > self.buffer.size {
+      self.buffer = Self::create_buffer(&logical_device, stb_data.len() as _, &self.debug_name)?;
+    }
+    self.buffer.update_gpu_memory_with_buffer(&stb_data, staging_buffer, transfer_command_buffers)?;
+    [
+      self.raygen_region,
+      self.miss_region,
+      self.hit_region,
+      self.callable_region,
+    ] = Self::create_regions(self.buffer.get_device_address(), region_sizes, handle_stride, group_counts);
+    self.group_counts = group_counts;
+    self.handle_size = handle_size;
+    self.record_size = record_size;
+    self.handle_stride = handle_stride;
+
+    log::debug!("The HalaShaderBindingTable \"{}\" is rebuilt.", self.debug_name);
+    Ok(())
+  }
+
+  /// Check the shader group counts match the pipeline.
+  /// param pipeline: The ray tracing pipeline.
+  /// param group_counts: The shader group counts.
+  /// return: The result.
+  fn check_group_counts(pipeline: &HalaRayTracingPipeline, group_counts: HalaShaderGroupCounts) -> Result<(), HalaGfxError> {
+    if group_counts != pipeline.group_counts {
+      return Err(HalaGfxError::new(
+        &format!(
+          "The shader group counts {:?} do not match the counts {:?} of the pipeline \"{}\".",
+          group_counts, pipeline.group_counts, pipeline.debug_name,
+        ),
+        None,
+      ));
+    }
+    Ok(())
+  }
+
+  /// Create the data of the shader binding table from the shader group handles of the pipeline.
+  /// param logical_device: The logical device.
+  /// param pipeline: The ray tracing pipeline.
+  /// param group_counts: The shader group counts.
+  /// param record_datas: The record data of each shader group, empty if the groups have no data.
+  /// return: The data of the shader binding table.
+  fn create_table_data(
+    logical_device: &HalaLogicalDevice,
+    pipeline: &HalaRayTracingPipeline,
+    group_counts: HalaShaderGroupCounts,
+    record_datas: &[&[u8]],
+  ) -> Result<HalaShaderBindingTableData, HalaGfxError> {
+    let group_count = group_counts.total();
+    if !record_datas.is_empty() && record_datas.len() != group_count as usize {
+      return Err(HalaGfxError::new(
+        &format!("The record data count {} does not match the shader group count {}.", record_datas.len(), group_count),
+        None,
+      ));
+    }
+
+    // Get the shader group handles.
+    let handle_size = logical_device.shader_group_handle_size;
+    let handle_alignment = logical_device.shader_group_handle_alignment;
+    let group_alignment = logical_device.shader_group_base_alignment;
+    let record_size = record_datas.iter().map(|data| data.len() as u32).max().unwrap_or(0);
+    // The alignments are powers of two.
+    let handle_stride = (handle_size + record_size + handle_alignment - 1) & !(handle_alignment - 1);
+    if handle_stride > logical_device.max_shader_group_stride {
+      return Err(HalaGfxError::new(
+        &format!("The record stride {} exceeds the max shader group stride {}.", handle_stride, logical_device.max_shader_group_stride),
+        None,
+      ));
+    }
 
     let data_size = handle_size * group_count;
     let handles = unsafe {
-      let logical_device = logical_device.borrow();
       logical_device.ray_tracing_pipeline_loader.get_ray_tracing_shader_group_handles(
         pipeline.raw,
         0,
@@ -106,44 +332,75 @@ impl HalaShaderBindingTable {
     };
 
     // Calculate the region sizes.
-    let raygen_region_size = raygen_shader_count * aligned_handle_size;
-    let raygen_region_aligned_size = (raygen_region_size + group_alignment - 1) & !(group_alignment - 1);
-    let miss_region_size = miss_shader_count * aligned_handle_size;
-    let miss_region_aligned_size = (miss_region_size + group_alignment - 1) & !(group_alignment - 1);
-    let hit_region_size = hit_shader_count * aligned_handle_size;
-    let hit_region_aligned_size = (hit_region_size + group_alignment - 1) & !(group_alignment - 1);
-    let callable_region_size = if callable_shader_count > 0 { callable_shader_count * aligned_handle_size } else { 0 };
-    let callable_region_aligned_size = if callable_shader_count > 0 { (callable_region_size + group_alignment - 1) & !(group_alignment - 1) } else { 0 };
-
-    // Create buffer.
-    let buffer_size = raygen_region_size + miss_region_size + hit_region_size + callable_region_size;
+    let region_counts = [group_counts.raygen, group_counts.miss, group_counts.hit, group_counts.callable];
+    let region_sizes = region_counts.map(|count| {
+      let region_size = count * handle_stride;
+      (region_size + group_alignment - 1) & !(group_alignment - 1)
+    });
+
+    let buffer_size = region_sizes.iter().sum::<u32>();
     let mut stb_data = Vec::with_capacity(buffer_size as _);
-    let mut offset = 0;
-    for &(group_shader_count, group_size, group_aligned_size) in [
-      (raygen_shader_count, raygen_region_size, raygen_region_aligned_size),
-      (miss_shader_count, miss_region_size, miss_region_aligned_size),
-      (hit_shader_count, hit_region_size, hit_region_aligned_size),
-      (callable_shader_count, callable_region_size, callable_region_aligned_size),
-    ].iter() {
-      let group_pad = group_aligned_size - group_size;
+    let mut group_index = 0;
+    for (&group_shader_count, &group_aligned_size) in region_counts.iter().zip(region_sizes.iter()) {
+      let group_pad = group_aligned_size - group_shader_count * handle_stride;
 
       for _ in 0..group_shader_count {
+        let offset = group_index * handle_size as usize;
         stb_data.extend_from_slice(&handles[offset..offset + handle_size as usize]);
-        offset += handle_size as usize;
-        stb_data.extend(std::iter::repeat(0u8).take(handle_pad as usize));
+        let record_data = record_datas.get(group_index).copied().unwrap_or(&[]);
+        stb_data.extend_from_slice(record_data);
+        stb_data.extend(std::iter::repeat_n(0u8, (handle_stride - handle_size) as usize - record_data.len()));
+        group_index += 1;
       }
-      stb_data.extend(std::iter::repeat(0u8).take(group_pad as usize));
+      stb_data.extend(std::iter::repeat_n(0u8, group_pad as usize));
     }
 
-    let buffer = HalaBuffer::new(
-      std::rc::Rc::clone(&logical_device),
-      stb_data.len() as _,
-        HalaBufferUsageFlags::SHADER_DEVICE_ADDRESS | HalaBufferUsageFlags::SHADER_BINDING_TABLE | HalaBufferUsageFlags::TRANSFER_DST,
-        HalaMemoryLocation::GpuOnly,
-        &format!("{}_buffer", debug_name)
-    )?;
-    buffer.update_gpu_memory_with_buffer(&stb_data, staging_buffer, transfer_command_buffers)?;
-    let address = buffer.get_device_address();
+    Ok(HalaShaderBindingTableData {
+      data: stb_data,
+      region_sizes,
+      handle_size,
+      record_size,
+      handle_stride,
+    })
+  }
+
+  /// Create the buffer of the shader binding table.
+  /// param logical_device: The logical device.
+  /// param size: The buffer size.
+  /// param debug_name: The debug name.
+  /// return: The buffer.
+  fn create_buffer(
+    logical_device: &std::rc::Rc<std::cell::RefCell<HalaLogicalDevice>>,
+    size: u64,
+    debug_name: &str,
+  ) -> Result<HalaBuffer, HalaGfxError> {
+    HalaBuffer::new(
+      std::rc::Rc::clone(logical_device),
+      size,
+      HalaBufferUsageFlags::SHADER_DEVICE_ADDRESS | HalaBufferUsageFlags::SHADER_BINDING_TABLE | HalaBufferUsageFlags::TRANSFER_DST,
+      HalaMemoryLocation::GpuOnly,
+      &format!("{}_buffer", debug_name)
+    )
+  }
+
+  /// Create the regions of the shader binding table.
+  /// param address: The device address of the buffer.
+  /// param region_sizes: The aligned sizes of the raygen, miss, hit and callable regions.
+  /// param handle_stride: The handle stride.
+  /// param group_counts: The shader group counts.
+  /// return: The raygen, miss, hit and callable regions.
+  fn create_regions(
+    address: u64,
+    region_sizes: [u32; 4],
+    handle_stride: u32,
+    group_counts: HalaShaderGroupCounts,
+  ) -> [vk::StridedDeviceAddressRegionKHR; 4] {
+    let [
+      raygen_region_aligned_size,
+      miss_region_aligned_size,
+      hit_region_aligned_size,
+      callable_region_aligned_size,
+    ] = region_sizes;
     let raygen_region = vk::StridedDeviceAddressRegionKHR::default()
       .device_address(address)
       .size(raygen_region_aligned_size as _)
@@ -151,27 +408,20 @@ impl HalaShaderBindingTable {
     let miss_region = vk::StridedDeviceAddressRegionKHR::default()
       .device_address(address + raygen_region.size)
       .size(miss_region_aligned_size as _)
-      .stride(aligned_handle_size as _);
+      .stride(handle_stride as _);
     let hit_region = vk::StridedDeviceAddressRegionKHR::default()
       .device_address(address + raygen_region.size + miss_region.size)
       .size(hit_region_aligned_size as _)
-      .stride(aligned_handle_size as _);
-    let callable_region = if callable_shader_count > 0 {
+      .stride(handle_stride as _);
+    let callable_region = if group_counts.callable > 0 {
       vk::StridedDeviceAddressRegionKHR::default()
         .device_address(address + raygen_region.size + miss_region.size + hit_region.size)
         .size(callable_region_aligned_size as _)
-        .stride(aligned_handle_size as _)
+        .stride(handle_stride as _)
     } else {
       vk::StridedDeviceAddressRegionKHR::default()
     };
 
-    log::debug!("The HalaShaderBindingTable is created.");
-    Ok(Self {
-      buffer,
-      raygen_region,
-      miss_region,
-      hit_region,
-      callable_region,
-    })
+    [raygen_region, miss_region, hit_region, callable_region]
   }
-}
\ No newline at end of file
+}
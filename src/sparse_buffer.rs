@@ -0,0 +1,300 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use ash::vk;
+
+use crate::{
+  HalaBufferUsageFlags,
+  HalaImageAspectFlags,
+  HalaGfxError,
+  HalaLogicalDevice,
+};
+
+/// The memory bind of a sparse buffer range.
+/// A bind with null memory unbinds the range.
+#[derive(Clone, Copy, Default)]
+pub struct HalaSparseMemoryBind {
+  pub resource_offset: u64,
+  pub size: u64,
+  pub memory: vk::DeviceMemory,
+  pub memory_offset: u64,
+}
+
+/// The AsRef trait implementation of the sparse memory bind.
+impl AsRef<HalaSparseMemoryBind> for HalaSparseMemoryBind {
+  fn as_ref(&self) -> &HalaSparseMemoryBind {
+    self
+  }
+}
+
+/// The implementation of the sparse memory bind.
+impl HalaSparseMemoryBind {
+  /// Create a memory bind which maps the allocation to the buffer range.
+  /// param resource_offset: The offset in the buffer, it must be a multiple of the page size.
+  /// param allocation: The allocation of the page.
+  /// return: The memory bind.
+  pub fn bind(resource_offset: u64, allocation: &gpu_allocator::vulkan::Allocation) -> Self {
+    Self {
+      resource_offset,
+      size: allocation.size(),
+      memory: unsafe { allocation.memory() },
+      memory_offset: allocation.offset(),
+    }
+  }
+
+  /// Create a memory bind which unmaps the buffer range.
+  /// param resource_offset: The offset in the buffer, it must be a multiple of the page size.
+  /// param size: The size of the range.
+  /// return: The memory bind.
+  pub fn unbind(resource_offset: u64, size: u64) -> Self {
+    Self {
+      resource_offset,
+      size,
+      memory: vk::DeviceMemory::null(),
+      memory_offset: 0,
+    }
+  }
+
+  /// Convert to the raw sparse memory bind.
+  /// return: The raw sparse memory bind.
+  pub(crate) fn to_raw(self) -> vk::SparseMemoryBind {
+    vk::SparseMemoryBind::default()
+      .resource_offset(self.resource_offset)
+      .size(self.size)
+      .memory(self.memory)
+      .memory_offset(self.memory_offset)
+  }
+}
+
+/// The memory bind of a sparse image region.
+/// A bind with null memory unbinds the region.
+#[derive(Clone, Copy, Default)]
+pub struct HalaSparseImageMemoryBind {
+  pub aspect_mask: HalaImageAspectFlags,
+  pub mip_level: u32,
+  pub array_layer: u32,
+  /// The offset of the region in texels, it must be a multiple of the image granularity.
+  pub offset: vk::Offset3D,
+  /// The extent of the region in texels, it must be a multiple of the image granularity or reach the edge of the mip level.
+  pub extent: vk::Extent3D,
+  pub memory: vk::DeviceMemory,
+  pub memory_offset: u64,
+}
+
+/// The AsRef trait implementation of the sparse image memory bind.
+impl AsRef<HalaSparseImageMemoryBind> for HalaSparseImageMemoryBind {
+  fn as_ref(&self) -> &HalaSparseImageMemoryBind {
+    self
+  }
+}
+
+/// The implementation of the sparse image memory bind.
+impl HalaSparseImageMemoryBind {
+  /// Create a memory bind which maps the allocation to the image region.
+  /// param aspect_mask: The aspect of the image.
+  /// param mip_level: The mip level.
+  /// param array_layer: The array layer.
+  /// param offset: The offset of the region in texels.
+  /// param extent: The extent of the region in texels.
+  /// param allocation: The allocation of the pages.
+  /// return: The memory bind.
+  pub fn bind(
+    aspect_mask: HalaImageAspectFlags,
+    mip_level: u32,
+    array_layer: u32,
+    offset: vk::Offset3D,
+    extent: vk::Extent3D,
+    allocation: &gpu_allocator::vulkan::Allocation,
+  ) -> Self {
+    Self {
+      aspect_mask,
+      mip_level,
+      array_layer,
+      offset,
+      extent,
+      memory: unsafe { allocation.memory() },
+      memory_offset: allocation.offset(),
+    }
+  }
+
+  /// Create a memory bind which unmaps the image region.
+  /// param aspect_mask: The aspect of the image.
+  /// param mip_level: The mip level.
+  /// param array_layer: The array layer.
+  /// param offset: The offset of the region in texels.
+  /// param extent: The extent of the region in texels.
+  /// return: The memory bind.
+  pub fn unbind(
+    aspect_mask: HalaImageAspectFlags,
+    mip_level: u32,
+    array_layer: u32,
+    offset: vk::Offset3D,
+    extent: vk::Extent3D,
+  ) -> Self {
+    Self {
+      aspect_mask,
+      mip_level,
+      array_layer,
+      offset,
+      extent,
+      memory: vk::DeviceMemory::null(),
+      memory_offset: 0,
+    }
+  }
+
+  /// Convert to the raw sparse image memory bind.
+  /// return: The raw sparse image memory bind.
+  pub(crate) fn to_raw(self) -> vk::SparseImageMemoryBind {
+    vk::SparseImageMemoryBind::default()
+      .subresource(vk::ImageSubresource {
+        aspect_mask: self.aspect_mask.into(),
+        mip_level: self.mip_level,
+        array_layer: self.array_layer,
+      })
+      .offset(self.offset)
+      .extent(self.extent)
+      .memory(self.memory)
+      .memory_offset(self.memory_offset)
+  }
+}
+
+/// The sparse buffer.
+/// The memory of the buffer is not bound at creation, the caller manages the pages and binds them with HalaLogicalDevice::bind_sparse.
+pub struct HalaSparseBuffer {
+  pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  pub raw: vk::Buffer,
+  pub memory_requirements: vk::MemoryRequirements,
+  pub size: u64,
+  pub is_residency: bool,
+  pub(crate) debug_name: String,
+}
+
+/// The AsRef trait implementation of the sparse buffer.
+impl AsRef<HalaSparseBuffer> for HalaSparseBuffer {
+  fn as_ref(&self) -> &HalaSparseBuffer {
+    self
+  }
+}
+
+/// The Drop trait implementation of the sparse buffer.
+impl Drop for HalaSparseBuffer {
+  fn drop(&mut self) {
+    unsafe {
+      self.logical_device.borrow().raw.destroy_buffer(self.raw, None);
+    }
+    log::debug!("A HalaSparseBuffer \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation of the sparse buffer.
+impl HalaSparseBuffer {
+  /// Create a sparse buffer.
+  /// param logical_device: The logical device.
+  /// param size: The size of the buffer.
+  /// param usage_flags: The usage flags of the buffer.
+  /// param is_residency: Whether the buffer can be partially resident.
+  /// param debug_name: The debug name of the buffer.
+  /// return: The sparse buffer.
+  pub fn new(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    size: u64,
+    usage_flags: HalaBufferUsageFlags,
+    is_residency: bool,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    let mut create_flags = vk::BufferCreateFlags::SPARSE_BINDING;
+    {
+      let logical_device = logical_device.borrow();
+      if logical_device.physical_device_features.sparse_binding != vk::TRUE {
+        return Err(HalaGfxError::new("The sparse binding feature is not supported.", None));
+      }
+      logical_device.check_sparse_binding_queue()?;
+      if is_residency {
+        if logical_device.physical_device_features.sparse_residency_buffer != vk::TRUE {
+          return Err(HalaGfxError::new("The sparse residency buffer feature is not supported.", None));
+        }
+        create_flags |= vk::BufferCreateFlags::SPARSE_RESIDENCY;
+      }
+    }
+
+    let buffer_info = vk::BufferCreateInfo::default()
+      .flags(create_flags)
+      .size(size)
+      .usage(usage_flags.into())
+      .sharing_mode(vk::SharingMode::EXCLUSIVE);
+    let (raw, memory_requirements) = unsafe {
+      let logical_device = logical_device.borrow();
+      let buffer = logical_device.raw.create_buffer(&buffer_info, None)
+        .map_err(|err| HalaGfxError::new("Failed to create sparse buffer.", Some(Box::new(err))))?;
+      logical_device.set_debug_name(buffer, debug_name)
+        .map_err(|err| HalaGfxError::new("Failed to set debug name of sparse buffer.", Some(Box::new(err))))?;
+      (buffer, logical_device.raw.get_buffer_memory_requirements(buffer))
+    };
+
+    log::debug!("A HalaSparseBuffer \"{}\" with page size {} is created.", debug_name, memory_requirements.alignment);
+    Ok(
+      Self {
+        logical_device,
+        raw,
+        memory_requirements,
+        size,
+        is_residency,
+        debug_name: debug_name.to_string(),
+      }
+    )
+  }
+
+  /// Get the page size, which is the granularity of the memory binds.
+  /// return: The page size in bytes.
+  pub fn page_size(&self) -> u64 {
+    self.memory_requirements.alignment
+  }
+
+  /// Get the number of the pages which cover the whole buffer.
+  /// return: The page count.
+  pub fn page_count(&self) -> u64 {
+    self.memory_requirements.size.div_ceil(self.page_size())
+  }
+
+  /// Allocate the GPU only memory of the pages.
+  /// param page_count: The number of the pages.
+  /// param debug_name: The debug name of the allocation.
+  /// return: The allocation.
+  pub fn allocate_pages(&self, page_count: u64, debug_name: &str) -> Result<gpu_allocator::vulkan::Allocation, HalaGfxError> {
+    let requirements = vk::MemoryRequirements {
+      size: self.page_size() * page_count,
+      alignment: self.page_size(),
+      memory_type_bits: self.memory_requirements.memory_type_bits,
+    };
+    self.logical_device.borrow_mut().gpu_allocator
+      .allocate(
+        &gpu_allocator::vulkan::AllocationCreateDesc {
+          name: debug_name,
+          requirements,
+          location: gpu_allocator::MemoryLocation::GpuOnly,
+          linear: true,
+          allocation_scheme: gpu_allocator::vulkan::AllocationScheme::GpuAllocatorManaged,
+        }
+      ).map_err(|err| HalaGfxError::new("Failed to allocate sparse buffer pages.", Some(Box::new(err))))
+  }
+
+  /// Free the memory of the pages.
+  /// The pages must be unbound and not used by the GPU.
+  /// param allocation: The allocation.
+  /// return: The result.
+  pub fn free_pages(&self, allocation: gpu_allocator::vulkan::Allocation) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow_mut().gpu_allocator
+      .free(allocation)
+      .map_err(|err| HalaGfxError::new("Failed to free sparse buffer pages.", Some(Box::new(err))))
+  }
+
+  /// Set the debug name.
+  /// param debug_name: The debug name.
+  /// return: The result.
+  pub fn set_debug_name(&mut self, debug_name: &str) -> Result<(), HalaGfxError> {
+    self.logical_device.borrow().set_debug_name(self.raw, debug_name)
+      .map_err(|err| HalaGfxError::new("Failed to set debug name of sparse buffer.", Some(Box::new(err))))?;
+    self.debug_name = debug_name.to_string();
+    Ok(())
+  }
+}
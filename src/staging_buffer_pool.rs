@@ -0,0 +1,97 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::{
+  HalaGfxError,
+  HalaLogicalDevice,
+  HalaBuffer,
+  HalaBufferUsageFlags,
+  HalaMemoryLocation,
+};
+
+/// The pool of the staging buffer used by the uploads.
+/// It keeps a CpuToGpu staging buffer which grows on demand and is reused by the following uploads.
+/// The uploads wait for the queue idle, so the staging buffer can be reused right after an upload is finished.
+pub struct HalaStagingBufferPool {
+  pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  pub buffer: Option<HalaBuffer>,
+  /// The largest size requested since the pool is created or shrunk.
+  pub high_water_mark: u64,
+  pub(crate) debug_name: String,
+}
+
+/// The implementation of the staging buffer pool.
+impl HalaStagingBufferPool {
+  /// Create a new staging buffer pool.
+  /// The staging buffer is created when it is acquired at the first time.
+  /// param logical_device: The logical device.
+  /// param debug_name: The debug name.
+  /// return: The staging buffer pool.
+  pub fn new(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    debug_name: &str,
+  ) -> Self {
+    log::debug!("A HalaStagingBufferPool \"{}\" is created.", debug_name);
+    Self {
+      logical_device,
+      buffer: None,
+      high_water_mark: 0,
+      debug_name: debug_name.to_string(),
+    }
+  }
+
+  /// Get the capacity of the staging buffer.
+  /// return: The capacity in bytes.
+  pub fn capacity(&self) -> u64 {
+    self.buffer.as_ref().map_or(0, |buffer| buffer.size)
+  }
+
+  /// Acquire a staging buffer which is at least the size.
+  /// The staging buffer is recreated with the next power of two size if it is too small.
+  /// param size: The size in bytes.
+  /// return: The staging buffer.
+  pub fn acquire(&mut self, size: u64) -> Result<&HalaBuffer, HalaGfxError> {
+    if size == 0 {
+      return Err(HalaGfxError::new("The size of the staging buffer must be greater than 0.", None));
+    }
+
+    self.high_water_mark = self.high_water_mark.max(size);
+    if self.capacity() < size {
+      // Drop the old staging buffer before allocating the new one.
+      self.buffer = None;
+      self.buffer = Some(self.create_buffer(size.next_power_of_two())?);
+    }
+
+    self.buffer.as_ref().ok_or(HalaGfxError::new("The staging buffer is not created.", None))
+  }
+
+  /// Shrink the staging buffer to the high-water mark and reset the high-water mark.
+  /// The staging buffer is released if nothing is acquired since the last shrink.
+  /// return: The result.
+  pub fn shrink(&mut self) -> Result<(), HalaGfxError> {
+    let size = if self.high_water_mark == 0 { 0 } else { self.high_water_mark.next_power_of_two() };
+    if size < self.capacity() {
+      self.buffer = None;
+      if size > 0 {
+        self.buffer = Some(self.create_buffer(size)?);
+      }
+      log::debug!("The HalaStagingBufferPool \"{}\" is shrunk to {} bytes.", self.debug_name, size);
+    }
+    self.high_water_mark = 0;
+
+    Ok(())
+  }
+
+  /// Create the staging buffer.
+  /// param size: The size in bytes.
+  /// return: The staging buffer.
+  fn create_buffer(&self, size: u64) -> Result<HalaBuffer, HalaGfxError> {
+    HalaBuffer::new(
+      Rc::clone(&self.logical_device),
+      size,
+      HalaBufferUsageFlags::TRANSFER_SRC,
+      HalaMemoryLocation::CpuToGpu,
+      &format!("{}.buffer", self.debug_name),
+    )
+  }
+}
@@ -19,7 +19,48 @@ use winit::raw_window_handle::{
 use winit::raw_window_handle::RawDisplayHandle;
 
 
-use crate::HalaGfxError;
+use crate::{
+  HalaGfxError,
+  HalaFormat,
+};
+
+/// The color space.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct HalaColorSpace(i32);
+impl HalaColorSpace {
+  pub const SRGB_NONLINEAR: Self = Self(vk::ColorSpaceKHR::SRGB_NONLINEAR.as_raw());
+  pub const DISPLAY_P3_NONLINEAR: Self = Self(vk::ColorSpaceKHR::DISPLAY_P3_NONLINEAR_EXT.as_raw());
+  pub const EXTENDED_SRGB_LINEAR: Self = Self(vk::ColorSpaceKHR::EXTENDED_SRGB_LINEAR_EXT.as_raw());
+  pub const DISPLAY_P3_LINEAR: Self = Self(vk::ColorSpaceKHR::DISPLAY_P3_LINEAR_EXT.as_raw());
+  pub const DCI_P3_NONLINEAR: Self = Self(vk::ColorSpaceKHR::DCI_P3_NONLINEAR_EXT.as_raw());
+  pub const BT709_LINEAR: Self = Self(vk::ColorSpaceKHR::BT709_LINEAR_EXT.as_raw());
+  pub const BT709_NONLINEAR: Self = Self(vk::ColorSpaceKHR::BT709_NONLINEAR_EXT.as_raw());
+  pub const BT2020_LINEAR: Self = Self(vk::ColorSpaceKHR::BT2020_LINEAR_EXT.as_raw());
+  pub const HDR10_ST2084: Self = Self(vk::ColorSpaceKHR::HDR10_ST2084_EXT.as_raw());
+  pub const HDR10_HLG: Self = Self(vk::ColorSpaceKHR::HDR10_HLG_EXT.as_raw());
+  pub const ADOBERGB_LINEAR: Self = Self(vk::ColorSpaceKHR::ADOBERGB_LINEAR_EXT.as_raw());
+  pub const ADOBERGB_NONLINEAR: Self = Self(vk::ColorSpaceKHR::ADOBERGB_NONLINEAR_EXT.as_raw());
+  pub const PASS_THROUGH: Self = Self(vk::ColorSpaceKHR::PASS_THROUGH_EXT.as_raw());
+  pub const EXTENDED_SRGB_NONLINEAR: Self = Self(vk::ColorSpaceKHR::EXTENDED_SRGB_NONLINEAR_EXT.as_raw());
+}
+
+impl std::fmt::Debug for HalaColorSpace {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{:?}", vk::ColorSpaceKHR::from_raw(self.0))
+  }
+}
+
+impl std::convert::From<vk::ColorSpaceKHR> for HalaColorSpace {
+  fn from(val: vk::ColorSpaceKHR) -> Self {
+    Self(val.as_raw())
+  }
+}
+
+impl std::convert::From<HalaColorSpace> for vk::ColorSpaceKHR {
+  fn from(val: HalaColorSpace) -> Self {
+    vk::ColorSpaceKHR::from_raw(val.0)
+  }
+}
 
 /// The surface.
 pub struct HalaSurface {
@@ -77,6 +118,21 @@ impl HalaSurface {
     )
   }
 
+  /// Get the available formats and color spaces of the surface.
+  /// param physical_device: The physical device.
+  /// return: The available formats and color spaces.
+  pub fn available_formats(&self, physical_device: &crate::HalaPhysicalDevice) -> Result<Vec<(HalaFormat, HalaColorSpace)>, HalaGfxError> {
+    let surface_formats = unsafe {
+      self.surface_loader.get_physical_device_surface_formats(physical_device.raw, self.raw)
+        .map_err(|err| HalaGfxError::new("Failed to get physical device surface formats.", Some(Box::new(err))))?
+    };
+    Ok(
+      surface_formats.iter()
+        .map(|format| (format.format.into(), format.color_space.into()))
+        .collect()
+    )
+  }
+
   /// Create a surface.
   /// param window: The window.
   /// param platform_surface_loader: The Vulkan platform surface loader.
@@ -7,8 +7,32 @@ use crate::{
   HalaGfxError,
   HalaCommandBufferSet,
   HalaFormat,
+  HalaSemaphore,
 };
 
+/// The swapchain status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HalaSwapchainStatus {
+  Optimal,
+  Suboptimal,
+  OutOfDate,
+}
+
+/// The HDR metadata.
+/// The primaries and the white point are in CIE 1931 xy chromaticity coordinates.
+/// The luminance values are in nits.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HalaHdrMetadata {
+  pub display_primary_red: (f32, f32),
+  pub display_primary_green: (f32, f32),
+  pub display_primary_blue: (f32, f32),
+  pub white_point: (f32, f32),
+  pub max_luminance: f32,
+  pub min_luminance: f32,
+  pub max_content_light_level: f32,
+  pub max_frame_average_light_level: f32,
+}
+
 /// The swapchain.
 pub struct HalaSwapchain {
   pub(crate) logical_device: Rc<RefCell<crate::HalaLogicalDevice>>,
@@ -89,6 +113,7 @@ impl HalaSwapchain {
       present_mode,
     ) = Self::create_swapchain(
       gpu_req,
+      instance,
       physical_device,
       &ld,
       surface,
@@ -132,7 +157,7 @@ impl HalaSwapchain {
         depth_stencil_image,
         depth_stencil_image_view,
         depth_stencil_memory,
-        has_stencil: depth_stencil_format == HalaFormat::D16_UNORM_S8_UINT || depth_stencil_format == HalaFormat::D24_UNORM_S8_UINT || depth_stencil_format == HalaFormat::D32_SFLOAT_S8_UINT,
+        has_stencil: depth_stencil_format.has_stencil(),
         num_of_images,
         current_image_index: 0,
         image_availables,
@@ -143,8 +168,8 @@ impl HalaSwapchain {
   }
 
   /// Acquire the next image.
-  pub(crate) fn acquire_next_image(&self) -> Result<usize, HalaGfxError> {
-    let (image_index, _) = unsafe {
+  pub(crate) fn acquire_next_frame_image(&self) -> Result<(usize, HalaSwapchainStatus), HalaGfxError> {
+    let result = unsafe {
       let logical_device = self.logical_device.borrow();
       logical_device.raw.queue_wait_idle(logical_device.get_graphics_queue(0))
         .map_err(|err| HalaGfxError::new("Failed to wait for queue idle.", Some(Box::new(err))))?;
@@ -154,9 +179,88 @@ impl HalaSwapchain {
         u64::MAX,
         self.image_availables[self.current_image_index],
         vk::Fence::null(),
-      ).map_err(|err| HalaGfxError::new("Failed to acquire next image.", Some(Box::new(err))))?
+      )
     };
-    Ok(image_index as usize)
+    match result {
+      Ok((image_index, false)) => Ok((image_index as usize, HalaSwapchainStatus::Optimal)),
+      Ok((image_index, true)) => Ok((image_index as usize, HalaSwapchainStatus::Suboptimal)),
+      Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok((0, HalaSwapchainStatus::OutOfDate)),
+      Err(err) => Err(HalaGfxError::new("Failed to acquire next image.", Some(Box::new(err)))),
+    }
+  }
+
+  /// Acquire the next image of the swapchain.
+  /// If the swapchain is out of date, an error is returned and HalaGfxError::is_out_of_date is true.
+  /// param timeout: The timeout in nanoseconds.
+  /// param signal_semaphore: The semaphore to be signaled when the image is available.
+  /// param fence: The fence to be signaled when the image is available.
+  /// return: The image index and whether the swapchain is suboptimal.
+  pub fn acquire_next_image(
+    &self,
+    timeout: u64,
+    signal_semaphore: Option<&HalaSemaphore>,
+    fence: Option<vk::Fence>,
+  ) -> Result<(u32, bool), HalaGfxError> {
+    unsafe {
+      self.swapchain_loader.acquire_next_image(
+        self.swapchain,
+        timeout,
+        signal_semaphore.map_or(vk::Semaphore::null(), |semaphore| semaphore.raw),
+        fence.unwrap_or(vk::Fence::null()),
+      ).map_err(|err| HalaGfxError::new("Failed to acquire next image.", Some(Box::new(err))))
+    }
+  }
+
+  /// Present the image of the swapchain.
+  /// If the swapchain is out of date, an error is returned and HalaGfxError::is_out_of_date is true.
+  /// param queue: The queue to present.
+  /// param image_index: The image index.
+  /// param wait_semaphores: The semaphores to wait before presenting.
+  /// return: Whether the swapchain is suboptimal.
+  pub fn present<S>(
+    &self,
+    queue: vk::Queue,
+    image_index: u32,
+    wait_semaphores: &[S],
+  ) -> Result<bool, HalaGfxError>
+    where S: AsRef<HalaSemaphore>
+  {
+    let wait_semaphores = wait_semaphores.iter().map(|semaphore| semaphore.as_ref().raw).collect::<Vec<_>>();
+    let swapchains = [self.swapchain];
+    let image_indices = [image_index];
+    let present_info = vk::PresentInfoKHR::default()
+      .wait_semaphores(&wait_semaphores)
+      .swapchains(&swapchains)
+      .image_indices(&image_indices);
+    unsafe {
+      self.swapchain_loader.queue_present(queue, &present_info)
+        .map_err(|err| HalaGfxError::new("Failed to present queue.", Some(Box::new(err))))
+    }
+  }
+
+  /// Set the HDR metadata of the swapchain.
+  /// param metadata: The HDR metadata.
+  /// return: The result.
+  pub fn set_hdr_metadata(&self, metadata: &HalaHdrMetadata) -> Result<(), HalaGfxError> {
+    let logical_device = self.logical_device.borrow();
+    let hdr_metadata_loader = logical_device.hdr_metadata_loader.as_ref()
+      .ok_or(HalaGfxError::new("The HDR metadata extension is not enabled.", None))?;
+    let hdr_metadata = vk::HdrMetadataEXT::default()
+      .display_primary_red(vk::XYColorEXT { x: metadata.display_primary_red.0, y: metadata.display_primary_red.1 })
+      .display_primary_green(vk::XYColorEXT { x: metadata.display_primary_green.0, y: metadata.display_primary_green.1 })
+      .display_primary_blue(vk::XYColorEXT { x: metadata.display_primary_blue.0, y: metadata.display_primary_blue.1 })
+      .white_point(vk::XYColorEXT { x: metadata.white_point.0, y: metadata.white_point.1 })
+      .max_luminance(metadata.max_luminance)
+      .min_luminance(metadata.min_luminance)
+      .max_content_light_level(metadata.max_content_light_level)
+      .max_frame_average_light_level(metadata.max_frame_average_light_level);
+    unsafe {
+      hdr_metadata_loader.set_hdr_metadata(
+        std::slice::from_ref(&self.swapchain),
+        std::slice::from_ref(&hdr_metadata),
+      );
+    }
+    Ok(())
   }
 
   /// Wait for draw fence.
@@ -216,7 +320,7 @@ impl HalaSwapchain {
   }
 
   /// Present.
-  pub(crate) fn present(&mut self, image_index: u32) -> Result<(), HalaGfxError> {
+  pub(crate) fn present_frame(&mut self, image_index: u32) -> Result<HalaSwapchainStatus, HalaGfxError> {
     let semaphores_finished = [self.render_finisheds[self.current_image_index]];
     let swapchains = [self.swapchain];
     let image_indices = [image_index];
@@ -224,19 +328,25 @@ impl HalaSwapchain {
       .wait_semaphores(&semaphores_finished)
       .swapchains(&swapchains)
       .image_indices(&image_indices);
-    unsafe {
+    let result = unsafe {
       let logical_device = self.logical_device.borrow();
       self.swapchain_loader.queue_present(
         logical_device.get_graphics_queue(0),
         &present_info,
-      ).map_err(|err| HalaGfxError::new("Failed to present queue.", Some(Box::new(err))))?;
-    }
+      )
+    };
     self.current_image_index = (self.current_image_index + 1) % self.num_of_images;
-    Ok(())
+    match result {
+      Ok(false) => Ok(HalaSwapchainStatus::Optimal),
+      Ok(true) => Ok(HalaSwapchainStatus::Suboptimal),
+      Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => Ok(HalaSwapchainStatus::OutOfDate),
+      Err(err) => Err(HalaGfxError::new("Failed to present queue.", Some(Box::new(err)))),
+    }
   }
 
   /// Create a swapchain.
   /// param gpu_req: The GPU requirements.
+  /// param instance: The instance.
   /// param physical_device: The physical device.
   /// param logical_device: The logical device.
   /// param surface: The surface.
@@ -244,6 +354,7 @@ impl HalaSwapchain {
   /// return: The Vulkan swapchain.
   fn create_swapchain(
     gpu_req: &crate::HalaGPURequirements,
+    instance: &crate::HalaInstance,
     physical_device: &crate::HalaPhysicalDevice,
     logical_device: &crate::HalaLogicalDevice,
     surface: &crate::HalaSurface,
@@ -288,7 +399,26 @@ impl HalaSwapchain {
       surface.surface_loader.get_physical_device_surface_formats(physical_device.raw, surface.raw)
         .map_err(|err| HalaGfxError::new("Failed to get physical device surface formats.", Some(Box::new(err))))?
     };
-    let format = if gpu_req.require_10bits_output {
+    let preferred_color_space: vk::ColorSpaceKHR = if instance.is_swapchain_colorspace_enabled {
+      gpu_req.preferred_color_space.into()
+    } else {
+      vk::ColorSpaceKHR::SRGB_NONLINEAR
+    };
+    let preferred_surface_format = if preferred_color_space != vk::ColorSpaceKHR::SRGB_NONLINEAR {
+      let found_surface_format = surface_formats.iter().find(|format| format.color_space == preferred_color_space);
+      if found_surface_format.is_none() {
+        log::warn!("Failed to find a format with {:?} color space, use {:?} color space instead.", preferred_color_space, vk::ColorSpaceKHR::SRGB_NONLINEAR);
+      }
+      found_surface_format
+    } else {
+      None
+    };
+    let color_space = preferred_surface_format
+      .map(|format| format.color_space)
+      .unwrap_or(vk::ColorSpaceKHR::SRGB_NONLINEAR);
+    let format = if let Some(surface_format) = preferred_surface_format {
+      surface_format.format
+    } else if gpu_req.require_10bits_output {
       let mut found = false;
       let mut found_format = vk::Format::UNDEFINED;
       for format in surface_formats.iter() {
@@ -348,7 +478,7 @@ impl HalaSwapchain {
       }
     };
     log::info!("Surface present mode: {:?}", present_mode);
-    log::info!("Surface format: {:?} color space: {:?}", format, vk::ColorSpaceKHR::SRGB_NONLINEAR);
+    log::info!("Surface format: {:?} color space: {:?}", format, color_space);
 
     let queue_family_indices = [logical_device.graphics_queue_family_index];
     let min_image_count = surface_capabilities.min_image_count;
@@ -367,7 +497,7 @@ impl HalaSwapchain {
           .min(max_image_count)
       )
       .image_format(format)
-      .image_color_space(vk::ColorSpaceKHR::SRGB_NONLINEAR)
+      .image_color_space(color_space)
       .image_extent(extent)
       .image_array_layers(1)
       .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_DST)
@@ -423,7 +553,7 @@ impl HalaSwapchain {
       swapchain_images,
       swapchain_imageviews,
       format.into(),
-      vk::ColorSpaceKHR::SRGB_NONLINEAR,
+      color_space,
       extent,
       present_mode,
     ))
@@ -448,20 +578,16 @@ impl HalaSwapchain {
       return Ok((HalaFormat::UNDEFINED, vk::Image::null(), vk::ImageView::null(), vk::DeviceMemory::null()));
     }
 
-    let (depth_stencil_format, depth_stencil_image_aspect) = if gpu_req.require_depth && gpu_req.require_stencil {
-      (vk::Format::D24_UNORM_S8_UINT, vk::ImageAspectFlags::DEPTH | vk::ImageAspectFlags::STENCIL)
-    } else if gpu_req.require_depth {
-      (vk::Format::D32_SFLOAT, vk::ImageAspectFlags::DEPTH)
-    } else if gpu_req.require_stencil {
-      (vk::Format::S8_UINT, vk::ImageAspectFlags::STENCIL)
+    let (depth_stencil_format, depth_stencil_image_aspect) = if gpu_req.require_depth {
+      let format = physical_device.find_supported_depth_format(instance, gpu_req.require_stencil)
+        .ok_or(HalaGfxError::new("Failed to find a depth stencil format.", None))?;
+      (vk::Format::from(format), format.aspect_flags().into())
     } else {
-      (vk::Format::UNDEFINED, vk::ImageAspectFlags::empty())
+      (vk::Format::S8_UINT, vk::ImageAspectFlags::STENCIL)
     };
 
-    let props = unsafe {
-      instance.raw.get_physical_device_format_properties(physical_device.raw, depth_stencil_format)
-    };
-    if props.optimal_tiling_features & vk::FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT == vk::FormatFeatureFlags::empty() {
+    let props = physical_device.format_properties(instance, HalaFormat::from(depth_stencil_format));
+    if !props.optimal_tiling_features.contains(crate::HalaFormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT) {
       return Err(HalaGfxError::new("Failed to find a depth stencil format.", None));
     }
 
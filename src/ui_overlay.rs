@@ -0,0 +1,244 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use crate::{
+  HalaGfxError,
+  HalaLogicalDevice,
+  HalaBuffer,
+  HalaBufferUsageFlags,
+  HalaMemoryLocation,
+  HalaCommandBufferSet,
+  HalaIndexType,
+  HalaFormat,
+  HalaVertexInputRate,
+  HalaVertexInputAttributeDescription,
+  HalaVertexInputBindingDescription,
+  HalaPushConstantRange,
+  HalaShaderStageFlags,
+};
+
+/// The vertex of the immediate-mode UI.
+/// It matches the vertex layout of imgui and egui.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct HalaUiVertex {
+  pub pos: [f32; 2],
+  pub uv: [f32; 2],
+  /// The packed RGBA8 color, the red channel is in the lowest byte.
+  pub color: u32,
+}
+
+/// The implementation of the UI vertex.
+impl HalaUiVertex {
+  /// Get the vertex attribute descriptions(position, uv, color).
+  /// return: The vertex attribute descriptions.
+  pub fn attribute_descriptions() -> [HalaVertexInputAttributeDescription; 3] {
+    [
+      HalaVertexInputAttributeDescription {
+        location: 0,
+        binding: 0,
+        format: HalaFormat::R32G32_SFLOAT,
+        offset: std::mem::offset_of!(HalaUiVertex, pos) as u32,
+      },
+      HalaVertexInputAttributeDescription {
+        location: 1,
+        binding: 0,
+        format: HalaFormat::R32G32_SFLOAT,
+        offset: std::mem::offset_of!(HalaUiVertex, uv) as u32,
+      },
+      HalaVertexInputAttributeDescription {
+        location: 2,
+        binding: 0,
+        format: HalaFormat::R8G8B8A8_UNORM,
+        offset: std::mem::offset_of!(HalaUiVertex, color) as u32,
+      },
+    ]
+  }
+
+  /// Get the vertex binding descriptions.
+  /// return: The vertex binding descriptions.
+  pub fn binding_descriptions() -> [HalaVertexInputBindingDescription; 1] {
+    [
+      HalaVertexInputBindingDescription {
+        binding: 0,
+        stride: std::mem::size_of::<HalaUiVertex>() as u32,
+        input_rate: HalaVertexInputRate::VERTEX,
+      },
+    ]
+  }
+}
+
+/// The push constants of the UI pipeline.
+/// The vertex shader computes the clip position as pos * scale + translate.
+#[repr(C)]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct HalaUiPushConstants {
+  pub scale: [f32; 2],
+  pub translate: [f32; 2],
+}
+
+/// The implementation of the UI push constants.
+impl HalaUiPushConstants {
+  /// Create the orthographic projection for the display rectangle.
+  /// param x: The left of the display.
+  /// param y: The top of the display.
+  /// param width: The width of the display.
+  /// param height: The height of the display.
+  /// return: The push constants.
+  pub fn ortho(x: f32, y: f32, width: f32, height: f32) -> Self {
+    let scale = [2.0 / width, 2.0 / height];
+    Self {
+      scale,
+      translate: [-1.0 - x * scale[0], -1.0 - y * scale[1]],
+    }
+  }
+
+  /// Get the push constant range used by the UI pipeline.
+  /// return: The push constant range.
+  pub fn range() -> HalaPushConstantRange {
+    HalaPushConstantRange {
+      stage_flags: HalaShaderStageFlags::VERTEX,
+      offset: 0,
+      size: std::mem::size_of::<HalaUiPushConstants>() as u32,
+    }
+  }
+
+  /// Get the push constants as a float array.
+  /// return: The float array.
+  pub fn to_array(self) -> [f32; 4] {
+    [self.scale[0], self.scale[1], self.translate[0], self.translate[1]]
+  }
+}
+
+/// The ring of the dynamic vertex and index buffers of the UI.
+/// Each frame in flight owns its own buffers, which grow when the UI needs more space.
+pub struct HalaUiBufferRing {
+  pub(crate) logical_device: Rc<RefCell<HalaLogicalDevice>>,
+  pub vertex_buffers: Vec<HalaBuffer>,
+  pub index_buffers: Vec<HalaBuffer>,
+  pub(crate) debug_name: String,
+}
+
+/// The Drop trait implementation of the UI buffer ring.
+impl Drop for HalaUiBufferRing {
+  fn drop(&mut self) {
+    log::debug!("A HalaUiBufferRing \"{}\" is dropped.", self.debug_name);
+  }
+}
+
+/// The implementation of the UI buffer ring.
+impl HalaUiBufferRing {
+  /// Create a new UI buffer ring.
+  /// param logical_device: The logical device.
+  /// param frames_in_flight: The number of the frames in flight.
+  /// param vertex_capacity: The initial vertex capacity of each frame.
+  /// param index_capacity: The initial index capacity of each frame.
+  /// param debug_name: The debug name.
+  /// return: The UI buffer ring.
+  pub fn new(
+    logical_device: Rc<RefCell<HalaLogicalDevice>>,
+    frames_in_flight: usize,
+    vertex_capacity: usize,
+    index_capacity: usize,
+    debug_name: &str,
+  ) -> Result<Self, HalaGfxError> {
+    if frames_in_flight == 0 {
+      return Err(HalaGfxError::new("The number of the frames in flight must be greater than 0.", None));
+    }
+
+    let mut vertex_buffers = Vec::with_capacity(frames_in_flight);
+    let mut index_buffers = Vec::with_capacity(frames_in_flight);
+    for frame_index in 0..frames_in_flight {
+      vertex_buffers.push(Self::create_vertex_buffer(&logical_device, vertex_capacity, frame_index, debug_name)?);
+      index_buffers.push(Self::create_index_buffer(&logical_device, index_capacity, frame_index, debug_name)?);
+    }
+
+    log::debug!("A HalaUiBufferRing \"{}\" is created.", debug_name);
+    Ok(Self {
+      logical_device,
+      vertex_buffers,
+      index_buffers,
+      debug_name: debug_name.to_string(),
+    })
+  }
+
+  /// Upload the vertices and indices of the frame.
+  /// The buffers of the frame are recreated with a doubled capacity if they are too small,
+  /// so the GPU must have finished the previous use of the frame.
+  /// param frame_index: The frame index.
+  /// param vertices: The vertices.
+  /// param indices: The indices.
+  /// return: The result.
+  pub fn upload(&mut self, frame_index: usize, vertices: &[HalaUiVertex], indices: &[u32]) -> Result<(), HalaGfxError> {
+    let vertex_size = std::mem::size_of_val(vertices) as u64;
+    if vertex_size > self.vertex_buffers[frame_index].size {
+      let capacity = vertices.len().next_power_of_two();
+      self.vertex_buffers[frame_index] = Self::create_vertex_buffer(&self.logical_device, capacity, frame_index, &self.debug_name)?;
+    }
+    let index_size = std::mem::size_of_val(indices) as u64;
+    if index_size > self.index_buffers[frame_index].size {
+      let capacity = indices.len().next_power_of_two();
+      self.index_buffers[frame_index] = Self::create_index_buffer(&self.logical_device, capacity, frame_index, &self.debug_name)?;
+    }
+
+    if !vertices.is_empty() {
+      self.vertex_buffers[frame_index].update_memory(0, vertices)?;
+    }
+    if !indices.is_empty() {
+      self.index_buffers[frame_index].update_memory(0, indices)?;
+    }
+
+    Ok(())
+  }
+
+  /// Bind the vertex and index buffers of the frame.
+  /// param index: The command buffer index.
+  /// param frame_index: The frame index.
+  /// param command_buffers: The command buffer set.
+  pub fn bind(&self, index: usize, frame_index: usize, command_buffers: &HalaCommandBufferSet) {
+    command_buffers.bind_vertex_buffers(index, 0, &[&self.vertex_buffers[frame_index]], &[0]);
+    command_buffers.bind_index_buffers(index, &[&self.index_buffers[frame_index]], &[0], HalaIndexType::UINT32);
+  }
+
+  /// Create a vertex buffer.
+  /// param logical_device: The logical device.
+  /// param capacity: The vertex capacity.
+  /// param frame_index: The frame index.
+  /// param debug_name: The debug name.
+  /// return: The vertex buffer.
+  fn create_vertex_buffer(
+    logical_device: &Rc<RefCell<HalaLogicalDevice>>,
+    capacity: usize,
+    frame_index: usize,
+    debug_name: &str,
+  ) -> Result<HalaBuffer, HalaGfxError> {
+    HalaBuffer::new(
+      Rc::clone(logical_device),
+      (capacity.max(1) * std::mem::size_of::<HalaUiVertex>()) as u64,
+      HalaBufferUsageFlags::VERTEX_BUFFER,
+      HalaMemoryLocation::CpuToGpu,
+      &format!("{}.vertex_buffer_{}", debug_name, frame_index),
+    )
+  }
+
+  /// Create an index buffer.
+  /// param logical_device: The logical device.
+  /// param capacity: The index capacity.
+  /// param frame_index: The frame index.
+  /// param debug_name: The debug name.
+  /// return: The index buffer.
+  fn create_index_buffer(
+    logical_device: &Rc<RefCell<HalaLogicalDevice>>,
+    capacity: usize,
+    frame_index: usize,
+    debug_name: &str,
+  ) -> Result<HalaBuffer, HalaGfxError> {
+    HalaBuffer::new(
+      Rc::clone(logical_device),
+      (capacity.max(1) * std::mem::size_of::<u32>()) as u64,
+      HalaBufferUsageFlags::INDEX_BUFFER,
+      HalaMemoryLocation::CpuToGpu,
+      &format!("{}.index_buffer_{}", debug_name, frame_index),
+    )
+  }
+}